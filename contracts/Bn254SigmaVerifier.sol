@@ -0,0 +1,74 @@
+// SPDX-License-Identifier: MIT
+// Generated by `zkp-cli solidity-verifier` (zkp-backend/src/zk/bn254_verifier.rs). Do not edit.
+pragma solidity ^0.8.0;
+
+/// @title Bn254SigmaVerifier
+/// @notice Verifies the BN254 equality and quantity-total proofs of the ZKP
+///         backend (`zk::bn254`) against commitments C = v*G + r*H on alt_bn128 G1.
+/// @dev The check runs in a precompile-only verifier contract deployed from
+///      VERIFIER_CODE; deploy it once and pass its address to the verify functions.
+library Bn254SigmaVerifier {
+    struct G1Point {
+        uint256 x;
+        uint256 y;
+    }
+
+    // R = k*H, s = k + c*delta_r
+    struct Proof {
+        G1Point r;
+        uint256 s;
+    }
+
+    bytes32 internal constant EQUALITY_TAG = keccak256("zkp.bn254.equality.v1");
+    bytes32 internal constant QUANTITY_TOTAL_TAG = keccak256("zkp.bn254.quantity-total.v1");
+
+    // Pedersen blinding generator H; the value generator G is (1, 2).
+    uint256 internal constant H_X = 11538815544934589282715074027520827418944671726036876111866394592134506445180;
+    uint256 internal constant H_Y = 362976366006982332126272892974433950016220920676702815845843046767812437434;
+
+    bytes internal constant VERIFIER_CODE = hex"6101b7600e6000396101b76000f336610140147f30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f00000016101203510167f30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f000000160403510166101206000610000377f30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001610120600020066102a05261004060a06101c0376040356102005260406101c060606101c060075afa166101e0517f30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47037f30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd4790066101e0526100406060610180376040610180608061018060065afa166102a0516101c05260406101c0606061018060075afa1661004060e0610180376040610180608061018060065afa167f1982bcf7e8c0ae705ab6b5b44a38d3df6bbedf0366908bf2e4ab33377c13797c610100527f00cd6ff5b1876cdce6cc770b8094d8257e5cf9e1816131570e148962f6f465ba6101205261012035610140526040610100606061010060075afa1661010051610180511416610120516101a051141660005260206000f3";
+
+    function deployVerifier() internal returns (address verifier) {
+        bytes memory code = VERIFIER_CODE;
+        assembly {
+            verifier := create(0, add(code, 0x20), mload(code))
+        }
+        require(verifier != address(0), "Bn254SigmaVerifier: deploy failed");
+    }
+
+    /// @notice cPrice and cPay commit to the same value.
+    function verifyEquality(
+        address verifier,
+        G1Point memory cPrice,
+        G1Point memory cPay,
+        Proof memory proof,
+        bytes32 contextHash
+    ) internal view returns (bool) {
+        return _verify(verifier, EQUALITY_TAG, contextHash, 1, cPrice, cPay, proof);
+    }
+
+    /// @notice cTotal commits to unitPrice times the value in cQuantity.
+    function verifyQuantityTotal(
+        address verifier,
+        G1Point memory cQuantity,
+        G1Point memory cTotal,
+        uint256 unitPrice,
+        Proof memory proof,
+        bytes32 contextHash
+    ) internal view returns (bool) {
+        return _verify(verifier, QUANTITY_TOTAL_TAG, contextHash, unitPrice, cTotal, cQuantity, proof);
+    }
+
+    function _verify(
+        address verifier,
+        bytes32 tag,
+        bytes32 contextHash,
+        uint256 k,
+        G1Point memory a,
+        G1Point memory b,
+        Proof memory proof
+    ) private view returns (bool) {
+        (bool ok, bytes memory result) = verifier.staticcall(abi.encode(tag, contextHash, k, a, b, proof));
+        return ok && result.length == 32 && abi.decode(result, (bool));
+    }
+}
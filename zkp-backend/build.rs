@@ -0,0 +1,19 @@
+//! Compiles `proto/` for the `zkp-grpc` binary.
+//!
+//! Uses protox rather than protoc, so no system protobuf install is needed.
+//! The encoded descriptor set is also written to OUT_DIR for server reflection.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use prost::Message;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("cargo:rerun-if-changed=proto");
+    let descriptors = protox::compile(["zkp/v1/zkp.proto"], ["proto"])?;
+    let out_dir = PathBuf::from(env::var("OUT_DIR")?);
+    fs::write(out_dir.join("zkp_descriptor.bin"), descriptors.encode_to_vec())?;
+    tonic_prost_build::configure().compile_fds(descriptors)?;
+    Ok(())
+}
@@ -0,0 +1,693 @@
+//! Request/response types and operations shared by the HTTP server and `zkp-cli`.
+//!
+//! Field names match the JSON bodies of the corresponding `/zkp/...` endpoints,
+//! so a request file written for the CLI can be POSTed unchanged and both
+//! produce the same output. Nothing here knows about actix; the server maps
+//! `ApiError` onto status codes.
+
+use curve25519_dalek_ng::{
+    ristretto::CompressedRistretto,
+    scalar::Scalar,
+};
+use hex::FromHex;
+use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+use utoipa::ToSchema;
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::zk::equality_proof::{prove_equality_within, verify_equality_within, EqualityProof};
+use crate::zk::opening_proof::{prove_opening, verify_opening};
+use crate::zk::pedersen::commit_scalar_with_blinding;
+use crate::zk::quantity_total_proof::{prove_quantity_total_within, verify_quantity_total_within, QuantityTotalProof};
+use crate::zk::validity::{
+    windowed_outcome, ReferenceSource, ValidityReference, ValidityWindow, WindowBasis, WindowStatus,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiError {
+    /// Malformed input (HTTP 400).
+    BadRequest(String),
+    /// Well-formed input the prover could not handle (HTTP 500).
+    Internal(String),
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BadRequest(msg) | Self::Internal(msg) => f.write_str(msg),
+        }
+    }
+}
+
+fn bad_req(msg: &str) -> ApiError {
+    ApiError::BadRequest(msg.to_string())
+}
+
+// =============================================================================
+// Input parsing
+// =============================================================================
+
+pub fn parse_hex32_bytes(hex_str: &str) -> Option<[u8; 32]> {
+    <[u8; 32]>::from_hex(hex_str.trim_start_matches("0x")).ok()
+}
+
+pub fn parse_compressed_ristretto(hex_str: &str) -> Option<CompressedRistretto> {
+    parse_hex32_bytes(hex_str).map(CompressedRistretto)
+}
+
+pub fn parse_scalar_hex(hex_str: &str) -> Option<Scalar> {
+    parse_hex32_bytes(hex_str).map(Scalar::from_bytes_mod_order)
+}
+
+pub fn parse_context_hash_hex(hex_str: &str) -> Option<[u8; 32]> {
+    parse_hex32_bytes(hex_str)
+}
+
+pub fn parse_decimal_scalar_strict(value: &str) -> Option<Scalar> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+
+    let parsed = BigUint::parse_bytes(trimmed.as_bytes(), 10)?;
+    let bytes_le = parsed.to_bytes_le();
+    if bytes_le.len() > 32 {
+        return None;
+    }
+
+    let mut scalar_bytes = [0u8; 32];
+    scalar_bytes[..bytes_le.len()].copy_from_slice(&bytes_le);
+    Scalar::from_canonical_bytes(scalar_bytes)
+}
+
+/// A JSON number or a decimal string (for values above 2^53).
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+#[serde(untagged)]
+pub enum IntegerLike {
+    U64(u64),
+    DecimalString(String),
+}
+
+impl IntegerLike {
+    pub fn parse_u64(&self, field_name: &str) -> Result<u64, String> {
+        match self {
+            Self::U64(value) => Ok(*value),
+            Self::DecimalString(value) => value
+                .trim()
+                .parse::<u64>()
+                .map_err(|_| format!("{field_name} must be a decimal u64 string")),
+        }
+    }
+
+    pub fn parse_u128(&self, field_name: &str) -> Result<u128, String> {
+        match self {
+            Self::U64(value) => Ok(u128::from(*value)),
+            Self::DecimalString(value) => value
+                .trim()
+                .parse::<u128>()
+                .map_err(|_| format!("{field_name} must be a decimal u128 string")),
+        }
+    }
+
+    pub fn parse_scalar(&self, field_name: &str) -> Result<Scalar, String> {
+        match self {
+            Self::U64(value) => Ok(Scalar::from(*value)),
+            Self::DecimalString(value) => parse_decimal_scalar_strict(value).ok_or_else(|| {
+                format!("{field_name} must be a canonical non-negative decimal scalar below the curve order")
+            }),
+        }
+    }
+}
+
+/// Result of a verify operation, with the bytes the server's replay
+/// registry and receipts are keyed on.
+#[derive(Debug, Clone)]
+pub struct Verification {
+    pub verified: bool,
+    /// Where the reference point fell in the proof's validity window; only
+    /// set for windowed proofs that otherwise verify.
+    pub validity: Option<WindowStatus>,
+    /// Set for windowed proofs; receipts sign it alongside the outcome.
+    pub reference: Option<ValidityReference>,
+    pub context: Vec<u8>,
+    pub proof: Vec<u8>,
+}
+
+fn parse_sigma_proof(proof_r_hex: &str, proof_s_hex: &str) -> Result<EqualityProof, ApiError> {
+    Ok(EqualityProof {
+        r_announcement: parse_hex32_bytes(proof_r_hex).ok_or_else(|| bad_req("invalid proof_r_hex"))?,
+        s_response: parse_hex32_bytes(proof_s_hex).ok_or_else(|| bad_req("invalid proof_s_hex"))?,
+    })
+}
+
+/// Rejects an empty or inverted validity window before proving.
+pub fn check_validity_window(window: &Option<ValidityWindow>) -> Result<(), ApiError> {
+    window.as_ref().map(ValidityWindow::check).transpose().map(|_| ()).map_err(ApiError::BadRequest)
+}
+
+/// Point a windowed proof is checked against: `reference_time` when given,
+/// otherwise the current unix time. Block-number windows have no clock to
+/// fall back on.
+pub fn reference_point(window: &Option<ValidityWindow>, reference_time: Option<u64>) -> Result<ValidityReference, ApiError> {
+    let basis = window.map(|window| window.basis).unwrap_or_default();
+    let (reference_time, source) = match (basis, reference_time) {
+        (_, Some(reference)) => (reference, ReferenceSource::Caller),
+        (WindowBasis::Block, None) => {
+            return Err(bad_req("reference_time is required for block-number validity windows"))
+        }
+        (WindowBasis::Timestamp, None) => (
+            SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+            ReferenceSource::Server,
+        ),
+    };
+    Ok(ValidityReference { reference_time, basis, source })
+}
+
+// =============================================================================
+// /zkp/generate-scalar-commitment-with-blinding
+// =============================================================================
+
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ScalarCommitmentWithBlindingRequest {
+    pub value: IntegerLike,
+    pub blinding_hex: String, // 32-byte hex string (64 hex chars)
+    #[serde(default)]
+    pub context_hash_hex: Option<String>, // When set, attach an opening proof bound to this context
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+pub struct ScalarCommitmentResponse {
+    pub commitment: String,
+    pub proof: Option<String>,
+    pub verified: bool,
+    pub proof_type: String,
+    /// Opening proof when `context_hash_hex` was set, in the field shape
+    /// /zkp/verify-opening-proof takes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proof_r_hex: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proof_s_value_hex: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proof_s_blinding_hex: Option<String>,
+}
+
+pub fn scalar_commitment_with_blinding(
+    req: &ScalarCommitmentWithBlindingRequest,
+) -> Result<ScalarCommitmentResponse, ApiError> {
+    let value = req.value.parse_scalar("value").map_err(ApiError::BadRequest)?;
+    let blinding = parse_scalar_hex(&req.blinding_hex)
+        .ok_or_else(|| bad_req("invalid blinding_hex: must be 32 bytes (64 hex chars)"))?;
+    let commitment = commit_scalar_with_blinding(value, blinding);
+
+    let context_hash = match &req.context_hash_hex {
+        Some(context_hash_hex) => {
+            parse_context_hash_hex(context_hash_hex).ok_or_else(|| bad_req("invalid context_hash_hex"))?
+        }
+        None => {
+            return Ok(ScalarCommitmentResponse {
+                commitment: hex::encode(commitment.as_bytes()),
+                proof: None,
+                verified: true,
+                proof_type: "pedersen-scalar-v2".to_string(),
+                proof_r_hex: None,
+                proof_s_value_hex: None,
+                proof_s_blinding_hex: None,
+            })
+        }
+    };
+
+    let proof = prove_opening(commitment, value, blinding, &context_hash).map_err(ApiError::Internal)?;
+    let verified = verify_opening(commitment, &proof, &context_hash);
+    Ok(ScalarCommitmentResponse {
+        commitment: hex::encode(commitment.as_bytes()),
+        proof: None,
+        verified,
+        proof_type: "pedersen-opening-v1".to_string(),
+        proof_r_hex: Some(hex::encode(proof.r_announcement)),
+        proof_s_value_hex: Some(hex::encode(proof.s_value)),
+        proof_s_blinding_hex: Some(hex::encode(proof.s_blinding)),
+    })
+}
+
+// =============================================================================
+// /zkp/generate-equality-proof, /zkp/verify-equality-proof
+// =============================================================================
+
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct EqualityProofRequest {
+    pub c_price_hex: String,      // 32-byte hex (64 chars), Pedersen commitment to price
+    pub c_pay_hex: String,        // 32-byte hex (64 chars), Pedersen commitment to payment
+    pub r_price_hex: String,      // 32-byte scalar hex — blinding of c_price
+    pub r_pay_hex: String,        // 32-byte scalar hex — blinding of c_pay
+    #[serde(default)]
+    pub binding_context: serde_json::Value, // {productId, txRef, chainId, escrowAddr, stage}
+    #[serde(default)]
+    pub context_hash_hex: Option<String>,
+    #[serde(default)]
+    pub validity: Option<ValidityWindow>,
+}
+
+/// Response of every Schnorr-style prove operation (equality, quantity-total, total-payment).
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+pub struct SigmaProofResponse {
+    pub proof_r_hex: String,   // hex of r_announcement (32 bytes)
+    pub proof_s_hex: String,   // hex of s_response (32 bytes)
+    pub verified: bool,        // immediate self-verification flag
+    /// The window bound into the proof; verifiers need it verbatim.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validity: Option<ValidityWindow>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct EqualityVerifyRequest {
+    pub c_price_hex: String,
+    pub c_pay_hex: String,
+    pub proof_r_hex: String,
+    pub proof_s_hex: String,
+    #[serde(default)]
+    pub binding_context: serde_json::Value,
+    #[serde(default)]
+    pub context_hash_hex: Option<String>,
+    #[serde(default)]
+    pub validity: Option<ValidityWindow>,
+    /// Unix seconds (or block number) to check `validity` against; defaults to now.
+    #[serde(default)]
+    pub reference_time: Option<u64>,
+}
+
+/// `context_hash_hex` when given, otherwise the serialized legacy binding context.
+fn equality_binding_bytes(
+    context_hash_hex: &Option<String>,
+    binding_context: &serde_json::Value,
+) -> Result<Vec<u8>, ApiError> {
+    match context_hash_hex {
+        Some(context_hash_hex) => parse_context_hash_hex(context_hash_hex)
+            .map(|bytes| bytes.to_vec())
+            .ok_or_else(|| bad_req("invalid context_hash_hex")),
+        None => Ok(serde_json::to_vec(binding_context).unwrap_or_default()),
+    }
+}
+
+pub fn generate_equality_proof(req: &EqualityProofRequest) -> Result<SigmaProofResponse, ApiError> {
+    let c_price = parse_compressed_ristretto(&req.c_price_hex)
+        .ok_or_else(|| bad_req("invalid c_price_hex: must be 32 bytes (64 hex chars)"))?;
+    let c_pay = parse_compressed_ristretto(&req.c_pay_hex)
+        .ok_or_else(|| bad_req("invalid c_pay_hex: must be 32 bytes (64 hex chars)"))?;
+    let r_price = parse_scalar_hex(&req.r_price_hex).ok_or_else(|| bad_req("invalid r_price_hex"))?;
+    let r_pay = parse_scalar_hex(&req.r_pay_hex).ok_or_else(|| bad_req("invalid r_pay_hex"))?;
+    let binding_bytes = equality_binding_bytes(&req.context_hash_hex, &req.binding_context)?;
+    check_validity_window(&req.validity)?;
+    let window = req.validity.as_ref();
+
+    let proof = prove_equality_within(c_price, c_pay, r_price, r_pay, &binding_bytes, window).map_err(ApiError::Internal)?;
+    Ok(SigmaProofResponse {
+        proof_r_hex: hex::encode(proof.r_announcement),
+        proof_s_hex: hex::encode(proof.s_response),
+        verified: verify_equality_within(c_price, c_pay, &proof, &binding_bytes, window),
+        validity: req.validity,
+    })
+}
+
+pub fn verify_equality_proof(req: &EqualityVerifyRequest) -> Result<Verification, ApiError> {
+    let c_price = parse_compressed_ristretto(&req.c_price_hex).ok_or_else(|| bad_req("invalid c_price_hex"))?;
+    let c_pay = parse_compressed_ristretto(&req.c_pay_hex).ok_or_else(|| bad_req("invalid c_pay_hex"))?;
+    let proof = parse_sigma_proof(&req.proof_r_hex, &req.proof_s_hex)?;
+    let binding_bytes = equality_binding_bytes(&req.context_hash_hex, &req.binding_context)?;
+    let reference = reference_point(&req.validity, req.reference_time)?;
+
+    let holds = verify_equality_within(c_price, c_pay, &proof, &binding_bytes, req.validity.as_ref());
+    let (verified, validity) = windowed_outcome(holds, req.validity.as_ref(), reference.reference_time);
+    Ok(Verification {
+        verified,
+        validity,
+        reference: req.validity.is_some().then_some(reference),
+        context: binding_bytes,
+        proof: [proof.r_announcement, proof.s_response].concat(),
+    })
+}
+
+// =============================================================================
+// /zkp/generate-quantity-total-proof, /zkp/verify-quantity-total-proof
+// =============================================================================
+
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct QuantityTotalProofRequest {
+    pub c_quantity_hex: String,
+    pub c_total_hex: String,
+    pub unit_price_wei: String,
+    pub r_quantity_hex: String,
+    pub r_total_hex: String,
+    pub context_hash_hex: String,
+    #[serde(default)]
+    pub validity: Option<ValidityWindow>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct QuantityTotalVerifyRequest {
+    pub c_quantity_hex: String,
+    pub c_total_hex: String,
+    pub unit_price_wei: String,
+    pub proof_r_hex: String,
+    pub proof_s_hex: String,
+    pub context_hash_hex: String,
+    #[serde(default)]
+    pub validity: Option<ValidityWindow>,
+    /// Unix seconds (or block number) to check `validity` against; defaults to now.
+    #[serde(default)]
+    pub reference_time: Option<u64>,
+}
+
+pub fn generate_quantity_total_proof(req: &QuantityTotalProofRequest) -> Result<SigmaProofResponse, ApiError> {
+    let c_quantity = parse_compressed_ristretto(&req.c_quantity_hex).ok_or_else(|| bad_req("invalid c_quantity_hex"))?;
+    let c_total = parse_compressed_ristretto(&req.c_total_hex).ok_or_else(|| bad_req("invalid c_total_hex"))?;
+    let unit_price = parse_decimal_scalar_strict(&req.unit_price_wei).ok_or_else(|| bad_req("invalid unit_price_wei"))?;
+    let r_quantity = parse_scalar_hex(&req.r_quantity_hex).ok_or_else(|| bad_req("invalid r_quantity_hex"))?;
+    let r_total = parse_scalar_hex(&req.r_total_hex).ok_or_else(|| bad_req("invalid r_total_hex"))?;
+    let context_hash = parse_context_hash_hex(&req.context_hash_hex).ok_or_else(|| bad_req("invalid context_hash_hex"))?;
+    check_validity_window(&req.validity)?;
+    let window = req.validity.as_ref();
+
+    let proof = prove_quantity_total_within(c_quantity, c_total, unit_price, r_quantity, r_total, &context_hash, window)
+        .map_err(ApiError::Internal)?;
+    Ok(SigmaProofResponse {
+        proof_r_hex: hex::encode(proof.r_announcement),
+        proof_s_hex: hex::encode(proof.s_response),
+        verified: verify_quantity_total_within(c_quantity, c_total, unit_price, &proof, &context_hash, window),
+        validity: req.validity,
+    })
+}
+
+pub fn verify_quantity_total_proof(req: &QuantityTotalVerifyRequest) -> Result<Verification, ApiError> {
+    let c_quantity = parse_compressed_ristretto(&req.c_quantity_hex).ok_or_else(|| bad_req("invalid c_quantity_hex"))?;
+    let c_total = parse_compressed_ristretto(&req.c_total_hex).ok_or_else(|| bad_req("invalid c_total_hex"))?;
+    let unit_price = parse_decimal_scalar_strict(&req.unit_price_wei).ok_or_else(|| bad_req("invalid unit_price_wei"))?;
+    let context_hash = parse_context_hash_hex(&req.context_hash_hex).ok_or_else(|| bad_req("invalid context_hash_hex"))?;
+    let proof: QuantityTotalProof = parse_sigma_proof(&req.proof_r_hex, &req.proof_s_hex)?;
+    let reference = reference_point(&req.validity, req.reference_time)?;
+
+    let holds = verify_quantity_total_within(c_quantity, c_total, unit_price, &proof, &context_hash, req.validity.as_ref());
+    let (verified, validity) = windowed_outcome(holds, req.validity.as_ref(), reference.reference_time);
+    Ok(Verification {
+        verified,
+        validity,
+        reference: req.validity.is_some().then_some(reference),
+        context: context_hash.to_vec(),
+        proof: [proof.r_announcement, proof.s_response].concat(),
+    })
+}
+
+// =============================================================================
+// /zkp/generate-total-payment-equality-proof, /zkp/verify-total-payment-equality-proof
+// =============================================================================
+
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TotalPaymentEqualityProofRequest {
+    pub c_total_hex: String,
+    pub c_pay_hex: String,
+    pub r_total_hex: String,
+    pub r_pay_hex: String,
+    pub context_hash_hex: String,
+    #[serde(default)]
+    pub validity: Option<ValidityWindow>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TotalPaymentEqualityVerifyRequest {
+    pub c_total_hex: String,
+    pub c_pay_hex: String,
+    pub proof_r_hex: String,
+    pub proof_s_hex: String,
+    pub context_hash_hex: String,
+    #[serde(default)]
+    pub validity: Option<ValidityWindow>,
+    /// Unix seconds (or block number) to check `validity` against; defaults to now.
+    #[serde(default)]
+    pub reference_time: Option<u64>,
+}
+
+pub fn generate_total_payment_equality_proof(
+    req: &TotalPaymentEqualityProofRequest,
+) -> Result<SigmaProofResponse, ApiError> {
+    let c_total = parse_compressed_ristretto(&req.c_total_hex).ok_or_else(|| bad_req("invalid c_total_hex"))?;
+    let c_pay = parse_compressed_ristretto(&req.c_pay_hex).ok_or_else(|| bad_req("invalid c_pay_hex"))?;
+    let r_total = parse_scalar_hex(&req.r_total_hex).ok_or_else(|| bad_req("invalid r_total_hex"))?;
+    let r_pay = parse_scalar_hex(&req.r_pay_hex).ok_or_else(|| bad_req("invalid r_pay_hex"))?;
+    let context_hash = parse_context_hash_hex(&req.context_hash_hex).ok_or_else(|| bad_req("invalid context_hash_hex"))?;
+    check_validity_window(&req.validity)?;
+    let window = req.validity.as_ref();
+
+    let proof = prove_equality_within(c_total, c_pay, r_total, r_pay, &context_hash, window).map_err(ApiError::Internal)?;
+    Ok(SigmaProofResponse {
+        proof_r_hex: hex::encode(proof.r_announcement),
+        proof_s_hex: hex::encode(proof.s_response),
+        verified: verify_equality_within(c_total, c_pay, &proof, &context_hash, window),
+        validity: req.validity,
+    })
+}
+
+pub fn verify_total_payment_equality_proof(
+    req: &TotalPaymentEqualityVerifyRequest,
+) -> Result<Verification, ApiError> {
+    let c_total = parse_compressed_ristretto(&req.c_total_hex).ok_or_else(|| bad_req("invalid c_total_hex"))?;
+    let c_pay = parse_compressed_ristretto(&req.c_pay_hex).ok_or_else(|| bad_req("invalid c_pay_hex"))?;
+    let context_hash = parse_context_hash_hex(&req.context_hash_hex).ok_or_else(|| bad_req("invalid context_hash_hex"))?;
+    let proof = parse_sigma_proof(&req.proof_r_hex, &req.proof_s_hex)?;
+    let reference = reference_point(&req.validity, req.reference_time)?;
+
+    let holds = verify_equality_within(c_total, c_pay, &proof, &context_hash, req.validity.as_ref());
+    let (verified, validity) = windowed_outcome(holds, req.validity.as_ref(), reference.reference_time);
+    Ok(Verification {
+        verified,
+        validity,
+        reference: req.validity.is_some().then_some(reference),
+        context: context_hash.to_vec(),
+        proof: [proof.r_announcement, proof.s_response].concat(),
+    })
+}
+
+// =============================================================================
+// /zkp/order-context-hash
+// =============================================================================
+
+/// Inputs of the order context hash, as computed by the frontend's
+/// `computeOrderContextHash`:
+///   keccak256(abi.encode(bytes32 orderId, bytes32 memoHash, bytes32 railgunTxRef,
+///                        uint256 productId, uint256 chainId, address escrowAddr,
+///                        bytes32 unitPriceHash))
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct OrderContextHashRequest {
+    pub order_id: String,
+    pub memo_hash: String,
+    pub railgun_tx_ref: String,
+    pub product_id: IntegerLike,
+    pub chain_id: IntegerLike,
+    pub escrow_addr: String,
+    /// Either the listing's `unitPriceHash`, or `unit_price_wei` to derive it.
+    #[serde(default)]
+    pub unit_price_hash: Option<String>,
+    #[serde(default)]
+    pub unit_price_wei: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+pub struct OrderContextHashResponse {
+    pub context_hash_hex: String,  // 0x-prefixed, as stored on-chain
+    pub unit_price_hash: String,
+}
+
+fn uint256_word(value: &IntegerLike, field_name: &str) -> Result<[u8; 32], ApiError> {
+    let parsed = match value {
+        IntegerLike::U64(value) => BigUint::from(*value),
+        IntegerLike::DecimalString(value) => BigUint::parse_bytes(value.trim().as_bytes(), 10)
+            .ok_or_else(|| bad_req(&format!("{field_name} must be a decimal integer string")))?,
+    };
+    let bytes = parsed.to_bytes_be();
+    if bytes.len() > 32 {
+        return Err(bad_req(&format!("{field_name} does not fit in uint256")));
+    }
+    let mut word = [0u8; 32];
+    word[32 - bytes.len()..].copy_from_slice(&bytes);
+    Ok(word)
+}
+
+fn bytes32_word(value: &str, field_name: &str) -> Result<[u8; 32], ApiError> {
+    parse_hex32_bytes(value.trim()).ok_or_else(|| bad_req(&format!("invalid {field_name}: must be 32 bytes")))
+}
+
+/// keccak256(abi.encode(uint256 unitPriceWei)), the listing's `unitPriceHash`.
+pub fn unit_price_hash(unit_price_wei: &str) -> Result<[u8; 32], ApiError> {
+    let word = uint256_word(&IntegerLike::DecimalString(unit_price_wei.to_string()), "unit_price_wei")?;
+    Ok(Keccak256::digest(word).into())
+}
+
+pub fn order_context_hash(req: &OrderContextHashRequest) -> Result<OrderContextHashResponse, ApiError> {
+    let unit_price_hash = match (&req.unit_price_hash, &req.unit_price_wei) {
+        (Some(hash), _) => bytes32_word(hash, "unit_price_hash")?,
+        (None, Some(wei)) => unit_price_hash(wei)?,
+        (None, None) => return Err(bad_req("expected unit_price_hash or unit_price_wei")),
+    };
+    let escrow = <[u8; 20]>::from_hex(req.escrow_addr.trim().trim_start_matches("0x"))
+        .map_err(|_| bad_req("invalid escrow_addr: must be a 20-byte address"))?;
+    let mut escrow_word = [0u8; 32];
+    escrow_word[12..].copy_from_slice(&escrow);
+
+    let words = [
+        bytes32_word(&req.order_id, "order_id")?,
+        bytes32_word(&req.memo_hash, "memo_hash")?,
+        bytes32_word(&req.railgun_tx_ref, "railgun_tx_ref")?,
+        uint256_word(&req.product_id, "product_id")?,
+        uint256_word(&req.chain_id, "chain_id")?,
+        escrow_word,
+        unit_price_hash,
+    ];
+    let mut hasher = Keccak256::new();
+    for word in &words {
+        hasher.update(word);
+    }
+
+    Ok(OrderContextHashResponse {
+        context_hash_hex: format!("0x{}", hex::encode(hasher.finalize())),
+        unit_price_hash: format!("0x{}", hex::encode(unit_price_hash)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_decimal_scalar_strict_accepts_large_canonical_value() {
+        let value = "340282366920938463463374607431768211455";
+        assert!(parse_decimal_scalar_strict(value).is_some());
+    }
+
+    #[test]
+    fn parse_decimal_scalar_strict_rejects_non_canonical_scalar() {
+        let value = "7237005577332262213973186563042994240857116359379907606001950938285454250990";
+        assert!(parse_decimal_scalar_strict(value).is_none());
+    }
+
+    #[test]
+    fn unit_price_hash_matches_abi_encoded_keccak() {
+        // keccak256(abi.encode(uint256(1)))
+        assert_eq!(
+            hex::encode(unit_price_hash("1").unwrap()),
+            "b10e2d527612073b26eecdfd717e6a320cf44b4afac2b0732d9fcbe2b7fa0cf6"
+        );
+    }
+
+    #[test]
+    fn prove_and_verify_quantity_total_round_trip() {
+        let blinding_hex = |byte: u8| hex::encode([byte; 32]);
+        let commit = |value: u64, byte: u8| {
+            scalar_commitment_with_blinding(&ScalarCommitmentWithBlindingRequest {
+                value: IntegerLike::U64(value),
+                blinding_hex: blinding_hex(byte),
+                context_hash_hex: None,
+            })
+            .unwrap()
+            .commitment
+        };
+        let (c_quantity, c_total) = (commit(3, 0x01), commit(30, 0x02));
+        let context_hash_hex = hex::encode([0x33u8; 32]);
+
+        let proof = generate_quantity_total_proof(&QuantityTotalProofRequest {
+            c_quantity_hex: c_quantity.clone(),
+            c_total_hex: c_total.clone(),
+            unit_price_wei: "10".to_string(),
+            r_quantity_hex: blinding_hex(0x01),
+            r_total_hex: blinding_hex(0x02),
+            context_hash_hex: context_hash_hex.clone(),
+            validity: None,
+        })
+        .unwrap();
+        let verification = verify_quantity_total_proof(&QuantityTotalVerifyRequest {
+            c_quantity_hex: c_quantity,
+            c_total_hex: c_total,
+            unit_price_wei: "10".to_string(),
+            proof_r_hex: proof.proof_r_hex,
+            proof_s_hex: proof.proof_s_hex,
+            context_hash_hex,
+            validity: None,
+            reference_time: None,
+        })
+        .unwrap();
+
+        assert!(proof.verified);
+        assert!(verification.verified);
+        assert_eq!(verification.validity, None);
+        assert_eq!(verification.reference, None);
+    }
+
+    #[test]
+    fn windowed_equality_proof_reports_expiry() {
+        let blinding_hex = |byte: u8| hex::encode([byte; 32]);
+        let commit = |byte: u8| {
+            scalar_commitment_with_blinding(&ScalarCommitmentWithBlindingRequest {
+                value: IntegerLike::U64(30),
+                blinding_hex: blinding_hex(byte),
+                context_hash_hex: None,
+            })
+            .unwrap()
+            .commitment
+        };
+        let (c_total, c_pay) = (commit(0x01), commit(0x02));
+        let window = ValidityWindow { not_before: Some(1_000), not_after: Some(2_000), basis: WindowBasis::Block };
+
+        let proof = generate_total_payment_equality_proof(&TotalPaymentEqualityProofRequest {
+            c_total_hex: c_total.clone(),
+            c_pay_hex: c_pay.clone(),
+            r_total_hex: blinding_hex(0x01),
+            r_pay_hex: blinding_hex(0x02),
+            context_hash_hex: hex::encode([0x33u8; 32]),
+            validity: Some(window),
+        })
+        .unwrap();
+        assert_eq!(proof.validity, Some(window));
+        let verify_at = |reference_time: Option<u64>| {
+            verify_total_payment_equality_proof(&TotalPaymentEqualityVerifyRequest {
+                c_total_hex: c_total.clone(),
+                c_pay_hex: c_pay.clone(),
+                proof_r_hex: proof.proof_r_hex.clone(),
+                proof_s_hex: proof.proof_s_hex.clone(),
+                context_hash_hex: hex::encode([0x33u8; 32]),
+                validity: Some(window),
+                reference_time,
+            })
+        };
+
+        let inside = verify_at(Some(1_500)).unwrap();
+        assert!(inside.verified);
+        assert_eq!(inside.validity, Some(WindowStatus::Valid));
+        let caller = ValidityReference { reference_time: 1_500, basis: WindowBasis::Block, source: ReferenceSource::Caller };
+        assert_eq!(inside.reference, Some(caller));
+        let late = verify_at(Some(2_001)).unwrap();
+        assert!(!late.verified);
+        assert_eq!(late.validity, Some(WindowStatus::Expired));
+        assert!(matches!(verify_at(None), Err(ApiError::BadRequest(_))));
+    }
+
+    #[test]
+    fn malformed_input_is_a_bad_request() {
+        let result = verify_total_payment_equality_proof(&TotalPaymentEqualityVerifyRequest {
+            c_total_hex: "zz".to_string(),
+            c_pay_hex: String::new(),
+            proof_r_hex: String::new(),
+            proof_s_hex: String::new(),
+            context_hash_hex: String::new(),
+            validity: None,
+            reference_time: None,
+        });
+        assert_eq!(result.unwrap_err(), ApiError::BadRequest("invalid c_total_hex".to_string()));
+    }
+}
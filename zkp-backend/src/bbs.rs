@@ -0,0 +1,450 @@
+//! BBS+ selective-disclosure endpoints (`zk::bbs`).
+//!
+//!   GET  /zkp/bbs/public-key     — issuer public key
+//!   POST /zkp/bbs/issue          — sign a VRC attribute vector (issuer scope)
+//!   POST /zkp/bbs/derive-proof   — reveal a subset, optionally linking hidden integers to Pedersen commitments
+//!   POST /zkp/bbs/verify         — verify a derived proof against an issuer key
+//!
+//! Attributes are `{ name, value, integer }`: integer attributes (e.g.
+//! unitPrice) must be decimal u64 and are the only ones that can be linked to
+//! a commitment. Every attribute is signed together with its name, so links
+//! name their attribute too. Keys, signatures and
+//! proofs use the compressed arkworks encoding.
+//!
+//! Configured from the environment:
+//!   ZKP_BBS_SECRET_KEY — 32-byte hex issuer key; required unless ZKP_DEV_MODE is
+//!                        set, which generates an ephemeral key
+
+use actix_web::{get, post, web, HttpResponse, Responder};
+use ark_bls12_381::Fr;
+use bulletproof_demo::api::parse_context_hash_hex;
+use bulletproof_demo::zk::bbs::{
+    self, attribute_scalar, name_offset, DisclosureProof, Link, LinkOpening, PublicKey, SecretKey, Signature,
+    MAX_ATTRIBUTES,
+};
+use curve25519_dalek_ng::ristretto::CompressedRistretto;
+use curve25519_dalek_ng::scalar::Scalar;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use utoipa::ToSchema;
+
+use crate::pool::WorkPools;
+use crate::receipts::{ReceiptSigner, VerificationReceipt};
+use crate::{bad_req, check_replay, dev_mode, metrics, ErrorResponse};
+use crate::replay::{ReplayRegistry, ReplayStatus};
+
+pub struct BbsIssuer {
+    secret: SecretKey,
+    public: PublicKey,
+}
+
+impl BbsIssuer {
+    fn new(secret: SecretKey) -> Self {
+        let public = secret.public_key();
+        Self { secret, public }
+    }
+
+    /// Loads `ZKP_BBS_SECRET_KEY`. Only dev mode may fall back to an
+    /// ephemeral key, since credentials it signs stop verifying on restart.
+    pub fn from_env() -> Result<Self, String> {
+        let configured = std::env::var("ZKP_BBS_SECRET_KEY").ok().filter(|value| !value.trim().is_empty());
+        let secret = match configured {
+            Some(value) => hex::decode(value.trim().trim_start_matches("0x"))
+                .ok()
+                .and_then(|b| SecretKey::from_bytes(&b))
+                .ok_or("ZKP_BBS_SECRET_KEY must be a 32-byte BLS12-381 scalar")?,
+            None if dev_mode() => {
+                warn!("ZKP_BBS_SECRET_KEY unset, using an ephemeral BBS+ key");
+                SecretKey::random()
+            }
+            None => return Err("ZKP_BBS_SECRET_KEY must be set to the issuer key".to_string()),
+        };
+        Ok(Self::new(secret))
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct BbsAttribute {
+    /// VRC field name, e.g. "orderId" or "unitPrice".
+    name: String,
+    value: String,
+    /// Decimal u64 kept in the low 64 bits of its scalar, so it can be linked to a commitment.
+    #[serde(default)]
+    integer: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct BbsPublicKeyResponse {
+    public_key_hex: String,
+    max_attributes: usize,
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct BbsIssueRequest {
+    attributes: Vec<BbsAttribute>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct BbsIssueResponse {
+    signature_hex: String,
+    public_key_hex: String,
+    attribute_count: usize,
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct BbsLinkOpening {
+    index: usize,
+    /// Pedersen commitment to the attribute, e.g. C_price.
+    commitment_hex: String,
+    blinding_hex: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct BbsDeriveProofRequest {
+    signature_hex: String,
+    /// The full signed vector, in signing order.
+    attributes: Vec<BbsAttribute>,
+    /// Indices to reveal.
+    #[serde(default)]
+    disclosed: Vec<usize>,
+    /// Hidden integer attributes to tie to commitments.
+    #[serde(default)]
+    links: Vec<BbsLinkOpening>,
+    context_hash_hex: String,
+    /// Issuer key; defaults to this server's.
+    #[serde(default)]
+    public_key_hex: Option<String>,
+}
+
+#[derive(Clone, Deserialize, Serialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct BbsDisclosedAttribute {
+    index: usize,
+    attribute: BbsAttribute,
+}
+
+#[derive(Clone, Deserialize, Serialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct BbsLink {
+    index: usize,
+    /// Name of the linked attribute, e.g. "unitPrice".
+    name: String,
+    commitment_hex: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct BbsDeriveProofResponse {
+    proof_hex: String,
+    public_key_hex: String,
+    attribute_count: usize,
+    disclosed: Vec<BbsDisclosedAttribute>,
+    links: Vec<BbsLink>,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct BbsVerifyRequest {
+    public_key_hex: String,
+    attribute_count: usize,
+    #[serde(default)]
+    disclosed: Vec<BbsDisclosedAttribute>,
+    #[serde(default)]
+    links: Vec<BbsLink>,
+    proof_hex: String,
+    context_hash_hex: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct BbsVerifyResponse {
+    verified: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    replay: Option<ReplayStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    receipt: Option<VerificationReceipt>,
+}
+
+fn decode_hex(hex_str: &str) -> Option<Vec<u8>> {
+    hex::decode(hex_str.trim_start_matches("0x")).ok()
+}
+
+fn parse_public_key(hex_str: &str) -> Result<PublicKey, String> {
+    decode_hex(hex_str).and_then(|bytes| PublicKey::from_bytes(&bytes)).ok_or_else(|| "invalid public_key_hex".to_string())
+}
+
+fn parse_commitment(hex_str: &str) -> Result<CompressedRistretto, String> {
+    decode_hex(hex_str)
+        .filter(|bytes| bytes.len() == 32)
+        .map(|bytes| CompressedRistretto::from_slice(&bytes))
+        .filter(|point| point.decompress().is_some())
+        .ok_or_else(|| "invalid commitment_hex".to_string())
+}
+
+fn scalars(attributes: &[BbsAttribute]) -> Result<Vec<Fr>, String> {
+    if attributes.is_empty() || attributes.len() > MAX_ATTRIBUTES {
+        return Err(format!("attributes must hold 1 to {MAX_ATTRIBUTES} entries"));
+    }
+    attributes.iter().map(|a| attribute_scalar(&a.name, &a.value, a.integer)).collect()
+}
+
+fn derive(req: &BbsDeriveProofRequest, issuer: &BbsIssuer) -> Result<BbsDeriveProofResponse, String> {
+    let pk = match &req.public_key_hex {
+        Some(hex_str) => parse_public_key(hex_str)?,
+        None => issuer.public.clone(),
+    };
+    let signature = decode_hex(&req.signature_hex)
+        .and_then(|bytes| Signature::from_bytes(&bytes))
+        .ok_or("invalid signature_hex")?;
+    let context_hash = parse_context_hash_hex(&req.context_hash_hex).ok_or("invalid context_hash_hex")?;
+    let messages = scalars(&req.attributes)?;
+
+    let mut disclosed = req.disclosed.clone();
+    disclosed.sort_unstable();
+    let mut openings = req
+        .links
+        .iter()
+        .map(|link| {
+            let blinding = decode_hex(&link.blinding_hex)
+                .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+                .and_then(Scalar::from_canonical_bytes)
+                .ok_or("invalid blinding_hex")?;
+            let attribute = req
+                .attributes
+                .get(link.index)
+                .filter(|attribute| attribute.integer)
+                .ok_or_else(|| format!("linked attribute {} must be an integer attribute", link.index))?;
+            Ok(LinkOpening {
+                index: link.index,
+                offset: name_offset(&attribute.name),
+                commitment: parse_commitment(&link.commitment_hex)?,
+                blinding,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    openings.sort_unstable_by_key(|link| link.index);
+
+    let proof = bbs::derive_proof(&pk, &signature, &messages, &disclosed, &openings, &context_hash)?;
+    Ok(BbsDeriveProofResponse {
+        proof_hex: hex::encode(proof.to_bytes()),
+        public_key_hex: hex::encode(pk.to_bytes()),
+        attribute_count: messages.len(),
+        disclosed: disclosed
+            .iter()
+            .map(|&index| BbsDisclosedAttribute { index, attribute: req.attributes[index].clone() })
+            .collect(),
+        links: openings
+            .iter()
+            .map(|link| BbsLink {
+                index: link.index,
+                name: req.attributes[link.index].name.clone(),
+                commitment_hex: hex::encode(link.commitment.as_bytes()),
+            })
+            .collect(),
+    })
+}
+
+/// Parsed verify request: key, disclosed scalars, links, proof, context.
+type VerifyInputs = (PublicKey, Vec<(usize, Fr)>, Vec<Link>, DisclosureProof, [u8; 32]);
+
+fn parse_verify(req: &BbsVerifyRequest) -> Result<VerifyInputs, String> {
+    let pk = parse_public_key(&req.public_key_hex)?;
+    let context_hash = parse_context_hash_hex(&req.context_hash_hex).ok_or("invalid context_hash_hex")?;
+    if req.attribute_count == 0 || req.attribute_count > MAX_ATTRIBUTES {
+        return Err(format!("attribute_count must be 1 to {MAX_ATTRIBUTES}"));
+    }
+    let mut disclosed = req
+        .disclosed
+        .iter()
+        .map(|d| Ok((d.index, attribute_scalar(&d.attribute.name, &d.attribute.value, d.attribute.integer)?)))
+        .collect::<Result<Vec<_>, String>>()?;
+    disclosed.sort_unstable_by_key(|(index, _)| *index);
+    let mut links = req
+        .links
+        .iter()
+        .map(|link| {
+            Ok(Link { index: link.index, offset: name_offset(&link.name), commitment: parse_commitment(&link.commitment_hex)? })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    links.sort_unstable_by_key(|link| link.index);
+
+    let unlinked = req.attribute_count.saturating_sub(disclosed.len() + links.len());
+    let proof = decode_hex(&req.proof_hex)
+        .and_then(|bytes| DisclosureProof::from_bytes(&bytes, unlinked, links.len()))
+        .ok_or("invalid proof_hex")?;
+    Ok((pk, disclosed, links, proof, context_hash))
+}
+
+#[utoipa::path(
+    tag = "bbs",
+    responses((status = 200, body = BbsPublicKeyResponse))
+)]
+#[get("/zkp/bbs/public-key")]
+pub async fn public_key(issuer: web::Data<BbsIssuer>) -> impl Responder {
+    HttpResponse::Ok().json(BbsPublicKeyResponse {
+        public_key_hex: hex::encode(issuer.public.to_bytes()),
+        max_attributes: MAX_ATTRIBUTES,
+    })
+}
+
+/// Requires the issuer route group; see `security`.
+#[utoipa::path(
+    tag = "bbs",
+    request_body = BbsIssueRequest,
+    responses(
+        (status = 200, body = BbsIssueResponse),
+        (status = 400, body = ErrorResponse),
+    )
+)]
+#[post("/zkp/bbs/issue")]
+pub async fn issue(
+    req: web::Json<BbsIssueRequest>,
+    issuer: web::Data<BbsIssuer>,
+    pools: web::Data<WorkPools>,
+) -> impl Responder {
+    pools.prove.run(move || {
+        let signature = match scalars(&req.attributes).and_then(|messages| bbs::sign(&issuer.secret, &messages)) {
+            Ok(signature) => signature,
+            Err(error) => return bad_req(&error),
+        };
+        HttpResponse::Ok().json(BbsIssueResponse {
+            signature_hex: hex::encode(signature.to_bytes()),
+            public_key_hex: hex::encode(issuer.public.to_bytes()),
+            attribute_count: req.attributes.len(),
+        })
+    })
+    .await
+}
+
+#[utoipa::path(
+    tag = "bbs",
+    request_body = BbsDeriveProofRequest,
+    responses(
+        (status = 200, body = BbsDeriveProofResponse),
+        (status = 400, body = ErrorResponse),
+    )
+)]
+#[post("/zkp/bbs/derive-proof")]
+pub async fn derive_proof(
+    req: web::Json<BbsDeriveProofRequest>,
+    issuer: web::Data<BbsIssuer>,
+    pools: web::Data<WorkPools>,
+) -> impl Responder {
+    pools.prove.run(move || match derive(&req, &issuer) {
+        Ok(response) => {
+            metrics::record_proof_size("bbs", response.proof_hex.len() / 2);
+            HttpResponse::Ok().json(response)
+        }
+        Err(error) => bad_req(&error),
+    })
+    .await
+}
+
+#[utoipa::path(
+    tag = "bbs",
+    request_body = BbsVerifyRequest,
+    responses(
+        (status = 200, body = BbsVerifyResponse),
+        (status = 400, body = ErrorResponse),
+    )
+)]
+#[post("/zkp/bbs/verify")]
+pub async fn verify(
+    req: web::Json<BbsVerifyRequest>,
+    replay: web::Data<ReplayRegistry>,
+    receipts: web::Data<ReceiptSigner>,
+    pools: web::Data<WorkPools>,
+) -> impl Responder {
+    pools.verify.run(move || {
+        let (pk, disclosed, links, proof, context_hash) = match parse_verify(&req) {
+            Ok(parsed) => parsed,
+            Err(error) => return bad_req(&error),
+        };
+
+        let verified = bbs::verify_proof(&pk, req.attribute_count, &disclosed, &links, &proof, &context_hash);
+        let replay = match check_replay(&replay, "bbs", &context_hash, &proof.to_bytes(), verified) {
+            Ok(status) => status,
+            Err(response) => return response,
+        };
+        let receipt = receipts.sign("bbs", &*req, &context_hash, verified);
+        metrics::record_verification("bbs", verified);
+        HttpResponse::Ok().json(BbsVerifyResponse { verified, replay, receipt })
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::{call_service, init_service, read_body_json, TestRequest};
+    use actix_web::App;
+    use bulletproofs::PedersenGens;
+    use serde_json::{json, Value};
+
+    #[actix_web::test]
+    async fn issue_derive_and_verify_with_hidden_price() {
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(BbsIssuer::new(SecretKey::random())))
+                .app_data(web::Data::new(WorkPools::from_env()))
+                .app_data(web::Data::new(ReplayRegistry::from_env()))
+                .app_data(web::Data::new(ReceiptSigner::disabled()))
+                .service(public_key)
+                .service(issue)
+                .service(derive_proof)
+                .service(verify),
+        )
+        .await;
+        let post = |uri: &str, body: &Value| TestRequest::post().uri(uri).set_json(body).to_request();
+
+        let attributes = json!([
+            { "name": "orderId", "value": "order-42" },
+            { "name": "memoHash", "value": "0x" },
+            { "name": "railgunTxRef", "value": "0x" },
+            { "name": "unitPrice", "value": "250", "integer": true },
+        ]);
+        let issued: Value = read_body_json(call_service(&app, post("/zkp/bbs/issue", &json!({ "attributes": attributes }))).await).await;
+
+        let blinding = Scalar::from(7u64);
+        let c_price = PedersenGens::default().commit(Scalar::from(250u64), blinding).compress();
+        let derive_body = json!({
+            "signature_hex": issued["signature_hex"],
+            "attributes": attributes,
+            "disclosed": [0],
+            "links": [{ "index": 3, "commitment_hex": hex::encode(c_price.as_bytes()), "blinding_hex": hex::encode(blinding.as_bytes()) }],
+            "context_hash_hex": "11".repeat(32),
+        });
+        let response = call_service(&app, post("/zkp/bbs/derive-proof", &derive_body)).await;
+        assert_eq!(response.status(), 200);
+        let derived: Value = read_body_json(response).await;
+        assert_eq!(derived["disclosed"][0]["attribute"]["name"], "orderId");
+
+        let mut verify_body = json!({
+            "public_key_hex": issued["public_key_hex"],
+            "attribute_count": derived["attribute_count"],
+            "disclosed": derived["disclosed"],
+            "links": derived["links"],
+            "proof_hex": derived["proof_hex"],
+            "context_hash_hex": "11".repeat(32),
+        });
+        let outcome: Value = read_body_json(call_service(&app, post("/zkp/bbs/verify", &verify_body)).await).await;
+        assert_eq!(outcome["verified"], true);
+        assert_eq!(derived["links"][0]["name"], "unitPrice");
+
+        let mut renamed = verify_body.clone();
+        renamed["links"][0]["name"] = json!("quantity");
+        let outcome: Value = read_body_json(call_service(&app, post("/zkp/bbs/verify", &renamed)).await).await;
+        assert_eq!(outcome["verified"], false);
+
+        verify_body["disclosed"][0]["attribute"]["value"] = json!("order-43");
+        let outcome: Value = read_body_json(call_service(&app, post("/zkp/bbs/verify", &verify_body)).await).await;
+        assert_eq!(outcome["verified"], false);
+
+        let mut wrong_price = derive_body.clone();
+        wrong_price["attributes"][3]["value"] = json!("251");
+        let response = call_service(&app, post("/zkp/bbs/derive-proof", &wrong_price)).await;
+        assert_eq!(response.status(), 400);
+    }
+}
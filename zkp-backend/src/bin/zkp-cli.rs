@@ -0,0 +1,266 @@
+//! Offline command-line front end for the ZKP library.
+//!
+//! Each subcommand reads one request (JSON or CBOR, from a file or stdin) in
+//! the same shape as the matching HTTP endpoint and writes the same response:
+//!   commit                → /zkp/generate-scalar-commitment-with-blinding
+//!   prove-equality        → /zkp/generate-equality-proof
+//!   prove-quantity-total  → /zkp/generate-quantity-total-proof
+//!   verify-vc             → /zkp/verify-vc (a bare VRC is accepted too)
+//!   verify-batch          → one verify call per entry of a JSON/CBOR array
+//!   context-hash          → /zkp/order-context-hash
+//!   solidity-verifier     → contracts/Bn254SigmaVerifier.sol (no input)
+//!
+//! Exit status: 0 on success, 1 if a verify subcommand found an invalid
+//! proof, 2 on malformed input.
+
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use bulletproof_demo::api::{self, ApiError};
+use bulletproof_demo::vc::{parse_order_vrc, verify_order_vrc, VcVerificationReport};
+use bulletproof_demo::zk::bn254_verifier::solidity_library;
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+#[derive(Parser)]
+#[command(name = "zkp-cli", version, about = "Offline proof generation and verification")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+
+    /// Request file; `-` or omitted reads stdin.
+    #[arg(short, long, global = true)]
+    input: Option<PathBuf>,
+
+    /// Response file; omitted writes stdout.
+    #[arg(short, long, global = true)]
+    output: Option<PathBuf>,
+
+    /// Request encoding; defaults to CBOR for `.cbor` files and JSON otherwise.
+    #[arg(long, value_enum, global = true)]
+    format: Option<Format>,
+
+    /// Response encoding.
+    #[arg(long, value_enum, global = true, default_value = "json")]
+    output_format: Format,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Pedersen commitment to a scalar with a given blinding.
+    Commit,
+    /// Schnorr equality proof between two commitments.
+    ProveEquality,
+    /// Proof that C_total commits to unit_price * quantity.
+    ProveQuantityTotal,
+    /// Check every embedded proof of an order VRC.
+    VerifyVc,
+    /// Verify a list of `{ "kind": ..., "request": ... }` entries.
+    VerifyBatch,
+    /// Order context hash as recorded on-chain.
+    ContextHash,
+    /// Solidity library verifying BN254 equality / quantity-total proofs.
+    SolidityVerifier,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Json,
+    Cbor,
+}
+
+/// One `verify-batch` entry; `request` is the body of the matching verify endpoint.
+#[derive(Deserialize)]
+#[serde(tag = "kind", content = "request", rename_all = "kebab-case")]
+enum BatchItem {
+    Equality(api::EqualityVerifyRequest),
+    QuantityTotal(api::QuantityTotalVerifyRequest),
+    TotalPaymentEquality(api::TotalPaymentEqualityVerifyRequest),
+    Vc(serde_json::Value),
+}
+
+#[derive(Serialize)]
+struct BatchResult {
+    index: usize,
+    kind: &'static str,
+    verified: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    report: Option<VcVerificationReport>,
+}
+
+#[derive(Serialize)]
+struct BatchResponse {
+    verified: bool,
+    results: Vec<BatchResult>,
+}
+
+struct Io {
+    input: Option<PathBuf>,
+    output: Option<PathBuf>,
+    format: Format,
+    output_format: Format,
+}
+
+impl Io {
+    fn from_cli(cli: &Cli) -> Self {
+        let input = cli.input.clone().filter(|path| path.as_os_str() != "-");
+        let format = cli.format.unwrap_or_else(|| match &input {
+            Some(path) if path.extension().is_some_and(|ext| ext == "cbor") => Format::Cbor,
+            _ => Format::Json,
+        });
+        Self { input, output: cli.output.clone(), format, output_format: cli.output_format }
+    }
+
+    fn read<T: DeserializeOwned>(&self) -> Result<T, String> {
+        let bytes = match &self.input {
+            Some(path) => fs::read(path).map_err(|e| format!("cannot read {}: {e}", path.display()))?,
+            None => {
+                let mut bytes = Vec::new();
+                io::stdin().read_to_end(&mut bytes).map_err(|e| format!("cannot read stdin: {e}"))?;
+                bytes
+            }
+        };
+        match self.format {
+            Format::Json => serde_json::from_slice(&bytes).map_err(|e| format!("invalid JSON request: {e}")),
+            Format::Cbor => ciborium::de::from_reader(bytes.as_slice()).map_err(|e| format!("invalid CBOR request: {e}")),
+        }
+    }
+
+    fn write<T: Serialize>(&self, value: &T) -> Result<(), String> {
+        let mut bytes = Vec::new();
+        match self.output_format {
+            Format::Json => {
+                serde_json::to_writer_pretty(&mut bytes, value).map_err(|e| e.to_string())?;
+                bytes.push(b'\n');
+            }
+            Format::Cbor => ciborium::ser::into_writer(value, &mut bytes).map_err(|e| e.to_string())?,
+        }
+        self.write_bytes(&bytes)
+    }
+
+    fn write_bytes(&self, bytes: &[u8]) -> Result<(), String> {
+        match &self.output {
+            Some(path) => fs::write(path, bytes).map_err(|e| format!("cannot write {}: {e}", path.display())),
+            None => io::stdout().write_all(bytes).map_err(|e| format!("cannot write stdout: {e}")),
+        }
+    }
+}
+
+/// `/zkp/verify-vc` takes `{ "vc": ... }`; files usually hold the bare credential.
+fn unwrap_vc(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(mut map) if map.contains_key("vc") => map.remove("vc").unwrap_or_default(),
+        other => other,
+    }
+}
+
+fn verify_vc(value: serde_json::Value) -> Result<VcVerificationReport, String> {
+    parse_order_vrc(&unwrap_vc(value)).map(|vrc| verify_order_vrc(&vrc))
+}
+
+fn verify_batch_item(index: usize, item: BatchItem) -> BatchResult {
+    let (kind, outcome): (_, Result<(bool, Option<VcVerificationReport>), String>) = match item {
+        BatchItem::Equality(req) => (
+            "equality",
+            api::verify_equality_proof(&req).map(|v| (v.verified, None)).map_err(|e| e.to_string()),
+        ),
+        BatchItem::QuantityTotal(req) => (
+            "quantity-total",
+            api::verify_quantity_total_proof(&req).map(|v| (v.verified, None)).map_err(|e| e.to_string()),
+        ),
+        BatchItem::TotalPaymentEquality(req) => (
+            "total-payment-equality",
+            api::verify_total_payment_equality_proof(&req).map(|v| (v.verified, None)).map_err(|e| e.to_string()),
+        ),
+        BatchItem::Vc(value) => ("vc", verify_vc(value).map(|report| (report.verified, Some(report)))),
+    };
+    match outcome {
+        Ok((verified, report)) => BatchResult { index, kind, verified, error: None, report },
+        Err(error) => BatchResult { index, kind, verified: false, error: Some(error), report: None },
+    }
+}
+
+enum Failure {
+    NotVerified,
+    Error(String),
+}
+
+impl From<String> for Failure {
+    fn from(error: String) -> Self {
+        Self::Error(error)
+    }
+}
+
+impl From<ApiError> for Failure {
+    fn from(error: ApiError) -> Self {
+        Self::Error(error.to_string())
+    }
+}
+
+fn run(cli: &Cli) -> Result<(), Failure> {
+    let io = Io::from_cli(cli);
+    let verified = match cli.command {
+        Command::Commit => {
+            let req: api::ScalarCommitmentWithBlindingRequest = io.read()?;
+            io.write(&api::scalar_commitment_with_blinding(&req)?)?;
+            true
+        }
+        Command::ProveEquality => {
+            let req: api::EqualityProofRequest = io.read()?;
+            io.write(&api::generate_equality_proof(&req)?)?;
+            true
+        }
+        Command::ProveQuantityTotal => {
+            let req: api::QuantityTotalProofRequest = io.read()?;
+            io.write(&api::generate_quantity_total_proof(&req)?)?;
+            true
+        }
+        Command::ContextHash => {
+            let req: api::OrderContextHashRequest = io.read()?;
+            io.write(&api::order_context_hash(&req)?)?;
+            true
+        }
+        Command::SolidityVerifier => {
+            io.write_bytes(solidity_library().as_bytes())?;
+            true
+        }
+        Command::VerifyVc => {
+            let report = verify_vc(io.read()?)?;
+            io.write(&report)?;
+            report.verified
+        }
+        Command::VerifyBatch => {
+            let items: Vec<BatchItem> = io.read()?;
+            let results: Vec<BatchResult> = items
+                .into_iter()
+                .enumerate()
+                .map(|(index, item)| verify_batch_item(index, item))
+                .collect();
+            let response = BatchResponse { verified: results.iter().all(|r| r.verified), results };
+            io.write(&response)?;
+            response.verified
+        }
+    };
+    if verified {
+        Ok(())
+    } else {
+        Err(Failure::NotVerified)
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(&cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(Failure::NotVerified) => ExitCode::from(1),
+        Err(Failure::Error(error)) => {
+            eprintln!("zkp-cli: {error}");
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,500 @@
+//! gRPC front end for the ZKP library (`proto/zkp/v1/zkp.proto`).
+//!
+//! Serves the same proofs as the HTTP JSON API with raw-bytes messages:
+//!   zkp.v1.CommitmentService     — Pedersen commitments
+//!   zkp.v1.RangeService          — /v2/commitments, /v2/proofs/range/verify
+//!   zkp.v1.EqualityService       — /zkp/generate-equality-proof, /zkp/verify-equality-proof
+//!   zkp.v1.QuantityTotalService  — /zkp/generate-quantity-total-proof, /zkp/verify-quantity-total-proof
+//!   zkp.v1.TxHashService         — /v2/txref, /v2/txref/verify
+//! plus grpc.reflection.v1 and grpc.health.v1.
+//!
+//! Proving and verifying run on tokio's blocking pool. Replay checks and
+//! receipts stay with the HTTP server.
+//!
+//! Access control is the HTTP server's `security::SecurityConfig`, read from
+//! the same variables. An interceptor authenticates each call (`x-api-key` or
+//! `authorization: Bearer <jwt>` metadata) and applies the rate limits; each
+//! RPC then checks its route group, `Verify` methods being verification and
+//! the rest generation. Messages are capped at the larger group body limit.
+//! Health and reflection stay public.
+//!
+//! Configured from the environment:
+//!   ZKP_GRPC_ADDR           — listen address (default 127.0.0.1:50051; use
+//!                             0.0.0.0:50051 to accept remote clients, as the
+//!                             Docker image does)
+//!   ZKP_LOG, ZKP_LOG_FORMAT — logging, as for the HTTP server
+//!   ZKP_SECURITY_CONFIG, ZKP_API_KEYS, ZKP_JWT_SECRET, ZKP_REQUIRE_AUTH,
+//!   ZKP_RATE_LIMIT_PER_IP, ZKP_RATE_LIMIT_PER_KEY, ZKP_MAX_BODY_BYTES
+//!                           — as for the HTTP server
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use bulletproof_demo::api::parse_decimal_scalar_strict;
+use bulletproof_demo::security::{Denied, Principal, RouteGroup, SecurityConfig, SecurityPolicy};
+use bulletproof_demo::telemetry;
+use bulletproof_demo::zk::equality_proof::{prove_equality, verify_equality, EqualityProof};
+use bulletproof_demo::zk::pedersen::{
+    commit_scalar_with_blinding, prove_value_commitment_with_binding, verify_value_commitment_with_binding,
+};
+use bulletproof_demo::zk::quantity_total_proof::{prove_quantity_total, verify_quantity_total};
+use bulletproof_demo::zk::txid_pedersen_proof::{
+    prove_txid_commitment_with_binding, verify_txid_commitment_with_binding,
+};
+use curve25519_dalek_ng::ristretto::CompressedRistretto;
+use curve25519_dalek_ng::scalar::Scalar;
+use rand::RngCore;
+use tonic::service::interceptor::InterceptedService;
+use tonic::transport::Server;
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+pub mod pb {
+    tonic::include_proto!("zkp.v1");
+
+    pub const FILE_DESCRIPTOR_SET: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/zkp_descriptor.bin"));
+}
+
+use pb::commitment_service_server::{CommitmentService, CommitmentServiceServer};
+use pb::equality_service_server::{EqualityService, EqualityServiceServer};
+use pb::quantity_total_service_server::{QuantityTotalService, QuantityTotalServiceServer};
+use pb::range_service_server::{RangeService, RangeServiceServer};
+use pb::tx_hash_service_server::{TxHashService, TxHashServiceServer};
+
+// =============================================================================
+// Field parsing
+// =============================================================================
+
+fn bytes32(field: &str, bytes: &[u8]) -> Result<[u8; 32], Status> {
+    bytes
+        .try_into()
+        .map_err(|_| Status::invalid_argument(format!("{field} must be 32 bytes")))
+}
+
+fn point(field: &str, bytes: &[u8]) -> Result<CompressedRistretto, Status> {
+    bytes32(field, bytes).map(CompressedRistretto)
+}
+
+fn scalar(field: &str, bytes: &[u8]) -> Result<Scalar, Status> {
+    bytes32(field, bytes).map(Scalar::from_bytes_mod_order)
+}
+
+/// Empty means "no binding tag".
+fn binding_tag(bytes: &[u8]) -> Result<Option<[u8; 32]>, Status> {
+    if bytes.is_empty() {
+        Ok(None)
+    } else {
+        bytes32("binding_tag", bytes).map(Some)
+    }
+}
+
+fn unit_price(value: &str) -> Result<Scalar, Status> {
+    parse_decimal_scalar_strict(value).ok_or_else(|| Status::invalid_argument("invalid unit_price_wei"))
+}
+
+fn sigma_proof(r_announcement: &[u8], s_response: &[u8]) -> Result<EqualityProof, Status> {
+    Ok(EqualityProof {
+        r_announcement: bytes32("r_announcement", r_announcement)?,
+        s_response: bytes32("s_response", s_response)?,
+    })
+}
+
+/// Runs CPU-bound proof work off the async executor.
+async fn blocking<T, F>(job: F) -> Result<Response<T>, Status>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, Status> + Send + 'static,
+{
+    tokio::task::spawn_blocking(job)
+        .await
+        .map_err(|_| Status::internal("proof task failed"))?
+        .map(Response::new)
+}
+
+fn verified(verified: bool) -> Result<pb::VerifyResponse, Status> {
+    Ok(pb::VerifyResponse { verified })
+}
+
+// =============================================================================
+// Access control
+// =============================================================================
+
+/// Who the interceptor authenticated, read back by each RPC.
+#[derive(Clone)]
+struct Caller(Option<Principal>);
+
+fn denied_status(denied: Denied) -> Status {
+    match denied {
+        Denied::Unauthorized(reason) => Status::unauthenticated(reason),
+        Denied::Forbidden => Status::permission_denied("credentials do not cover this method"),
+        Denied::TooLarge(limit) => Status::resource_exhausted(format!("message exceeds {limit} bytes")),
+        Denied::RateLimited(retry_after) => {
+            Status::resource_exhausted(format!("rate limit exceeded, retry after {retry_after} s"))
+        }
+    }
+}
+
+/// Authenticates and rate-limits every call. The route group is only known
+/// inside the RPC, which finishes the check with `ZkpServices::authorize`.
+fn interceptor(policy: Arc<SecurityPolicy>) -> impl FnMut(Request<()>) -> Result<Request<()>, Status> + Clone {
+    move |mut request: Request<()>| {
+        let now = Instant::now();
+        // Per-IP first, so failed credential guesses also spend tokens.
+        if let Some(addr) = request.remote_addr() {
+            policy.limit_ip(addr.ip(), now).map_err(denied_status)?;
+        }
+        let metadata = request.metadata();
+        let value_of = |name| metadata.get(name).map(|value| value.to_str().unwrap_or_default());
+        let principal = policy.authenticate(value_of("x-api-key"), value_of("authorization")).map_err(denied_status)?;
+        policy.limit_principal(principal.as_ref(), now).map_err(denied_status)?;
+        request.extensions_mut().insert(Caller(principal));
+        Ok(request)
+    }
+}
+
+// =============================================================================
+// Services
+// =============================================================================
+
+pub struct ZkpServices {
+    policy: Arc<SecurityPolicy>,
+}
+
+impl Default for ZkpServices {
+    fn default() -> Self {
+        Self { policy: Arc::new(SecurityPolicy::new(SecurityConfig::default())) }
+    }
+}
+
+impl ZkpServices {
+    /// Calls that did not pass the interceptor count as anonymous.
+    fn authorize<T>(&self, request: &Request<T>, group: RouteGroup) -> Result<(), Status> {
+        let caller = request.extensions().get::<Caller>().and_then(|caller| caller.0.as_ref());
+        self.policy.authorize(group, caller).map_err(denied_status)
+    }
+}
+
+#[tonic::async_trait]
+impl CommitmentService for ZkpServices {
+    async fn commit(&self, request: Request<pb::CommitRequest>) -> Result<Response<pb::CommitResponse>, Status> {
+        self.authorize(&request, RouteGroup::Generation)?;
+        let req = request.into_inner();
+        let blinding = scalar("blinding", &req.blinding)?;
+        let commitment = commit_scalar_with_blinding(Scalar::from(req.value), blinding);
+        Ok(Response::new(pb::CommitResponse { commitment: commitment.as_bytes().to_vec() }))
+    }
+}
+
+#[tonic::async_trait]
+impl RangeService for ZkpServices {
+    async fn prove(&self, request: Request<pb::RangeProveRequest>) -> Result<Response<pb::CommitmentProof>, Status> {
+        self.authorize(&request, RouteGroup::Generation)?;
+        let req = request.into_inner();
+        let blinding = if req.blinding.is_empty() {
+            let mut bytes = [0u8; 32];
+            rand::rngs::OsRng.fill_bytes(&mut bytes);
+            Scalar::from_bytes_mod_order(bytes)
+        } else {
+            scalar("blinding", &req.blinding)?
+        };
+        let tag = binding_tag(&req.binding_tag)?;
+        blocking(move || {
+            let (commitment, proof, verified) =
+                prove_value_commitment_with_binding(req.value, blinding, tag.as_ref().map(|tag| &tag[..]));
+            Ok(pb::CommitmentProof { commitment: commitment.as_bytes().to_vec(), proof, verified })
+        })
+        .await
+    }
+
+    async fn verify(&self, request: Request<pb::RangeVerifyRequest>) -> Result<Response<pb::VerifyResponse>, Status> {
+        self.authorize(&request, RouteGroup::Verification)?;
+        let req = request.into_inner();
+        let commitment = point("commitment", &req.commitment)?;
+        let tag = binding_tag(&req.binding_tag)?;
+        blocking(move || {
+            verified(verify_value_commitment_with_binding(commitment, req.proof, tag.as_ref().map(|tag| &tag[..])))
+        })
+        .await
+    }
+}
+
+#[tonic::async_trait]
+impl EqualityService for ZkpServices {
+    async fn prove(&self, request: Request<pb::EqualityProveRequest>) -> Result<Response<pb::SigmaProof>, Status> {
+        self.authorize(&request, RouteGroup::Generation)?;
+        let req = request.into_inner();
+        let c_price = point("c_price", &req.c_price)?;
+        let c_pay = point("c_pay", &req.c_pay)?;
+        let r_price = scalar("r_price", &req.r_price)?;
+        let r_pay = scalar("r_pay", &req.r_pay)?;
+        blocking(move || {
+            let proof = prove_equality(c_price, c_pay, r_price, r_pay, &req.context).map_err(Status::invalid_argument)?;
+            Ok(pb::SigmaProof {
+                r_announcement: proof.r_announcement.to_vec(),
+                s_response: proof.s_response.to_vec(),
+                verified: verify_equality(c_price, c_pay, &proof, &req.context),
+            })
+        })
+        .await
+    }
+
+    async fn verify(&self, request: Request<pb::EqualityVerifyRequest>) -> Result<Response<pb::VerifyResponse>, Status> {
+        self.authorize(&request, RouteGroup::Verification)?;
+        let req = request.into_inner();
+        let c_price = point("c_price", &req.c_price)?;
+        let c_pay = point("c_pay", &req.c_pay)?;
+        let proof = sigma_proof(&req.r_announcement, &req.s_response)?;
+        blocking(move || verified(verify_equality(c_price, c_pay, &proof, &req.context))).await
+    }
+}
+
+#[tonic::async_trait]
+impl QuantityTotalService for ZkpServices {
+    async fn prove(&self, request: Request<pb::QuantityTotalProveRequest>) -> Result<Response<pb::SigmaProof>, Status> {
+        self.authorize(&request, RouteGroup::Generation)?;
+        let req = request.into_inner();
+        let c_quantity = point("c_quantity", &req.c_quantity)?;
+        let c_total = point("c_total", &req.c_total)?;
+        let unit_price = unit_price(&req.unit_price_wei)?;
+        let r_quantity = scalar("r_quantity", &req.r_quantity)?;
+        let r_total = scalar("r_total", &req.r_total)?;
+        let context_hash = bytes32("context_hash", &req.context_hash)?;
+        blocking(move || {
+            let proof = prove_quantity_total(c_quantity, c_total, unit_price, r_quantity, r_total, &context_hash)
+                .map_err(Status::invalid_argument)?;
+            Ok(pb::SigmaProof {
+                r_announcement: proof.r_announcement.to_vec(),
+                s_response: proof.s_response.to_vec(),
+                verified: verify_quantity_total(c_quantity, c_total, unit_price, &proof, &context_hash),
+            })
+        })
+        .await
+    }
+
+    async fn verify(
+        &self,
+        request: Request<pb::QuantityTotalVerifyRequest>,
+    ) -> Result<Response<pb::VerifyResponse>, Status> {
+        self.authorize(&request, RouteGroup::Verification)?;
+        let req = request.into_inner();
+        let c_quantity = point("c_quantity", &req.c_quantity)?;
+        let c_total = point("c_total", &req.c_total)?;
+        let unit_price = unit_price(&req.unit_price_wei)?;
+        let context_hash = bytes32("context_hash", &req.context_hash)?;
+        let proof = sigma_proof(&req.r_announcement, &req.s_response)?;
+        blocking(move || verified(verify_quantity_total(c_quantity, c_total, unit_price, &proof, &context_hash))).await
+    }
+}
+
+#[tonic::async_trait]
+impl TxHashService for ZkpServices {
+    async fn commit(&self, request: Request<pb::TxHashCommitRequest>) -> Result<Response<pb::CommitmentProof>, Status> {
+        self.authorize(&request, RouteGroup::Generation)?;
+        let req = request.into_inner();
+        let tx_hash = bytes32("tx_hash", &req.tx_hash)?;
+        let tag = binding_tag(&req.binding_tag)?;
+        blocking(move || {
+            let (commitment, proof, verified) = prove_txid_commitment_with_binding(
+                Scalar::from_bytes_mod_order(tx_hash),
+                tag.as_ref().map(|tag| &tag[..]),
+            );
+            Ok(pb::CommitmentProof { commitment: commitment.as_bytes().to_vec(), proof, verified })
+        })
+        .await
+    }
+
+    async fn verify(&self, request: Request<pb::TxHashVerifyRequest>) -> Result<Response<pb::VerifyResponse>, Status> {
+        self.authorize(&request, RouteGroup::Verification)?;
+        let req = request.into_inner();
+        let commitment = point("commitment", &req.commitment)?;
+        let tag = binding_tag(&req.binding_tag)?;
+        blocking(move || {
+            verified(verify_txid_commitment_with_binding(commitment, req.proof, tag.as_ref().map(|tag| &tag[..])))
+        })
+        .await
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    telemetry::init();
+
+    let addr = std::env::var("ZKP_GRPC_ADDR")
+        .unwrap_or_else(|_| "127.0.0.1:50051".to_string())
+        .parse()?;
+    let policy = Arc::new(SecurityPolicy::new(SecurityConfig::from_env()?));
+    let max_message = policy.config().json_limit();
+    let services = Arc::new(ZkpServices { policy: policy.clone() });
+    let guard = interceptor(policy);
+
+    let (health_reporter, health_service) = tonic_health::server::health_reporter();
+    health_reporter.set_serving::<CommitmentServiceServer<ZkpServices>>().await;
+    health_reporter.set_serving::<RangeServiceServer<ZkpServices>>().await;
+    health_reporter.set_serving::<EqualityServiceServer<ZkpServices>>().await;
+    health_reporter.set_serving::<QuantityTotalServiceServer<ZkpServices>>().await;
+    health_reporter.set_serving::<TxHashServiceServer<ZkpServices>>().await;
+
+    let reflection = tonic_reflection::server::Builder::configure()
+        .register_encoded_file_descriptor_set(pb::FILE_DESCRIPTOR_SET)
+        .register_encoded_file_descriptor_set(tonic_health::pb::FILE_DESCRIPTOR_SET)
+        .build_v1()?;
+
+    info!(%addr, "starting ZKP gRPC server");
+    Server::builder()
+        .add_service(health_service)
+        .add_service(reflection)
+        .add_service(InterceptedService::new(
+            CommitmentServiceServer::from_arc(services.clone()).max_decoding_message_size(max_message),
+            guard.clone(),
+        ))
+        .add_service(InterceptedService::new(
+            RangeServiceServer::from_arc(services.clone()).max_decoding_message_size(max_message),
+            guard.clone(),
+        ))
+        .add_service(InterceptedService::new(
+            EqualityServiceServer::from_arc(services.clone()).max_decoding_message_size(max_message),
+            guard.clone(),
+        ))
+        .add_service(InterceptedService::new(
+            QuantityTotalServiceServer::from_arc(services.clone()).max_decoding_message_size(max_message),
+            guard.clone(),
+        ))
+        .add_service(InterceptedService::new(
+            TxHashServiceServer::from_arc(services).max_decoding_message_size(max_message),
+            guard,
+        ))
+        .serve(addr)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn range_proofs_round_trip_as_raw_bytes() {
+        let services = &ZkpServices::default();
+        let tag = vec![9u8; 32];
+        let proof = RangeService::prove(
+            services,
+            Request::new(pb::RangeProveRequest { value: 1_000, blinding: vec![], binding_tag: tag.clone() }),
+        )
+        .await
+        .unwrap()
+        .into_inner();
+        assert!(proof.verified);
+        assert_eq!(proof.commitment.len(), 32);
+
+        let verify = |binding_tag: Vec<u8>| {
+            RangeService::verify(
+                services,
+                Request::new(pb::RangeVerifyRequest {
+                    commitment: proof.commitment.clone(),
+                    proof: proof.proof.clone(),
+                    binding_tag,
+                }),
+            )
+        };
+        assert!(verify(tag).await.unwrap().into_inner().verified);
+        assert!(!verify(vec![8u8; 32]).await.unwrap().into_inner().verified);
+    }
+
+    #[tokio::test]
+    async fn equality_proofs_match_commitments() {
+        let services = &ZkpServices::default();
+        let commit = |value: u64, blinding: u8| async move {
+            CommitmentService::commit(services, Request::new(pb::CommitRequest { value, blinding: vec![blinding; 32] }))
+                .await
+                .unwrap()
+                .into_inner()
+                .commitment
+        };
+        let (c_price, c_pay) = (commit(50, 1).await, commit(50, 2).await);
+        let context = vec![7u8; 32];
+        let proof = EqualityService::prove(
+            services,
+            Request::new(pb::EqualityProveRequest {
+                c_price: c_price.clone(),
+                c_pay: c_pay.clone(),
+                r_price: vec![1u8; 32],
+                r_pay: vec![2u8; 32],
+                context: context.clone(),
+            }),
+        )
+        .await
+        .unwrap()
+        .into_inner();
+
+        let verify = EqualityService::verify(
+            services,
+            Request::new(pb::EqualityVerifyRequest {
+                c_price,
+                c_pay,
+                r_announcement: proof.r_announcement,
+                s_response: proof.s_response,
+                context,
+            }),
+        )
+        .await
+        .unwrap();
+        assert!(verify.into_inner().verified);
+    }
+
+    /// The request an RPC sees after `passed` went through the interceptor.
+    fn with_caller<T>(passed: Request<()>, message: T) -> Request<T> {
+        let mut request = Request::new(message);
+        *request.extensions_mut() = passed.into_parts().1;
+        request
+    }
+
+    #[tokio::test]
+    async fn calls_are_checked_against_the_http_security_policy() {
+        use bulletproof_demo::security::{ApiKey, GroupPolicy};
+
+        const KEY: &str = "verify-only-key-0123456789";
+        let config = SecurityConfig {
+            api_keys: vec![ApiKey { id: "indexer".into(), key: KEY.into(), groups: vec![RouteGroup::Verification] }],
+            generation: GroupPolicy { require_auth: true, ..GroupPolicy::default() },
+            ..SecurityConfig::default()
+        };
+        let policy = Arc::new(SecurityPolicy::new(config));
+        let services = ZkpServices { policy: policy.clone() };
+        let mut guard = interceptor(policy);
+        let call = |key: Option<&str>| {
+            let mut request = Request::new(());
+            if let Some(key) = key {
+                request.metadata_mut().insert("x-api-key", key.parse().unwrap());
+            }
+            request
+        };
+        let prove_request = pb::RangeProveRequest { value: 1, blinding: vec![], binding_tag: vec![] };
+
+        let anonymous = with_caller(guard(call(None)).unwrap(), prove_request.clone());
+        let status = RangeService::prove(&services, anonymous).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::Unauthenticated);
+
+        let scoped = with_caller(guard(call(Some(KEY))).unwrap(), prove_request);
+        let status = RangeService::prove(&services, scoped).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+
+        let verify_request = pb::RangeVerifyRequest { commitment: vec![0u8; 32], proof: vec![], binding_tag: vec![] };
+        let scoped = with_caller(guard(call(Some(KEY))).unwrap(), verify_request);
+        assert!(RangeService::verify(&services, scoped).await.is_ok());
+
+        assert_eq!(guard(call(Some("wrong-key-0123456789"))).unwrap_err().code(), tonic::Code::Unauthenticated);
+    }
+
+    #[tokio::test]
+    async fn malformed_fields_are_invalid_arguments() {
+        let services = &ZkpServices::default();
+        let status = TxHashService::commit(
+            services,
+            Request::new(pb::TxHashCommitRequest { tx_hash: vec![0u8; 31], binding_tag: vec![] }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        assert_eq!(status.message(), "tx_hash must be 32 bytes");
+    }
+}
@@ -0,0 +1,234 @@
+//! Carbon footprint aggregation endpoints (`zk::carbon_footprint`).
+//!
+//!   POST /zkp/carbon-footprint/prove   — aggregate component commitments into one capped product footprint
+//!   POST /zkp/carbon-footprint/verify  — check the returned artifact
+//!
+//! The artifact is self-contained: component commitments, product commitment,
+//! declared cap, context and proof, so the auditor UI can store and re-verify
+//! it as is. Amounts are integer g CO2e.
+
+use actix_web::{post, web, HttpResponse, Responder};
+use bulletproof_demo::api::{parse_compressed_ristretto, parse_context_hash_hex, parse_scalar_hex, IntegerLike};
+use bulletproof_demo::zk::carbon_footprint::{prove_footprint, verify_footprint, FootprintProof, FootprintStatement};
+use curve25519_dalek_ng::ristretto::CompressedRistretto;
+use curve25519_dalek_ng::scalar::Scalar;
+use rand::rngs::OsRng;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::pool::WorkPools;
+use crate::receipts::{ReceiptSigner, VerificationReceipt};
+use crate::{bad_req, check_replay, metrics, ErrorResponse};
+use crate::replay::{ReplayRegistry, ReplayStatus};
+
+#[derive(Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FootprintProveRequest {
+    /// Per-component CO2e commitments from the component VCs.
+    component_commitments_hex: Vec<String>,
+    /// Opening of the sum of the component commitments.
+    total_value: IntegerLike,
+    total_blinding_hex: String,
+    /// Blinding of the product commitment; random when omitted.
+    #[serde(default)]
+    product_blinding_hex: Option<String>,
+    /// Declared footprint cap.
+    cap: IntegerLike,
+    context_hash_hex: String,
+}
+
+#[derive(Clone, Deserialize, Serialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FootprintArtifact {
+    component_commitments_hex: Vec<String>,
+    product_commitment_hex: String,
+    /// Decimal g CO2e.
+    cap: String,
+    context_hash_hex: String,
+    proof_hex: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct FootprintProveResponse {
+    artifact: FootprintArtifact,
+    /// Opening of `product_commitment_hex` for the seller; never published.
+    product_blinding_hex: String,
+    verified: bool,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FootprintVerifyRequest {
+    artifact: FootprintArtifact,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct FootprintVerifyResponse {
+    verified: bool,
+    component_count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    replay: Option<ReplayStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    receipt: Option<VerificationReceipt>,
+}
+
+fn parse_components(components: &[String]) -> Result<Vec<CompressedRistretto>, String> {
+    components
+        .iter()
+        .map(|hex_str| parse_compressed_ristretto(hex_str).ok_or_else(|| "invalid component_commitments_hex".to_string()))
+        .collect()
+}
+
+fn prove_artifact(req: &FootprintProveRequest) -> Result<(FootprintArtifact, Scalar, bool), String> {
+    let components = parse_components(&req.component_commitments_hex)?;
+    let total_value = req.total_value.parse_u64("total_value")?;
+    let total_blinding = parse_scalar_hex(&req.total_blinding_hex).ok_or("invalid total_blinding_hex")?;
+    let product_blinding = match &req.product_blinding_hex {
+        Some(hex_str) => parse_scalar_hex(hex_str).ok_or("invalid product_blinding_hex")?,
+        None => Scalar::random(&mut OsRng),
+    };
+    let cap = req.cap.parse_u64("cap")?;
+    let context_hash = parse_context_hash_hex(&req.context_hash_hex).ok_or("invalid context_hash_hex")?;
+
+    let (statement, proof) = prove_footprint(&components, total_value, total_blinding, product_blinding, cap, &context_hash)?;
+    let verified = verify_footprint(&statement, &proof, &context_hash);
+    let artifact = FootprintArtifact {
+        component_commitments_hex: components.iter().map(|c| hex::encode(c.as_bytes())).collect(),
+        product_commitment_hex: hex::encode(statement.product.as_bytes()),
+        cap: cap.to_string(),
+        context_hash_hex: hex::encode(context_hash),
+        proof_hex: hex::encode(proof.to_bytes()),
+    };
+    Ok((artifact, product_blinding, verified))
+}
+
+fn parse_artifact(artifact: &FootprintArtifact) -> Result<(FootprintStatement, FootprintProof, [u8; 32]), String> {
+    let statement = FootprintStatement {
+        components: parse_components(&artifact.component_commitments_hex)?,
+        product: parse_compressed_ristretto(&artifact.product_commitment_hex).ok_or("invalid product_commitment_hex")?,
+        cap: artifact.cap.trim().parse::<u64>().map_err(|_| "cap must be a decimal u64")?,
+    };
+    let proof = hex::decode(artifact.proof_hex.trim_start_matches("0x"))
+        .ok()
+        .and_then(|bytes| FootprintProof::from_bytes(&bytes))
+        .ok_or("invalid proof_hex")?;
+    let context_hash = parse_context_hash_hex(&artifact.context_hash_hex).ok_or("invalid context_hash_hex")?;
+    Ok((statement, proof, context_hash))
+}
+
+#[utoipa::path(
+    tag = "carbon-footprint",
+    request_body = FootprintProveRequest,
+    responses(
+        (status = 200, body = FootprintProveResponse),
+        (status = 400, body = ErrorResponse),
+    )
+)]
+#[post("/zkp/carbon-footprint/prove")]
+pub async fn prove(req: web::Json<FootprintProveRequest>, pools: web::Data<WorkPools>) -> impl Responder {
+    pools.prove.run(move || match prove_artifact(&req) {
+        Ok((artifact, product_blinding, verified)) => {
+            metrics::record_proof_size("carbon-footprint", artifact.proof_hex.len() / 2);
+            HttpResponse::Ok().json(FootprintProveResponse {
+                artifact,
+                product_blinding_hex: hex::encode(product_blinding.as_bytes()),
+                verified,
+            })
+        }
+        Err(error) => bad_req(&error),
+    })
+    .await
+}
+
+#[utoipa::path(
+    tag = "carbon-footprint",
+    request_body = FootprintVerifyRequest,
+    responses(
+        (status = 200, body = FootprintVerifyResponse),
+        (status = 400, body = ErrorResponse),
+    )
+)]
+#[post("/zkp/carbon-footprint/verify")]
+pub async fn verify(
+    req: web::Json<FootprintVerifyRequest>,
+    replay: web::Data<ReplayRegistry>,
+    receipts: web::Data<ReceiptSigner>,
+    pools: web::Data<WorkPools>,
+) -> impl Responder {
+    pools.verify.run(move || {
+        let (statement, proof, context_hash) = match parse_artifact(&req.artifact) {
+            Ok(parsed) => parsed,
+            Err(error) => return bad_req(&error),
+        };
+
+        let verified = verify_footprint(&statement, &proof, &context_hash);
+        let replay = match check_replay(&replay, "carbon-footprint", &context_hash, &proof.to_bytes(), verified) {
+            Ok(status) => status,
+            Err(response) => return response,
+        };
+        let receipt = receipts.sign("carbon-footprint", &*req, &context_hash, verified);
+        metrics::record_verification("carbon-footprint", verified);
+        HttpResponse::Ok().json(FootprintVerifyResponse {
+            verified,
+            component_count: statement.components.len(),
+            replay,
+            receipt,
+        })
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::{call_service, init_service, read_body_json, TestRequest};
+    use actix_web::App;
+    use bulletproofs::PedersenGens;
+    use serde_json::{json, Value};
+
+    #[actix_web::test]
+    async fn artifact_round_trip() {
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(WorkPools::from_env()))
+                .app_data(web::Data::new(ReplayRegistry::from_env()))
+                .app_data(web::Data::new(ReceiptSigner::disabled()))
+                .service(prove)
+                .service(verify),
+        )
+        .await;
+        let post = |uri: &str, body: &Value| TestRequest::post().uri(uri).set_json(body).to_request();
+
+        let pc_gens = PedersenGens::default();
+        let parts = [(41_000u64, 3u64), (12_500, 5), (3_200, 11)];
+        let commitments: Vec<String> = parts
+            .iter()
+            .map(|(v, r)| hex::encode(pc_gens.commit(Scalar::from(*v), Scalar::from(*r)).compress().as_bytes()))
+            .collect();
+        let prove_body = json!({
+            "component_commitments_hex": commitments,
+            "total_value": 56_700,
+            "total_blinding_hex": hex::encode(Scalar::from(19u64).as_bytes()),
+            "cap": "60000",
+            "context_hash_hex": "11".repeat(32),
+        });
+        let response = call_service(&app, post("/zkp/carbon-footprint/prove", &prove_body)).await;
+        assert_eq!(response.status(), 200);
+        let proved: Value = read_body_json(response).await;
+        assert_eq!(proved["verified"], true);
+
+        let mut artifact = proved["artifact"].clone();
+        let outcome: Value = read_body_json(call_service(&app, post("/zkp/carbon-footprint/verify", &json!({ "artifact": artifact }))).await).await;
+        assert_eq!(outcome["verified"], true);
+        assert_eq!(outcome["component_count"], 3);
+
+        artifact["cap"] = json!("56000");
+        let outcome: Value = read_body_json(call_service(&app, post("/zkp/carbon-footprint/verify", &json!({ "artifact": artifact }))).await).await;
+        assert_eq!(outcome["verified"], false);
+
+        let mut over_cap = prove_body.clone();
+        over_cap["cap"] = json!(50_000);
+        let response = call_service(&app, post("/zkp/carbon-footprint/prove", &over_cap)).await;
+        assert_eq!(response.status(), 400);
+    }
+}
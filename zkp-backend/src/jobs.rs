@@ -0,0 +1,937 @@
+//! Asynchronous proving jobs.
+//!
+//! `POST /zkp/jobs` accepts a batch of prove requests and returns a job id at
+//! once; `GET /zkp/jobs/{id}` reports progress and per-item results. Each item
+//! is one of the synchronous prove bodies, tagged with its proof kind:
+//!   value-range    — `/v2/commitments`
+//!   txid           — `/v2/txref`
+//!   equality       — `/zkp/generate-equality-proof`
+//!   quantity-total — `/zkp/generate-quantity-total-proof`
+//!   total-payment  — `/zkp/generate-total-payment-equality-proof`
+//!   aggregated-range — one range proof over up to 16 values, checked by `/zkp/mpc/verify`
+//!
+//! Jobs and their items live in SQLite. A single background worker takes the
+//! oldest unfinished item, runs it on the prove pool and stores the result,
+//! so after a restart it simply carries on with whatever is left. An item's
+//! request (which holds witness values) is erased once it has a result.
+//!
+//! With `callback_url` set, the finished job's `JobNotice` is POSTed there,
+//! signed with the receipt key over `CALLBACK_DOMAIN || body`:
+//!   x-zkp-signature      — hex signature
+//!   x-zkp-signature-alg  — "ed25519" | "secp256k1"
+//!   x-zkp-public-key     — same key as `/zkp/receipts/public-key`
+//! Failed deliveries are retried with exponential back-off.
+//!
+//! Callback URLs must be https and resolve only to public addresses; loopback,
+//! private, link-local (cloud metadata) and similar ranges are refused both
+//! when the job is created and again at delivery, so a DNS change cannot
+//! point a stored callback at an internal service.
+//!
+//! Configured from the environment:
+//!   ZKP_JOBS_DB             — SQLite file path; required unless ZKP_DEV_MODE is set,
+//!                             which keeps jobs in memory only
+//!   ZKP_JOB_MAX_ITEMS       — items accepted per job (default 1000)
+//!   ZKP_JOB_CALLBACK_HOSTS  — comma-separated host allowlist for callback URLs;
+//!                             unset allows any public host
+//!   ZKP_DEV_MODE            — also allows http and non-public callback addresses
+
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use actix_web::http::Uri;
+use actix_web::{get, post, web, HttpResponse, Responder};
+use bulletproof_demo::api;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::Notify;
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+
+use crate::mpc::{self, AggregatedRangeRequest};
+use crate::pool::{PoolError, WorkPools};
+use crate::receipts::{ReceiptSigner, SERVICE_VERSION};
+use crate::v2::{self, CommitmentRequest, TxRefRequest};
+use crate::{bad_req, dev_mode, metrics, ErrorResponse};
+
+pub const CALLBACK_DOMAIN: &[u8] = b"zkp-backend/job-callback/v1";
+const DEFAULT_MAX_ITEMS: usize = 1000;
+const MAX_CALLBACK_ATTEMPTS: u32 = 6;
+const CALLBACK_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long the idle worker sleeps before re-checking callbacks due for retry.
+const IDLE_POLL: Duration = Duration::from_secs(5);
+
+/// One prove request inside a job, tagged with its proof kind.
+#[derive(Deserialize, Serialize, ToSchema)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum JobItem {
+    ValueRange(CommitmentRequest),
+    Txid(TxRefRequest),
+    Equality(api::EqualityProofRequest),
+    QuantityTotal(api::QuantityTotalProofRequest),
+    TotalPayment(api::TotalPaymentEqualityProofRequest),
+    AggregatedRange(AggregatedRangeRequest),
+}
+
+impl JobItem {
+    fn kind(&self) -> &'static str {
+        match self {
+            JobItem::ValueRange(_) => "value-range",
+            JobItem::Txid(_) => "txid",
+            JobItem::Equality(_) => "equality",
+            JobItem::QuantityTotal(_) => "quantity-total",
+            JobItem::TotalPayment(_) => "total-payment",
+            JobItem::AggregatedRange(_) => "aggregated-range",
+        }
+    }
+
+    /// Runs the same code as the item's synchronous endpoint.
+    fn run(&self) -> Result<Value, String> {
+        let sigma = |kind: &str, response: Result<api::SigmaProofResponse, api::ApiError>| {
+            let response = response.map_err(|e| e.to_string())?;
+            metrics::record_proof_hex(kind, &[&response.proof_r_hex, &response.proof_s_hex]);
+            serde_json::to_value(response).map_err(|e| e.to_string())
+        };
+        match self {
+            JobItem::ValueRange(req) => {
+                v2::prove_commitment(req).and_then(|r| serde_json::to_value(r).map_err(|e| e.to_string()))
+            }
+            JobItem::Txid(req) => v2::prove_txref(req).and_then(|r| serde_json::to_value(r).map_err(|e| e.to_string())),
+            JobItem::Equality(req) => sigma("equality", api::generate_equality_proof(req)),
+            JobItem::QuantityTotal(req) => sigma("quantity-total", api::generate_quantity_total_proof(req)),
+            JobItem::TotalPayment(req) => sigma("equality", api::generate_total_payment_equality_proof(req)),
+            JobItem::AggregatedRange(req) => {
+                mpc::prove_aggregated(req).and_then(|r| serde_json::to_value(r).map_err(|e| e.to_string()))
+            }
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JobRequest {
+    items: Vec<JobItem>,
+    /// https URL that receives the signed `JobNotice` when the job completes.
+    /// Must resolve to public addresses only (see the module docs).
+    #[serde(default)]
+    callback_url: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum JobState {
+    Queued,
+    Running,
+    Completed,
+}
+
+impl JobState {
+    fn as_str(&self) -> &'static str {
+        match self {
+            JobState::Queued => "queued",
+            JobState::Running => "running",
+            JobState::Completed => "completed",
+        }
+    }
+
+    fn parse(value: &str) -> JobState {
+        match value {
+            "running" => JobState::Running,
+            "completed" => JobState::Completed,
+            _ => JobState::Queued,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct JobAccepted {
+    job_id: String,
+    status: JobState,
+    total: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemState {
+    Pending,
+    Done,
+    Failed,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct JobItemStatus {
+    index: usize,
+    kind: String,
+    status: ItemState,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CallbackStatus {
+    url: String,
+    delivered: bool,
+    attempts: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_error: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct JobStatus {
+    job_id: String,
+    status: JobState,
+    total: usize,
+    completed: usize,
+    failed: usize,
+    /// Unix seconds.
+    created_at: u64,
+    updated_at: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    callback: Option<CallbackStatus>,
+    items: Vec<JobItemStatus>,
+}
+
+/// Body POSTed to a job's `callback_url`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, ToSchema)]
+pub struct JobNotice {
+    pub job_id: String,
+    pub status: JobState,
+    pub total: usize,
+    pub completed: usize,
+    pub failed: usize,
+    /// Unix seconds.
+    pub finished_at: u64,
+    pub service_version: String,
+}
+
+fn now_unix() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn db_error(e: rusqlite::Error) -> String {
+    format!("job store: {e}")
+}
+
+/// Next unfinished item, oldest job first.
+struct PendingItem {
+    job_id: String,
+    index: i64,
+    item: JobItem,
+}
+
+struct PendingCallback {
+    url: String,
+    notice: JobNotice,
+    attempts: u32,
+}
+
+/// Where job callbacks may be delivered.
+#[derive(Clone, Debug, Default)]
+pub struct CallbackPolicy {
+    /// Lower-case host names; `None` allows any public host.
+    allowed_hosts: Option<Vec<String>>,
+    /// Dev mode: plain http and non-public addresses are accepted.
+    allow_insecure: bool,
+}
+
+impl CallbackPolicy {
+    fn from_env() -> Self {
+        let allowed_hosts = std::env::var("ZKP_JOB_CALLBACK_HOSTS").ok().map(|hosts| {
+            hosts.split(',').map(|host| host.trim().to_ascii_lowercase()).filter(|host| !host.is_empty()).collect()
+        });
+        let allow_insecure = dev_mode();
+        if allow_insecure {
+            warn!("ZKP_DEV_MODE set, job callbacks may use http and non-public addresses");
+        }
+        Self { allowed_hosts, allow_insecure }
+    }
+
+    /// Checks the URL's form and resolves its host. Blocks on DNS.
+    fn check(&self, url: &str) -> Result<(), String> {
+        if url.len() > 2048 || url.contains(char::is_whitespace) {
+            return Err("malformed URL".to_string());
+        }
+        let uri: Uri = url.parse().map_err(|_| "malformed URL".to_string())?;
+        let authority = uri.authority().ok_or_else(|| "URL has no host".to_string())?;
+        if authority.as_str().contains('@') {
+            return Err("URL must not carry credentials".to_string());
+        }
+        let port = match uri.scheme_str() {
+            Some("https") => 443,
+            Some("http") if self.allow_insecure => 80,
+            _ => return Err("must be an https URL".to_string()),
+        };
+        let host = authority.host().trim_start_matches('[').trim_end_matches(']').to_ascii_lowercase();
+        if let Some(allowed) = &self.allowed_hosts {
+            if !allowed.contains(&host) {
+                return Err(format!("host {host} is not in ZKP_JOB_CALLBACK_HOSTS"));
+            }
+        }
+        self.resolve(&host, authority.port_u16().unwrap_or(port)).map(drop)
+    }
+
+    /// Resolves a callback host, refusing it if any address is non-public.
+    fn resolve(&self, host: &str, port: u16) -> Result<Vec<SocketAddr>, String> {
+        let addrs: Vec<SocketAddr> = (host, port)
+            .to_socket_addrs()
+            .map_err(|e| format!("cannot resolve {host}: {e}"))?
+            .collect();
+        if addrs.is_empty() {
+            return Err(format!("cannot resolve {host}"));
+        }
+        if !self.allow_insecure {
+            if let Some(addr) = addrs.iter().find(|addr| !is_public(addr.ip())) {
+                return Err(format!("{host} resolves to non-public address {}", addr.ip()));
+            }
+        }
+        Ok(addrs)
+    }
+}
+
+/// False for loopback, private, link-local, shared, documentation, multicast
+/// and unspecified addresses, including IPv4 addresses mapped into IPv6.
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            !(v4.is_private()
+                || v4.is_loopback()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_multicast()
+                || v4.is_documentation()
+                || a == 0
+                || (a == 100 && (64..128).contains(&b))
+                || (a == 198 && (18..20).contains(&b))
+                || a >= 240)
+        }
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => is_public(IpAddr::V4(v4)),
+            None => {
+                let first = v6.segments()[0];
+                !(v6.is_loopback()
+                    || v6.is_unspecified()
+                    || v6.is_multicast()
+                    || (first & 0xfe00) == 0xfc00
+                    || (first & 0xffc0) == 0xfe80
+                    || (first == 0x2001 && v6.segments()[1] == 0x0db8))
+            }
+        },
+    }
+}
+
+pub struct JobStore {
+    conn: Mutex<Connection>,
+    max_items: usize,
+    callbacks: CallbackPolicy,
+    wake: Notify,
+}
+
+impl JobStore {
+    pub fn open(path: &str, max_items: usize) -> rusqlite::Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS jobs (
+                id                 TEXT PRIMARY KEY,
+                state              TEXT NOT NULL,
+                created_at         INTEGER NOT NULL,
+                updated_at         INTEGER NOT NULL,
+                callback_url       TEXT,
+                callback_attempts  INTEGER NOT NULL DEFAULT 0,
+                callback_next_at   INTEGER NOT NULL DEFAULT 0,
+                callback_delivered INTEGER NOT NULL DEFAULT 0,
+                callback_error     TEXT
+            );
+            CREATE TABLE IF NOT EXISTS job_items (
+                job_id  TEXT NOT NULL REFERENCES jobs(id),
+                idx     INTEGER NOT NULL,
+                kind    TEXT NOT NULL,
+                request TEXT,
+                result  TEXT,
+                error   TEXT,
+                done    INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (job_id, idx)
+            );
+            CREATE INDEX IF NOT EXISTS job_items_pending ON job_items (done, job_id, idx);",
+        )?;
+        Ok(Self { conn: Mutex::new(conn), max_items, callbacks: CallbackPolicy::default(), wake: Notify::new() })
+    }
+
+    /// Builds the store from `ZKP_JOBS_DB`, `ZKP_JOB_MAX_ITEMS` and the
+    /// callback settings.
+    pub fn from_env() -> Result<Self, String> {
+        let mut store = Self::open_from_env()?;
+        store.callbacks = CallbackPolicy::from_env();
+        Ok(store)
+    }
+
+    fn open_from_env() -> Result<Self, String> {
+        let max_items = std::env::var("ZKP_JOB_MAX_ITEMS")
+            .ok()
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(DEFAULT_MAX_ITEMS);
+        let path = match std::env::var("ZKP_JOBS_DB").ok().filter(|path| !path.trim().is_empty()) {
+            Some(path) => path,
+            None if dev_mode() => {
+                warn!("ZKP_JOBS_DB unset, jobs are kept in memory and lost on restart");
+                ":memory:".to_string()
+            }
+            None => return Err("ZKP_JOBS_DB must name the job store file".to_string()),
+        };
+        let store = Self::open(&path, max_items).map_err(|e| format!("failed to open job store {path}: {e}"))?;
+        info!(%path, max_items, "job store ready");
+        Ok(store)
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Connection>, String> {
+        self.conn.lock().map_err(|_| "job store lock poisoned".to_string())
+    }
+
+    pub fn create(&self, items: &[JobItem], callback_url: Option<&str>) -> Result<String, String> {
+        let job_id = hex::encode(rand::random::<[u8; 16]>());
+        let now = now_unix() as i64;
+        let mut conn = self.lock()?;
+        let tx = conn.transaction().map_err(db_error)?;
+        tx.execute(
+            "INSERT INTO jobs (id, state, created_at, updated_at, callback_url) VALUES (?1, ?2, ?3, ?3, ?4)",
+            params![job_id, JobState::Queued.as_str(), now, callback_url],
+        )
+        .map_err(db_error)?;
+        for (index, item) in items.iter().enumerate() {
+            let request = serde_json::to_string(item).map_err(|e| e.to_string())?;
+            tx.execute(
+                "INSERT INTO job_items (job_id, idx, kind, request) VALUES (?1, ?2, ?3, ?4)",
+                params![job_id, index as i64, item.kind(), request],
+            )
+            .map_err(db_error)?;
+        }
+        tx.commit().map_err(db_error)?;
+        drop(conn);
+        self.wake.notify_one();
+        Ok(job_id)
+    }
+
+    pub fn status(&self, job_id: &str) -> Result<Option<JobStatus>, String> {
+        let conn = self.lock()?;
+        let job = conn
+            .query_row(
+                "SELECT state, created_at, updated_at, callback_url, callback_attempts, callback_delivered, callback_error
+                 FROM jobs WHERE id = ?1",
+                params![job_id],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, i64>(1)?,
+                        row.get::<_, i64>(2)?,
+                        row.get::<_, Option<String>>(3)?,
+                        row.get::<_, u32>(4)?,
+                        row.get::<_, bool>(5)?,
+                        row.get::<_, Option<String>>(6)?,
+                    ))
+                },
+            )
+            .optional()
+            .map_err(db_error)?;
+        let Some((state, created_at, updated_at, callback_url, attempts, delivered, last_error)) = job else {
+            return Ok(None);
+        };
+
+        let mut statement = conn
+            .prepare("SELECT idx, kind, result, error, done FROM job_items WHERE job_id = ?1 ORDER BY idx")
+            .map_err(db_error)?;
+        let items = statement
+            .query_map(params![job_id], |row| {
+                let result: Option<String> = row.get(2)?;
+                let error: Option<String> = row.get(3)?;
+                let status = match (row.get::<_, bool>(4)?, &error) {
+                    (false, _) => ItemState::Pending,
+                    (true, Some(_)) => ItemState::Failed,
+                    (true, None) => ItemState::Done,
+                };
+                Ok(JobItemStatus {
+                    index: row.get::<_, i64>(0)? as usize,
+                    kind: row.get(1)?,
+                    status,
+                    result: result.and_then(|json| serde_json::from_str(&json).ok()),
+                    error,
+                })
+            })
+            .map_err(db_error)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(db_error)?;
+
+        Ok(Some(JobStatus {
+            job_id: job_id.to_string(),
+            status: JobState::parse(&state),
+            total: items.len(),
+            completed: items.iter().filter(|item| item.status != ItemState::Pending).count(),
+            failed: items.iter().filter(|item| item.status == ItemState::Failed).count(),
+            created_at: created_at as u64,
+            updated_at: updated_at as u64,
+            callback: callback_url.map(|url| CallbackStatus { url, delivered, attempts, last_error }),
+            items,
+        }))
+    }
+
+    fn next_pending(&self) -> Result<Option<PendingItem>, String> {
+        let conn = self.lock()?;
+        let next = conn
+            .query_row(
+                "SELECT i.job_id, i.idx, i.request FROM job_items i JOIN jobs j ON j.id = i.job_id
+                 WHERE i.done = 0 ORDER BY j.created_at, j.rowid, i.idx LIMIT 1",
+                [],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?, row.get::<_, Option<String>>(2)?)),
+            )
+            .optional()
+            .map_err(db_error)?;
+        let Some((job_id, index, request)) = next else {
+            return Ok(None);
+        };
+        match request.as_deref().map(serde_json::from_str::<JobItem>) {
+            Some(Ok(item)) => Ok(Some(PendingItem { job_id, index, item })),
+            _ => {
+                // Unreadable rows are failed rather than retried forever.
+                drop(conn);
+                self.finish_item(&job_id, index, Err("stored request is unreadable".to_string()))?;
+                self.next_pending()
+            }
+        }
+    }
+
+    /// Stores an item's outcome; returns the job's notice if this was its last item.
+    fn finish_item(&self, job_id: &str, index: i64, outcome: Result<Value, String>) -> Result<Option<JobNotice>, String> {
+        let (result, error) = match outcome {
+            Ok(value) => (Some(value.to_string()), None),
+            Err(error) => (None, Some(error)),
+        };
+        let now = now_unix() as i64;
+        let mut conn = self.lock()?;
+        let tx = conn.transaction().map_err(db_error)?;
+        tx.execute(
+            "UPDATE job_items SET result = ?3, error = ?4, done = 1, request = NULL WHERE job_id = ?1 AND idx = ?2",
+            params![job_id, index, result, error],
+        )
+        .map_err(db_error)?;
+        let (total, completed, failed): (usize, usize, usize) = tx
+            .query_row(
+                "SELECT COUNT(*), COALESCE(SUM(done), 0), COUNT(error) FROM job_items WHERE job_id = ?1",
+                params![job_id],
+                |row| Ok((row.get::<_, i64>(0)? as usize, row.get::<_, i64>(1)? as usize, row.get::<_, i64>(2)? as usize)),
+            )
+            .map_err(db_error)?;
+        let state = if completed == total { JobState::Completed } else { JobState::Running };
+        tx.execute(
+            "UPDATE jobs SET state = ?2, updated_at = ?3 WHERE id = ?1",
+            params![job_id, state.as_str(), now],
+        )
+        .map_err(db_error)?;
+        tx.commit().map_err(db_error)?;
+
+        Ok((state == JobState::Completed).then(|| JobNotice {
+            job_id: job_id.to_string(),
+            status: state,
+            total,
+            completed,
+            failed,
+            finished_at: now as u64,
+            service_version: SERVICE_VERSION.to_string(),
+        }))
+    }
+
+    fn due_callbacks(&self) -> Result<Vec<PendingCallback>, String> {
+        let conn = self.lock()?;
+        let mut statement = conn
+            .prepare(
+                "SELECT j.id, j.callback_url, j.callback_attempts, j.updated_at,
+                        COUNT(i.idx), COUNT(i.error)
+                 FROM jobs j JOIN job_items i ON i.job_id = j.id
+                 WHERE j.state = 'completed' AND j.callback_url IS NOT NULL AND j.callback_delivered = 0
+                   AND j.callback_attempts < ?1 AND j.callback_next_at <= ?2
+                 GROUP BY j.id",
+            )
+            .map_err(db_error)?;
+        let rows = statement
+            .query_map(params![MAX_CALLBACK_ATTEMPTS, now_unix() as i64], |row| {
+                let total = row.get::<_, i64>(4)? as usize;
+                Ok(PendingCallback {
+                    url: row.get(1)?,
+                    attempts: row.get(2)?,
+                    notice: JobNotice {
+                        job_id: row.get(0)?,
+                        status: JobState::Completed,
+                        total,
+                        completed: total,
+                        failed: row.get::<_, i64>(5)? as usize,
+                        finished_at: row.get::<_, i64>(3)? as u64,
+                        service_version: SERVICE_VERSION.to_string(),
+                    },
+                })
+            })
+            .map_err(db_error)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(db_error)?;
+        Ok(rows)
+    }
+
+    fn record_callback(&self, job_id: &str, attempts: u32, outcome: Result<(), String>) -> Result<(), String> {
+        // 10 s, 20 s, 40 s, ... between attempts.
+        let next_at = now_unix() + 10 * (1u64 << attempts.min(16));
+        let (delivered, error) = match outcome {
+            Ok(()) => (true, None),
+            Err(error) => (false, Some(error)),
+        };
+        self.lock()?
+            .execute(
+                "UPDATE jobs SET callback_attempts = ?2, callback_next_at = ?3, callback_delivered = ?4, callback_error = ?5
+                 WHERE id = ?1",
+                params![job_id, attempts + 1, next_at as i64, delivered, error],
+            )
+            .map_err(db_error)?;
+        Ok(())
+    }
+}
+
+// =============================================================================
+// Worker
+// =============================================================================
+
+/// Serialized notice plus the headers carrying its signature.
+struct SignedNotice {
+    body: Vec<u8>,
+    headers: Vec<(&'static str, String)>,
+}
+
+fn signed_notice(receipts: &ReceiptSigner, notice: &JobNotice) -> Result<SignedNotice, String> {
+    let body = serde_json::to_vec(notice).map_err(|e| e.to_string())?;
+    let (Some(signature), Some(algorithm), Some(public_key)) = (
+        receipts.sign_message(CALLBACK_DOMAIN, &body),
+        receipts.algorithm(),
+        receipts.public_key_hex(),
+    ) else {
+        return Err("receipt signing key not configured".to_string());
+    };
+    let algorithm = serde_json::to_value(algorithm).ok().and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default();
+    let headers = vec![
+        ("x-zkp-signature", signature),
+        ("x-zkp-signature-alg", algorithm),
+        ("x-zkp-public-key", public_key),
+    ];
+    Ok(SignedNotice { body, headers })
+}
+
+fn post_callback(policy: &CallbackPolicy, url: &str, notice: &SignedNotice) -> Result<(), String> {
+    // Re-checked here and pinned through the resolver, so the connection
+    // goes to the addresses that passed the check.
+    policy.check(url)?;
+    let resolver_policy = policy.clone();
+    let agent = ureq::AgentBuilder::new()
+        .timeout(CALLBACK_TIMEOUT)
+        .redirects(0)
+        .resolver(move |netloc: &str| {
+            let (host, port) = netloc.rsplit_once(':').ok_or_else(|| std::io::Error::other("missing port"))?;
+            let port = port.parse().map_err(std::io::Error::other)?;
+            let host = host.trim_start_matches('[').trim_end_matches(']');
+            resolver_policy.resolve(host, port).map_err(std::io::Error::other)
+        })
+        .build();
+    let mut request = agent.post(url).set("content-type", "application/json");
+    for (name, value) in &notice.headers {
+        request = request.set(name, value);
+    }
+    request.send_bytes(&notice.body).map(|_| ()).map_err(|e| e.to_string())
+}
+
+async fn deliver_callbacks(store: &JobStore, receipts: &web::Data<ReceiptSigner>) {
+    let due = match store.due_callbacks() {
+        Ok(due) => due,
+        Err(e) => {
+            error!(error = %e, "cannot list job callbacks");
+            return;
+        }
+    };
+    for callback in due {
+        let outcome = match signed_notice(receipts, &callback.notice) {
+            Ok(signed) => {
+                let url = callback.url.clone();
+                let policy = store.callbacks.clone();
+                web::block(move || post_callback(&policy, &url, &signed))
+                    .await
+                    .unwrap_or_else(|e| Err(e.to_string()))
+            }
+            Err(e) => Err(e),
+        };
+        match &outcome {
+            Ok(()) => info!(job_id = %callback.notice.job_id, "job callback delivered"),
+            Err(e) => warn!(job_id = %callback.notice.job_id, attempt = callback.attempts + 1, error = %e, "job callback failed"),
+        }
+        if let Err(e) = store.record_callback(&callback.notice.job_id, callback.attempts, outcome) {
+            error!(error = %e, "cannot record job callback");
+        }
+    }
+}
+
+/// Background loop running pending job items and delivering callbacks.
+pub async fn run_worker(store: web::Data<JobStore>, pools: web::Data<WorkPools>, receipts: web::Data<ReceiptSigner>) {
+    loop {
+        deliver_callbacks(&store, &receipts).await;
+        let next = match store.next_pending() {
+            Ok(Some(next)) => next,
+            Ok(None) => {
+                let _ = tokio::time::timeout(IDLE_POLL, store.wake.notified()).await;
+                continue;
+            }
+            Err(e) => {
+                error!(error = %e, "cannot read job queue");
+                tokio::time::sleep(IDLE_POLL).await;
+                continue;
+            }
+        };
+
+        let item = next.item;
+        let outcome = match pools.prove.execute(move || item.run()).await {
+            Ok(outcome) => outcome,
+            Err(PoolError::Saturated) => {
+                // Interactive requests have the pool; try again shortly.
+                tokio::time::sleep(Duration::from_millis(200)).await;
+                continue;
+            }
+            Err(error) => Err(format!("job item {}", error.reason())),
+        };
+        match store.finish_item(&next.job_id, next.index, outcome) {
+            Ok(Some(notice)) => info!(job_id = %notice.job_id, total = notice.total, failed = notice.failed, "job completed"),
+            Ok(None) => {}
+            Err(e) => error!(error = %e, "cannot store job result"),
+        }
+    }
+}
+
+// =============================================================================
+// Endpoints
+// =============================================================================
+
+#[utoipa::path(
+    tag = "jobs",
+    request_body = JobRequest,
+    responses(
+        (status = 202, body = JobAccepted),
+        (status = 400, body = ErrorResponse),
+        (status = 500, body = ErrorResponse),
+    )
+)]
+#[post("/zkp/jobs")]
+pub async fn create_job(
+    req: web::Json<JobRequest>,
+    store: web::Data<JobStore>,
+    receipts: web::Data<ReceiptSigner>,
+) -> impl Responder {
+    if req.items.is_empty() {
+        return bad_req("items must not be empty");
+    }
+    if req.items.len() > store.max_items {
+        return bad_req(&format!("at most {} items per job", store.max_items));
+    }
+    if let Some(url) = &req.callback_url {
+        if receipts.algorithm().is_none() {
+            return bad_req("callback_url requires a receipt signing key (ZKP_RECEIPT_KEY)");
+        }
+        let (policy, url) = (store.callbacks.clone(), url.clone());
+        match web::block(move || policy.check(&url)).await {
+            Ok(Ok(())) => {}
+            Ok(Err(reason)) => return bad_req(&format!("invalid callback_url: {reason}")),
+            Err(e) => return bad_req(&format!("invalid callback_url: {e}")),
+        }
+    }
+
+    match store.create(&req.items, req.callback_url.as_deref()) {
+        Ok(job_id) => {
+            info!(%job_id, items = req.items.len(), callback = req.callback_url.is_some(), "job queued");
+            HttpResponse::Accepted().json(JobAccepted { job_id, status: JobState::Queued, total: req.items.len() })
+        }
+        Err(e) => {
+            error!(error = %e, "cannot create job");
+            HttpResponse::InternalServerError().json(ErrorResponse { error: "cannot create job".to_string() })
+        }
+    }
+}
+
+#[utoipa::path(
+    tag = "jobs",
+    params(("id" = String, Path, description = "Job id returned by POST /zkp/jobs")),
+    responses(
+        (status = 200, body = JobStatus),
+        (status = 404, body = ErrorResponse),
+        (status = 500, body = ErrorResponse),
+    )
+)]
+#[get("/zkp/jobs/{id}")]
+pub async fn job_status(id: web::Path<String>, store: web::Data<JobStore>) -> impl Responder {
+    match store.status(&id) {
+        Ok(Some(status)) => HttpResponse::Ok().json(status),
+        Ok(None) => HttpResponse::NotFound().json(ErrorResponse { error: "unknown job".to_string() }),
+        Err(e) => {
+            error!(error = %e, "cannot read job");
+            HttpResponse::InternalServerError().json(ErrorResponse { error: "cannot read job".to_string() })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::receipts::{verify_signature, ReceiptAlgorithm};
+    use serde_json::json;
+
+    fn items() -> Vec<JobItem> {
+        serde_json::from_value(json!([
+            { "kind": "value-range", "value": 42 },
+            { "kind": "txid", "tx_hash_hex": "ab".repeat(32) },
+            { "kind": "txid", "tx_hash_hex": "not hex" },
+        ]))
+        .unwrap()
+    }
+
+    fn run_all(store: &JobStore) -> Option<JobNotice> {
+        let mut notice = None;
+        while let Some(next) = store.next_pending().unwrap() {
+            notice = store.finish_item(&next.job_id, next.index, next.item.run()).unwrap();
+        }
+        notice
+    }
+
+    #[test]
+    fn items_keep_their_endpoint_fields() {
+        let rejected = serde_json::from_value::<JobItem>(json!({ "kind": "txid", "tx_hash_hex": "00", "extra": 1 }));
+        assert!(rejected.is_err());
+        let unknown = serde_json::from_value::<JobItem>(json!({ "kind": "delivery" }));
+        assert!(unknown.is_err());
+    }
+
+    #[test]
+    fn aggregated_range_items_verify_like_mpc_proofs() {
+        use bulletproof_demo::zk::mpc_range;
+        use curve25519_dalek_ng::ristretto::CompressedRistretto;
+
+        let item: JobItem = serde_json::from_value(json!({
+            "kind": "aggregated-range",
+            "values": [5, "70000"],
+            "bits": 32,
+            "context_hash_hex": "11".repeat(32),
+        }))
+        .unwrap();
+        let result = item.run().unwrap();
+        assert_eq!(result["verified"], true);
+        let commitments: Vec<_> = result["commitments_hex"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| CompressedRistretto(hex::decode(c.as_str().unwrap()).unwrap().try_into().unwrap()))
+            .collect();
+        let proof = hex::decode(result["proof_hex"].as_str().unwrap()).unwrap();
+        assert!(mpc_range::verify(&commitments, &proof, &[0x11; 32], 32));
+
+        let uneven: JobItem = serde_json::from_value(json!({
+            "kind": "aggregated-range",
+            "values": [1, 2, 3],
+            "context_hash_hex": "11".repeat(32),
+        }))
+        .unwrap();
+        assert!(uneven.run().is_err());
+    }
+
+    #[test]
+    fn jobs_record_progress_and_erase_requests() {
+        let store = JobStore::open(":memory:", 10).unwrap();
+        let job_id = store.create(&items(), None).unwrap();
+        assert_eq!(store.status(&job_id).unwrap().unwrap().status, JobState::Queued);
+
+        let notice = run_all(&store).unwrap();
+        assert_eq!((notice.total, notice.completed, notice.failed), (3, 3, 1));
+
+        let status = store.status(&job_id).unwrap().unwrap();
+        assert_eq!(status.status, JobState::Completed);
+        assert_eq!(status.items[0].result.as_ref().unwrap()["verified"], true);
+        assert_eq!(status.items[2].status, ItemState::Failed);
+
+        let stored: i64 = store
+            .lock()
+            .unwrap()
+            .query_row("SELECT COUNT(request) FROM job_items", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(stored, 0);
+        assert!(store.status("missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn unfinished_jobs_resume_after_reopening() {
+        let path = std::env::temp_dir().join(format!("zkp-jobs-{}.sqlite", hex::encode(rand::random::<[u8; 8]>())));
+        let path = path.to_str().unwrap();
+
+        let job_id = {
+            let store = JobStore::open(path, 10).unwrap();
+            let job_id = store.create(&items()[..2], Some("https://example.invalid/hook")).unwrap();
+            let first = store.next_pending().unwrap().unwrap();
+            store.finish_item(&first.job_id, first.index, first.item.run()).unwrap();
+            job_id
+        };
+
+        let store = JobStore::open(path, 10).unwrap();
+        assert_eq!(store.status(&job_id).unwrap().unwrap().status, JobState::Running);
+        let notice = run_all(&store).unwrap();
+        assert_eq!(notice.job_id, job_id);
+
+        let due = store.due_callbacks().unwrap();
+        assert_eq!(due.len(), 1);
+        store.record_callback(&job_id, 0, Err("connection refused".into())).unwrap();
+        assert!(store.due_callbacks().unwrap().is_empty());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn callbacks_only_reach_public_https_hosts() {
+        let policy = CallbackPolicy::default();
+        assert!(policy.check("https://93.184.216.34/hook").is_ok());
+        assert!(policy.check("http://93.184.216.34/hook").is_err());
+        assert!(policy.check("https://127.0.0.1/hook").is_err());
+        assert!(policy.check("https://localhost:8443/hook").is_err());
+        assert!(policy.check("https://169.254.169.254/latest/meta-data").is_err());
+        assert!(policy.check("https://10.0.0.8/hook").is_err());
+        assert!(policy.check("https://[::1]/hook").is_err());
+        assert!(policy.check("https://[::ffff:192.168.1.1]/hook").is_err());
+        assert!(policy.check("https://user:pw@93.184.216.34/hook").is_err());
+
+        let allowlisted = CallbackPolicy { allowed_hosts: Some(vec!["93.184.216.34".into()]), ..policy };
+        assert!(allowlisted.check("https://93.184.216.34/hook").is_ok());
+        assert!(allowlisted.check("https://93.184.216.35/hook").is_err());
+
+        let dev = CallbackPolicy { allow_insecure: true, ..CallbackPolicy::default() };
+        assert!(dev.check("http://127.0.0.1:9000/hook").is_ok());
+    }
+
+    #[test]
+    fn notices_are_signed_with_the_receipt_key() {
+        let notice = JobNotice {
+            job_id: "00".into(),
+            status: JobState::Completed,
+            total: 1,
+            completed: 1,
+            failed: 0,
+            finished_at: 1,
+            service_version: SERVICE_VERSION.into(),
+        };
+        assert!(signed_notice(&ReceiptSigner::disabled(), &notice).is_err());
+
+        let signer = ReceiptSigner::from_secret(ReceiptAlgorithm::Ed25519, &[7u8; 32]).unwrap();
+        let SignedNotice { body, headers } = signed_notice(&signer, &notice).unwrap();
+        assert_eq!(headers[1], ("x-zkp-signature-alg", "ed25519".to_string()));
+        let message = [CALLBACK_DOMAIN, &body].concat();
+        assert_eq!(verify_signature(ReceiptAlgorithm::Ed25519, &headers[2].1, &message, &headers[0].1), Ok(true));
+        assert_eq!(verify_signature(ReceiptAlgorithm::Ed25519, &headers[2].1, &body, &headers[0].1), Ok(false));
+    }
+}
@@ -1,4 +1,7 @@
 //! Library crate exposing ZKP modules for testing
 
 pub mod zk;
-
+pub mod api;
+pub mod vc;
+pub mod security;
+pub mod telemetry;
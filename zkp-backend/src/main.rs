@@ -1,77 +1,135 @@
 //! Actix‑web entry point exposing classic Bulletproof (BP) **and** Bulletproofs‑Plus (BP⁺) endpoints.
 
 use bulletproof_demo::zk;
-
-use actix_cors::Cors;
-use actix_web::{get, post, web, App, HttpResponse, HttpServer, Responder};
+use bulletproof_demo::vc::{parse_order_vrc, verify_order_vrc, VcVerificationReport};
+use bulletproof_demo::api::{
+    self, parse_compressed_ristretto, parse_context_hash_hex, parse_hex32_bytes, parse_scalar_hex,
+    ApiError, IntegerLike,
+};
+
+mod bbs;
+mod carbon_footprint;
+mod jobs;
+mod metrics;
+mod mpc;
+mod note_link;
+mod passport;
+mod pool;
+mod receipts;
+mod recycled_content;
+mod replay;
+mod revocation;
+mod v2;
+
+use actix_web::{get, middleware, post, web, App, HttpResponse, HttpServer, Responder};
 use curve25519_dalek_ng::{scalar::Scalar, ristretto::CompressedRistretto as NgCompressed}; // Dalek‑NG (classic BP)
 use hex::{decode as hex_decode, FromHex, ToHex};
-use num_bigint::BigUint;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use tracing::{debug, error, info};
+use utoipa::{OpenApi, ToSchema};
+use utoipa_scalar::Servable;
 
 // ─── Local circuits ────────────────────────────────────────────────────────
 use zk::bp_plus_pedersen::{prove_txid_commitment as prove_plus, verify_txid_commitment as verify_plus};
 use zk::txid_pedersen_proof::{prove_txid_commitment, prove_txid_commitment_from_hex_with_binding, verify_txid_commitment, verify_txid_commitment_with_binding};
 use bulletproofs::r1cs::ConstraintSystem;
-use zk::pedersen::{commit_scalar_with_blinding, prove_value_commitment, prove_value_commitment_with_blinding, prove_value_commitment_with_binding, verify_value_commitment, verify_value_commitment_with_binding};
-use zk::equality_proof::{prove_equality, verify_equality, EqualityProof};
-use zk::quantity_total_proof::{prove_quantity_total, verify_quantity_total, QuantityTotalProof};
-
-
-fn bad_req(msg: &str) -> HttpResponse {
-    HttpResponse::BadRequest().json(json!({ "error": msg }))
+use zk::pedersen::{commit_scalar_with_blinding, prove_value_commitment, prove_value_commitment_with_blinding, prove_value_commitment_within, verify_value_commitment, verify_value_commitment_within};
+use zk::validity::{windowed_outcome, ValidityWindow, WindowStatus};
+use zk::opening_proof::{prove_opening, verify_opening, OpeningProof};
+use zk::comparison::{prove_greater_or_equal, verify_greater_or_equal};
+use zk::delivery_proof::{prove_delivery, verify_delivery, DeliveryMode, DeliveryProof};
+use receipts::{verify_receipt, ReceiptAlgorithm, ReceiptSigner, VerificationReceipt, SERVICE_VERSION};
+use jobs::JobStore;
+use mpc::MpcSessions;
+use revocation::RevocationRegistry;
+use bbs::BbsIssuer;
+use note_link::NoteLinkKeys;
+use pool::WorkPools;
+use replay::{nullifier, ReplayMode, ReplayRegistry, ReplayStatus};
+use bulletproof_demo::security::{self, SecurityConfig, SecurityPolicy};
+use bulletproof_demo::telemetry;
+
+
+/// Body of every 4xx/5xx response.
+#[derive(Serialize, ToSchema)]
+struct ErrorResponse {
+    pub(crate) error: String,
 }
 
-#[get("/health")]
-async fn health() -> impl Responder {
-    HttpResponse::Ok().json(json!({
-        "ok": true,
-        "service": "zkp-backend",
-    }))
+fn bad_req(msg: &str) -> HttpResponse {
+    debug!(error = msg, "bad request");
+    metrics::record_bad_request(msg);
+    HttpResponse::BadRequest().json(ErrorResponse { error: msg.to_string() })
 }
 
-fn parse_hex32_bytes(hex_str: &str) -> Option<[u8; 32]> {
-    <[u8; 32]>::from_hex(hex_str.trim_start_matches("0x")).ok()
+fn api_error(error: ApiError) -> HttpResponse {
+    match error {
+        ApiError::BadRequest(msg) => bad_req(&msg),
+        ApiError::Internal(msg) => HttpResponse::InternalServerError().json(ErrorResponse { error: msg }),
+    }
 }
 
-fn parse_compressed_ristretto(hex_str: &str) -> Option<curve25519_dalek_ng::ristretto::CompressedRistretto> {
-    parse_hex32_bytes(hex_str).map(curve25519_dalek_ng::ristretto::CompressedRistretto)
+/// `ZKP_DEV_MODE=1|true` relaxes checks that only make sense in production,
+/// such as https-only, public-address job callbacks, persistent job and
+/// revocation stores and configured BBS+ issuer and receipt signing keys.
+pub(crate) fn dev_mode() -> bool {
+    matches!(std::env::var("ZKP_DEV_MODE").as_deref().map(str::trim), Ok("1" | "true"))
 }
 
-fn parse_scalar_hex(hex_str: &str) -> Option<Scalar> {
-    parse_hex32_bytes(hex_str).map(Scalar::from_bytes_mod_order)
+#[derive(Serialize, ToSchema)]
+struct HealthResponse {
+    ok: bool,
+    service: &'static str,
 }
 
-fn parse_context_hash_hex(hex_str: &str) -> Option<[u8; 32]> {
-    parse_hex32_bytes(hex_str)
+#[utoipa::path(tag = "health", responses((status = 200, body = HealthResponse)))]
+#[get("/health")]
+async fn health() -> impl Responder {
+    HttpResponse::Ok().json(HealthResponse {
+        ok: true,
+        service: "zkp-backend",
+    })
 }
 
-fn parse_decimal_scalar_strict(value: &str) -> Option<Scalar> {
-    let trimmed = value.trim();
-    if trimmed.is_empty() {
-        return None;
+/// Records a verified proof in the replay registry.
+///
+/// Failed verifications are never recorded. In "record" mode nothing is
+/// reported back; in "reject" mode a duplicate becomes a 409 response.
+fn check_replay(
+    registry: &ReplayRegistry,
+    kind: &str,
+    context: &[u8],
+    proof: &[u8],
+    verified: bool,
+) -> Result<Option<ReplayStatus>, HttpResponse> {
+    if !verified {
+        return Ok(None);
     }
-
-    let parsed = BigUint::parse_bytes(trimmed.as_bytes(), 10)?;
-    let bytes_le = parsed.to_bytes_le();
-    if bytes_le.len() > 32 {
-        return None;
+    let status = match registry.observe(kind, context, proof) {
+        Ok(Some(status)) => status,
+        Ok(None) => return Ok(None),
+        Err(error) => return Err(HttpResponse::InternalServerError().json(json!({ "error": error }))),
+    };
+    match registry.mode() {
+        ReplayMode::Record => Ok(None),
+        ReplayMode::Reject if status.duplicate => Err(HttpResponse::Conflict().json(json!({
+            "error": "proof already submitted",
+            "replay": status,
+        }))),
+        _ => Ok(Some(status)),
     }
-
-    let mut scalar_bytes = [0u8; 32];
-    scalar_bytes[..bytes_le.len()].copy_from_slice(&bytes_le);
-    Scalar::from_canonical_bytes(scalar_bytes)
 }
 
 // =============================================================================
 // Classic Bulletproof (R1CS) endpoints
 // =============================================================================
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
 struct ZkpRequest { tx_hash: String }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct ProofResponse { commitments: Vec<String>, proof: String }
 impl ProofResponse {
     fn new<T>(coms: &[T], proof: Vec<u8>) -> Self
@@ -107,32 +165,47 @@ impl ProofCommitmentBytes for curve25519_dalek_ng::ristretto::CompressedRistrett
     }
 }
 
-#[derive(Serialize)]
-struct ZkpVerifyResult { verified: bool }
+#[derive(Serialize, ToSchema)]
+struct ZkpVerifyResult {
+    verified: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    replay: Option<ReplayStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    receipt: Option<VerificationReceipt>,
+}
 
+#[utoipa::path(
+    tag = "txid",
+    request_body = ZkpRequest,
+    responses(
+        (status = 200, body = ProofResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 500, body = ErrorResponse),
+    )
+)]
 #[post("/zkp/generate")]
-async fn generate_zkp(tx: web::Json<ZkpRequest>) -> impl Responder {
-    println!("[API] /zkp/generate - Classic BP generation");
-    let bytes = match hex_decode(tx.tx_hash.trim_start_matches("0x")) {
-        Ok(b) if b.len() >= 32 => b,
-        _ => {
-            println!("[API] ❌ Invalid tx_hash format");
-            return HttpResponse::BadRequest().json(json!({"error":"invalid tx_hash"}));
-        },
-    };
-    let mut arr = [0u8; 32];
-    arr.copy_from_slice(&bytes[..32]);
-    let value = Scalar::from_bytes_mod_order(arr);
+async fn generate_zkp(tx: web::Json<ZkpRequest>, pools: web::Data<WorkPools>) -> impl Responder {
+    pools.prove.run(move || {
+        let bytes = match hex_decode(tx.tx_hash.trim_start_matches("0x")) {
+            Ok(b) if b.len() >= 32 => b,
+            _ => return bad_req("invalid tx_hash"),
+        };
+        let mut arr = [0u8; 32];
+        arr.copy_from_slice(&bytes[..32]);
+        let value = Scalar::from_bytes_mod_order(arr);
 
-    println!("[API] Generating proof...");
-    let (commitment, proof_bytes, _) = prove_txid_commitment(value);
-    let _verified = verify_txid_commitment(commitment, proof_bytes.clone());
-    println!("[API] ✅ Proof generated: {} bytes", proof_bytes.len());
+        let (commitment, proof_bytes, _) = prove_txid_commitment(value);
+        let _verified = verify_txid_commitment(commitment, proof_bytes.clone());
+        debug!(proof_len = proof_bytes.len(), "classic BP proof generated");
+        metrics::record_proof_size("txid", proof_bytes.len());
 
-    HttpResponse::Ok().json(ProofResponse::new(&[commitment.to_bytes()], proof_bytes))
+        HttpResponse::Ok().json(ProofResponse::new(&[commitment.to_bytes()], proof_bytes))
+    })
+    .await
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, ToSchema)]
+#[serde(deny_unknown_fields)]
 struct ZkpVerifyInput { 
     commitment: String, 
     proof: String,
@@ -140,87 +213,77 @@ struct ZkpVerifyInput {
     binding_tag_hex: Option<String>, // Feature 2: Optional binding tag for linkable commitments
 }
 
+#[utoipa::path(
+    tag = "txid",
+    request_body = ZkpVerifyInput,
+    responses(
+        (status = 200, body = ZkpVerifyResult),
+        (status = 400, body = ErrorResponse),
+        (status = 409, description = "Replay registry in reject mode and proof already seen"),
+    )
+)]
 #[post("/zkp/verify")]
-async fn verify_zkp(input: web::Json<ZkpVerifyInput>) -> impl Responder {
-    println!("[API] /zkp/verify - Received verification request");
-    
-    let com_bytes: [u8; 32] = match <[u8;32]>::from_hex(&input.commitment) {
-        Ok(b) => {
-            println!("[API] ✅ Commitment parsed: {} bytes", b.len());
-            b
-        },
-        Err(e) => {
-            println!("[API] ❌ Failed to parse commitment: {:?}", e);
-            return HttpResponse::BadRequest().json(json!({"error":"bad commitment"}));
-        },
-    };
-    
-    let proof_bytes = match Vec::from_hex(&input.proof) {
-        Ok(p) => {
-            println!("[API] ✅ Proof parsed: {} bytes", p.len());
-            p
-        },
-        Err(e) => {
-            println!("[API] ❌ Failed to parse proof: {:?}", e);
-            return HttpResponse::BadRequest().json(json!({"error":"bad proof"}));
-        },
-    };
-    
-    // Parse binding tag if provided (Feature 2: Linkable Commitment)
-    let binding_tag = if let Some(ref binding_hex) = input.binding_tag_hex {
-        println!("[API] Binding tag provided for verification: {} (length: {})", binding_hex, binding_hex.len());
-        match hex::decode(binding_hex.strip_prefix("0x").unwrap_or(binding_hex)) {
-            Ok(bytes) if bytes.len() == 32 => {
-                println!("[API] ✅ Binding tag parsed successfully: {} bytes", bytes.len());
-                Some(bytes)
-            },
-            Ok(bytes) => {
-                println!("[API] ❌ Invalid binding tag length: expected 32 bytes, got {}", bytes.len());
-                return HttpResponse::BadRequest().json(json!({
-                    "error": "invalid binding_tag_hex: must be 32 bytes (64 hex chars)"
-                }));
-            },
-            Err(e) => {
-                println!("[API] ❌ Failed to decode binding tag hex: {:?}", e);
-                return HttpResponse::BadRequest().json(json!({
-                    "error": "invalid binding_tag_hex: must be valid hex"
-                }));
-            },
-        }
-    } else {
-        println!("[API] No binding tag provided (backward compatible verification)");
-        None
-    };
-    
-    // Verify with optional binding tag
-    println!("[API] Verifying TX hash commitment{}...", if binding_tag.is_some() { " with binding tag" } else { "" });
-    let ok = verify_txid_commitment_with_binding(
-        NgCompressed(com_bytes), 
-        proof_bytes,
-        binding_tag.as_ref().map(|b| b.as_slice()),
-    );
-    
-    if ok {
-        println!("[API] ✅ Verification SUCCESS");
-    } else {
-        println!("[API] ❌ Verification FAILED");
-    }
-    
-    HttpResponse::Ok().json(ZkpVerifyResult { verified: ok })
+async fn verify_zkp(
+    input: web::Json<ZkpVerifyInput>,
+    replay: web::Data<ReplayRegistry>,
+    receipts: web::Data<ReceiptSigner>,
+    pools: web::Data<WorkPools>,
+) -> impl Responder {
+    pools.verify.run(move || {
+        let com_bytes: [u8; 32] = match <[u8;32]>::from_hex(&input.commitment) {
+            Ok(b) => b,
+            Err(_) => return bad_req("bad commitment"),
+        };
+
+        let proof_bytes = match Vec::from_hex(&input.proof) {
+            Ok(p) => p,
+            Err(_) => return bad_req("bad proof"),
+        };
+
+        // Parse binding tag if provided (Feature 2: Linkable Commitment)
+        let binding_tag = if let Some(ref binding_hex) = input.binding_tag_hex {
+            match hex::decode(binding_hex.strip_prefix("0x").unwrap_or(binding_hex)) {
+                Ok(bytes) if bytes.len() == 32 => Some(bytes),
+                Ok(_) => return bad_req("invalid binding_tag_hex: must be 32 bytes (64 hex chars)"),
+                Err(_) => return bad_req("invalid binding_tag_hex: must be valid hex"),
+            }
+        } else {
+            None
+        };
+
+        // Verify with optional binding tag
+        let ok = verify_txid_commitment_with_binding(
+            NgCompressed(com_bytes), 
+            proof_bytes.clone(),
+            binding_tag.as_deref(),
+        );
+        info!(verified = ok, binding_tag = binding_tag.is_some(), "tx hash commitment verification");
+
+        let replay = match check_replay(&replay, "txid", binding_tag.as_deref().unwrap_or_default(), &proof_bytes, ok) {
+            Ok(status) => status,
+            Err(response) => return response,
+        };
+
+        let receipt = receipts.sign("txid", &*input, binding_tag.as_deref().unwrap_or_default(), ok);
+        metrics::record_verification("txid", ok);
+        HttpResponse::Ok().json(ZkpVerifyResult { verified: ok, replay, receipt })
+    })
+    .await
 }
 
 // =============================================================================
 // Transaction Hash Commitment endpoints (for privacy)
 // =============================================================================
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
 struct TxHashCommitRequest { 
     tx_hash: String,
     #[serde(default)]
     binding_tag_hex: Option<String>, // Feature 2: Optional binding tag for linkable commitments
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct TxHashCommitResponse {
     commitment: String,
     proof: String,
@@ -230,890 +293,1653 @@ struct TxHashCommitResponse {
 /// Generate a Pedersen commitment to a transaction hash
 /// This hides the transaction hash in the VC, making it harder to link to Etherscan
 /// Feature 2: Supports optional binding_tag_hex to link purchase and delivery TX commitments
+#[utoipa::path(
+    tag = "txid",
+    request_body = TxHashCommitRequest,
+    responses(
+        (status = 200, body = TxHashCommitResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 500, body = ErrorResponse),
+    )
+)]
 #[post("/zkp/commit-tx-hash")]
-async fn commit_tx_hash(req: web::Json<TxHashCommitRequest>) -> impl Responder {
-    println!("[API] /zkp/commit-tx-hash - Received request");
-    let tx_hash = req.tx_hash.trim();
-    println!("[API] TX hash: {} (length: {})", tx_hash, tx_hash.len());
-    
-    // Validate hex format
-    let tx_hash_clean = tx_hash.strip_prefix("0x").unwrap_or(tx_hash);
-    if tx_hash_clean.len() != 64 {
-        println!("[API] ❌ Invalid tx_hash format: expected 64 hex chars, got {}", tx_hash_clean.len());
-        return HttpResponse::BadRequest().json(json!({
-            "error": "Invalid tx_hash format. Expected 64 hex characters (32 bytes)"
-        }));
-    }
-    println!("[API] ✅ TX hash format valid");
-    
-    // Parse binding tag if provided (Feature 2: Linkable Commitment)
-    let binding_tag = if let Some(ref binding_hex) = req.binding_tag_hex {
-        println!("[API] Binding tag provided: {} (length: {})", binding_hex, binding_hex.len());
-        match hex::decode(binding_hex.strip_prefix("0x").unwrap_or(binding_hex)) {
-            Ok(bytes) if bytes.len() == 32 => {
-                println!("[API] ✅ Binding tag parsed successfully: {} bytes", bytes.len());
-                Some(bytes)
-            },
-            Ok(bytes) => {
-                println!("[API] ❌ Invalid binding tag length: expected 32 bytes, got {}", bytes.len());
-                return HttpResponse::BadRequest().json(json!({
-                    "error": "invalid binding_tag_hex: must be 32 bytes (64 hex chars)"
-                }));
-            },
-            Err(e) => {
-                println!("[API] ❌ Failed to decode binding tag hex: {:?}", e);
-                return HttpResponse::BadRequest().json(json!({
-                    "error": "invalid binding_tag_hex: must be valid hex"
-                }));
-            },
+async fn commit_tx_hash(req: web::Json<TxHashCommitRequest>, pools: web::Data<WorkPools>) -> impl Responder {
+    pools.prove.run(move || {
+        let tx_hash = req.tx_hash.trim();
+
+        // Validate hex format
+        let tx_hash_clean = tx_hash.strip_prefix("0x").unwrap_or(tx_hash);
+        if tx_hash_clean.len() != 64 {
+            return bad_req("Invalid tx_hash format. Expected 64 hex characters (32 bytes)");
         }
-    } else {
-        println!("[API] No binding tag provided (backward compatible mode)");
-        None
-    };
-    
-    // Generate commitment with optional binding tag
-    println!("[API] Generating TX hash commitment{}...", if binding_tag.is_some() { " with binding tag" } else { "" });
-    let (commitment, proof_bytes, verified) = prove_txid_commitment_from_hex_with_binding(
-        tx_hash,
-        binding_tag.as_ref().map(|b| b.as_slice()),
-    );
-    
-    println!("[API] ✅ Commitment generated: {} bytes, proof: {} bytes, verified: {}", 
-             commitment.as_bytes().len(), proof_bytes.len(), verified);
-    
-    HttpResponse::Ok().json(TxHashCommitResponse {
-        commitment: hex::encode(commitment.as_bytes()),
-        proof: hex::encode(&proof_bytes),
-        verified,
+
+        // Parse binding tag if provided (Feature 2: Linkable Commitment)
+        let binding_tag = if let Some(ref binding_hex) = req.binding_tag_hex {
+            match hex::decode(binding_hex.strip_prefix("0x").unwrap_or(binding_hex)) {
+                Ok(bytes) if bytes.len() == 32 => Some(bytes),
+                Ok(_) => return bad_req("invalid binding_tag_hex: must be 32 bytes (64 hex chars)"),
+                Err(_) => return bad_req("invalid binding_tag_hex: must be valid hex"),
+            }
+        } else {
+            None
+        };
+
+        // Generate commitment with optional binding tag
+        let (commitment, proof_bytes, verified) = prove_txid_commitment_from_hex_with_binding(
+            tx_hash,
+            binding_tag.as_deref(),
+        );
+
+        debug!(proof_len = proof_bytes.len(), verified, binding_tag = binding_tag.is_some(), "tx hash commitment generated");
+        metrics::record_proof_size("txid", proof_bytes.len());
+
+        HttpResponse::Ok().json(TxHashCommitResponse {
+            commitment: hex::encode(commitment.as_bytes()),
+            proof: hex::encode(&proof_bytes),
+            verified,
+        })
     })
+    .await
 }
 
 // =============================================================================
 // Bulletproofs‑Plus endpoints (64‑bit range proof)
 // =============================================================================
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
 struct TxHashPayload { tx_hash: String }
 
+#[utoipa::path(
+    tag = "txid",
+    request_body = TxHashPayload,
+    responses(
+        (status = 200, body = ProofResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 500, body = ErrorResponse),
+    )
+)]
 #[post("/zkp/prove_plus")]
-async fn prove_plus_ep(payload: web::Json<TxHashPayload>) -> impl Responder {
-    println!("[API] /zkp/prove_plus - BP+ proof generation");
-    let bytes = match hex_decode(payload.tx_hash.trim_start_matches("0x")) {
-        Ok(b) => b,
-        Err(_) => {
-            println!("[API] ❌ Invalid hex format");
-            return bad_req("bad hex");
-        },
-    };
-    if bytes.len() < 32 {
-        println!("[API] ❌ TX hash too short: {} bytes", bytes.len());
-        return bad_req("tx_hash too short");
-    }
+async fn prove_plus_ep(payload: web::Json<TxHashPayload>, pools: web::Data<WorkPools>) -> impl Responder {
+    pools.prove.run(move || {
+        let bytes = match hex_decode(payload.tx_hash.trim_start_matches("0x")) {
+            Ok(b) => b,
+            Err(_) => return bad_req("bad hex"),
+        };
+        if bytes.len() < 32 {
+            return bad_req("tx_hash too short");
+        }
 
-    let mut arr = [0u8; 32];
-    arr.copy_from_slice(&bytes[..32]);
-    println!("[API] Generating BP+ proof...");
-    let (coms, proof) = prove_plus(arr);
-    println!("[API] ✅ BP+ proof generated: {} commitments, proof: {} bytes", coms.len(), proof.len());
-    HttpResponse::Ok().json(ProofResponse::new(&coms, proof))
+        let mut arr = [0u8; 32];
+        arr.copy_from_slice(&bytes[..32]);
+        let (coms, proof) = prove_plus(arr);
+        debug!(commitments = coms.len(), proof_len = proof.len(), "BP+ proof generated");
+        metrics::record_proof_size("txid-bp-plus", proof.len());
+        HttpResponse::Ok().json(ProofResponse::new(&coms, proof))
+    })
+    .await
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, ToSchema)]
+#[serde(deny_unknown_fields)]
 struct ProofVerifyPayload {
     commitments: Vec<String>,
     proof: String,
 }
 
+#[utoipa::path(
+    tag = "txid",
+    request_body = ProofVerifyPayload,
+    responses(
+        (status = 200, body = ZkpVerifyResult),
+        (status = 400, body = ErrorResponse),
+    )
+)]
 #[post("/zkp/verify_plus")]
-async fn verify_plus_ep(payload: web::Json<ProofVerifyPayload>) -> impl Responder {
-    println!("[API] /zkp/verify_plus - BP+ verification");
-    use curve25519_dalek::ristretto::CompressedRistretto;
-    let commitments: Result<Vec<_>, _> = payload.commitments.iter()
-        .map(|hex| <[u8;32]>::from_hex(hex).map(CompressedRistretto))
-        .collect();
-    let commitments = match commitments {
-        Ok(c) => {
-            println!("[API] ✅ Parsed {} commitments", c.len());
-            c
-        },
-        Err(_) => {
-            println!("[API] ❌ Failed to parse commitments");
-            return HttpResponse::BadRequest().json(json!({"error":"bad commitments"}));
-        },
-    };
-    let proof = match Vec::from_hex(&payload.proof) {
-        Ok(p) => {
-            println!("[API] ✅ Parsed proof: {} bytes", p.len());
-            p
-        },
-        _ => {
-            println!("[API] ❌ Failed to parse proof");
-            return HttpResponse::BadRequest().json(json!({"error":"bad proof"}));
-        },
-    };
-    println!("[API] Verifying BP+ proof...");
-    let ok = verify_plus(commitments, proof);
-    println!("[API] {} BP+ verification", if ok { "✅" } else { "❌" });
-    HttpResponse::Ok().json(ZkpVerifyResult { verified: ok })
+async fn verify_plus_ep(
+    payload: web::Json<ProofVerifyPayload>,
+    receipts: web::Data<ReceiptSigner>,
+    pools: web::Data<WorkPools>,
+) -> impl Responder {
+    pools.verify.run(move || {
+        use curve25519_dalek::ristretto::CompressedRistretto;
+        let commitments: Result<Vec<_>, _> = payload.commitments.iter()
+            .map(|hex| <[u8;32]>::from_hex(hex).map(CompressedRistretto))
+            .collect();
+        let commitments = match commitments {
+            Ok(c) => c,
+            Err(_) => return bad_req("bad commitments"),
+        };
+        let proof = match Vec::from_hex(&payload.proof) {
+            Ok(p) => p,
+            _ => return bad_req("bad proof"),
+        };
+        let ok = verify_plus(commitments, proof);
+        info!(verified = ok, "BP+ verification");
+        let receipt = receipts.sign("txid-bp-plus", &*payload, &[], ok);
+        metrics::record_verification("txid-bp-plus", ok);
+        HttpResponse::Ok().json(ZkpVerifyResult { verified: ok, replay: None, receipt })
+    })
+    .await
 }
 
+#[utoipa::path(
+    tag = "txid",
+    request_body = ZkpRequest,
+    responses(
+        (status = 200, body = ProofResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 500, body = ErrorResponse),
+    )
+)]
 #[post("/zkp/generate_bp4")]
-async fn generate_bp4(tx: web::Json<ZkpRequest>) -> impl Responder {
-    println!("[API] /zkp/generate_bp4 - 4-limb BP generation");
-    let bytes = match hex_decode(tx.tx_hash.trim_start_matches("0x")) {
-        Ok(b) if b.len() >= 32 => b,
-        _ => {
-            println!("[API] ❌ Invalid tx_hash format");
-            return HttpResponse::BadRequest().json(json!({"error":"invalid tx_hash"}));
-        },
-    };
-    let mut arr = [0u8; 32];
-    arr.copy_from_slice(&bytes[..32]);
-    println!("[API] Generating 4-limb proof...");
-    let (commitments, proof_bytes, verified) = zk::txid_pedersen_proof::prove_txid_commitment_4limb(arr);
-    if !verified {
-        println!("[API] ❌ Proof generation failed");
-        return HttpResponse::InternalServerError().json(json!({"error":"proof failed"}));
-    }
-    println!("[API] ✅ 4-limb proof generated: {} commitments, proof: {} bytes", commitments.len(), proof_bytes.len());
-    HttpResponse::Ok().json(ProofResponse::new(&commitments, proof_bytes))
+async fn generate_bp4(tx: web::Json<ZkpRequest>, pools: web::Data<WorkPools>) -> impl Responder {
+    pools.prove.run(move || {
+        let bytes = match hex_decode(tx.tx_hash.trim_start_matches("0x")) {
+            Ok(b) if b.len() >= 32 => b,
+            _ => return bad_req("invalid tx_hash"),
+        };
+        let mut arr = [0u8; 32];
+        arr.copy_from_slice(&bytes[..32]);
+        let (commitments, proof_bytes, verified) = zk::txid_pedersen_proof::prove_txid_commitment_4limb(arr);
+        if !verified {
+            error!("4-limb proof generation failed");
+            return HttpResponse::InternalServerError().json(json!({"error":"proof failed"}));
+        }
+        debug!(commitments = commitments.len(), proof_len = proof_bytes.len(), "4-limb proof generated");
+        metrics::record_proof_size("txid-bp4", proof_bytes.len());
+        HttpResponse::Ok().json(ProofResponse::new(&commitments, proof_bytes))
+    })
+    .await
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, ToSchema)]
+#[serde(deny_unknown_fields)]
 struct BP4VerifyInput {
     commitments: Vec<String>,
     proof: String,
 }
 
+#[utoipa::path(
+    tag = "txid",
+    request_body = BP4VerifyInput,
+    responses(
+        (status = 200, body = ZkpVerifyResult),
+        (status = 400, body = ErrorResponse),
+    )
+)]
 #[post("/zkp/verify_bp4")]
-async fn verify_bp4(input: web::Json<BP4VerifyInput>) -> impl Responder {
-    println!("[API] /zkp/verify_bp4 - 4-limb BP verification");
-    use curve25519_dalek_ng::ristretto::CompressedRistretto;
-    let commitments: Result<Vec<_>, _> = input.commitments.iter()
-        .map(|hex| <[u8;32]>::from_hex(hex).map(CompressedRistretto))
-        .collect();
-    let commitments = match commitments {
-        Ok(c) => {
-            println!("[API] ✅ Parsed {} commitments", c.len());
-            c
-        },
-        Err(_) => {
-            println!("[API] ❌ Failed to parse commitments");
-            return HttpResponse::BadRequest().json(json!({"error":"bad commitments"}));
-        },
-    };
-    let proof = match Vec::from_hex(&input.proof) {
-        Ok(p) => {
-            println!("[API] ✅ Parsed proof: {} bytes", p.len());
-            p
-        },
-        _ => {
-            println!("[API] ❌ Failed to parse proof");
-            return HttpResponse::BadRequest().json(json!({"error":"bad proof"}));
-        },
-    };
-    println!("[API] Verifying 4-limb proof...");
-    // Use the same verification logic as in the prover
-    let pc_gens = bulletproofs::PedersenGens::default();
-    let bp_gens = bulletproofs::BulletproofGens::new(64, 4);
-    let mut transcript = merlin::Transcript::new(b"TxIDPedersenZKP4Limb");
-    let mut verifier = bulletproofs::r1cs::Verifier::new(&mut transcript);
-    for com in &commitments {
-        let var = verifier.commit(*com);
-        verifier.constrain(var - var); // always zero, just to keep structure
-    }
-    let proof_obj = match bulletproofs::r1cs::R1CSProof::from_bytes(&proof) {
-        Ok(p) => p,
-        Err(_) => {
-            println!("[API] ❌ Malformed proof");
-            return HttpResponse::BadRequest().json(json!({"error":"malformed proof"}));
-        },
-    };
-    let ok = verifier.verify(&proof_obj, &pc_gens, &bp_gens).is_ok();
-    println!("[API] {} 4-limb verification", if ok { "✅" } else { "❌" });
-    HttpResponse::Ok().json(ZkpVerifyResult { verified: ok })
+async fn verify_bp4(
+    input: web::Json<BP4VerifyInput>,
+    receipts: web::Data<ReceiptSigner>,
+    pools: web::Data<WorkPools>,
+) -> impl Responder {
+    pools.verify.run(move || {
+        use curve25519_dalek_ng::ristretto::CompressedRistretto;
+        let commitments: Result<Vec<_>, _> = input.commitments.iter()
+            .map(|hex| <[u8;32]>::from_hex(hex).map(CompressedRistretto))
+            .collect();
+        let commitments = match commitments {
+            Ok(c) => c,
+            Err(_) => return bad_req("bad commitments"),
+        };
+        let proof = match Vec::from_hex(&input.proof) {
+            Ok(p) => p,
+            _ => return bad_req("bad proof"),
+        };
+        // Use the same verification logic as in the prover
+        let pc_gens = bulletproofs::PedersenGens::default();
+        let bp_gens = bulletproofs::BulletproofGens::new(64, 4);
+        let mut transcript = merlin::Transcript::new(b"TxIDPedersenZKP4Limb");
+        let mut verifier = bulletproofs::r1cs::Verifier::new(&mut transcript);
+        for com in &commitments {
+            let var = verifier.commit(*com);
+            #[allow(clippy::eq_op)]
+            verifier.constrain(var - var); // always zero, just to keep structure
+        }
+        let proof_obj = match bulletproofs::r1cs::R1CSProof::from_bytes(&proof) {
+            Ok(p) => p,
+            Err(_) => return bad_req("malformed proof"),
+        };
+        let ok = verifier.verify(&proof_obj, &pc_gens, &bp_gens).is_ok();
+        info!(verified = ok, "4-limb verification");
+        let receipt = receipts.sign("txid-bp4", &*input, &[], ok);
+        metrics::record_verification("txid-bp4", ok);
+        HttpResponse::Ok().json(ZkpVerifyResult { verified: ok, replay: None, receipt })
+    })
+    .await
 }
 
 // =============================================================================
 // Server bootstrap
 // =============================================================================
 
-#[derive(Deserialize)]
-#[serde(untagged)]
-enum IntegerLike {
-    U64(u64),
-    DecimalString(String),
-}
-
-impl IntegerLike {
-    fn parse_u64(&self, field_name: &str) -> Result<u64, HttpResponse> {
-        match self {
-            Self::U64(value) => Ok(*value),
-            Self::DecimalString(value) => value
-                .trim()
-                .parse::<u64>()
-                .map_err(|_| bad_req(&format!("{field_name} must be a decimal u64 string"))),
-        }
-    }
-
-    fn parse_scalar(&self, field_name: &str) -> Result<Scalar, HttpResponse> {
-        let scalar = match self {
-            Self::U64(value) => Scalar::from(*value),
-            Self::DecimalString(value) => parse_decimal_scalar_strict(value).ok_or_else(|| {
-                bad_req(&format!(
-                    "{field_name} must be a canonical non-negative decimal scalar below the curve order"
-                ))
-            })?,
-        };
-        Ok(scalar)
-    }
-}
-
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
 struct ValueCommitRequest { value: IntegerLike }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct ValueCommitResponse { commitment: String, proof: String }
 
+#[utoipa::path(
+    tag = "value",
+    request_body = ValueCommitRequest,
+    responses(
+        (status = 200, body = ValueCommitResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 500, body = ErrorResponse),
+    )
+)]
 #[post("/zkp/commit-value")]
-async fn commit_value(req: web::Json<ValueCommitRequest>) -> impl Responder {
-    let value = match req.value.parse_u64("value") {
-        Ok(value) => value,
-        Err(response) => return response,
-    };
-    println!("[API] /zkp/commit-value - Value commitment (value: {})", value);
-    let (commitment, proof_bytes, _verified) = prove_value_commitment(value);
-    let commitment_hex = hex::encode(commitment.as_bytes());
-    let proof_len = proof_bytes.len();
-    let proof_hex = hex::encode(&proof_bytes);
-    println!("[API] ✅ Value commitment generated: proof {} bytes", proof_len);
-    HttpResponse::Ok().json(ValueCommitResponse {
-        commitment: commitment_hex,
-        proof: proof_hex,
+async fn commit_value(req: web::Json<ValueCommitRequest>, pools: web::Data<WorkPools>) -> impl Responder {
+    pools.prove.run(move || {
+        let value = match req.value.parse_u64("value") {
+            Ok(value) => value,
+            Err(error) => return bad_req(&error),
+        };
+        let (commitment, proof_bytes, _verified) = prove_value_commitment(value);
+        let commitment_hex = hex::encode(commitment.as_bytes());
+        let proof_len = proof_bytes.len();
+        let proof_hex = hex::encode(&proof_bytes);
+        debug!(proof_len, "value commitment generated");
+        metrics::record_proof_size("value-range", proof_len);
+        HttpResponse::Ok().json(ValueCommitResponse {
+            commitment: commitment_hex,
+            proof: proof_hex,
+        })
     })
+    .await
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, ToSchema)]
+#[serde(deny_unknown_fields)]
 struct ValueVerifyRequest { commitment: String, proof: String }
 
-#[derive(Serialize)]
-struct ValueVerifyResponse { verified: bool }
+#[derive(Serialize, ToSchema)]
+struct ValueVerifyResponse {
+    verified: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    receipt: Option<VerificationReceipt>,
+}
 
+#[utoipa::path(
+    tag = "value",
+    request_body = ValueVerifyRequest,
+    responses(
+        (status = 200, body = ValueVerifyResponse),
+        (status = 400, body = ErrorResponse),
+    )
+)]
 #[post("/zkp/verify-value")]
-async fn verify_value(req: web::Json<ValueVerifyRequest>) -> impl Responder {
-    println!("[API] /zkp/verify-value - Value commitment verification");
-    let com_bytes = match <[u8;32]>::from_hex(&req.commitment) {
-        Ok(b) => b,
-        Err(_) => {
-            println!("[API] ❌ Failed to parse commitment");
-            return HttpResponse::BadRequest().json(json!({"error":"bad commitment"}));
-        },
-    };
-    let proof_bytes = match Vec::from_hex(&req.proof) {
-        Ok(p) => p,
-        Err(_) => {
-            println!("[API] ❌ Failed to parse proof");
-            return HttpResponse::BadRequest().json(json!({"error":"bad proof"}));
-        },
-    };
-    println!("[API] Verifying value commitment...");
-    let verified = verify_value_commitment(curve25519_dalek_ng::ristretto::CompressedRistretto(com_bytes), proof_bytes);
-    println!("[API] {} Value verification", if verified { "✅" } else { "❌" });
-    HttpResponse::Ok().json(ValueVerifyResponse { verified })
+async fn verify_value(
+    req: web::Json<ValueVerifyRequest>,
+    receipts: web::Data<ReceiptSigner>,
+    pools: web::Data<WorkPools>,
+) -> impl Responder {
+    pools.verify.run(move || {
+        let com_bytes = match <[u8;32]>::from_hex(&req.commitment) {
+            Ok(b) => b,
+            Err(_) => return bad_req("bad commitment"),
+        };
+        let proof_bytes = match Vec::from_hex(&req.proof) {
+            Ok(p) => p,
+            Err(_) => return bad_req("bad proof"),
+        };
+        let verified = verify_value_commitment(curve25519_dalek_ng::ristretto::CompressedRistretto(com_bytes), proof_bytes);
+        info!(verified, "value verification");
+        let receipt = receipts.sign("value", &*req, &[], verified);
+        metrics::record_verification("value-range", verified);
+        HttpResponse::Ok().json(ValueVerifyResponse { verified, receipt })
+    })
+    .await
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
 struct ValueCommitmentRequest {
     value: IntegerLike,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct ValueCommitmentResponse {
     commitment: String, // hex-encoded
     proof: String,      // hex-encoded
     verified: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    validity: Option<ValidityWindow>,
 }
 
+#[utoipa::path(
+    tag = "value",
+    request_body = ValueCommitmentRequest,
+    responses(
+        (status = 200, body = ValueCommitmentResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 500, body = ErrorResponse),
+    )
+)]
 #[post("/zkp/generate-value-commitment")]
-async fn generate_value_commitment(req: web::Json<ValueCommitmentRequest>) -> impl Responder {
-    let value = match req.value.parse_u64("value") {
-        Ok(value) => value,
-        Err(response) => return response,
-    };
-    println!("[API] /zkp/generate-value-commitment - Value: {}", value);
-    let (commitment, proof_bytes, verified) = prove_value_commitment(value);
-    let proof_len = proof_bytes.len();
-    println!("[API] ✅ Generated: proof {} bytes, verified: {}", proof_len, verified);
-    HttpResponse::Ok().json(ValueCommitmentResponse {
-        commitment: hex::encode(commitment.as_bytes()),
-        proof: hex::encode(proof_bytes),
-        verified,
+async fn generate_value_commitment(req: web::Json<ValueCommitmentRequest>, pools: web::Data<WorkPools>) -> impl Responder {
+    pools.prove.run(move || {
+        let value = match req.value.parse_u64("value") {
+            Ok(value) => value,
+            Err(error) => return bad_req(&error),
+        };
+        let (commitment, proof_bytes, verified) = prove_value_commitment(value);
+        let proof_len = proof_bytes.len();
+        debug!(proof_len, verified, "value commitment generated");
+        metrics::record_proof_size("value-range", proof_len);
+        HttpResponse::Ok().json(ValueCommitmentResponse {
+            commitment: hex::encode(commitment.as_bytes()),
+            proof: hex::encode(proof_bytes),
+            verified,
+            validity: None,
+        })
     })
+    .await
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
 struct ValueCommitmentWithBlindingRequest {
     value: IntegerLike,
     blinding_hex: String, // 32-byte hex string (64 hex chars)
 }
 
-#[derive(Serialize)]
-struct ScalarCommitmentResponse {
-    commitment: String,
-    proof: Option<String>,
-    verified: bool,
-    proof_type: String,
-}
-
+#[utoipa::path(
+    tag = "value",
+    request_body = ValueCommitmentWithBlindingRequest,
+    responses(
+        (status = 200, body = ValueCommitmentResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 500, body = ErrorResponse),
+    )
+)]
 #[post("/zkp/generate-value-commitment-with-blinding")]
-async fn generate_value_commitment_with_blinding_ep(req: web::Json<ValueCommitmentWithBlindingRequest>) -> impl Responder {
-    let value = match req.value.parse_u64("value") {
-        Ok(value) => value,
-        Err(response) => return response,
-    };
-    println!("[API] /zkp/generate-value-commitment-with-blinding - Value: {}", value);
-    // Parse blinding factor from hex string
-    let blinding_bytes = match hex_decode(req.blinding_hex.trim_start_matches("0x")) {
-        Ok(b) if b.len() == 32 => {
-            let mut arr = [0u8; 32];
-            arr.copy_from_slice(&b[..32]);
-            arr
-        },
-        _ => {
-            println!("[API] ❌ Invalid blinding_hex format");
-            return HttpResponse::BadRequest().json(json!({ "error": "invalid blinding_hex: must be 32 bytes (64 hex chars)" }));
-        },
-    };
-    
-    let blinding = Scalar::from_bytes_mod_order(blinding_bytes);
-    println!("[API] ✅ Blinding factor parsed");
-    
-    let (commitment, proof_bytes, verified) = prove_value_commitment_with_blinding(value, blinding);
-    let proof_len = proof_bytes.len();
-    println!("[API] ✅ Generated with blinding: proof {} bytes, verified: {}", proof_len, verified);
-    HttpResponse::Ok().json(ValueCommitmentResponse {
-        commitment: hex::encode(commitment.as_bytes()),
-        proof: hex::encode(proof_bytes),
-        verified,
+async fn generate_value_commitment_with_blinding_ep(req: web::Json<ValueCommitmentWithBlindingRequest>, pools: web::Data<WorkPools>) -> impl Responder {
+    pools.prove.run(move || {
+        let value = match req.value.parse_u64("value") {
+            Ok(value) => value,
+            Err(error) => return bad_req(&error),
+        };
+        // Parse blinding factor from hex string
+        let blinding_bytes = match hex_decode(req.blinding_hex.trim_start_matches("0x")) {
+            Ok(b) if b.len() == 32 => {
+                let mut arr = [0u8; 32];
+                arr.copy_from_slice(&b[..32]);
+                arr
+            },
+            _ => return bad_req("invalid blinding_hex: must be 32 bytes (64 hex chars)"),
+        };
+
+        let blinding = Scalar::from_bytes_mod_order(blinding_bytes);
+
+        let (commitment, proof_bytes, verified) = prove_value_commitment_with_blinding(value, blinding);
+        let proof_len = proof_bytes.len();
+        debug!(proof_len, verified, "value commitment with blinding generated");
+        metrics::record_proof_size("value-range", proof_len);
+        HttpResponse::Ok().json(ValueCommitmentResponse {
+            commitment: hex::encode(commitment.as_bytes()),
+            proof: hex::encode(proof_bytes),
+            verified,
+            validity: None,
+        })
     })
+    .await
 }
 
+#[utoipa::path(
+    tag = "value",
+    request_body = api::ScalarCommitmentWithBlindingRequest,
+    responses(
+        (status = 200, body = api::ScalarCommitmentResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 500, body = ErrorResponse),
+    )
+)]
 #[post("/zkp/generate-scalar-commitment-with-blinding")]
 async fn generate_scalar_commitment_with_blinding_ep(
-    req: web::Json<ValueCommitmentWithBlindingRequest>
+    req: web::Json<api::ScalarCommitmentWithBlindingRequest>,
+    pools: web::Data<WorkPools>,
 ) -> impl Responder {
-    let value = match req.value.parse_scalar("value") {
-        Ok(value) => value,
-        Err(response) => return response,
-    };
-    println!("[API] /zkp/generate-scalar-commitment-with-blinding");
-
-    let blinding_bytes = match hex_decode(req.blinding_hex.trim_start_matches("0x")) {
-        Ok(b) if b.len() == 32 => {
-            let mut arr = [0u8; 32];
-            arr.copy_from_slice(&b[..32]);
-            arr
-        },
-        _ => {
-            println!("[API] Invalid blinding_hex format");
-            return HttpResponse::BadRequest().json(json!({ "error": "invalid blinding_hex: must be 32 bytes (64 hex chars)" }));
-        },
-    };
-
-    let blinding = Scalar::from_bytes_mod_order(blinding_bytes);
-    let commitment = commit_scalar_with_blinding(value, blinding);
-
-    HttpResponse::Ok().json(ScalarCommitmentResponse {
-        commitment: hex::encode(commitment.as_bytes()),
-        proof: None,
-        verified: true,
-        proof_type: "pedersen-scalar-v2".to_string(),
+    pools.prove.run(move || {
+        match api::scalar_commitment_with_blinding(&req) {
+            Ok(response) => {
+                if let (Some(r), Some(s_value), Some(s_blinding)) =
+                    (&response.proof_r_hex, &response.proof_s_value_hex, &response.proof_s_blinding_hex)
+                {
+                    metrics::record_proof_hex("opening", &[r, s_value, s_blinding]);
+                }
+                HttpResponse::Ok().json(response)
+            }
+            Err(error) => api_error(error),
+        }
     })
+    .await
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
 struct ValueCommitmentWithBindingRequest {
     value: IntegerLike,
     blinding_hex: String, // 32-byte hex string (64 hex chars)
     binding_tag_hex: Option<String>, // Optional 32-byte hex string (64 hex chars)
+    validity: Option<ValidityWindow>, // Optional window bound into the proof
 }
 
+#[utoipa::path(
+    tag = "value",
+    request_body = ValueCommitmentWithBindingRequest,
+    responses(
+        (status = 200, body = ValueCommitmentResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 500, body = ErrorResponse),
+    )
+)]
 #[post("/zkp/generate-value-commitment-with-binding")]
-async fn generate_value_commitment_with_binding_ep(req: web::Json<ValueCommitmentWithBindingRequest>) -> impl Responder {
-    let value = match req.value.parse_u64("value") {
-        Ok(value) => value,
-        Err(response) => return response,
-    };
-    println!("[API] /zkp/generate-value-commitment-with-binding - Value: {}", value);
-    // Parse blinding factor from hex string
-    let blinding_bytes = match hex_decode(req.blinding_hex.trim_start_matches("0x")) {
-        Ok(b) if b.len() == 32 => {
-            let mut arr = [0u8; 32];
-            arr.copy_from_slice(&b[..32]);
-            arr
-        },
-        _ => {
-            println!("[API] ❌ Invalid blinding_hex format");
-            return HttpResponse::BadRequest().json(json!({ "error": "invalid blinding_hex: must be 32 bytes (64 hex chars)" }));
-        },
-    };
-    
-    let blinding = Scalar::from_bytes_mod_order(blinding_bytes);
-    println!("[API] ✅ Blinding factor parsed");
-    
-    // Parse binding tag if provided
-    let binding_tag = if let Some(ref binding_hex) = req.binding_tag_hex {
-        println!("[API] Binding tag provided");
-        match hex_decode(binding_hex.trim_start_matches("0x")) {
+async fn generate_value_commitment_with_binding_ep(req: web::Json<ValueCommitmentWithBindingRequest>, pools: web::Data<WorkPools>) -> impl Responder {
+    pools.prove.run(move || {
+        let value = match req.value.parse_u64("value") {
+            Ok(value) => value,
+            Err(error) => return bad_req(&error),
+        };
+        // Parse blinding factor from hex string
+        let blinding_bytes = match hex_decode(req.blinding_hex.trim_start_matches("0x")) {
             Ok(b) if b.len() == 32 => {
-                println!("[API] ✅ Binding tag parsed: {} bytes", b.len());
-                Some(b)
-            },
-            _ => {
-                println!("[API] ❌ Invalid binding_tag_hex format");
-                return HttpResponse::BadRequest().json(json!({ "error": "invalid binding_tag_hex: must be 32 bytes (64 hex chars)" }));
+                let mut arr = [0u8; 32];
+                arr.copy_from_slice(&b[..32]);
+                arr
             },
+            _ => return bad_req("invalid blinding_hex: must be 32 bytes (64 hex chars)"),
+        };
+
+        let blinding = Scalar::from_bytes_mod_order(blinding_bytes);
+
+        // Parse binding tag if provided
+        let binding_tag = if let Some(ref binding_hex) = req.binding_tag_hex {
+            match hex_decode(binding_hex.trim_start_matches("0x")) {
+                Ok(b) if b.len() == 32 => Some(b),
+                _ => return bad_req("invalid binding_tag_hex: must be 32 bytes (64 hex chars)"),
+            }
+        } else {
+            None
+        };
+
+        if let Err(error) = api::check_validity_window(&req.validity) {
+            return api_error(error);
         }
-    } else {
-        println!("[API] No binding tag provided");
-        None
-    };
-    
-    let (commitment, proof_bytes, verified) = prove_value_commitment_with_binding(
-        value,
-        blinding,
-        binding_tag.as_ref().map(|b| b.as_slice()),
-    );
-    let proof_len = proof_bytes.len();
-    println!("[API] ✅ Generated with binding: proof {} bytes, verified: {}", proof_len, verified);
-    HttpResponse::Ok().json(ValueCommitmentResponse {
-        commitment: hex::encode(commitment.as_bytes()),
-        proof: hex::encode(proof_bytes),
-        verified,
+
+        let (commitment, proof_bytes, verified) = prove_value_commitment_within(
+            value,
+            blinding,
+            binding_tag.as_deref(),
+            req.validity.as_ref(),
+        );
+        let proof_len = proof_bytes.len();
+        debug!(proof_len, verified, binding_tag = binding_tag.is_some(), "value commitment with binding generated");
+        metrics::record_proof_size("value-range", proof_len);
+        HttpResponse::Ok().json(ValueCommitmentResponse {
+            commitment: hex::encode(commitment.as_bytes()),
+            proof: hex::encode(proof_bytes),
+            verified,
+            validity: req.validity,
+        })
     })
+    .await
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, ToSchema)]
+#[serde(deny_unknown_fields)]
 struct ValueVerifyInput {
     commitment: String, // hex
     proof: String,      // hex
     binding_tag_hex: Option<String>, // Optional 32-byte hex string (64 hex chars)
+    validity: Option<ValidityWindow>, // Window the proof was made with, if any
+    reference_time: Option<u64>,      // Checked against `validity`; defaults to now
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct ValueVerifyResult {
     verified: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    validity: Option<WindowStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    replay: Option<ReplayStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    receipt: Option<VerificationReceipt>,
 }
 
+#[utoipa::path(
+    tag = "value",
+    request_body = ValueVerifyInput,
+    responses(
+        (status = 200, body = ValueVerifyResult),
+        (status = 400, body = ErrorResponse),
+        (status = 409, description = "Replay registry in reject mode and proof already seen"),
+    )
+)]
 #[post("/zkp/verify-value-commitment")]
-async fn verify_value_commitment_ep(input: web::Json<ValueVerifyInput>) -> impl Responder {
-    println!("[API] /zkp/verify-value-commitment - Value commitment verification");
-    let com_bytes = match <[u8;32]>::from_hex(&input.commitment) {
-        Ok(b) => {
-            println!("[API] ✅ Commitment parsed: {} bytes", b.len());
-            b
-        },
-        Err(_) => {
-            println!("[API] ❌ Failed to parse commitment");
-            return HttpResponse::BadRequest().json(json!({ "error": "bad commitment" }));
-        },
-    };
-    let proof_bytes = match Vec::from_hex(&input.proof) {
-        Ok(p) => {
-            println!("[API] ✅ Proof parsed: {} bytes", p.len());
-            p
-        },
-        Err(_) => {
-            println!("[API] ❌ Failed to parse proof");
-            return HttpResponse::BadRequest().json(json!({ "error": "bad proof" }));
-        },
-    };
-    
-    // Parse binding tag if provided
-    let binding_tag = if let Some(ref binding_hex) = input.binding_tag_hex {
-        println!("[API] Binding tag provided for verification");
-        match hex_decode(binding_hex.trim_start_matches("0x")) {
-            Ok(b) if b.len() == 32 => {
-                println!("[API] ✅ Binding tag parsed: {} bytes", b.len());
-                Some(b)
-            },
-            _ => {
-                println!("[API] ❌ Invalid binding tag format");
-                return HttpResponse::BadRequest().json(json!({ "error": "invalid binding_tag_hex: must be 32 bytes (64 hex chars)" }));
-            },
-        }
-    } else {
-        println!("[API] No binding tag provided (backward compatible)");
-        None
-    };
-    
-    println!("[API] Verifying value commitment{}...", if binding_tag.is_some() { " with binding tag" } else { "" });
-    let verified = verify_value_commitment_with_binding(
-        curve25519_dalek_ng::ristretto::CompressedRistretto(com_bytes),
-        proof_bytes,
-        binding_tag.as_ref().map(|b| b.as_slice()),
-    );
-    println!("[API] {} Value commitment verification", if verified { "✅" } else { "❌" });
-    HttpResponse::Ok().json(ValueVerifyResult { verified })
+async fn verify_value_commitment_ep(
+    input: web::Json<ValueVerifyInput>,
+    replay: web::Data<ReplayRegistry>,
+    receipts: web::Data<ReceiptSigner>,
+    pools: web::Data<WorkPools>,
+) -> impl Responder {
+    pools.verify.run(move || {
+        let com_bytes = match <[u8;32]>::from_hex(&input.commitment) {
+            Ok(b) => b,
+            Err(_) => return bad_req("bad commitment"),
+        };
+        let proof_bytes = match Vec::from_hex(&input.proof) {
+            Ok(p) => p,
+            Err(_) => return bad_req("bad proof"),
+        };
+
+        // Parse binding tag if provided
+        let binding_tag = if let Some(ref binding_hex) = input.binding_tag_hex {
+            match hex_decode(binding_hex.trim_start_matches("0x")) {
+                Ok(b) if b.len() == 32 => Some(b),
+                _ => return bad_req("invalid binding_tag_hex: must be 32 bytes (64 hex chars)"),
+            }
+        } else {
+            None
+        };
+
+        let reference = match api::reference_point(&input.validity, input.reference_time) {
+            Ok(reference) => reference,
+            Err(error) => return api_error(error),
+        };
+
+        let holds = verify_value_commitment_within(
+            curve25519_dalek_ng::ristretto::CompressedRistretto(com_bytes),
+            proof_bytes.clone(),
+            binding_tag.as_deref(),
+            input.validity.as_ref(),
+        );
+        let (verified, validity) = windowed_outcome(holds, input.validity.as_ref(), reference.reference_time);
+        info!(verified, binding_tag = binding_tag.is_some(), ?validity, "value commitment verification");
+        let replay = match check_replay(&replay, "value-range", binding_tag.as_deref().unwrap_or_default(), &proof_bytes, verified) {
+            Ok(status) => status,
+            Err(response) => return response,
+        };
+        let reference = input.validity.is_some().then_some(reference);
+        let context = binding_tag.as_deref().unwrap_or_default();
+        let receipt = receipts.sign_windowed("value-range", &*input, context, verified, reference);
+        metrics::record_verification("value-range", verified);
+        HttpResponse::Ok().json(ValueVerifyResult { verified, validity, replay, receipt })
+    })
+    .await
 }
 
 // =============================================================================
 // Equality proof endpoints (Chaum-Pedersen DLEQ Schnorr sigma protocol)
 // =============================================================================
 
-#[derive(Deserialize)]
-struct EqualityProofRequest {
-    c_price_hex: String,      // 32-byte hex (64 chars), Pedersen commitment to price
-    c_pay_hex: String,        // 32-byte hex (64 chars), Pedersen commitment to payment
-    r_price_hex: String,      // 32-byte scalar hex — blinding of c_price
-    r_pay_hex: String,        // 32-byte scalar hex — blinding of c_pay
-    #[serde(default)]
-    binding_context: serde_json::Value, // {productId, txRef, chainId, escrowAddr, stage}
-    #[serde(default)]
-    context_hash_hex: Option<String>,
+#[derive(Serialize, ToSchema)]
+struct EqualityVerifyResponse {
+    verified: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    validity: Option<WindowStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    replay: Option<ReplayStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    receipt: Option<VerificationReceipt>,
 }
 
-#[derive(Serialize)]
-struct EqualityProofResponse {
-    proof_r_hex: String,   // hex of r_announcement (32 bytes)
-    proof_s_hex: String,   // hex of s_response (32 bytes)
-    verified: bool,        // immediate self-verification flag
+#[derive(Serialize, ToSchema)]
+struct QuantityTotalVerifyResponse {
+    verified: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    validity: Option<WindowStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    replay: Option<ReplayStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    receipt: Option<VerificationReceipt>,
 }
 
-#[derive(Deserialize)]
-struct EqualityVerifyRequest {
-    c_price_hex: String,
-    c_pay_hex: String,
-    proof_r_hex: String,
-    proof_s_hex: String,
-    #[serde(default)]
-    binding_context: serde_json::Value,
-    #[serde(default)]
-    context_hash_hex: Option<String>,
+#[utoipa::path(
+    tag = "equality",
+    request_body = api::EqualityProofRequest,
+    responses(
+        (status = 200, body = api::SigmaProofResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 500, body = ErrorResponse),
+    )
+)]
+#[post("/zkp/generate-equality-proof")]
+async fn generate_equality_proof_ep(req: web::Json<api::EqualityProofRequest>, pools: web::Data<WorkPools>) -> impl Responder {
+    pools.prove.run(move || {
+        match api::generate_equality_proof(&req) {
+            Ok(response) => {
+                metrics::record_proof_hex("equality", &[&response.proof_r_hex, &response.proof_s_hex]);
+                HttpResponse::Ok().json(response)
+            }
+            Err(error) => api_error(error),
+        }
+    })
+    .await
 }
 
-#[derive(Serialize)]
-struct EqualityVerifyResponse {
-    verified: bool,
+#[utoipa::path(
+    tag = "equality",
+    request_body = api::EqualityVerifyRequest,
+    responses(
+        (status = 200, body = EqualityVerifyResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 409, description = "Replay registry in reject mode and proof already seen"),
+    )
+)]
+#[post("/zkp/verify-equality-proof")]
+async fn verify_equality_proof_ep(
+    req: web::Json<api::EqualityVerifyRequest>,
+    replay: web::Data<ReplayRegistry>,
+    receipts: web::Data<ReceiptSigner>,
+    pools: web::Data<WorkPools>,
+) -> impl Responder {
+    pools.verify.run(move || {
+        let outcome = match api::verify_equality_proof(&req) {
+            Ok(outcome) => outcome,
+            Err(error) => return api_error(error),
+        };
+        let verified = outcome.verified;
+        let replay = match check_replay(&replay, "equality", &outcome.context, &outcome.proof, verified) {
+            Ok(status) => status,
+            Err(response) => return response,
+        };
+        let receipt = receipts.sign_windowed("equality", &*req, &outcome.context, verified, outcome.reference);
+        metrics::record_verification("equality", verified);
+        HttpResponse::Ok().json(EqualityVerifyResponse { verified, validity: outcome.validity, replay, receipt })
+    })
+    .await
 }
 
-#[derive(Deserialize)]
-struct QuantityTotalProofRequest {
-    c_quantity_hex: String,
-    c_total_hex: String,
-    unit_price_wei: String,
-    r_quantity_hex: String,
-    r_total_hex: String,
-    context_hash_hex: String,
+#[utoipa::path(
+    tag = "equality",
+    request_body = api::QuantityTotalProofRequest,
+    responses(
+        (status = 200, body = api::SigmaProofResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 500, body = ErrorResponse),
+    )
+)]
+#[post("/zkp/generate-quantity-total-proof")]
+async fn generate_quantity_total_proof_ep(req: web::Json<api::QuantityTotalProofRequest>, pools: web::Data<WorkPools>) -> impl Responder {
+    pools.prove.run(move || {
+        match api::generate_quantity_total_proof(&req) {
+            Ok(response) => {
+                metrics::record_proof_hex("quantity-total", &[&response.proof_r_hex, &response.proof_s_hex]);
+                HttpResponse::Ok().json(response)
+            }
+            Err(error) => api_error(error),
+        }
+    })
+    .await
 }
 
-#[derive(Deserialize)]
-struct QuantityTotalVerifyRequest {
-    c_quantity_hex: String,
-    c_total_hex: String,
-    unit_price_wei: String,
-    proof_r_hex: String,
-    proof_s_hex: String,
+#[utoipa::path(
+    tag = "equality",
+    request_body = api::QuantityTotalVerifyRequest,
+    responses(
+        (status = 200, body = QuantityTotalVerifyResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 409, description = "Replay registry in reject mode and proof already seen"),
+    )
+)]
+#[post("/zkp/verify-quantity-total-proof")]
+async fn verify_quantity_total_proof_ep(
+    req: web::Json<api::QuantityTotalVerifyRequest>,
+    replay: web::Data<ReplayRegistry>,
+    receipts: web::Data<ReceiptSigner>,
+    pools: web::Data<WorkPools>,
+) -> impl Responder {
+    pools.verify.run(move || {
+        let outcome = match api::verify_quantity_total_proof(&req) {
+            Ok(outcome) => outcome,
+            Err(error) => return api_error(error),
+        };
+        let verified = outcome.verified;
+        let replay = match check_replay(&replay, "quantity-total", &outcome.context, &outcome.proof, verified) {
+            Ok(status) => status,
+            Err(response) => return response,
+        };
+        let receipt = receipts.sign_windowed("quantity-total", &*req, &outcome.context, verified, outcome.reference);
+        metrics::record_verification("quantity-total", verified);
+        HttpResponse::Ok().json(QuantityTotalVerifyResponse { verified, validity: outcome.validity, replay, receipt })
+    })
+    .await
+}
+
+#[utoipa::path(
+    tag = "equality",
+    request_body = api::TotalPaymentEqualityProofRequest,
+    responses(
+        (status = 200, body = api::SigmaProofResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 500, body = ErrorResponse),
+    )
+)]
+#[post("/zkp/generate-total-payment-equality-proof")]
+async fn generate_total_payment_equality_proof_ep(req: web::Json<api::TotalPaymentEqualityProofRequest>, pools: web::Data<WorkPools>) -> impl Responder {
+    pools.prove.run(move || {
+        match api::generate_total_payment_equality_proof(&req) {
+            Ok(response) => {
+                metrics::record_proof_hex("equality", &[&response.proof_r_hex, &response.proof_s_hex]);
+                HttpResponse::Ok().json(response)
+            }
+            Err(error) => api_error(error),
+        }
+    })
+    .await
+}
+
+#[utoipa::path(
+    tag = "equality",
+    request_body = api::TotalPaymentEqualityVerifyRequest,
+    responses(
+        (status = 200, body = EqualityVerifyResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 409, description = "Replay registry in reject mode and proof already seen"),
+    )
+)]
+#[post("/zkp/verify-total-payment-equality-proof")]
+async fn verify_total_payment_equality_proof_ep(
+    req: web::Json<api::TotalPaymentEqualityVerifyRequest>,
+    replay: web::Data<ReplayRegistry>,
+    receipts: web::Data<ReceiptSigner>,
+    pools: web::Data<WorkPools>,
+) -> impl Responder {
+    pools.verify.run(move || {
+        let outcome = match api::verify_total_payment_equality_proof(&req) {
+            Ok(outcome) => outcome,
+            Err(error) => return api_error(error),
+        };
+        let verified = outcome.verified;
+        let replay = match check_replay(&replay, "equality", &outcome.context, &outcome.proof, verified) {
+            Ok(status) => status,
+            Err(response) => return response,
+        };
+        let receipt = receipts.sign_windowed("equality", &*req, &outcome.context, verified, outcome.reference);
+        metrics::record_verification("equality", verified);
+        HttpResponse::Ok().json(EqualityVerifyResponse { verified, validity: outcome.validity, replay, receipt })
+    })
+    .await
+}
+
+#[utoipa::path(
+    tag = "order",
+    request_body = api::OrderContextHashRequest,
+    responses(
+        (status = 200, body = api::OrderContextHashResponse),
+        (status = 400, body = ErrorResponse),
+    )
+)]
+#[post("/zkp/order-context-hash")]
+async fn order_context_hash_ep(req: web::Json<api::OrderContextHashRequest>) -> impl Responder {
+    match api::order_context_hash(&req) {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(error) => api_error(error),
+    }
+}
+
+// =============================================================================
+// Opening proof endpoints (Okamoto proof of knowledge of (v, r))
+// =============================================================================
+
+#[derive(Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+struct OpeningProofRequest {
+    value: IntegerLike,
+    blinding_hex: String,
     context_hash_hex: String,
 }
 
-#[derive(Serialize)]
-struct QuantityTotalProofResponse {
+#[derive(Serialize, ToSchema)]
+struct OpeningProofResponse {
+    commitment: String,
     proof_r_hex: String,
-    proof_s_hex: String,
+    proof_s_value_hex: String,
+    proof_s_blinding_hex: String,
     verified: bool,
 }
 
-#[derive(Serialize)]
-struct QuantityTotalVerifyResponse {
+#[derive(Deserialize, Serialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+struct OpeningVerifyRequest {
+    commitment_hex: String,
+    proof_r_hex: String,
+    proof_s_value_hex: String,
+    proof_s_blinding_hex: String,
+    context_hash_hex: String,
+}
+
+#[derive(Serialize, ToSchema)]
+struct OpeningVerifyResponse {
     verified: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    replay: Option<ReplayStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    receipt: Option<VerificationReceipt>,
 }
 
-#[derive(Deserialize)]
-struct TotalPaymentEqualityProofRequest {
-    c_total_hex: String,
-    c_pay_hex: String,
-    r_total_hex: String,
-    r_pay_hex: String,
-    context_hash_hex: String,
+#[utoipa::path(
+    tag = "opening",
+    request_body = OpeningProofRequest,
+    responses(
+        (status = 200, body = OpeningProofResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 500, body = ErrorResponse),
+    )
+)]
+#[post("/zkp/generate-opening-proof")]
+async fn generate_opening_proof_ep(req: web::Json<OpeningProofRequest>, pools: web::Data<WorkPools>) -> impl Responder {
+    pools.prove.run(move || {
+        let value = match req.value.parse_scalar("value") {
+            Ok(value) => value,
+            Err(error) => return bad_req(&error),
+        };
+        let blinding = match parse_scalar_hex(&req.blinding_hex) {
+            Some(value) => value,
+            None => return bad_req("invalid blinding_hex: must be 32 bytes (64 hex chars)"),
+        };
+        let context_hash = match parse_context_hash_hex(&req.context_hash_hex) {
+            Some(bytes) => bytes,
+            None => return bad_req("invalid context_hash_hex"),
+        };
+
+        let commitment = commit_scalar_with_blinding(value, blinding);
+        match prove_opening(commitment, value, blinding, &context_hash) {
+            Ok(proof) => {
+                let verified = verify_opening(commitment, &proof, &context_hash);
+                metrics::record_proof_size("opening", proof.r_announcement.len() + proof.s_value.len() + proof.s_blinding.len());
+                HttpResponse::Ok().json(OpeningProofResponse {
+                    commitment: hex::encode(commitment.as_bytes()),
+                    proof_r_hex: hex::encode(proof.r_announcement),
+                    proof_s_value_hex: hex::encode(proof.s_value),
+                    proof_s_blinding_hex: hex::encode(proof.s_blinding),
+                    verified,
+                })
+            }
+            Err(error) => HttpResponse::InternalServerError().json(json!({ "error": error })),
+        }
+    })
+    .await
 }
 
-#[derive(Deserialize)]
-struct TotalPaymentEqualityVerifyRequest {
-    c_total_hex: String,
-    c_pay_hex: String,
-    proof_r_hex: String,
-    proof_s_hex: String,
+#[utoipa::path(
+    tag = "opening",
+    request_body = OpeningVerifyRequest,
+    responses(
+        (status = 200, body = OpeningVerifyResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 409, description = "Replay registry in reject mode and proof already seen"),
+    )
+)]
+#[post("/zkp/verify-opening-proof")]
+async fn verify_opening_proof_ep(
+    req: web::Json<OpeningVerifyRequest>,
+    replay: web::Data<ReplayRegistry>,
+    receipts: web::Data<ReceiptSigner>,
+    pools: web::Data<WorkPools>,
+) -> impl Responder {
+    pools.verify.run(move || {
+        let commitment = match parse_compressed_ristretto(&req.commitment_hex) {
+            Some(value) => value,
+            None => return bad_req("invalid commitment_hex"),
+        };
+        let context_hash = match parse_context_hash_hex(&req.context_hash_hex) {
+            Some(bytes) => bytes,
+            None => return bad_req("invalid context_hash_hex"),
+        };
+        let proof = OpeningProof {
+            r_announcement: match parse_hex32_bytes(&req.proof_r_hex) {
+                Some(bytes) => bytes,
+                None => return bad_req("invalid proof_r_hex"),
+            },
+            s_value: match parse_hex32_bytes(&req.proof_s_value_hex) {
+                Some(bytes) => bytes,
+                None => return bad_req("invalid proof_s_value_hex"),
+            },
+            s_blinding: match parse_hex32_bytes(&req.proof_s_blinding_hex) {
+                Some(bytes) => bytes,
+                None => return bad_req("invalid proof_s_blinding_hex"),
+            },
+        };
+
+        let verified = verify_opening(commitment, &proof, &context_hash);
+        let proof_bytes = [proof.r_announcement, proof.s_value, proof.s_blinding].concat();
+        let replay = match check_replay(&replay, "opening", &context_hash, &proof_bytes, verified) {
+            Ok(status) => status,
+            Err(response) => return response,
+        };
+        let receipt = receipts.sign("opening", &*req, &context_hash, verified);
+        metrics::record_verification("opening", verified);
+        HttpResponse::Ok().json(OpeningVerifyResponse { verified, replay, receipt })
+    })
+    .await
+}
+
+// =============================================================================
+// Comparison proof endpoints (a >= b via range proof on C_a - C_b)
+// =============================================================================
+
+fn default_bit_range() -> usize {
+    64
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+struct ComparisonProofRequest {
+    c_a_hex: String,
+    c_b_hex: String,
+    a: IntegerLike,
+    b: IntegerLike,
+    r_a_hex: String,
+    r_b_hex: String,
     context_hash_hex: String,
+    #[serde(default = "default_bit_range")]
+    bit_range: usize,
 }
 
-#[post("/zkp/generate-equality-proof")]
-async fn generate_equality_proof_ep(req: web::Json<EqualityProofRequest>) -> impl Responder {
-    println!("[API] /zkp/generate-equality-proof");
+#[derive(Serialize, ToSchema)]
+struct ComparisonProofResponse {
+    proof: String,
+    bit_range: usize,
+    verified: bool,
+}
 
-    let c_price = match parse_compressed_ristretto(&req.c_price_hex) {
-        Some(c) => c,
-        None => return bad_req("invalid c_price_hex: must be 32 bytes (64 hex chars)"),
-    };
-    let c_pay = match parse_compressed_ristretto(&req.c_pay_hex) {
-        Some(c) => c,
-        None => return bad_req("invalid c_pay_hex: must be 32 bytes (64 hex chars)"),
-    };
-    let r_price = match parse_scalar_hex(&req.r_price_hex) {
-        Some(s) => s,
-        None => return bad_req("invalid r_price_hex"),
-    };
-    let r_pay = match parse_scalar_hex(&req.r_pay_hex) {
-        Some(s) => s,
-        None => return bad_req("invalid r_pay_hex"),
-    };
+#[derive(Deserialize, Serialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+struct ComparisonVerifyRequest {
+    c_a_hex: String,
+    c_b_hex: String,
+    proof: String,
+    context_hash_hex: String,
+    #[serde(default = "default_bit_range")]
+    bit_range: usize,
+}
 
-    let binding_bytes = if let Some(context_hash_hex) = &req.context_hash_hex {
-        match parse_context_hash_hex(context_hash_hex) {
-            Some(bytes) => bytes.to_vec(),
+#[derive(Serialize, ToSchema)]
+struct ComparisonVerifyResponse {
+    verified: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    replay: Option<ReplayStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    receipt: Option<VerificationReceipt>,
+}
+
+/// Proves that `c_a` commits to a value >= the value in `c_b`. The proof also
+/// range-checks `c_b`, so a wrapped ("negative") `b` cannot pass.
+#[utoipa::path(
+    tag = "comparison",
+    request_body = ComparisonProofRequest,
+    responses(
+        (status = 200, body = ComparisonProofResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 500, body = ErrorResponse),
+    )
+)]
+#[post("/zkp/generate-comparison-proof")]
+async fn generate_comparison_proof_ep(req: web::Json<ComparisonProofRequest>, pools: web::Data<WorkPools>) -> impl Responder {
+    pools.prove.run(move || {
+        let c_a = match parse_compressed_ristretto(&req.c_a_hex) {
+            Some(value) => value,
+            None => return bad_req("invalid c_a_hex"),
+        };
+        let c_b = match parse_compressed_ristretto(&req.c_b_hex) {
+            Some(value) => value,
+            None => return bad_req("invalid c_b_hex"),
+        };
+        let a = match req.a.parse_u64("a") {
+            Ok(value) => value,
+            Err(error) => return bad_req(&error),
+        };
+        let b = match req.b.parse_u64("b") {
+            Ok(value) => value,
+            Err(error) => return bad_req(&error),
+        };
+        let r_a = match parse_scalar_hex(&req.r_a_hex) {
+            Some(value) => value,
+            None => return bad_req("invalid r_a_hex"),
+        };
+        let r_b = match parse_scalar_hex(&req.r_b_hex) {
+            Some(value) => value,
+            None => return bad_req("invalid r_b_hex"),
+        };
+        let context_hash = match parse_context_hash_hex(&req.context_hash_hex) {
+            Some(bytes) => bytes,
             None => return bad_req("invalid context_hash_hex"),
-        }
-    } else {
-        serde_json::to_vec(&req.binding_context).unwrap_or_default()
-    };
+        };
 
-    match prove_equality(c_price, c_pay, r_price, r_pay, &binding_bytes) {
-        Ok(proof) => {
-            let verified = verify_equality(c_price, c_pay, &proof, &binding_bytes);
-            println!("[API] Equality proof generated, self-verified: {}", verified);
-            HttpResponse::Ok().json(EqualityProofResponse {
-                proof_r_hex: hex::encode(proof.r_announcement),
-                proof_s_hex: hex::encode(proof.s_response),
-                verified,
-            })
-        }
-        Err(e) => {
-            println!("[API] Equality proof generation failed: {}", e);
-            HttpResponse::InternalServerError().json(json!({ "error": e }))
+        match prove_greater_or_equal(c_a, c_b, a, b, r_a, r_b, &context_hash, req.bit_range) {
+            Ok(proof) => {
+                let verified = verify_greater_or_equal(c_a, c_b, &proof, &context_hash, req.bit_range);
+                metrics::record_proof_size("comparison", proof.len());
+                HttpResponse::Ok().json(ComparisonProofResponse {
+                    proof: hex::encode(proof),
+                    bit_range: req.bit_range,
+                    verified,
+                })
+            }
+            Err(error) => bad_req(&error),
         }
-    }
+    })
+    .await
 }
 
-#[post("/zkp/verify-equality-proof")]
-async fn verify_equality_proof_ep(req: web::Json<EqualityVerifyRequest>) -> impl Responder {
-    println!("[API] /zkp/verify-equality-proof");
+/// Verifies a comparison proof, including its range proof on `c_b`.
+#[utoipa::path(
+    tag = "comparison",
+    request_body = ComparisonVerifyRequest,
+    responses(
+        (status = 200, body = ComparisonVerifyResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 409, description = "Replay registry in reject mode and proof already seen"),
+    )
+)]
+#[post("/zkp/verify-comparison-proof")]
+async fn verify_comparison_proof_ep(
+    req: web::Json<ComparisonVerifyRequest>,
+    replay: web::Data<ReplayRegistry>,
+    receipts: web::Data<ReceiptSigner>,
+    pools: web::Data<WorkPools>,
+) -> impl Responder {
+    pools.verify.run(move || {
+        let c_a = match parse_compressed_ristretto(&req.c_a_hex) {
+            Some(value) => value,
+            None => return bad_req("invalid c_a_hex"),
+        };
+        let c_b = match parse_compressed_ristretto(&req.c_b_hex) {
+            Some(value) => value,
+            None => return bad_req("invalid c_b_hex"),
+        };
+        let proof = match Vec::from_hex(req.proof.trim_start_matches("0x")) {
+            Ok(bytes) => bytes,
+            Err(_) => return bad_req("invalid proof"),
+        };
+        let context_hash = match parse_context_hash_hex(&req.context_hash_hex) {
+            Some(bytes) => bytes,
+            None => return bad_req("invalid context_hash_hex"),
+        };
 
-    let c_price = match parse_compressed_ristretto(&req.c_price_hex) {
-        Some(c) => c,
-        None => return bad_req("invalid c_price_hex"),
-    };
-    let c_pay = match parse_compressed_ristretto(&req.c_pay_hex) {
-        Some(c) => c,
-        None => return bad_req("invalid c_pay_hex"),
-    };
+        let verified = verify_greater_or_equal(c_a, c_b, &proof, &context_hash, req.bit_range);
+        let replay = match check_replay(&replay, "comparison", &context_hash, &proof, verified) {
+            Ok(status) => status,
+            Err(response) => return response,
+        };
+        let receipt = receipts.sign("comparison", &*req, &context_hash, verified);
+        metrics::record_verification("comparison", verified);
+        HttpResponse::Ok().json(ComparisonVerifyResponse { verified, replay, receipt })
+    })
+    .await
+}
 
-    let r_bytes = match parse_hex32_bytes(&req.proof_r_hex) {
-        Some(bytes) => bytes,
-        None => return bad_req("invalid proof_r_hex"),
-    };
-    let s_bytes = match parse_hex32_bytes(&req.proof_s_hex) {
-        Some(bytes) => bytes,
-        None => return bad_req("invalid proof_s_hex"),
-    };
+// =============================================================================
+// Delivery-quantity proof endpoints (transporter confirmation stage)
+// =============================================================================
+
+#[derive(Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+struct DeliveryProofRequest {
+    mode: DeliveryMode,            // "full" (C_delivered == C_quantity) or "partial" (<=)
+    c_quantity_hex: String,        // buyer's order commitment
+    quantity: IntegerLike,
+    r_quantity_hex: String,
+    delivered_quantity: IntegerLike,
+    r_delivered_hex: String,       // transporter-chosen blinding for C_delivered
+    context_hash_hex: String,      // delivery context hash
+}
 
-    let proof = EqualityProof { r_announcement: r_bytes, s_response: s_bytes };
-    let binding_bytes = if let Some(context_hash_hex) = &req.context_hash_hex {
-        match parse_context_hash_hex(context_hash_hex) {
-            Some(bytes) => bytes.to_vec(),
+#[derive(Serialize, ToSchema)]
+struct DeliveryProofResponse {
+    mode: DeliveryMode,
+    c_delivered_hex: String,
+    proof: String,
+    verified: bool,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+struct DeliveryVerifyRequest {
+    mode: DeliveryMode,
+    c_quantity_hex: String,
+    c_delivered_hex: String,
+    proof: String,
+    context_hash_hex: String,
+}
+
+#[derive(Serialize, ToSchema)]
+struct DeliveryVerifyResponse {
+    verified: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    replay: Option<ReplayStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    receipt: Option<VerificationReceipt>,
+}
+
+#[utoipa::path(
+    tag = "delivery",
+    request_body = DeliveryProofRequest,
+    responses(
+        (status = 200, body = DeliveryProofResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 500, body = ErrorResponse),
+    )
+)]
+#[post("/zkp/generate-delivery-proof")]
+async fn generate_delivery_proof_ep(req: web::Json<DeliveryProofRequest>, pools: web::Data<WorkPools>) -> impl Responder {
+    pools.prove.run(move || {
+        let c_quantity = match parse_compressed_ristretto(&req.c_quantity_hex) {
+            Some(value) => value,
+            None => return bad_req("invalid c_quantity_hex"),
+        };
+        let quantity = match req.quantity.parse_u64("quantity") {
+            Ok(value) => value,
+            Err(error) => return bad_req(&error),
+        };
+        let r_quantity = match parse_scalar_hex(&req.r_quantity_hex) {
+            Some(value) => value,
+            None => return bad_req("invalid r_quantity_hex"),
+        };
+        let delivered = match req.delivered_quantity.parse_u64("delivered_quantity") {
+            Ok(value) => value,
+            Err(error) => return bad_req(&error),
+        };
+        let r_delivered = match parse_scalar_hex(&req.r_delivered_hex) {
+            Some(value) => value,
+            None => return bad_req("invalid r_delivered_hex"),
+        };
+        let context_hash = match parse_context_hash_hex(&req.context_hash_hex) {
+            Some(bytes) => bytes,
             None => return bad_req("invalid context_hash_hex"),
+        };
+
+        let c_delivered = commit_scalar_with_blinding(Scalar::from(delivered), r_delivered);
+        match prove_delivery(
+            req.mode,
+            c_quantity,
+            c_delivered,
+            quantity,
+            r_quantity,
+            delivered,
+            r_delivered,
+            &context_hash,
+        ) {
+            Ok(proof) => {
+                let verified = verify_delivery(c_quantity, c_delivered, &proof, &context_hash);
+                let proof_bytes = proof.to_bytes();
+                metrics::record_proof_size("delivery", proof_bytes.len());
+                HttpResponse::Ok().json(DeliveryProofResponse {
+                    mode: req.mode,
+                    c_delivered_hex: hex::encode(c_delivered.as_bytes()),
+                    proof: hex::encode(proof_bytes),
+                    verified,
+                })
+            }
+            Err(error) => bad_req(&error),
         }
-    } else {
-        serde_json::to_vec(&req.binding_context).unwrap_or_default()
-    };
+    })
+    .await
+}
 
-    let verified = verify_equality(c_price, c_pay, &proof, &binding_bytes);
-    println!("[API] Equality proof verification: {}", verified);
-    HttpResponse::Ok().json(EqualityVerifyResponse { verified })
+#[utoipa::path(
+    tag = "delivery",
+    request_body = DeliveryVerifyRequest,
+    responses(
+        (status = 200, body = DeliveryVerifyResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 409, description = "Replay registry in reject mode and proof already seen"),
+    )
+)]
+#[post("/zkp/verify-delivery-proof")]
+async fn verify_delivery_proof_ep(
+    req: web::Json<DeliveryVerifyRequest>,
+    replay: web::Data<ReplayRegistry>,
+    receipts: web::Data<ReceiptSigner>,
+    pools: web::Data<WorkPools>,
+) -> impl Responder {
+    pools.verify.run(move || {
+        let c_quantity = match parse_compressed_ristretto(&req.c_quantity_hex) {
+            Some(value) => value,
+            None => return bad_req("invalid c_quantity_hex"),
+        };
+        let c_delivered = match parse_compressed_ristretto(&req.c_delivered_hex) {
+            Some(value) => value,
+            None => return bad_req("invalid c_delivered_hex"),
+        };
+        let proof = match Vec::from_hex(req.proof.trim_start_matches("0x"))
+            .ok()
+            .and_then(|bytes| DeliveryProof::from_bytes(req.mode, &bytes))
+        {
+            Some(proof) => proof,
+            None => return bad_req("invalid proof"),
+        };
+        let context_hash = match parse_context_hash_hex(&req.context_hash_hex) {
+            Some(bytes) => bytes,
+            None => return bad_req("invalid context_hash_hex"),
+        };
+
+        let verified = verify_delivery(c_quantity, c_delivered, &proof, &context_hash);
+        let replay = match check_replay(&replay, "delivery", &context_hash, &proof.to_bytes(), verified) {
+            Ok(status) => status,
+            Err(response) => return response,
+        };
+        let receipt = receipts.sign("delivery", &*req, &context_hash, verified);
+        metrics::record_verification("delivery", verified);
+        HttpResponse::Ok().json(DeliveryVerifyResponse { verified, replay, receipt })
+    })
+    .await
 }
 
-#[post("/zkp/generate-quantity-total-proof")]
-async fn generate_quantity_total_proof_ep(req: web::Json<QuantityTotalProofRequest>) -> impl Responder {
-    println!("[API] /zkp/generate-quantity-total-proof");
+// =============================================================================
+// Order VRC verification
+// =============================================================================
 
-    let c_quantity = match parse_compressed_ristretto(&req.c_quantity_hex) {
-        Some(value) => value,
-        None => return bad_req("invalid c_quantity_hex"),
-    };
-    let c_total = match parse_compressed_ristretto(&req.c_total_hex) {
-        Some(value) => value,
-        None => return bad_req("invalid c_total_hex"),
-    };
-    let unit_price = match parse_decimal_scalar_strict(&req.unit_price_wei) {
-        Some(value) => value,
-        None => return bad_req("invalid unit_price_wei"),
-    };
-    let r_quantity = match parse_scalar_hex(&req.r_quantity_hex) {
-        Some(value) => value,
-        None => return bad_req("invalid r_quantity_hex"),
-    };
-    let r_total = match parse_scalar_hex(&req.r_total_hex) {
-        Some(value) => value,
-        None => return bad_req("invalid r_total_hex"),
-    };
-    let context_hash = match parse_context_hash_hex(&req.context_hash_hex) {
-        Some(bytes) => bytes,
-        None => return bad_req("invalid context_hash_hex"),
-    };
+#[derive(Deserialize, Serialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+struct VerifyVcRequest {
+    vc: serde_json::Value,          // the full schemaVersion 5.0 order VRC
+}
 
-    match prove_quantity_total(c_quantity, c_total, unit_price, r_quantity, r_total, &context_hash) {
-        Ok(proof) => {
-            let verified = verify_quantity_total(c_quantity, c_total, unit_price, &proof, &context_hash);
-            HttpResponse::Ok().json(QuantityTotalProofResponse {
-                proof_r_hex: hex::encode(proof.r_announcement),
-                proof_s_hex: hex::encode(proof.s_response),
-                verified,
-            })
-        }
-        Err(error) => HttpResponse::InternalServerError().json(json!({ "error": error })),
-    }
+#[derive(Serialize, ToSchema)]
+struct VerifyVcResponse {
+    #[serde(flatten)]
+    report: VcVerificationReport,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    receipt: Option<VerificationReceipt>,
 }
 
-#[post("/zkp/verify-quantity-total-proof")]
-async fn verify_quantity_total_proof_ep(req: web::Json<QuantityTotalVerifyRequest>) -> impl Responder {
-    println!("[API] /zkp/verify-quantity-total-proof");
+#[utoipa::path(
+    tag = "vc",
+    request_body = VerifyVcRequest,
+    responses(
+        (status = 200, body = VerifyVcResponse),
+        (status = 400, body = ErrorResponse),
+    )
+)]
+#[post("/zkp/verify-vc")]
+async fn verify_vc_ep(
+    req: web::Json<VerifyVcRequest>,
+    receipts: web::Data<ReceiptSigner>,
+    pools: web::Data<WorkPools>,
+) -> impl Responder {
+    pools.verify.run(move || {
+        let vrc = match parse_order_vrc(&req.vc) {
+            Ok(vrc) => vrc,
+            Err(error) => return bad_req(&error),
+        };
+        let report = verify_order_vrc(&vrc);
+        info!(verified = report.verified, "VRC verification");
+        let context_hash = vrc.credential_subject.attestation.context_hash.as_deref()
+            .and_then(parse_context_hash_hex)
+            .map(|bytes| bytes.to_vec())
+            .unwrap_or_default();
+        let receipt = receipts.sign("order-vrc", &*req, &context_hash, report.verified);
+        metrics::record_verification("order-vrc", report.verified);
+        HttpResponse::Ok().json(VerifyVcResponse { report, receipt })
+    })
+    .await
+}
 
-    let c_quantity = match parse_compressed_ristretto(&req.c_quantity_hex) {
-        Some(value) => value,
-        None => return bad_req("invalid c_quantity_hex"),
-    };
-    let c_total = match parse_compressed_ristretto(&req.c_total_hex) {
-        Some(value) => value,
-        None => return bad_req("invalid c_total_hex"),
-    };
-    let unit_price = match parse_decimal_scalar_strict(&req.unit_price_wei) {
-        Some(value) => value,
-        None => return bad_req("invalid unit_price_wei"),
-    };
-    let context_hash = match parse_context_hash_hex(&req.context_hash_hex) {
-        Some(bytes) => bytes,
-        None => return bad_req("invalid context_hash_hex"),
-    };
-    let proof = QuantityTotalProof {
-        r_announcement: match parse_hex32_bytes(&req.proof_r_hex) {
-            Some(bytes) => bytes,
-            None => return bad_req("invalid proof_r_hex"),
-        },
-        s_response: match parse_hex32_bytes(&req.proof_s_hex) {
-            Some(bytes) => bytes,
-            None => return bad_req("invalid proof_s_hex"),
-        },
-    };
+// =============================================================================
+// Verification receipt endpoints
+// =============================================================================
 
-    let verified = verify_quantity_total(c_quantity, c_total, unit_price, &proof, &context_hash);
-    HttpResponse::Ok().json(QuantityTotalVerifyResponse { verified })
+#[derive(Serialize, ToSchema)]
+struct ReceiptKeyResponse {
+    enabled: bool,
+    algorithm: Option<ReceiptAlgorithm>,
+    public_key: Option<String>,
+    service_version: &'static str,
 }
 
-#[post("/zkp/generate-total-payment-equality-proof")]
-async fn generate_total_payment_equality_proof_ep(req: web::Json<TotalPaymentEqualityProofRequest>) -> impl Responder {
-    println!("[API] /zkp/generate-total-payment-equality-proof");
+#[utoipa::path(tag = "receipts", responses((status = 200, body = ReceiptKeyResponse)))]
+#[get("/zkp/receipts/public-key")]
+async fn receipts_public_key_ep(receipts: web::Data<ReceiptSigner>) -> impl Responder {
+    HttpResponse::Ok().json(ReceiptKeyResponse {
+        enabled: receipts.algorithm().is_some(),
+        algorithm: receipts.algorithm(),
+        public_key: receipts.public_key_hex(),
+        service_version: SERVICE_VERSION,
+    })
+}
 
-    let c_total = match parse_compressed_ristretto(&req.c_total_hex) {
-        Some(value) => value,
-        None => return bad_req("invalid c_total_hex"),
-    };
-    let c_pay = match parse_compressed_ristretto(&req.c_pay_hex) {
-        Some(value) => value,
-        None => return bad_req("invalid c_pay_hex"),
-    };
-    let r_total = match parse_scalar_hex(&req.r_total_hex) {
-        Some(value) => value,
-        None => return bad_req("invalid r_total_hex"),
-    };
-    let r_pay = match parse_scalar_hex(&req.r_pay_hex) {
-        Some(value) => value,
-        None => return bad_req("invalid r_pay_hex"),
-    };
-    let context_hash = match parse_context_hash_hex(&req.context_hash_hex) {
-        Some(bytes) => bytes,
-        None => return bad_req("invalid context_hash_hex"),
-    };
+#[derive(Serialize, ToSchema)]
+struct ReceiptVerifyResponse {
+    valid: bool,
+    /// The receipt names this service's current signing key.
+    issued_by_this_service: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[utoipa::path(
+    tag = "receipts",
+    request_body = VerificationReceipt,
+    responses(
+        (status = 200, body = ReceiptVerifyResponse),
+        (status = 400, body = ErrorResponse),
+    )
+)]
+#[post("/zkp/receipts/verify")]
+async fn verify_receipt_ep(
+    req: web::Json<VerificationReceipt>,
+    receipts: web::Data<ReceiptSigner>,
+) -> impl Responder {
+    let result = verify_receipt(&req);
+    let issued_by_this_service = receipts
+        .public_key_hex()
+        .is_some_and(|key| key.eq_ignore_ascii_case(req.public_key.trim_start_matches("0x")));
+    HttpResponse::Ok().json(ReceiptVerifyResponse {
+        valid: result.is_ok(),
+        issued_by_this_service,
+        error: result.err(),
+    })
+}
 
-    match prove_equality(c_total, c_pay, r_total, r_pay, &context_hash) {
-        Ok(proof) => {
-            let verified = verify_equality(c_total, c_pay, &proof, &context_hash);
-            HttpResponse::Ok().json(EqualityProofResponse {
-                proof_r_hex: hex::encode(proof.r_announcement),
-                proof_s_hex: hex::encode(proof.s_response),
-                verified,
-            })
+// =============================================================================
+// Replay registry endpoints
+// =============================================================================
+
+#[derive(Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+struct ReplayStatusRequest {
+    #[serde(default)]
+    nullifier: Option<String>,      // hex nullifier, or derive it from the fields below
+    #[serde(default)]
+    proof_kind: Option<String>,     // e.g. "equality", "quantity-total", "opening"
+    #[serde(default)]
+    context_hash_hex: Option<String>,
+    #[serde(default)]
+    proof_hex: Option<String>,      // proof bytes as submitted for verification; the note commitment for "note-link"
+}
+
+#[derive(Serialize, ToSchema)]
+struct ReplayStatusResponse {
+    enabled: bool,
+    mode: ReplayMode,
+    nullifier: Option<String>,
+    seen: bool,
+    status: Option<ReplayStatus>,
+}
+
+#[utoipa::path(
+    tag = "replay",
+    request_body = ReplayStatusRequest,
+    responses(
+        (status = 200, body = ReplayStatusResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 500, body = ErrorResponse),
+    )
+)]
+#[post("/zkp/replay/status")]
+async fn replay_status_ep(
+    req: web::Json<ReplayStatusRequest>,
+    replay: web::Data<ReplayRegistry>,
+) -> impl Responder {
+    let id = match (&req.nullifier, &req.proof_kind, &req.proof_hex) {
+        (Some(id), _, _) => id.trim_start_matches("0x").to_ascii_lowercase(),
+        (None, Some(kind), Some(proof_hex)) => {
+            let context = match hex_decode(req.context_hash_hex.as_deref().unwrap_or_default().trim_start_matches("0x")) {
+                Ok(bytes) => bytes,
+                Err(_) => return bad_req("invalid context_hash_hex"),
+            };
+            let proof = match hex_decode(proof_hex.trim_start_matches("0x")) {
+                Ok(bytes) => bytes,
+                Err(_) => return bad_req("invalid proof_hex"),
+            };
+            nullifier(kind, &context, &proof)
         }
+        _ => return bad_req("expected nullifier or proof_kind + proof_hex"),
+    };
+
+    match replay.lookup(&id) {
+        Ok(status) => HttpResponse::Ok().json(ReplayStatusResponse {
+            enabled: replay.enabled(),
+            mode: replay.mode(),
+            nullifier: Some(id),
+            seen: status.is_some(),
+            status,
+        }),
         Err(error) => HttpResponse::InternalServerError().json(json!({ "error": error })),
     }
 }
 
-#[post("/zkp/verify-total-payment-equality-proof")]
-async fn verify_total_payment_equality_proof_ep(req: web::Json<TotalPaymentEqualityVerifyRequest>) -> impl Responder {
-    println!("[API] /zkp/verify-total-payment-equality-proof");
+// =============================================================================
+// OpenAPI document
+// =============================================================================
 
-    let c_total = match parse_compressed_ristretto(&req.c_total_hex) {
-        Some(value) => value,
-        None => return bad_req("invalid c_total_hex"),
-    };
-    let c_pay = match parse_compressed_ristretto(&req.c_pay_hex) {
-        Some(value) => value,
-        None => return bad_req("invalid c_pay_hex"),
-    };
-    let context_hash = match parse_context_hash_hex(&req.context_hash_hex) {
-        Some(bytes) => bytes,
-        None => return bad_req("invalid context_hash_hex"),
-    };
-    let proof = EqualityProof {
-        r_announcement: match parse_hex32_bytes(&req.proof_r_hex) {
-            Some(bytes) => bytes,
-            None => return bad_req("invalid proof_r_hex"),
-        },
-        s_response: match parse_hex32_bytes(&req.proof_s_hex) {
-            Some(bytes) => bytes,
-            None => return bad_req("invalid proof_s_hex"),
-        },
-    };
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "ZKP backend", description = "Pedersen commitments, range and sigma proofs for the EV battery supply chain."),
+    paths(
+        health,
+        generate_zkp,
+        verify_zkp,
+        commit_tx_hash,
+        prove_plus_ep,
+        verify_plus_ep,
+        generate_bp4,
+        verify_bp4,
+        commit_value,
+        verify_value,
+        generate_value_commitment,
+        generate_value_commitment_with_blinding_ep,
+        generate_scalar_commitment_with_blinding_ep,
+        generate_value_commitment_with_binding_ep,
+        verify_value_commitment_ep,
+        generate_equality_proof_ep,
+        verify_equality_proof_ep,
+        generate_quantity_total_proof_ep,
+        verify_quantity_total_proof_ep,
+        generate_total_payment_equality_proof_ep,
+        verify_total_payment_equality_proof_ep,
+        order_context_hash_ep,
+        generate_opening_proof_ep,
+        verify_opening_proof_ep,
+        generate_comparison_proof_ep,
+        verify_comparison_proof_ep,
+        generate_delivery_proof_ep,
+        verify_delivery_proof_ep,
+        verify_vc_ep,
+        receipts_public_key_ep,
+        verify_receipt_ep,
+        replay_status_ep,
+        v2::create_commitment,
+        v2::verify_range,
+        v2::prove_equality,
+        v2::verify_equality,
+        v2::prove_quantity_total,
+        v2::verify_quantity_total,
+        v2::commit_txref,
+        v2::verify_txref,
+        v2::deprecations,
+        metrics::metrics_ep,
+        jobs::create_job,
+        jobs::job_status,
+        note_link::create_keys,
+        note_link::current_keys,
+        note_link::prove,
+        note_link::verify,
+        mpc::create_session,
+        mpc::session_status,
+        mpc::submit_bit_commitment,
+        mpc::submit_poly_commitment,
+        mpc::submit_proof_share,
+        mpc::verify,
+        revocation::update_statuses,
+        revocation::current_root,
+        revocation::issue_witness,
+        revocation::verify,
+        bbs::public_key,
+        bbs::issue,
+        bbs::derive_proof,
+        bbs::verify,
+        passport::prove,
+        passport::verify,
+        carbon_footprint::prove,
+        carbon_footprint::verify,
+        recycled_content::prove,
+        recycled_content::verify,
+    ),
+    tags(
+        (name = "health"),
+        (name = "txid", description = "Transaction-hash commitments (BP, BP+, 4-limb BP)"),
+        (name = "value", description = "Value and scalar commitments"),
+        (name = "equality", description = "Equality and quantity-total sigma proofs"),
+        (name = "order", description = "Order context helpers"),
+        (name = "opening", description = "Proof of knowledge of a commitment opening"),
+        (name = "comparison", description = "a >= b comparison proofs"),
+        (name = "delivery", description = "Delivery-quantity proofs"),
+        (name = "vc", description = "Order VRC verification"),
+        (name = "receipts", description = "Signed verification receipts"),
+        (name = "replay", description = "Proof replay registry"),
+        (name = "v2", description = "Versioned API; supersedes the overlapping /zkp routes"),
+        (name = "jobs", description = "Asynchronous batch proving"),
+        (name = "note-link", description = "Groth16 linkage between payment commitments and Railgun notes"),
+        (name = "mpc", description = "Multi-party aggregated range proof sessions"),
+        (name = "revocation", description = "Credential status accumulator and witnesses"),
+        (name = "bbs", description = "BBS+ selective-disclosure credentials"),
+        (name = "passport", description = "Battery passport threshold proofs"),
+        (name = "carbon-footprint", description = "Component carbon footprint aggregation under a declared cap"),
+        (name = "recycled-content", description = "Minimum recycled-content share proofs"),
+    )
+)]
+struct ApiDoc;
+
+/// The generated document with the legacy routes from `v2::LEGACY_ROUTES` flagged as deprecated.
+fn openapi_doc() -> utoipa::openapi::OpenApi {
+    let mut doc = ApiDoc::openapi();
+    for (route, _) in v2::LEGACY_ROUTES {
+        if let Some(operation) = doc.paths.paths.get_mut(*route).and_then(|item| item.post.as_mut()) {
+            operation.deprecated = Some(utoipa::openapi::Deprecated::True);
+        }
+    }
+    doc
+}
+
+#[get("/openapi.json")]
+async fn openapi_json(openapi: web::Data<utoipa::openapi::OpenApi>) -> impl Responder {
+    HttpResponse::Ok().json(openapi.get_ref())
+}
+
+/// Malformed or unknown-field JSON bodies get the same `{ "error": ... }` shape as other 400s.
+fn json_config() -> web::JsonConfig {
+    web::JsonConfig::default().error_handler(|err, _req| {
+        let response = bad_req(&err.to_string());
+        actix_web::error::InternalError::from_response(err, response).into()
+    })
+}
 
-    let verified = verify_equality(c_total, c_pay, &proof, &context_hash);
-    HttpResponse::Ok().json(EqualityVerifyResponse { verified })
+/// Every route except the docs UI, which needs the rendered OpenAPI document.
+fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(health)
+        .service(generate_zkp)
+        .service(verify_zkp)
+        .service(commit_tx_hash)
+        .service(prove_plus_ep)
+        .service(verify_plus_ep)
+        .service(generate_bp4)
+        .service(verify_bp4)
+        .service(commit_value)
+        .service(verify_value)
+        .service(generate_value_commitment)
+        .service(generate_value_commitment_with_blinding_ep)
+        .service(generate_scalar_commitment_with_blinding_ep)
+        .service(generate_value_commitment_with_binding_ep)
+        .service(verify_value_commitment_ep)
+        .service(generate_equality_proof_ep)
+        .service(verify_equality_proof_ep)
+        .service(generate_quantity_total_proof_ep)
+        .service(verify_quantity_total_proof_ep)
+        .service(generate_total_payment_equality_proof_ep)
+        .service(verify_total_payment_equality_proof_ep)
+        .service(generate_opening_proof_ep)
+        .service(verify_opening_proof_ep)
+        .service(generate_comparison_proof_ep)
+        .service(verify_comparison_proof_ep)
+        .service(generate_delivery_proof_ep)
+        .service(verify_delivery_proof_ep)
+        .service(order_context_hash_ep)
+        .service(verify_vc_ep)
+        .service(receipts_public_key_ep)
+        .service(verify_receipt_ep)
+        .service(replay_status_ep)
+        .service(jobs::create_job)
+        .service(jobs::job_status)
+        .service(note_link::create_keys)
+        .service(note_link::current_keys)
+        .service(note_link::prove)
+        .service(note_link::verify)
+        .service(mpc::create_session)
+        .service(mpc::session_status)
+        .service(mpc::submit_bit_commitment)
+        .service(mpc::submit_poly_commitment)
+        .service(mpc::submit_proof_share)
+        .service(mpc::verify)
+        .service(revocation::update_statuses)
+        .service(revocation::current_root)
+        .service(revocation::issue_witness)
+        .service(revocation::verify)
+        .service(bbs::public_key)
+        .service(bbs::issue)
+        .service(bbs::derive_proof)
+        .service(bbs::verify)
+        .service(passport::prove)
+        .service(passport::verify)
+        .service(carbon_footprint::prove)
+        .service(carbon_footprint::verify)
+        .service(recycled_content::prove)
+        .service(recycled_content::verify)
+        .configure(v2::configure)
+        .service(openapi_json)
+        .service(metrics::metrics_ep);
 }
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
+    telemetry::init();
     let host = std::env::var("ZKP_HOST").unwrap_or_else(|_| "0.0.0.0".to_string());
     let port = std::env::var("ZKP_PORT")
         .ok()
         .and_then(|value| value.parse::<u16>().ok())
         .unwrap_or(5010);
 
-    println!("[SERVER] =========================================");
-    println!("[SERVER] Starting ZKP Backend Server");
-    println!("[SERVER] Listening on http://{}:{}", host, port);
-    println!("[SERVER] =========================================");
-    HttpServer::new(|| {
+    info!(%host, port, "starting ZKP backend server");
+    let replay = web::Data::new(ReplayRegistry::from_env());
+    let receipts = web::Data::new(ReceiptSigner::from_env().map_err(std::io::Error::other)?);
+    let legacy_usage = web::Data::new(v2::LegacyUsage::default());
+    let security = SecurityConfig::from_env().map_err(std::io::Error::other)?;
+    let security = web::Data::new(SecurityPolicy::new(security));
+    let pools = web::Data::new(WorkPools::from_env());
+    let jobs = web::Data::new(JobStore::from_env().map_err(std::io::Error::other)?);
+    let note_link_keys = web::Data::new(NoteLinkKeys::from_env());
+    let mpc_sessions = web::Data::new(MpcSessions::from_env());
+    let revocation = web::Data::new(RevocationRegistry::from_env().map_err(std::io::Error::other)?);
+    let bbs_issuer = web::Data::new(BbsIssuer::from_env().map_err(std::io::Error::other)?);
+    actix_web::rt::spawn(jobs::run_worker(jobs.clone(), pools.clone(), receipts.clone()));
+    let openapi = openapi_doc();
+    let openapi_data = web::Data::new(openapi.clone());
+    HttpServer::new(move || {
         App::new()
-            .wrap(Cors::default().allow_any_origin().allow_any_method().allow_any_header())
-            .service(health)
-            .service(generate_zkp)
-            .service(verify_zkp)
-            .service(commit_tx_hash)
-            .service(prove_plus_ep)
-            .service(verify_plus_ep)
-            .service(generate_bp4)
-            .service(verify_bp4)
-            .service(commit_value)
-            .service(verify_value)
-            .service(generate_value_commitment)
-            .service(generate_value_commitment_with_blinding_ep)
-            .service(generate_scalar_commitment_with_blinding_ep)
-            .service(generate_value_commitment_with_binding_ep)
-            .service(verify_value_commitment_ep)
-            .service(generate_equality_proof_ep)
-            .service(verify_equality_proof_ep)
-            .service(generate_quantity_total_proof_ep)
-            .service(verify_quantity_total_proof_ep)
-            .service(generate_total_payment_equality_proof_ep)
-            .service(verify_total_payment_equality_proof_ep)
+            .wrap(middleware::from_fn(v2::deprecation))
+            .wrap(middleware::from_fn(security::enforce))
+            .wrap(security.config().cors())
+            .wrap(middleware::from_fn(metrics::track))
+            .wrap(middleware::from_fn(telemetry::request_span))
+            .app_data(json_config().limit(security.config().json_limit()))
+            .app_data(security.clone())
+            .app_data(pools.clone())
+            .app_data(jobs.clone())
+            .app_data(note_link_keys.clone())
+            .app_data(mpc_sessions.clone())
+            .app_data(revocation.clone())
+            .app_data(bbs_issuer.clone())
+            .app_data(legacy_usage.clone())
+            .app_data(openapi_data.clone())
+            .app_data(replay.clone())
+            .app_data(receipts.clone())
+            .configure(configure)
+            .service(utoipa_scalar::Scalar::with_url("/docs", openapi.clone()))
     })
     .bind((host.as_str(), port))?
     .run()
@@ -1122,17 +1948,70 @@ async fn main() -> std::io::Result<()> {
 
 #[cfg(test)]
 mod tests {
-    use super::parse_decimal_scalar_strict;
+    use super::*;
+    use actix_web::test::{call_and_read_body_json, init_service, TestRequest};
 
     #[test]
-    fn parse_decimal_scalar_strict_accepts_large_canonical_value() {
-        let value = "340282366920938463463374607431768211455";
-        assert!(parse_decimal_scalar_strict(value).is_some());
+    fn openapi_documents_request_and_response_schemas() {
+        let doc = serde_json::to_value(ApiDoc::openapi()).unwrap();
+
+        let verify = &doc["paths"]["/zkp/verify-quantity-total-proof"]["post"];
+        assert!(verify["requestBody"].is_object());
+        assert!(verify["responses"]["200"].is_object());
+        assert!(doc["components"]["schemas"]["QuantityTotalVerifyRequest"].is_object());
+        assert!(doc["paths"]["/health"]["get"].is_object());
+    }
+
+    #[test]
+    fn openapi_marks_legacy_routes_deprecated() {
+        let doc = serde_json::to_value(openapi_doc()).unwrap();
+
+        assert_eq!(doc["paths"]["/zkp/commit-value"]["post"]["deprecated"], true);
+        assert!(doc["paths"]["/v2/commitments"]["post"]["deprecated"].is_null());
+        assert!(doc["paths"]["/zkp/generate-opening-proof"]["post"]["deprecated"].is_null());
+    }
+
+    #[actix_web::test]
+    async fn scalar_commitment_opening_proof_verifies_as_returned() {
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(WorkPools::from_env()))
+                .app_data(web::Data::new(ReplayRegistry::from_env()))
+                .app_data(web::Data::new(ReceiptSigner::disabled()))
+                .service(generate_scalar_commitment_with_blinding_ep)
+                .service(verify_opening_proof_ep),
+        )
+        .await;
+        let context_hash_hex = "44".repeat(32);
+        let request = TestRequest::post()
+            .uri("/zkp/generate-scalar-commitment-with-blinding")
+            .set_json(json!({ "value": 30, "blinding_hex": "07".repeat(32), "context_hash_hex": context_hash_hex }))
+            .to_request();
+        let proved: serde_json::Value = call_and_read_body_json(&app, request).await;
+        assert_eq!(proved["proof_type"], "pedersen-opening-v1");
+
+        let request = TestRequest::post()
+            .uri("/zkp/verify-opening-proof")
+            .set_json(json!({
+                "commitment_hex": proved["commitment"],
+                "proof_r_hex": proved["proof_r_hex"],
+                "proof_s_value_hex": proved["proof_s_value_hex"],
+                "proof_s_blinding_hex": proved["proof_s_blinding_hex"],
+                "context_hash_hex": context_hash_hex,
+            }))
+            .to_request();
+        let outcome: serde_json::Value = call_and_read_body_json(&app, request).await;
+        assert_eq!(outcome["verified"], true);
     }
 
     #[test]
-    fn parse_decimal_scalar_strict_rejects_non_canonical_scalar() {
-        let value = "7237005577332262213973186563042994240857116359379907606001950938285454250990";
-        assert!(parse_decimal_scalar_strict(value).is_none());
+    fn unknown_request_fields_are_rejected() {
+        let body = json!({
+            "commitment": "00",
+            "proof": "00",
+            "binding_tag_hex": null,
+            "bindingTag": "typo",
+        });
+        assert!(serde_json::from_value::<ValueVerifyInput>(body).is_err());
     }
 }
@@ -0,0 +1,334 @@
+//! Prometheus metrics, served at `/metrics`.
+//!
+//!   zkp_http_requests_total{route, method, status}
+//!   zkp_bad_requests_total{reason}
+//!   zkp_verifications_total{proof_kind, outcome}     outcome = "verified" | "failed"
+//!   zkp_prove_duration_seconds{proof_kind}           histogram, successful prove routes
+//!   zkp_verify_duration_seconds{proof_kind}          histogram, successful verify routes
+//!   zkp_proof_size_bytes{proof_kind}                 histogram, proofs returned by prove routes
+//!   zkp_pool_queued{pool}                            jobs waiting for a prove/verify worker
+//!   zkp_pool_rejected_total{pool, reason}            reason = "saturated" | "timeout" | "panicked"
+//!
+//! `proof_kind` uses the names replay nullifiers and receipts already use
+//! (txid, txid-bp-plus, txid-bp4, value-range, equality, quantity-total,
+//! opening, comparison, delivery, order-vrc, passport, carbon-footprint,
+//! recycled-content, bbs, mpc-range, note-link, revocation), one per `zk::*`
+//! proof type. Job submissions are timed under `jobs`.
+
+use std::sync::LazyLock;
+use std::time::Instant;
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::middleware::Next;
+use actix_web::{get, HttpResponse, Responder};
+use prometheus::{
+    exponential_buckets, histogram_opts, opts, Encoder, HistogramVec, IntCounterVec, IntGaugeVec, Registry,
+    TextEncoder,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Operation {
+    Prove,
+    Verify,
+}
+
+/// Route pattern → (operation, proof kind), for the latency histograms.
+const PROOF_ROUTES: &[(&str, Operation, &str)] = &[
+    ("/zkp/generate", Operation::Prove, "txid"),
+    ("/zkp/commit-tx-hash", Operation::Prove, "txid"),
+    ("/zkp/verify", Operation::Verify, "txid"),
+    ("/zkp/prove_plus", Operation::Prove, "txid-bp-plus"),
+    ("/zkp/verify_plus", Operation::Verify, "txid-bp-plus"),
+    ("/zkp/generate_bp4", Operation::Prove, "txid-bp4"),
+    ("/zkp/verify_bp4", Operation::Verify, "txid-bp4"),
+    ("/zkp/commit-value", Operation::Prove, "value-range"),
+    ("/zkp/generate-value-commitment", Operation::Prove, "value-range"),
+    ("/zkp/generate-value-commitment-with-blinding", Operation::Prove, "value-range"),
+    ("/zkp/generate-value-commitment-with-binding", Operation::Prove, "value-range"),
+    ("/zkp/verify-value", Operation::Verify, "value-range"),
+    ("/zkp/verify-value-commitment", Operation::Verify, "value-range"),
+    ("/zkp/generate-scalar-commitment-with-blinding", Operation::Prove, "opening"),
+    ("/zkp/generate-opening-proof", Operation::Prove, "opening"),
+    ("/zkp/verify-opening-proof", Operation::Verify, "opening"),
+    ("/zkp/generate-equality-proof", Operation::Prove, "equality"),
+    ("/zkp/verify-equality-proof", Operation::Verify, "equality"),
+    ("/zkp/generate-total-payment-equality-proof", Operation::Prove, "equality"),
+    ("/zkp/verify-total-payment-equality-proof", Operation::Verify, "equality"),
+    ("/zkp/generate-quantity-total-proof", Operation::Prove, "quantity-total"),
+    ("/zkp/verify-quantity-total-proof", Operation::Verify, "quantity-total"),
+    ("/zkp/generate-comparison-proof", Operation::Prove, "comparison"),
+    ("/zkp/verify-comparison-proof", Operation::Verify, "comparison"),
+    ("/zkp/generate-delivery-proof", Operation::Prove, "delivery"),
+    ("/zkp/verify-delivery-proof", Operation::Verify, "delivery"),
+    ("/zkp/verify-vc", Operation::Verify, "order-vrc"),
+    ("/zkp/passport/prove", Operation::Prove, "passport"),
+    ("/zkp/passport/verify", Operation::Verify, "passport"),
+    ("/zkp/carbon-footprint/prove", Operation::Prove, "carbon-footprint"),
+    ("/zkp/carbon-footprint/verify", Operation::Verify, "carbon-footprint"),
+    ("/zkp/recycled-content/prove", Operation::Prove, "recycled-content"),
+    ("/zkp/recycled-content/verify", Operation::Verify, "recycled-content"),
+    ("/zkp/bbs/issue", Operation::Prove, "bbs"),
+    ("/zkp/bbs/derive-proof", Operation::Prove, "bbs"),
+    ("/zkp/bbs/verify", Operation::Verify, "bbs"),
+    ("/zkp/mpc/sessions", Operation::Prove, "mpc-range"),
+    ("/zkp/mpc/sessions/{id}/bit-commitments", Operation::Prove, "mpc-range"),
+    ("/zkp/mpc/sessions/{id}/poly-commitments", Operation::Prove, "mpc-range"),
+    ("/zkp/mpc/sessions/{id}/proof-shares", Operation::Prove, "mpc-range"),
+    ("/zkp/mpc/verify", Operation::Verify, "mpc-range"),
+    ("/zkp/note-link/keys", Operation::Prove, "note-link"),
+    ("/zkp/note-link/prove", Operation::Prove, "note-link"),
+    ("/zkp/note-link/verify", Operation::Verify, "note-link"),
+    ("/zkp/revocation/updates", Operation::Prove, "revocation"),
+    ("/zkp/revocation/witnesses", Operation::Prove, "revocation"),
+    ("/zkp/revocation/verify", Operation::Verify, "revocation"),
+    ("/zkp/jobs", Operation::Prove, "jobs"),
+    ("/v2/commitments", Operation::Prove, "value-range"),
+    ("/v2/proofs/range/verify", Operation::Verify, "value-range"),
+    ("/v2/proofs/equality", Operation::Prove, "equality"),
+    ("/v2/proofs/equality/verify", Operation::Verify, "equality"),
+    ("/v2/proofs/quantity-total", Operation::Prove, "quantity-total"),
+    ("/v2/proofs/quantity-total/verify", Operation::Verify, "quantity-total"),
+    ("/v2/txref", Operation::Prove, "txid"),
+    ("/v2/txref/verify", Operation::Verify, "txid"),
+];
+
+struct Metrics {
+    registry: Registry,
+    http_requests: IntCounterVec,
+    bad_requests: IntCounterVec,
+    verifications: IntCounterVec,
+    prove_duration: HistogramVec,
+    verify_duration: HistogramVec,
+    proof_size: HistogramVec,
+    pool_queued: IntGaugeVec,
+    pool_rejected: IntCounterVec,
+}
+
+impl Metrics {
+    fn new() -> prometheus::Result<Self> {
+        // 1 ms .. ~16 s; classic BP proving sits around 10-100 ms.
+        let latency_buckets = exponential_buckets(0.001, 2.0, 15)?;
+        let metrics = Self {
+            registry: Registry::new(),
+            http_requests: IntCounterVec::new(
+                opts!("zkp_http_requests_total", "HTTP requests by route pattern, method and status"),
+                &["route", "method", "status"],
+            )?,
+            bad_requests: IntCounterVec::new(
+                opts!("zkp_bad_requests_total", "Requests rejected with 400, by reason"),
+                &["reason"],
+            )?,
+            verifications: IntCounterVec::new(
+                opts!("zkp_verifications_total", "Verification outcomes by proof kind"),
+                &["proof_kind", "outcome"],
+            )?,
+            prove_duration: HistogramVec::new(
+                histogram_opts!("zkp_prove_duration_seconds", "Latency of successful prove requests", latency_buckets.clone()),
+                &["proof_kind"],
+            )?,
+            verify_duration: HistogramVec::new(
+                histogram_opts!("zkp_verify_duration_seconds", "Latency of successful verify requests", latency_buckets),
+                &["proof_kind"],
+            )?,
+            proof_size: HistogramVec::new(
+                histogram_opts!("zkp_proof_size_bytes", "Size of generated proofs", exponential_buckets(32.0, 2.0, 10)?),
+                &["proof_kind"],
+            )?,
+            pool_queued: IntGaugeVec::new(opts!("zkp_pool_queued", "Jobs waiting for a worker, by pool"), &["pool"])?,
+            pool_rejected: IntCounterVec::new(
+                opts!("zkp_pool_rejected_total", "Jobs turned away by a worker pool, by reason"),
+                &["pool", "reason"],
+            )?,
+        };
+        metrics.registry.register(Box::new(metrics.http_requests.clone()))?;
+        metrics.registry.register(Box::new(metrics.bad_requests.clone()))?;
+        metrics.registry.register(Box::new(metrics.verifications.clone()))?;
+        metrics.registry.register(Box::new(metrics.prove_duration.clone()))?;
+        metrics.registry.register(Box::new(metrics.verify_duration.clone()))?;
+        metrics.registry.register(Box::new(metrics.proof_size.clone()))?;
+        metrics.registry.register(Box::new(metrics.pool_queued.clone()))?;
+        metrics.registry.register(Box::new(metrics.pool_rejected.clone()))?;
+        Ok(metrics)
+    }
+}
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(|| Metrics::new().expect("metric definitions are valid"));
+
+pub fn record_verification(proof_kind: &str, verified: bool) {
+    let outcome = if verified { "verified" } else { "failed" };
+    METRICS.verifications.with_label_values(&[proof_kind, outcome]).inc();
+}
+
+pub fn record_proof_size(proof_kind: &str, bytes: usize) {
+    METRICS.proof_size.with_label_values(&[proof_kind]).observe(bytes as f64);
+}
+
+/// Size of a hex-encoded proof, for handlers that only hold the response.
+pub fn record_proof_hex(proof_kind: &str, hex_parts: &[&str]) {
+    record_proof_size(proof_kind, hex_parts.iter().map(|part| part.len() / 2).sum());
+}
+
+pub fn set_pool_queued(pool: &str, queued: usize) {
+    METRICS.pool_queued.with_label_values(&[pool]).set(queued as i64);
+}
+
+pub fn record_pool_rejection(pool: &str, reason: &str) {
+    METRICS.pool_rejected.with_label_values(&[pool, reason]).inc();
+}
+
+/// Counts a 400 under the part of its message before the first `:`, so
+/// messages that echo input (serde errors) still map onto a small label set.
+pub fn record_bad_request(message: &str) {
+    METRICS.bad_requests.with_label_values(&[bad_request_reason(message)]).inc();
+}
+
+fn bad_request_reason(message: &str) -> &str {
+    let reason = message.split(':').next().unwrap_or_default().trim();
+    match reason.char_indices().nth(64) {
+        Some((end, _)) => &reason[..end],
+        None => reason,
+    }
+}
+
+/// Middleware counting requests and timing the prove/verify routes.
+pub async fn track(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let route = req.match_pattern().unwrap_or_else(|| "unmatched".to_string());
+    let method = req.method().to_string();
+    let started = Instant::now();
+
+    let result = next.call(req).await;
+    let status = match &result {
+        Ok(res) => res.status(),
+        Err(error) => error.as_response_error().status_code(),
+    };
+    METRICS
+        .http_requests
+        .with_label_values(&[&route, &method, status.as_str()])
+        .inc();
+
+    if status.is_success() {
+        if let Some((_, operation, kind)) = PROOF_ROUTES.iter().find(|(pattern, _, _)| *pattern == route) {
+            let histogram = match operation {
+                Operation::Prove => &METRICS.prove_duration,
+                Operation::Verify => &METRICS.verify_duration,
+            };
+            histogram.with_label_values(&[kind]).observe(started.elapsed().as_secs_f64());
+        }
+    }
+    result
+}
+
+fn render() -> Result<String, String> {
+    let mut buffer = Vec::new();
+    TextEncoder::new()
+        .encode(&METRICS.registry.gather(), &mut buffer)
+        .map_err(|e| e.to_string())?;
+    String::from_utf8(buffer).map_err(|e| e.to_string())
+}
+
+#[utoipa::path(tag = "health", responses((status = 200, description = "Prometheus text exposition format", content_type = "text/plain")))]
+#[get("/metrics")]
+pub async fn metrics_ep() -> impl Responder {
+    match render() {
+        Ok(body) => HttpResponse::Ok()
+            .content_type("text/plain; version=0.0.4; charset=utf-8")
+            .body(body),
+        Err(error) => HttpResponse::InternalServerError().body(error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::{call_service, init_service, TestRequest};
+    use actix_web::{middleware, web, App};
+    use serde_json::json;
+
+    use crate::pool::WorkPools;
+
+    #[test]
+    fn bad_request_reasons_drop_echoed_input() {
+        assert_eq!(
+            bad_request_reason("Json deserialize error: unknown field `secret`, expected one of `value`"),
+            "Json deserialize error"
+        );
+        assert_eq!(bad_request_reason("bad proof"), "bad proof");
+        assert_eq!(bad_request_reason(&"x".repeat(200)).len(), 64);
+    }
+
+    #[test]
+    fn rendered_metrics_include_recorded_series() {
+        record_verification("delivery", true);
+        record_verification("delivery", false);
+        record_proof_size("delivery", 700);
+
+        let body = render().unwrap();
+        assert!(body.contains(r#"zkp_verifications_total{outcome="verified",proof_kind="delivery"}"#));
+        assert!(body.contains(r#"zkp_verifications_total{outcome="failed",proof_kind="delivery"}"#));
+        assert!(body.contains(r#"zkp_proof_size_bytes_count{proof_kind="delivery"} 1"#));
+    }
+
+    #[test]
+    fn every_proof_route_is_registered_once() {
+        for (i, (route, _, _)) in PROOF_ROUTES.iter().enumerate() {
+            assert!(PROOF_ROUTES[i + 1..].iter().all(|(other, _, _)| other != route), "{route} listed twice");
+        }
+    }
+
+    /// POST routes that neither prove nor verify a proof.
+    const UNTIMED_ROUTES: &[&str] = &["/zkp/order-context-hash", "/zkp/receipts/verify", "/zkp/replay/status"];
+
+    /// Drives every documented route through the real app and the `track`
+    /// middleware, then checks the rendered metrics.
+    #[actix_web::test]
+    async fn every_route_is_tracked_and_every_proof_route_timed() {
+        let app = init_service(
+            App::new()
+                .wrap(middleware::from_fn(track))
+                .app_data(web::Data::new(WorkPools::from_env()))
+                .configure(crate::configure),
+        )
+        .await;
+
+        let doc = crate::openapi_doc();
+        let mut post_routes = Vec::new();
+        for (route, item) in &doc.paths.paths {
+            // Path parameters only need to match; the handler may reject them.
+            let uri: String = route
+                .split('/')
+                .map(|segment| if segment.starts_with('{') { "x" } else { segment })
+                .collect::<Vec<_>>()
+                .join("/");
+            if item.get.is_some() {
+                call_service(&app, TestRequest::get().uri(&uri).to_request()).await;
+            }
+            if item.post.is_some() {
+                call_service(&app, TestRequest::post().uri(&uri).set_json(json!({})).to_request()).await;
+                post_routes.push(route.as_str());
+            }
+        }
+        let opening = json!({ "value": 9, "blinding_hex": "07".repeat(32), "context_hash_hex": "11".repeat(32) });
+        let response =
+            call_service(&app, TestRequest::post().uri("/zkp/generate-opening-proof").set_json(opening).to_request()).await;
+        assert!(response.status().is_success());
+
+        let body = render().unwrap();
+        for route in doc.paths.paths.keys() {
+            assert!(body.contains(&format!(r#"route="{route}""#)), "{route} not tracked under its pattern");
+        }
+        let untimed: Vec<_> = post_routes
+            .iter()
+            .filter(|route| !UNTIMED_ROUTES.contains(route))
+            .filter(|route| PROOF_ROUTES.iter().all(|(pattern, _, _)| pattern != *route))
+            .collect();
+        assert!(untimed.is_empty(), "routes without a latency histogram: {untimed:?}");
+        let stale: Vec<_> =
+            PROOF_ROUTES.iter().filter(|(pattern, _, _)| !post_routes.contains(pattern)).map(|(pattern, _, _)| pattern).collect();
+        assert!(stale.is_empty(), "timed patterns no POST route matches: {stale:?}");
+        assert!(body.contains(r#"zkp_prove_duration_seconds_count{proof_kind="opening"}"#));
+    }
+}
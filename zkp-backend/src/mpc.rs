@@ -0,0 +1,618 @@
+//! Multi-party aggregated range proof sessions (`zk::mpc_range`).
+//!
+//!   POST /zkp/mpc/sessions                          — open a session for m parties, n bits, one order context
+//!   GET  /zkp/mpc/sessions/{id}                     — round, submitted positions, challenges, final proof
+//!   POST /zkp/mpc/sessions/{id}/bit-commitments     — round 1 message of one position
+//!   POST /zkp/mpc/sessions/{id}/poly-commitments    — round 2 message of one position
+//!   POST /zkp/mpc/sessions/{id}/proof-shares        — round 3 message of one position
+//!   POST /zkp/mpc/verify                            — verify the aggregated proof
+//!
+//! The server only acts as dealer: parties run their side (zkp-wasm's
+//! `MpcRangeParty`) and post messages, then poll the session for the next
+//! challenge. When the last share arrives the proof is aggregated and
+//! checked; a bad share fails the session and names its position.
+//!
+//! Sessions live in memory. Configured from the environment:
+//!   ZKP_MPC_SESSION_TTL_SECS — idle sessions are dropped after this long (default 900)
+//!   ZKP_MPC_MAX_SESSIONS     — open sessions allowed at once (default 1024)
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use actix_web::{get, post, web, HttpResponse, Responder};
+use bulletproof_demo::api::{parse_context_hash_hex, parse_scalar_hex, IntegerLike};
+use bulletproof_demo::zk::mpc_range::{
+    self, committed_value, decode_bit_commitment, decode_poly_commitment, decode_proof_share, encode_bit_challenge,
+    encode_poly_challenge,
+};
+use bulletproofs::range_proof_mpc::messages::{BitCommitment, PolyCommitment, ProofShare};
+use curve25519_dalek_ng::ristretto::CompressedRistretto;
+use curve25519_dalek_ng::scalar::Scalar;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+use crate::pool::WorkPools;
+use crate::receipts::{ReceiptSigner, VerificationReceipt};
+use crate::replay::{ReplayRegistry, ReplayStatus};
+use crate::{bad_req, check_replay, metrics, ErrorResponse};
+
+const DEFAULT_TTL: Duration = Duration::from_secs(900);
+const DEFAULT_MAX_SESSIONS: usize = 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Round {
+    BitCommitments,
+    PolyCommitments,
+    ProofShares,
+    Complete,
+    Failed,
+}
+
+struct Session {
+    context_hash: [u8; 32],
+    bits: usize,
+    parties: usize,
+    touched: Instant,
+    bit_commitments: Vec<Option<BitCommitment>>,
+    poly_commitments: Vec<Option<PolyCommitment>>,
+    proof_shares: Vec<Option<ProofShare>>,
+    bit_challenge: Option<Vec<u8>>,
+    poly_challenge: Option<Vec<u8>>,
+    proof: Option<Vec<u8>>,
+    error: Option<String>,
+}
+
+fn all<T: Clone>(slots: &[Option<T>]) -> Option<Vec<T>> {
+    slots.iter().cloned().collect()
+}
+
+impl Session {
+    fn round(&self) -> Round {
+        if self.error.is_some() {
+            Round::Failed
+        } else if self.proof.is_some() {
+            Round::Complete
+        } else if self.poly_challenge.is_some() {
+            Round::ProofShares
+        } else if self.bit_challenge.is_some() {
+            Round::PolyCommitments
+        } else {
+            Round::BitCommitments
+        }
+    }
+
+    fn submitted(&self) -> Vec<usize> {
+        let present = |j: usize| match self.round() {
+            Round::BitCommitments => self.bit_commitments[j].is_some(),
+            Round::PolyCommitments => self.poly_commitments[j].is_some(),
+            _ => self.proof_shares[j].is_some(),
+        };
+        (0..self.parties).filter(|&j| present(j)).collect()
+    }
+
+    fn commitments(&self) -> Option<Vec<CompressedRistretto>> {
+        Some(all(&self.bit_commitments)?.iter().map(committed_value).collect())
+    }
+
+    fn view(&self, session_id: &str) -> SessionResponse {
+        SessionResponse {
+            session_id: session_id.to_string(),
+            context_hash_hex: hex::encode(self.context_hash),
+            bits: self.bits,
+            parties: self.parties,
+            round: self.round(),
+            submitted: self.submitted(),
+            commitments_hex: self
+                .commitments()
+                .map(|commitments| commitments.iter().map(|c| hex::encode(c.as_bytes())).collect()),
+            bit_challenge_hex: self.bit_challenge.as_ref().map(hex::encode),
+            poly_challenge_hex: self.poly_challenge.as_ref().map(hex::encode),
+            proof_hex: self.proof.as_ref().map(hex::encode),
+            error: self.error.clone(),
+        }
+    }
+
+    /// Stores one position's message; completing a round runs the dealer.
+    fn submit(&mut self, round: Round, position: usize, message_hex: &str) -> Result<(), HttpResponse> {
+        if self.round() != round {
+            return Err(HttpResponse::Conflict().json(ErrorResponse {
+                error: format!("session is not accepting {}", round_name(round)),
+            }));
+        }
+        if position >= self.parties {
+            return Err(bad_req(&format!("position must be below {}", self.parties)));
+        }
+        let occupied = match round {
+            Round::BitCommitments => self.bit_commitments[position].is_some(),
+            Round::PolyCommitments => self.poly_commitments[position].is_some(),
+            _ => self.proof_shares[position].is_some(),
+        };
+        if occupied {
+            return Err(HttpResponse::Conflict().json(ErrorResponse {
+                error: format!("position {position} already submitted this round"),
+            }));
+        }
+        let bytes = hex::decode(message_hex.trim_start_matches("0x")).map_err(|_| bad_req("invalid message_hex"))?;
+        match round {
+            Round::BitCommitments => {
+                let message = decode_bit_commitment(&bytes).ok_or_else(|| bad_req("invalid bit commitment"))?;
+                self.bit_commitments[position] = Some(message);
+            }
+            Round::PolyCommitments => {
+                let message = decode_poly_commitment(&bytes).ok_or_else(|| bad_req("invalid poly commitment"))?;
+                self.poly_commitments[position] = Some(message);
+            }
+            _ => {
+                let message = decode_proof_share(&bytes, self.bits).ok_or_else(|| bad_req("invalid proof share"))?;
+                self.proof_shares[position] = Some(message);
+            }
+        }
+        self.advance();
+        Ok(())
+    }
+
+    fn advance(&mut self) {
+        let Some(bit_commitments) = all(&self.bit_commitments) else {
+            return;
+        };
+        let outcome = match (all(&self.poly_commitments), all(&self.proof_shares)) {
+            (None, _) => mpc_range::bit_challenge(&self.context_hash, self.bits, &bit_commitments)
+                .map(|challenge| self.bit_challenge = Some(encode_bit_challenge(&challenge))),
+            (Some(poly_commitments), None) => {
+                mpc_range::poly_challenge(&self.context_hash, self.bits, &bit_commitments, &poly_commitments)
+                    .map(|challenge| self.poly_challenge = Some(encode_poly_challenge(&challenge)))
+            }
+            (Some(poly_commitments), Some(shares)) => {
+                mpc_range::aggregate(&self.context_hash, self.bits, &bit_commitments, &poly_commitments, &shares)
+                    .map(|proof| {
+                        metrics::record_proof_size("mpc-range", proof.len());
+                        self.proof = Some(proof);
+                    })
+            }
+        };
+        if let Err(error) = outcome {
+            warn!(%error, "mpc session failed");
+            self.error = Some(error);
+        }
+    }
+}
+
+fn round_name(round: Round) -> &'static str {
+    match round {
+        Round::BitCommitments => "bit commitments",
+        Round::PolyCommitments => "poly commitments",
+        Round::ProofShares => "proof shares",
+        Round::Complete | Round::Failed => "messages",
+    }
+}
+
+pub struct MpcSessions {
+    sessions: Mutex<HashMap<String, Session>>,
+    ttl: Duration,
+    max_sessions: usize,
+}
+
+impl MpcSessions {
+    pub fn from_env() -> Self {
+        let ttl = std::env::var("ZKP_MPC_SESSION_TTL_SECS")
+            .ok()
+            .and_then(|value| value.trim().parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_TTL);
+        let max_sessions = std::env::var("ZKP_MPC_MAX_SESSIONS")
+            .ok()
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(DEFAULT_MAX_SESSIONS);
+        Self { sessions: Mutex::new(HashMap::new()), ttl, max_sessions }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Session>> {
+        let mut sessions = self.sessions.lock().expect("mpc sessions lock");
+        sessions.retain(|_, session| session.touched.elapsed() < self.ttl);
+        sessions
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CreateSessionRequest {
+    context_hash_hex: String,
+    /// Number of positions; a power of two up to 16.
+    parties: usize,
+    /// Each value is proven to lie in [0, 2^bits): 8, 16, 32 or 64.
+    #[serde(default = "default_bits")]
+    bits: usize,
+}
+
+fn default_bits() -> usize {
+    64
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SessionResponse {
+    session_id: String,
+    context_hash_hex: String,
+    bits: usize,
+    parties: usize,
+    round: Round,
+    /// Positions that have sent their message for the current round.
+    submitted: Vec<usize>,
+    /// V_j of every position, in order, once round 1 is complete.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    commitments_hex: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bit_challenge_hex: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    poly_challenge_hex: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    proof_hex: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RoundMessageRequest {
+    position: usize,
+    message_hex: String,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct MpcVerifyRequest {
+    commitments_hex: Vec<String>,
+    #[serde(default = "default_bits")]
+    bits: usize,
+    context_hash_hex: String,
+    proof_hex: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct MpcVerifyResponse {
+    verified: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    replay: Option<ReplayStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    receipt: Option<VerificationReceipt>,
+}
+
+/// A `/zkp/jobs` item of kind "aggregated-range": one prover holding every
+/// opening. The result verifies with `POST /zkp/mpc/verify`.
+#[derive(Deserialize, Serialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AggregatedRangeRequest {
+    /// A power of two up to 16 values.
+    values: Vec<IntegerLike>,
+    /// 32-byte scalars, one per value; random blindings are used when omitted.
+    #[serde(default)]
+    blindings_hex: Option<Vec<String>>,
+    #[serde(default = "default_bits")]
+    bits: usize,
+    context_hash_hex: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct AggregatedRangeResponse {
+    commitments_hex: Vec<String>,
+    bits: usize,
+    context_hash_hex: String,
+    proof_hex: String,
+    verified: bool,
+}
+
+/// Runs a job item of kind "aggregated-range".
+pub(crate) fn prove_aggregated(req: &AggregatedRangeRequest) -> Result<AggregatedRangeResponse, String> {
+    let context_hash = parse_context_hash_hex(&req.context_hash_hex).ok_or("invalid context_hash_hex")?;
+    let values = req.values.iter().map(|value| value.parse_u64("values")).collect::<Result<Vec<_>, _>>()?;
+    let blindings = match &req.blindings_hex {
+        Some(blindings_hex) => blindings_hex
+            .iter()
+            .map(|blinding_hex| parse_scalar_hex(blinding_hex))
+            .collect::<Option<Vec<_>>>()
+            .ok_or("invalid blindings_hex: each must be 32 bytes (64 hex chars)")?,
+        None => values
+            .iter()
+            .map(|_| {
+                let mut bytes = [0u8; 32];
+                OsRng.fill_bytes(&mut bytes);
+                Scalar::from_bytes_mod_order(bytes)
+            })
+            .collect(),
+    };
+
+    let (commitments, proof) = mpc_range::prove(&values, &blindings, &context_hash, req.bits)?;
+    let verified = mpc_range::verify(&commitments, &proof, &context_hash, req.bits);
+    metrics::record_proof_size("mpc-range", proof.len());
+    Ok(AggregatedRangeResponse {
+        commitments_hex: commitments.iter().map(|c| hex::encode(c.as_bytes())).collect(),
+        bits: req.bits,
+        context_hash_hex: hex::encode(context_hash),
+        proof_hex: hex::encode(proof),
+        verified,
+    })
+}
+
+fn not_found() -> HttpResponse {
+    HttpResponse::NotFound().json(ErrorResponse { error: "unknown or expired session".to_string() })
+}
+
+#[utoipa::path(
+    tag = "mpc",
+    request_body = CreateSessionRequest,
+    responses(
+        (status = 201, body = SessionResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 503, body = ErrorResponse),
+    )
+)]
+#[post("/zkp/mpc/sessions")]
+pub async fn create_session(req: web::Json<CreateSessionRequest>, sessions: web::Data<MpcSessions>) -> impl Responder {
+    let Some(context_hash) = parse_context_hash_hex(&req.context_hash_hex) else {
+        return bad_req("invalid context_hash_hex");
+    };
+    if let Err(error) = mpc_range::check_parameters(req.bits, req.parties) {
+        return bad_req(&error);
+    }
+
+    let mut open = sessions.lock();
+    if open.len() >= sessions.max_sessions {
+        return HttpResponse::ServiceUnavailable().json(ErrorResponse { error: "too many open mpc sessions".to_string() });
+    }
+    let session_id = hex::encode(rand::random::<[u8; 16]>());
+    let session = Session {
+        context_hash,
+        bits: req.bits,
+        parties: req.parties,
+        touched: Instant::now(),
+        bit_commitments: vec![None; req.parties],
+        poly_commitments: vec![None; req.parties],
+        proof_shares: vec![None; req.parties],
+        bit_challenge: None,
+        poly_challenge: None,
+        proof: None,
+        error: None,
+    };
+    let response = session.view(&session_id);
+    info!(%session_id, parties = req.parties, bits = req.bits, "opened mpc session");
+    open.insert(session_id, session);
+    HttpResponse::Created().json(response)
+}
+
+#[utoipa::path(
+    tag = "mpc",
+    params(("id" = String, Path)),
+    responses(
+        (status = 200, body = SessionResponse),
+        (status = 404, body = ErrorResponse),
+    )
+)]
+#[get("/zkp/mpc/sessions/{id}")]
+pub async fn session_status(path: web::Path<String>, sessions: web::Data<MpcSessions>) -> impl Responder {
+    let session_id = path.into_inner();
+    match sessions.lock().get(&session_id) {
+        Some(session) => HttpResponse::Ok().json(session.view(&session_id)),
+        None => not_found(),
+    }
+}
+
+async fn submit(
+    round: Round,
+    session_id: String,
+    req: RoundMessageRequest,
+    sessions: web::Data<MpcSessions>,
+    pools: web::Data<WorkPools>,
+) -> HttpResponse {
+    // The last proof share triggers aggregation, so every round runs on the pool.
+    pools.prove.run(move || {
+        let mut open = sessions.lock();
+        let Some(session) = open.get_mut(&session_id) else {
+            return not_found();
+        };
+        session.touched = Instant::now();
+        match session.submit(round, req.position, &req.message_hex) {
+            Ok(()) => HttpResponse::Ok().json(session.view(&session_id)),
+            Err(response) => response,
+        }
+    })
+    .await
+}
+
+#[utoipa::path(
+    tag = "mpc",
+    params(("id" = String, Path)),
+    request_body = RoundMessageRequest,
+    responses(
+        (status = 200, body = SessionResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+        (status = 409, body = ErrorResponse),
+    )
+)]
+#[post("/zkp/mpc/sessions/{id}/bit-commitments")]
+pub async fn submit_bit_commitment(
+    path: web::Path<String>,
+    req: web::Json<RoundMessageRequest>,
+    sessions: web::Data<MpcSessions>,
+    pools: web::Data<WorkPools>,
+) -> impl Responder {
+    submit(Round::BitCommitments, path.into_inner(), req.into_inner(), sessions, pools).await
+}
+
+#[utoipa::path(
+    tag = "mpc",
+    params(("id" = String, Path)),
+    request_body = RoundMessageRequest,
+    responses(
+        (status = 200, body = SessionResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+        (status = 409, body = ErrorResponse),
+    )
+)]
+#[post("/zkp/mpc/sessions/{id}/poly-commitments")]
+pub async fn submit_poly_commitment(
+    path: web::Path<String>,
+    req: web::Json<RoundMessageRequest>,
+    sessions: web::Data<MpcSessions>,
+    pools: web::Data<WorkPools>,
+) -> impl Responder {
+    submit(Round::PolyCommitments, path.into_inner(), req.into_inner(), sessions, pools).await
+}
+
+#[utoipa::path(
+    tag = "mpc",
+    params(("id" = String, Path)),
+    request_body = RoundMessageRequest,
+    responses(
+        (status = 200, body = SessionResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+        (status = 409, body = ErrorResponse),
+    )
+)]
+#[post("/zkp/mpc/sessions/{id}/proof-shares")]
+pub async fn submit_proof_share(
+    path: web::Path<String>,
+    req: web::Json<RoundMessageRequest>,
+    sessions: web::Data<MpcSessions>,
+    pools: web::Data<WorkPools>,
+) -> impl Responder {
+    submit(Round::ProofShares, path.into_inner(), req.into_inner(), sessions, pools).await
+}
+
+#[utoipa::path(
+    tag = "mpc",
+    request_body = MpcVerifyRequest,
+    responses(
+        (status = 200, body = MpcVerifyResponse),
+        (status = 400, body = ErrorResponse),
+    )
+)]
+#[post("/zkp/mpc/verify")]
+pub async fn verify(
+    req: web::Json<MpcVerifyRequest>,
+    replay: web::Data<ReplayRegistry>,
+    receipts: web::Data<ReceiptSigner>,
+    pools: web::Data<WorkPools>,
+) -> impl Responder {
+    pools.verify.run(move || {
+        let Some(context_hash) = parse_context_hash_hex(&req.context_hash_hex) else {
+            return bad_req("invalid context_hash_hex");
+        };
+        let Some(commitments) = req
+            .commitments_hex
+            .iter()
+            .map(|c| bulletproof_demo::api::parse_hex32_bytes(c).map(CompressedRistretto))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return bad_req("invalid commitments_hex: each must be 32 bytes");
+        };
+        let Ok(proof_bytes) = hex::decode(req.proof_hex.trim_start_matches("0x")) else {
+            return bad_req("invalid proof_hex");
+        };
+
+        let verified = mpc_range::verify(&commitments, &proof_bytes, &context_hash, req.bits);
+        let replay = match check_replay(&replay, "mpc-range", &context_hash, &proof_bytes, verified) {
+            Ok(status) => status,
+            Err(response) => return response,
+        };
+        let receipt = receipts.sign("mpc-range", &*req, &context_hash, verified);
+        metrics::record_verification("mpc-range", verified);
+        HttpResponse::Ok().json(MpcVerifyResponse { verified, replay, receipt })
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::{call_service, init_service, read_body_json, TestRequest};
+    use actix_web::App;
+    use bulletproof_demo::zk::mpc_range::MpcParty;
+    use curve25519_dalek_ng::scalar::Scalar;
+    use serde_json::{json, Value};
+
+    fn message(position: usize, bytes: Vec<u8>) -> Value {
+        json!({ "position": position, "message_hex": hex::encode(bytes) })
+    }
+
+    fn challenge(status: &Value, field: &str) -> Vec<u8> {
+        hex::decode(status[field].as_str().unwrap()).unwrap()
+    }
+
+    #[actix_web::test]
+    async fn buyer_and_seller_build_one_proof() {
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(MpcSessions::from_env()))
+                .app_data(web::Data::new(WorkPools::from_env()))
+                .app_data(web::Data::new(ReplayRegistry::from_env()))
+                .app_data(web::Data::new(ReceiptSigner::disabled()))
+                .service(create_session)
+                .service(session_status)
+                .service(submit_bit_commitment)
+                .service(submit_poly_commitment)
+                .service(submit_proof_share)
+                .service(verify),
+        )
+        .await;
+        let context_hash_hex = "11".repeat(32);
+        let post = |uri: String, body: Value| TestRequest::post().uri(&uri).set_json(body).to_request();
+
+        let response = call_service(&app, post("/zkp/mpc/sessions".into(), json!({ "context_hash_hex": context_hash_hex, "parties": 2, "bits": 32 }))).await;
+        assert_eq!(response.status(), 201);
+        let session: Value = read_body_json(response).await;
+        let base = format!("/zkp/mpc/sessions/{}", session["session_id"].as_str().unwrap());
+
+        let mut seller = MpcParty::new(1_000, Scalar::from(7u64), 32).unwrap();
+        let mut buyer = MpcParty::new(4, Scalar::from(9u64), 32).unwrap();
+
+        let response = call_service(&app, post(format!("{base}/poly-commitments"), message(0, vec![0; 64]))).await;
+        assert_eq!(response.status(), 409);
+
+        let seller_bits = seller.bit_commitment(0).unwrap();
+        call_service(&app, post(format!("{base}/bit-commitments"), message(0, seller_bits.clone()))).await;
+        let response = call_service(&app, post(format!("{base}/bit-commitments"), message(0, seller_bits))).await;
+        assert_eq!(response.status(), 409);
+        let response = call_service(&app, post(format!("{base}/bit-commitments"), message(1, vec![0xff; 96]))).await;
+        assert_eq!(response.status(), 400);
+        let response = call_service(&app, post(format!("{base}/bit-commitments"), message(1, buyer.bit_commitment(1).unwrap()))).await;
+        let status: Value = read_body_json(response).await;
+        assert_eq!(status["round"], "poly_commitments");
+        let y_z = challenge(&status, "bit_challenge_hex");
+
+        call_service(&app, post(format!("{base}/poly-commitments"), message(0, seller.poly_commitment(&y_z).unwrap()))).await;
+        let response = call_service(&app, post(format!("{base}/poly-commitments"), message(1, buyer.poly_commitment(&y_z).unwrap()))).await;
+        let x = challenge(&read_body_json(response).await, "poly_challenge_hex");
+
+        call_service(&app, post(format!("{base}/proof-shares"), message(1, buyer.proof_share(&x).unwrap()))).await;
+        let response = call_service(&app, post(format!("{base}/proof-shares"), message(0, seller.proof_share(&x).unwrap()))).await;
+        let status: Value = read_body_json(response).await;
+        assert_eq!(status["round"], "complete");
+        assert_eq!(status["commitments_hex"][0], hex::encode(seller.commitment().as_bytes()));
+        assert_eq!(status["commitments_hex"][1], hex::encode(buyer.commitment().as_bytes()));
+
+        let verify_body = json!({
+            "commitments_hex": status["commitments_hex"],
+            "bits": 32,
+            "context_hash_hex": context_hash_hex,
+            "proof_hex": status["proof_hex"],
+        });
+        let outcome: Value = read_body_json(call_service(&app, post("/zkp/mpc/verify".into(), verify_body)).await).await;
+        assert_eq!(outcome["verified"], true);
+
+        let other_context = json!({
+            "commitments_hex": status["commitments_hex"],
+            "bits": 32,
+            "context_hash_hex": "12".repeat(32),
+            "proof_hex": status["proof_hex"],
+        });
+        let outcome: Value = read_body_json(call_service(&app, post("/zkp/mpc/verify".into(), other_context)).await).await;
+        assert_eq!(outcome["verified"], false);
+    }
+}
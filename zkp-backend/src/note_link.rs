@@ -0,0 +1,468 @@
+//! Railgun note linkage endpoints (`zk::note_link`).
+//!
+//!   POST /zkp/note-link/keys    — run a fresh Groth16 setup, replacing the current keys (admin scope)
+//!   GET  /zkp/note-link/keys    — current verifying key
+//!   POST /zkp/note-link/prove   — prove a note, its Baby Jubjub twin and `C_pay` hold the same amount
+//!   POST /zkp/note-link/verify  — verify against the server's key or a supplied one
+//!
+//! A proof is the Groth16 proof (note <-> twin) plus the cross-group
+//! `pay_link` (twin <-> `C_pay`); both must verify. A note commitment is
+//! accepted once per `context_hash`, whatever the proof bytes. Verifying
+//! against a supplied key records nothing in the replay registry, so a proof
+//! checked under a caller's own key cannot burn a note before the real
+//! submission, and returns no receipt, since the key is not the server's.
+//!
+//! Field elements (npk, token hash, note commitment) are 32-byte big-endian
+//! hex below the BN254 group order; the twin, keys and proofs use the
+//! compressed arkworks encoding. `C_pay` and `r_pay` are Ristretto hex as in
+//! the other payment endpoints.
+//!
+//! Configured from the environment:
+//!   ZKP_NOTE_LINK_KEYS — proving key file; loaded at startup and rewritten by
+//!                        POST /zkp/note-link/keys. Unset keeps keys in memory.
+
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+
+use actix_web::{get, post, web, HttpResponse, Responder};
+use ark_bn254::{Bn254, Fr};
+use ark_ed_on_bn254::{EdwardsAffine, Fr as BabyJubjubScalar};
+use ark_ff::{BigInteger, PrimeField, UniformRand};
+use ark_groth16::{Proof, ProvingKey, VerifyingKey};
+use bulletproof_demo::api::{
+    parse_compressed_ristretto, parse_context_hash_hex, parse_hex32_bytes, parse_scalar_hex, IntegerLike,
+};
+use bulletproof_demo::zk::note_link::{
+    self, context_scalar, from_bytes, key_id, to_bytes, NoteLinkStatement, NoteLinkWitness, PayLink,
+};
+use rand::rngs::OsRng;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+use crate::pool::WorkPools;
+use crate::receipts::{ReceiptSigner, VerificationReceipt};
+use crate::{bad_req, check_replay, metrics, ErrorResponse};
+use crate::replay::{ReplayRegistry, ReplayStatus};
+
+/// Proving key of the current setup; the verifying key is `pk.vk`.
+pub struct NoteLinkKeys {
+    path: Option<PathBuf>,
+    current: RwLock<Option<Arc<ProvingKey<Bn254>>>>,
+}
+
+impl NoteLinkKeys {
+    pub fn from_env() -> Self {
+        let path = std::env::var("ZKP_NOTE_LINK_KEYS").ok().map(PathBuf::from);
+        let current = path.as_ref().and_then(|path| match std::fs::read(path) {
+            Ok(bytes) => match from_bytes::<ProvingKey<Bn254>>(&bytes) {
+                Some(pk) => {
+                    info!(path = %path.display(), key_id = %key_id(&pk.vk), "loaded note-link keys");
+                    Some(Arc::new(pk))
+                }
+                None => {
+                    warn!(path = %path.display(), "ignoring unreadable note-link keys");
+                    None
+                }
+            },
+            Err(_) => None,
+        });
+        Self { path, current: RwLock::new(current) }
+    }
+
+    fn current(&self) -> Option<Arc<ProvingKey<Bn254>>> {
+        self.current.read().expect("note-link keys lock").clone()
+    }
+
+    fn replace(&self, pk: ProvingKey<Bn254>) -> Result<Arc<ProvingKey<Bn254>>, String> {
+        if let Some(path) = &self.path {
+            std::fs::write(path, to_bytes(&pk)).map_err(|e| format!("cannot write {}: {e}", path.display()))?;
+        }
+        let pk = Arc::new(pk);
+        *self.current.write().expect("note-link keys lock") = Some(pk.clone());
+        Ok(pk)
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct NoteLinkKeyResponse {
+    key_id: String,
+    verifying_key_hex: String,
+}
+
+impl NoteLinkKeyResponse {
+    fn new(vk: &VerifyingKey<Bn254>) -> Self {
+        Self { key_id: key_id(vk), verifying_key_hex: hex::encode(to_bytes(vk)) }
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct NoteLinkProveRequest {
+    /// Railgun note public key.
+    npk_hex: String,
+    token_hash_hex: String,
+    /// Note value in base units, below 2^120.
+    value: IntegerLike,
+    /// Baby Jubjub scalar blinding the twin; random when omitted.
+    #[serde(default)]
+    blinding_hex: Option<String>,
+    context_hash_hex: String,
+    /// Payment commitment to the same value.
+    c_pay_hex: String,
+    /// Blinding of `c_pay_hex`.
+    r_pay_hex: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct NoteLinkProveResponse {
+    note_commitment_hex: String,
+    twin_commitment_hex: String,
+    blinding_hex: String,
+    proof_hex: String,
+    pay_link_hex: String,
+    key_id: String,
+    verified: bool,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct NoteLinkVerifyRequest {
+    note_commitment_hex: String,
+    token_hash_hex: String,
+    twin_commitment_hex: String,
+    context_hash_hex: String,
+    proof_hex: String,
+    c_pay_hex: String,
+    pay_link_hex: String,
+    /// Verify against this key instead of the server's current one; the
+    /// proof is then neither recorded for replay protection nor receipted.
+    #[serde(default)]
+    verifying_key_hex: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct NoteLinkVerifyResponse {
+    verified: bool,
+    key_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    replay: Option<ReplayStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    receipt: Option<VerificationReceipt>,
+}
+
+fn no_keys() -> HttpResponse {
+    HttpResponse::Conflict().json(ErrorResponse {
+        error: "no note-link keys; POST /zkp/note-link/keys first".to_string(),
+    })
+}
+
+/// Canonical BN254 scalar-field element.
+fn parse_field_hex(hex_str: &str, field_name: &str) -> Result<Fr, String> {
+    let bytes = parse_hex32_bytes(hex_str).ok_or_else(|| format!("invalid {field_name}: must be 32 bytes"))?;
+    let value = Fr::from_be_bytes_mod_order(&bytes);
+    if value.into_bigint().to_bytes_be() != bytes {
+        return Err(format!("invalid {field_name}: not below the BN254 group order"));
+    }
+    Ok(value)
+}
+
+fn parse_encoded<T: ark_serialize::CanonicalDeserialize>(hex_str: &str, field_name: &str) -> Result<T, String> {
+    hex::decode(hex_str.trim_start_matches("0x"))
+        .ok()
+        .and_then(|bytes| from_bytes(&bytes))
+        .ok_or_else(|| format!("invalid {field_name}"))
+}
+
+fn parse_statement(req: &NoteLinkVerifyRequest) -> Result<(NoteLinkStatement, [u8; 32]), String> {
+    let context_hash = parse_context_hash_hex(&req.context_hash_hex).ok_or("invalid context_hash_hex")?;
+    let statement = NoteLinkStatement {
+        note_commitment: parse_field_hex(&req.note_commitment_hex, "note_commitment_hex")?,
+        token_hash: parse_field_hex(&req.token_hash_hex, "token_hash_hex")?,
+        twin: parse_encoded::<EdwardsAffine>(&req.twin_commitment_hex, "twin_commitment_hex")?,
+        context: context_scalar(&context_hash),
+    };
+    Ok((statement, context_hash))
+}
+
+/// Replaces the proving and verifying keys. Only credentials granted the
+/// admin route group may call this; see `security`.
+#[utoipa::path(
+    tag = "note-link",
+    responses(
+        (status = 200, body = NoteLinkKeyResponse),
+        (status = 500, body = ErrorResponse),
+    )
+)]
+#[post("/zkp/note-link/keys")]
+pub async fn create_keys(keys: web::Data<NoteLinkKeys>, pools: web::Data<WorkPools>) -> impl Responder {
+    pools.prove.run(move || {
+        let pk = match note_link::setup().and_then(|pk| keys.replace(pk)) {
+            Ok(pk) => pk,
+            Err(error) => return HttpResponse::InternalServerError().json(ErrorResponse { error }),
+        };
+        let response = NoteLinkKeyResponse::new(&pk.vk);
+        info!(key_id = %response.key_id, "generated note-link keys");
+        HttpResponse::Ok().json(response)
+    })
+    .await
+}
+
+#[utoipa::path(
+    tag = "note-link",
+    responses(
+        (status = 200, body = NoteLinkKeyResponse),
+        (status = 409, body = ErrorResponse),
+    )
+)]
+#[get("/zkp/note-link/keys")]
+pub async fn current_keys(keys: web::Data<NoteLinkKeys>) -> impl Responder {
+    match keys.current() {
+        Some(pk) => HttpResponse::Ok().json(NoteLinkKeyResponse::new(&pk.vk)),
+        None => no_keys(),
+    }
+}
+
+#[utoipa::path(
+    tag = "note-link",
+    request_body = NoteLinkProveRequest,
+    responses(
+        (status = 200, body = NoteLinkProveResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 409, body = ErrorResponse),
+    )
+)]
+#[post("/zkp/note-link/prove")]
+pub async fn prove(
+    req: web::Json<NoteLinkProveRequest>,
+    keys: web::Data<NoteLinkKeys>,
+    pools: web::Data<WorkPools>,
+) -> impl Responder {
+    let Some(pk) = keys.current() else {
+        return no_keys();
+    };
+    pools.prove.run(move || {
+        let witness = match parse_witness(&req) {
+            Ok(witness) => witness,
+            Err(error) => return bad_req(&error),
+        };
+        let (token_hash, context_hash) = match (
+            parse_field_hex(&req.token_hash_hex, "token_hash_hex"),
+            parse_context_hash_hex(&req.context_hash_hex),
+        ) {
+            (Ok(token_hash), Some(context_hash)) => (token_hash, context_hash),
+            (Err(error), _) => return bad_req(&error),
+            (_, None) => return bad_req("invalid context_hash_hex"),
+        };
+        let Some(c_pay) = parse_compressed_ristretto(&req.c_pay_hex) else {
+            return bad_req("invalid c_pay_hex");
+        };
+        let Some(r_pay) = parse_scalar_hex(&req.r_pay_hex) else {
+            return bad_req("invalid r_pay_hex");
+        };
+        let statement = witness.statement(token_hash, &context_hash);
+        let proof = match note_link::prove(&pk, &statement, &witness) {
+            Ok(proof) => proof,
+            Err(error) => return bad_req(&error),
+        };
+        let pay_link = match note_link::prove_pay_link(&statement, &witness, c_pay, r_pay) {
+            Ok(pay_link) => pay_link,
+            Err(error) => return bad_req(&error),
+        };
+        let proof_bytes = to_bytes(&proof);
+        let pay_link_bytes = pay_link.to_bytes();
+        metrics::record_proof_size("note-link", proof_bytes.len() + pay_link_bytes.len());
+        HttpResponse::Ok().json(NoteLinkProveResponse {
+            note_commitment_hex: hex::encode(statement.note_commitment.into_bigint().to_bytes_be()),
+            twin_commitment_hex: hex::encode(to_bytes(&statement.twin)),
+            blinding_hex: hex::encode(to_bytes(&witness.blinding)),
+            proof_hex: hex::encode(proof_bytes),
+            pay_link_hex: hex::encode(pay_link_bytes),
+            key_id: key_id(&pk.vk),
+            verified: note_link::verify(&pk.vk, &statement, &proof)
+                && note_link::verify_pay_link(&statement, c_pay, &pay_link),
+        })
+    })
+    .await
+}
+
+fn parse_witness(req: &NoteLinkProveRequest) -> Result<NoteLinkWitness, String> {
+    let value = req.value.parse_u128("value")?;
+    let blinding = match &req.blinding_hex {
+        Some(hex_str) => parse_encoded::<BabyJubjubScalar>(hex_str, "blinding_hex")?,
+        None => BabyJubjubScalar::rand(&mut OsRng),
+    };
+    Ok(NoteLinkWitness { npk: parse_field_hex(&req.npk_hex, "npk_hex")?, value, blinding })
+}
+
+#[utoipa::path(
+    tag = "note-link",
+    request_body = NoteLinkVerifyRequest,
+    responses(
+        (status = 200, body = NoteLinkVerifyResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 409, body = ErrorResponse),
+    )
+)]
+#[post("/zkp/note-link/verify")]
+pub async fn verify(
+    req: web::Json<NoteLinkVerifyRequest>,
+    keys: web::Data<NoteLinkKeys>,
+    replay: web::Data<ReplayRegistry>,
+    receipts: web::Data<ReceiptSigner>,
+    pools: web::Data<WorkPools>,
+) -> impl Responder {
+    let server_key = keys.current();
+    pools.verify.run(move || {
+        let vk = match (&req.verifying_key_hex, server_key) {
+            (Some(hex_str), _) => match parse_encoded::<VerifyingKey<Bn254>>(hex_str, "verifying_key_hex") {
+                Ok(vk) => vk,
+                Err(error) => return bad_req(&error),
+            },
+            (None, Some(pk)) => pk.vk.clone(),
+            (None, None) => return no_keys(),
+        };
+        let (statement, context_hash) = match parse_statement(&req) {
+            Ok(parsed) => parsed,
+            Err(error) => return bad_req(&error),
+        };
+        let proof = match parse_encoded::<Proof<Bn254>>(&req.proof_hex, "proof_hex") {
+            Ok(proof) => proof,
+            Err(error) => return bad_req(&error),
+        };
+        let Some(c_pay) = parse_compressed_ristretto(&req.c_pay_hex) else {
+            return bad_req("invalid c_pay_hex");
+        };
+        let Some(pay_link) = hex::decode(req.pay_link_hex.trim_start_matches("0x"))
+            .ok()
+            .and_then(|bytes| PayLink::from_bytes(&bytes))
+        else {
+            return bad_req("invalid pay_link_hex");
+        };
+
+        let verified =
+            note_link::verify(&vk, &statement, &proof) && note_link::verify_pay_link(&statement, c_pay, &pay_link);
+        // Replay is keyed on the statement: a Groth16 proof can be re-randomized.
+        let (replay, receipt) = if req.verifying_key_hex.is_some() {
+            (None, None)
+        } else {
+            let note = statement.note_commitment.into_bigint().to_bytes_be();
+            match check_replay(&replay, "note-link", &context_hash, &note, verified) {
+                Ok(status) => (status, receipts.sign("note-link", &*req, &context_hash, verified)),
+                Err(response) => return response,
+            }
+        };
+        metrics::record_verification("note-link", verified);
+        HttpResponse::Ok().json(NoteLinkVerifyResponse { verified, key_id: key_id(&vk), replay, receipt })
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::{call_service, init_service, read_body_json, TestRequest};
+    use actix_web::App;
+    use ark_groth16::Groth16;
+    use bulletproofs::PedersenGens;
+    use curve25519_dalek_ng::scalar::Scalar;
+    use serde_json::{json, Value};
+
+    use crate::receipts::ReceiptAlgorithm;
+    use crate::replay::ReplayMode;
+
+    #[actix_web::test]
+    async fn keys_prove_and_verify_round_trip() {
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(NoteLinkKeys { path: None, current: RwLock::new(None) }))
+                .app_data(web::Data::new(WorkPools::from_env()))
+                .app_data(web::Data::new(ReplayRegistry::open(":memory:", ReplayMode::Reject).unwrap()))
+                .app_data(web::Data::new(ReceiptSigner::from_secret(ReceiptAlgorithm::Ed25519, &[7u8; 32]).unwrap()))
+                .service(create_keys)
+                .service(current_keys)
+                .service(prove)
+                .service(verify),
+        )
+        .await;
+
+        let r_pay = Scalar::from(77u64);
+        let c_pay = PedersenGens::default().commit(Scalar::from(1_000_000_000_000_000_000u64), r_pay).compress();
+        let (c_pay_hex, r_pay_hex) = (hex::encode(c_pay.as_bytes()), hex::encode(r_pay.as_bytes()));
+        let prove_body = json!({
+            "npk_hex": "00".repeat(31) + "2a",
+            "token_hash_hex": "00".repeat(12) + &"ab".repeat(20),
+            "value": "1000000000000000000",
+            "context_hash_hex": "11".repeat(32),
+            "c_pay_hex": c_pay_hex,
+            "r_pay_hex": r_pay_hex,
+        });
+        let response = call_service(&app, TestRequest::post().uri("/zkp/note-link/prove").set_json(&prove_body).to_request()).await;
+        assert_eq!(response.status(), 409);
+
+        let response = call_service(&app, TestRequest::post().uri("/zkp/note-link/keys").to_request()).await;
+        assert_eq!(response.status(), 200);
+        let keys: Value = read_body_json(response).await;
+
+        let response = call_service(&app, TestRequest::post().uri("/zkp/note-link/prove").set_json(&prove_body).to_request()).await;
+        assert_eq!(response.status(), 200);
+        let proof: Value = read_body_json(response).await;
+        assert_eq!(proof["verified"], true);
+        assert_eq!(proof["key_id"], keys["key_id"]);
+
+        let mut verify_body = json!({
+            "note_commitment_hex": proof["note_commitment_hex"],
+            "token_hash_hex": prove_body["token_hash_hex"],
+            "twin_commitment_hex": proof["twin_commitment_hex"],
+            "context_hash_hex": prove_body["context_hash_hex"],
+            "proof_hex": proof["proof_hex"],
+            "c_pay_hex": prove_body["c_pay_hex"],
+            "pay_link_hex": proof["pay_link_hex"],
+            "verifying_key_hex": keys["verifying_key_hex"],
+        });
+        for _ in 0..2 {
+            let response = call_service(&app, TestRequest::post().uri("/zkp/note-link/verify").set_json(&verify_body).to_request()).await;
+            let outcome: Value = read_body_json(response).await;
+            assert_eq!(outcome["verified"], true);
+            assert!(outcome["replay"].is_null());
+            assert!(outcome["receipt"].is_null());
+        }
+
+        // Only checks against the server's key are recorded for replay.
+        let mut server_key = verify_body.clone();
+        server_key.as_object_mut().unwrap().remove("verifying_key_hex");
+        let response = call_service(&app, TestRequest::post().uri("/zkp/note-link/verify").set_json(&server_key).to_request()).await;
+        assert_eq!(response.status(), 200);
+        let outcome: Value = read_body_json(response).await;
+        assert!(outcome["receipt"].is_object());
+        let response = call_service(&app, TestRequest::post().uri("/zkp/note-link/verify").set_json(&server_key).to_request()).await;
+        assert_eq!(response.status(), 409);
+
+        // A re-randomized proof of the same statement is still a replay.
+        let vk: VerifyingKey<Bn254> = from_bytes(&hex::decode(keys["verifying_key_hex"].as_str().unwrap()).unwrap()).unwrap();
+        let original: Proof<Bn254> = from_bytes(&hex::decode(proof["proof_hex"].as_str().unwrap()).unwrap()).unwrap();
+        let rerandomized = Groth16::<Bn254>::rerandomize_proof(&vk, &original, &mut OsRng);
+        assert_ne!(to_bytes(&rerandomized), to_bytes(&original));
+        server_key["proof_hex"] = json!(hex::encode(to_bytes(&rerandomized)));
+        let response = call_service(&app, TestRequest::post().uri("/zkp/note-link/verify").set_json(&server_key).to_request()).await;
+        assert_eq!(response.status(), 409);
+
+        let mut other_payment = verify_body.clone();
+        let r_other = Scalar::from(78u64);
+        let c_other = PedersenGens::default().commit(Scalar::from(999_999_999_999_999_999u64), r_other).compress();
+        other_payment["c_pay_hex"] = json!(hex::encode(c_other.as_bytes()));
+        let response = call_service(&app, TestRequest::post().uri("/zkp/note-link/verify").set_json(&other_payment).to_request()).await;
+        let outcome: Value = read_body_json(response).await;
+        assert_eq!(outcome["verified"], false);
+
+        verify_body["context_hash_hex"] = json!("12".repeat(32));
+        let response = call_service(&app, TestRequest::post().uri("/zkp/note-link/verify").set_json(&verify_body).to_request()).await;
+        let outcome: Value = read_body_json(response).await;
+        assert_eq!(outcome["verified"], false);
+
+        let mut too_large = prove_body.clone();
+        too_large["value"] = json!("1329227995784915872903807060280344576");
+        let response = call_service(&app, TestRequest::post().uri("/zkp/note-link/prove").set_json(&too_large).to_request()).await;
+        assert_eq!(response.status(), 400);
+    }
+}
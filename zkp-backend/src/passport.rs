@@ -0,0 +1,314 @@
+//! Battery passport threshold endpoints (`zk::passport`).
+//!
+//!   POST /zkp/passport/prove   — commit module telemetry and prove its thresholds
+//!   POST /zkp/passport/verify  — verify a `listing.batteryPassport` section, optionally against a buyer policy
+//!
+//! Units: state of health in basis points (8000 = 80 %), capacity in Wh,
+//! cycle count in full equivalent cycles.
+
+use actix_web::{post, web, HttpResponse, Responder};
+use bulletproof_demo::api::{parse_compressed_ristretto, parse_context_hash_hex, parse_scalar_hex, IntegerLike};
+use bulletproof_demo::zk::passport::{
+    prove_threshold, verify_threshold, BatteryAttribute, BatteryPassport, PassportCommitments, PassportContext,
+    ThresholdProof, ThresholdProofData, PASSPORT_SCHEMA_VERSION,
+};
+use curve25519_dalek_ng::scalar::Scalar;
+use rand::rngs::OsRng;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::pool::WorkPools;
+use crate::receipts::{ReceiptSigner, VerificationReceipt};
+use crate::{bad_req, check_replay, metrics, ErrorResponse};
+use crate::replay::{ReplayRegistry, ReplayStatus};
+
+#[derive(Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PassportAttributeInput {
+    attribute: BatteryAttribute,
+    value: IntegerLike,
+    /// Minimum for state of health and capacity, maximum for cycle count.
+    threshold: IntegerLike,
+    /// Random when omitted.
+    #[serde(default)]
+    blinding_hex: Option<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PassportProveRequest {
+    product_id: String,
+    batch: String,
+    context_hash_hex: String,
+    attributes: Vec<PassportAttributeInput>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct PassportBlinding {
+    attribute: BatteryAttribute,
+    blinding_hex: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct PassportProveResponse {
+    /// Drop-in `credentialSubject.listing.batteryPassport` section.
+    battery_passport: BatteryPassport,
+    /// Openings for the seller; never published.
+    blindings: Vec<PassportBlinding>,
+    verified: bool,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PassportRequirement {
+    attribute: BatteryAttribute,
+    threshold: IntegerLike,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PassportVerifyRequest {
+    battery_passport: BatteryPassport,
+    /// Buyer policy; each entry needs a proof with an equal or stricter threshold.
+    #[serde(default)]
+    required: Vec<PassportRequirement>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct PassportProofResult {
+    attribute: BatteryAttribute,
+    threshold: String,
+    verified: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct PassportVerifyResponse {
+    /// Every proof verifies and every requirement is covered.
+    verified: bool,
+    results: Vec<PassportProofResult>,
+    /// Requirements no verified proof covers.
+    unmet: Vec<BatteryAttribute>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    replay: Option<ReplayStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    receipt: Option<VerificationReceipt>,
+}
+
+fn prove_passport(req: &PassportProveRequest) -> Result<(BatteryPassport, Vec<PassportBlinding>, bool), String> {
+    let context_hash = parse_context_hash_hex(&req.context_hash_hex).ok_or("invalid context_hash_hex")?;
+    if req.attributes.is_empty() {
+        return Err("attributes must not be empty".to_string());
+    }
+    let ctx = PassportContext { context_hash: &context_hash, product_id: &req.product_id, batch: &req.batch };
+
+    let mut commitments = PassportCommitments::default();
+    let mut threshold_proofs = Vec::with_capacity(req.attributes.len());
+    let mut blindings = Vec::with_capacity(req.attributes.len());
+    let mut verified = true;
+    for input in &req.attributes {
+        if commitments.get(input.attribute).is_some() {
+            return Err(format!("{} is listed twice", input.attribute.as_str()));
+        }
+        let value = input.value.parse_u64("value")?;
+        let threshold = input.threshold.parse_u64("threshold")?;
+        let blinding = match &input.blinding_hex {
+            Some(hex_str) => parse_scalar_hex(hex_str).ok_or("invalid blinding_hex")?,
+            None => Scalar::random(&mut OsRng),
+        };
+        let (commitment, proof) = prove_threshold(&ctx, input.attribute, threshold, value, blinding)?;
+        verified &= verify_threshold(&ctx, input.attribute, threshold, commitment, &proof);
+        commitments.set(input.attribute, hex::encode(commitment.as_bytes()));
+        threshold_proofs.push(ThresholdProofData {
+            attribute: input.attribute,
+            threshold: threshold.to_string(),
+            proof_hex: hex::encode(proof.to_bytes()),
+        });
+        blindings.push(PassportBlinding { attribute: input.attribute, blinding_hex: hex::encode(blinding.as_bytes()) });
+    }
+
+    let passport = BatteryPassport {
+        schema_version: PASSPORT_SCHEMA_VERSION.to_string(),
+        product_id: req.product_id.clone(),
+        batch: req.batch.clone(),
+        context_hash: hex::encode(context_hash),
+        commitments,
+        threshold_proofs,
+    };
+    Ok((passport, blindings, verified))
+}
+
+struct VerifyOutcome {
+    context_hash: [u8; 32],
+    results: Vec<PassportProofResult>,
+    unmet: Vec<BatteryAttribute>,
+    proof_bytes: Vec<u8>,
+}
+
+fn verify_passport(req: &PassportVerifyRequest) -> Result<VerifyOutcome, String> {
+    let passport = &req.battery_passport;
+    if passport.schema_version != PASSPORT_SCHEMA_VERSION {
+        return Err(format!("unsupported batteryPassport schemaVersion {}", passport.schema_version));
+    }
+    let context_hash = parse_context_hash_hex(&passport.context_hash).ok_or("invalid batteryPassport.contextHash")?;
+    let ctx = PassportContext { context_hash: &context_hash, product_id: &passport.product_id, batch: &passport.batch };
+
+    let mut results = Vec::with_capacity(passport.threshold_proofs.len());
+    let mut proven = Vec::new();
+    let mut proof_bytes = Vec::new();
+    for entry in &passport.threshold_proofs {
+        let threshold = entry.threshold.trim().parse::<u64>().map_err(|_| "threshold must be a decimal u64")?;
+        let commitment = passport
+            .commitments
+            .get(entry.attribute)
+            .ok_or_else(|| format!("no commitment for {}", entry.attribute.as_str()))?;
+        let commitment = parse_compressed_ristretto(commitment).ok_or("invalid commitment")?;
+        let proof = hex::decode(entry.proof_hex.trim_start_matches("0x"))
+            .ok()
+            .and_then(|bytes| ThresholdProof::from_bytes(&bytes))
+            .ok_or("invalid proofHex")?;
+        let verified = verify_threshold(&ctx, entry.attribute, threshold, commitment, &proof);
+        if verified {
+            proven.push((entry.attribute, threshold));
+        }
+        proof_bytes.extend(proof.to_bytes());
+        results.push(PassportProofResult { attribute: entry.attribute, threshold: entry.threshold.clone(), verified });
+    }
+
+    let mut unmet = Vec::new();
+    for requirement in &req.required {
+        let required = requirement.threshold.parse_u64("threshold")?;
+        let covered = proven.iter().any(|&(attribute, threshold)| {
+            attribute == requirement.attribute
+                && if attribute.is_maximum() { threshold <= required } else { threshold >= required }
+        });
+        if !covered {
+            unmet.push(requirement.attribute);
+        }
+    }
+    Ok(VerifyOutcome { context_hash, results, unmet, proof_bytes })
+}
+
+#[utoipa::path(
+    tag = "passport",
+    request_body = PassportProveRequest,
+    responses(
+        (status = 200, body = PassportProveResponse),
+        (status = 400, body = ErrorResponse),
+    )
+)]
+#[post("/zkp/passport/prove")]
+pub async fn prove(req: web::Json<PassportProveRequest>, pools: web::Data<WorkPools>) -> impl Responder {
+    pools.prove.run(move || match prove_passport(&req) {
+        Ok((battery_passport, blindings, verified)) => {
+            for proof in &battery_passport.threshold_proofs {
+                metrics::record_proof_size("passport", proof.proof_hex.len() / 2);
+            }
+            HttpResponse::Ok().json(PassportProveResponse { battery_passport, blindings, verified })
+        }
+        Err(error) => bad_req(&error),
+    })
+    .await
+}
+
+#[utoipa::path(
+    tag = "passport",
+    request_body = PassportVerifyRequest,
+    responses(
+        (status = 200, body = PassportVerifyResponse),
+        (status = 400, body = ErrorResponse),
+    )
+)]
+#[post("/zkp/passport/verify")]
+pub async fn verify(
+    req: web::Json<PassportVerifyRequest>,
+    replay: web::Data<ReplayRegistry>,
+    receipts: web::Data<ReceiptSigner>,
+    pools: web::Data<WorkPools>,
+) -> impl Responder {
+    pools.verify.run(move || {
+        let outcome = match verify_passport(&req) {
+            Ok(outcome) => outcome,
+            Err(error) => return bad_req(&error),
+        };
+
+        let verified = !outcome.results.is_empty() && outcome.results.iter().all(|r| r.verified) && outcome.unmet.is_empty();
+        let replay = match check_replay(&replay, "passport", &outcome.context_hash, &outcome.proof_bytes, verified) {
+            Ok(status) => status,
+            Err(response) => return response,
+        };
+        let receipt = receipts.sign("passport", &*req, &outcome.context_hash, verified);
+        metrics::record_verification("passport", verified);
+        HttpResponse::Ok().json(PassportVerifyResponse {
+            verified,
+            results: outcome.results,
+            unmet: outcome.unmet,
+            replay,
+            receipt,
+        })
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::{call_service, init_service, read_body_json, TestRequest};
+    use actix_web::App;
+    use serde_json::{json, Value};
+
+    #[actix_web::test]
+    async fn passport_proofs_meet_buyer_policy() {
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(WorkPools::from_env()))
+                .app_data(web::Data::new(ReplayRegistry::from_env()))
+                .app_data(web::Data::new(ReceiptSigner::disabled()))
+                .service(prove)
+                .service(verify),
+        )
+        .await;
+        let post = |uri: &str, body: &Value| TestRequest::post().uri(uri).set_json(body).to_request();
+
+        let prove_body = json!({
+            "product_id": "42",
+            "batch": "B-2026-07",
+            "context_hash_hex": "11".repeat(32),
+            "attributes": [
+                { "attribute": "stateOfHealth", "value": 8650, "threshold": 8000 },
+                { "attribute": "capacity", "value": "64000", "threshold": "60000" },
+                { "attribute": "cycleCount", "value": 1200, "threshold": 1500 },
+            ],
+        });
+        let response = call_service(&app, post("/zkp/passport/prove", &prove_body)).await;
+        assert_eq!(response.status(), 200);
+        let proved: Value = read_body_json(response).await;
+        assert_eq!(proved["verified"], true);
+        let passport = &proved["battery_passport"];
+        assert!(passport["commitments"]["cycleCountCommitment"].is_string());
+
+        let policy = json!({
+            "battery_passport": passport,
+            "required": [
+                { "attribute": "stateOfHealth", "threshold": 8000 },
+                { "attribute": "cycleCount", "threshold": 2000 },
+            ],
+        });
+        let outcome: Value = read_body_json(call_service(&app, post("/zkp/passport/verify", &policy)).await).await;
+        assert_eq!(outcome["verified"], true);
+
+        let stricter = json!({ "battery_passport": passport, "required": [{ "attribute": "capacity", "threshold": 70000 }] });
+        let outcome: Value = read_body_json(call_service(&app, post("/zkp/passport/verify", &stricter)).await).await;
+        assert_eq!(outcome["verified"], false);
+        assert_eq!(outcome["unmet"][0], "capacity");
+
+        let mut moved = passport.clone();
+        moved["batch"] = json!("B-2026-08");
+        let outcome: Value = read_body_json(call_service(&app, post("/zkp/passport/verify", &json!({ "battery_passport": moved }))).await).await;
+        assert_eq!(outcome["verified"], false);
+
+        let mut failing = prove_body.clone();
+        failing["attributes"][0]["value"] = json!(7900);
+        let response = call_service(&app, post("/zkp/passport/prove", &failing)).await;
+        assert_eq!(response.status(), 400);
+    }
+}
@@ -0,0 +1,264 @@
+//! Bounded worker pools for CPU-bound proving and verification.
+//!
+//! Handlers hand their work to `WorkPools::prove` or `WorkPools::verify`
+//! instead of running it on the actix event loop. Each pool runs jobs through
+//! `web::block` with at most `threads` of them in flight; up to `queue` more
+//! may wait for a slot, beyond that the request gets 503 + `Retry-After`.
+//! Every job has a deadline covering both queueing and execution.
+//!
+//! Configured from the environment:
+//!   ZKP_PROVE_THREADS / ZKP_VERIFY_THREADS — concurrent jobs (default: CPU count)
+//!   ZKP_PROVE_QUEUE / ZKP_VERIFY_QUEUE     — waiting jobs (default 64 / 256)
+//!   ZKP_JOB_TIMEOUT_MS                     — per-request deadline (default 30000)
+//!
+//! Proving and verification use separate pools, so a burst of 64-bit range
+//! proofs cannot starve verifiers; `/health` and other GET routes never touch
+//! either pool.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use actix_web::body::MessageBody;
+use actix_web::http::header::{self, HeaderMap};
+use actix_web::http::StatusCode;
+use actix_web::web::Bytes;
+use actix_web::{web, HttpResponse};
+use tokio::sync::Semaphore;
+use tokio::time::{timeout_at, Instant};
+use tracing::{info, warn, Span};
+
+use crate::{metrics, ErrorResponse};
+
+const DEFAULT_PROVE_QUEUE: usize = 64;
+const DEFAULT_VERIFY_QUEUE: usize = 256;
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+/// Suggested back-off for rejected jobs.
+const RETRY_AFTER_SECS: u64 = 2;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PoolError {
+    /// Every worker is busy and the queue is full.
+    Saturated,
+    /// The deadline passed while queued or running.
+    TimedOut,
+    /// The job panicked.
+    Panicked,
+}
+
+impl PoolError {
+    pub fn reason(&self) -> &'static str {
+        match self {
+            PoolError::Saturated => "saturated",
+            PoolError::TimedOut => "timeout",
+            PoolError::Panicked => "panicked",
+        }
+    }
+
+    fn response(&self) -> HttpResponse {
+        let busy = |message: &str| {
+            HttpResponse::ServiceUnavailable()
+                .insert_header((header::RETRY_AFTER, RETRY_AFTER_SECS.to_string()))
+                .json(ErrorResponse { error: message.to_string() })
+        };
+        match self {
+            PoolError::Saturated => busy("server busy, retry later"),
+            PoolError::TimedOut => busy("request timed out"),
+            PoolError::Panicked => {
+                HttpResponse::InternalServerError().json(ErrorResponse { error: "internal error".to_string() })
+            }
+        }
+    }
+}
+
+/// `HttpResponse` is not `Send`; handlers' JSON responses cross back from
+/// the worker thread as plain parts.
+struct ResponseParts {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl ResponseParts {
+    fn from_response(response: HttpResponse) -> Self {
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.into_body().try_into_bytes().unwrap_or_default();
+        Self { status, headers, body }
+    }
+
+    fn into_response(self) -> HttpResponse {
+        let mut response = HttpResponse::with_body(self.status, self.body);
+        *response.headers_mut() = self.headers;
+        response.map_into_boxed_body()
+    }
+}
+
+/// Decrements the queue gauge even if the waiting request is dropped.
+struct QueueSlot<'a> {
+    pool: &'a BlockingPool,
+}
+
+impl Drop for QueueSlot<'_> {
+    fn drop(&mut self) {
+        let queued = self.pool.queued.fetch_sub(1, Ordering::SeqCst) - 1;
+        metrics::set_pool_queued(self.pool.name, queued);
+    }
+}
+
+pub struct BlockingPool {
+    name: &'static str,
+    slots: Arc<Semaphore>,
+    queue_limit: usize,
+    queued: AtomicUsize,
+    timeout: Duration,
+}
+
+impl BlockingPool {
+    pub fn new(name: &'static str, threads: usize, queue_limit: usize, timeout: Duration) -> Self {
+        Self {
+            name,
+            slots: Arc::new(Semaphore::new(threads.max(1))),
+            queue_limit,
+            queued: AtomicUsize::new(0),
+            timeout,
+        }
+    }
+
+    fn enqueue(&self) -> Result<QueueSlot<'_>, PoolError> {
+        let queued = self.queued.fetch_add(1, Ordering::SeqCst);
+        let slot = QueueSlot { pool: self };
+        if queued >= self.queue_limit {
+            return Err(PoolError::Saturated);
+        }
+        metrics::set_pool_queued(self.name, queued + 1);
+        Ok(slot)
+    }
+
+    /// Runs `job` on the blocking pool, holding a worker slot until it
+    /// returns; a job that outlives its deadline keeps the slot but its
+    /// result is dropped.
+    pub async fn execute<T, F>(&self, job: F) -> Result<T, PoolError>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let deadline = Instant::now() + self.timeout;
+        let permit = match self.slots.clone().try_acquire_owned() {
+            Ok(permit) => permit,
+            Err(_) => {
+                let _slot = self.enqueue()?;
+                match timeout_at(deadline, self.slots.clone().acquire_owned()).await {
+                    Ok(Ok(permit)) => permit,
+                    Ok(Err(_)) => return Err(PoolError::Saturated),
+                    Err(_) => return Err(PoolError::TimedOut),
+                }
+            }
+        };
+
+        let span = Span::current();
+        let task = web::block(move || {
+            let _permit = permit;
+            span.in_scope(job)
+        });
+        match timeout_at(deadline, task).await {
+            Ok(Ok(value)) => Ok(value),
+            Ok(Err(_)) => Err(PoolError::Panicked),
+            Err(_) => Err(PoolError::TimedOut),
+        }
+    }
+
+    /// `execute` for handlers: pool failures become 503 / 500 responses.
+    pub async fn run<F>(&self, job: F) -> HttpResponse
+    where
+        F: FnOnce() -> HttpResponse + Send + 'static,
+    {
+        match self.execute(move || ResponseParts::from_response(job())).await {
+            Ok(parts) => parts.into_response(),
+            Err(error) => {
+                warn!(pool = self.name, reason = error.reason(), "job rejected");
+                metrics::record_pool_rejection(self.name, error.reason());
+                error.response()
+            }
+        }
+    }
+}
+
+pub struct WorkPools {
+    pub prove: BlockingPool,
+    pub verify: BlockingPool,
+}
+
+fn env_usize(name: &str) -> Option<usize> {
+    std::env::var(name).ok().and_then(|value| value.trim().parse().ok())
+}
+
+impl WorkPools {
+    pub fn from_env() -> Self {
+        let cpus = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+        let timeout = env_usize("ZKP_JOB_TIMEOUT_MS")
+            .map(|ms| Duration::from_millis(ms as u64))
+            .unwrap_or(DEFAULT_TIMEOUT);
+        let prove = BlockingPool::new(
+            "prove",
+            env_usize("ZKP_PROVE_THREADS").unwrap_or(cpus),
+            env_usize("ZKP_PROVE_QUEUE").unwrap_or(DEFAULT_PROVE_QUEUE),
+            timeout,
+        );
+        let verify = BlockingPool::new(
+            "verify",
+            env_usize("ZKP_VERIFY_THREADS").unwrap_or(cpus),
+            env_usize("ZKP_VERIFY_QUEUE").unwrap_or(DEFAULT_VERIFY_QUEUE),
+            timeout,
+        );
+        info!(
+            prove_threads = prove.slots.available_permits(),
+            prove_queue = prove.queue_limit,
+            verify_threads = verify.slots.available_permits(),
+            verify_queue = verify.queue_limit,
+            timeout_ms = timeout.as_millis() as u64,
+            "worker pools ready"
+        );
+        Self { prove, verify }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[actix_web::test]
+    async fn full_pool_rejects_instead_of_queueing_forever() {
+        let pool = Arc::new(BlockingPool::new("test", 1, 0, Duration::from_secs(5)));
+        let (release, wait) = mpsc::channel::<()>();
+        let busy = actix_web::rt::spawn({
+            let pool = pool.clone();
+            async move { pool.execute(move || wait.recv().is_ok()).await }
+        });
+        while pool.slots.available_permits() > 0 {
+            actix_web::rt::task::yield_now().await;
+        }
+
+        assert_eq!(pool.execute(|| ()).await, Err(PoolError::Saturated));
+        let response = pool.run(|| HttpResponse::Ok().finish()).await;
+        assert_eq!(response.status(), 503);
+        assert!(response.headers().contains_key(header::RETRY_AFTER));
+
+        release.send(()).unwrap();
+        assert_eq!(busy.await.unwrap(), Ok(true));
+        assert_eq!(pool.execute(|| 7).await, Ok(7));
+
+        let response = pool.run(|| HttpResponse::Created().insert_header(("x-kind", "txid")).body("{}")).await;
+        assert_eq!(response.status(), 201);
+        assert_eq!(response.headers().get("x-kind").unwrap(), "txid");
+        assert_eq!(response.into_body().try_into_bytes().unwrap(), "{}");
+    }
+
+    #[actix_web::test]
+    async fn slow_jobs_time_out() {
+        let pool = BlockingPool::new("test", 1, 1, Duration::from_millis(20));
+        let result = pool.execute(|| std::thread::sleep(Duration::from_millis(200))).await;
+        assert_eq!(result, Err(PoolError::TimedOut));
+    }
+}
@@ -0,0 +1,341 @@
+//! Signed verification receipts.
+//!
+//! With a signing key configured, every verify endpoint attaches a receipt
+//! signed over
+//!   domain || proof_kind || public_inputs || context_hash || verified || timestamp || service_version
+//!          [|| reference_time || basis || source]
+//! (each field length-prefixed, public inputs as compact JSON), so a third
+//! party can check an audit bundle without re-running the proofs. The last
+//! three fields are present for windowed proofs: `verified` then holds at
+//! `reference_time`, which is `timestamp` only when `source` is "server".
+//!
+//! `context_hash` is the proof's 32-byte context hash as hex; contexts of any
+//! other length (legacy JSON binding contexts, binding tags) are SHA-256'd first.
+//!
+//! Configured from the environment:
+//!   ZKP_RECEIPT_KEY — 32-byte hex secret key; required unless ZKP_DEV_MODE is
+//!                     set, where leaving it unset disables receipts
+//!   ZKP_RECEIPT_ALG — "ed25519" (default) or "secp256k1"
+//!
+//! A malformed key or unknown algorithm fails startup rather than silently
+//! dropping signatures.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ed25519_dalek::{Signer as _, Verifier as _};
+use hex::FromHex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+
+use crate::dev_mode;
+use bulletproof_demo::zk::validity::ValidityReference;
+use utoipa::ToSchema;
+
+const RECEIPT_DOMAIN: &[u8] = b"zkp-backend/verification-receipt/v1";
+pub const SERVICE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ReceiptAlgorithm {
+    Ed25519,
+    Secp256k1,
+}
+
+impl ReceiptAlgorithm {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "ed25519" => Some(Self::Ed25519),
+            "secp256k1" => Some(Self::Secp256k1),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct VerificationReceipt {
+    pub proof_kind: String,
+    pub public_inputs: serde_json::Value,
+    pub context_hash: String,
+    pub verified: bool,
+    /// Unix seconds at which the verification ran.
+    pub timestamp: u64,
+    pub service_version: String,
+    /// Point a validity window was checked against, for windowed proofs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validity_reference: Option<ValidityReference>,
+    pub algorithm: ReceiptAlgorithm,
+    /// Ed25519: 32-byte key. secp256k1: 33-byte compressed SEC1 key.
+    pub public_key: String,
+    /// Ed25519 signature, or secp256k1 ECDSA (r || s) over SHA-256 of the message.
+    pub signature: String,
+}
+
+impl VerificationReceipt {
+    fn signing_bytes(&self) -> Vec<u8> {
+        let public_inputs = serde_json::to_vec(&self.public_inputs).unwrap_or_default();
+        let timestamp = self.timestamp.to_be_bytes();
+        let reference_time;
+        let mut fields: Vec<&[u8]> = vec![
+            self.proof_kind.as_bytes(),
+            &public_inputs,
+            self.context_hash.as_bytes(),
+            if self.verified { b"1" } else { b"0" },
+            &timestamp,
+            self.service_version.as_bytes(),
+        ];
+        if let Some(reference) = &self.validity_reference {
+            reference_time = reference.reference_time.to_be_bytes();
+            fields.extend([&reference_time[..], reference.basis.as_str().as_bytes(), reference.source.as_str().as_bytes()]);
+        }
+        let mut message = RECEIPT_DOMAIN.to_vec();
+        for field in fields {
+            message.extend_from_slice(&(field.len() as u64).to_be_bytes());
+            message.extend_from_slice(field);
+        }
+        message
+    }
+}
+
+enum SigningKey {
+    Ed25519(ed25519_dalek::SigningKey),
+    Secp256k1(k256::ecdsa::SigningKey),
+}
+
+pub struct ReceiptSigner {
+    key: Option<SigningKey>,
+}
+
+fn now_unix() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn context_digest(context: &[u8]) -> String {
+    if context.len() == 32 {
+        hex::encode(context)
+    } else {
+        hex::encode(Sha256::digest(context))
+    }
+}
+
+impl ReceiptSigner {
+    pub fn disabled() -> Self {
+        Self { key: None }
+    }
+
+    pub fn from_secret(algorithm: ReceiptAlgorithm, secret: &[u8; 32]) -> Result<Self, String> {
+        let key = match algorithm {
+            ReceiptAlgorithm::Ed25519 => SigningKey::Ed25519(ed25519_dalek::SigningKey::from_bytes(secret)),
+            ReceiptAlgorithm::Secp256k1 => SigningKey::Secp256k1(
+                k256::ecdsa::SigningKey::from_bytes(secret.into())
+                    .map_err(|_| "invalid secp256k1 secret key".to_string())?,
+            ),
+        };
+        Ok(Self { key: Some(key) })
+    }
+
+    /// Builds the signer from `ZKP_RECEIPT_KEY` / `ZKP_RECEIPT_ALG`. Only dev
+    /// mode may run without a key.
+    pub fn from_env() -> Result<Self, String> {
+        let secret_hex = match std::env::var("ZKP_RECEIPT_KEY") {
+            Ok(value) if !value.trim().is_empty() => value,
+            _ if dev_mode() => {
+                warn!("ZKP_RECEIPT_KEY unset, verification receipts disabled");
+                return Ok(Self::disabled());
+            }
+            _ => return Err("ZKP_RECEIPT_KEY must be set to the receipt signing key".to_string()),
+        };
+        let algorithm = match std::env::var("ZKP_RECEIPT_ALG") {
+            Ok(value) => ReceiptAlgorithm::parse(&value).ok_or_else(|| format!("unknown ZKP_RECEIPT_ALG {value:?}"))?,
+            Err(_) => ReceiptAlgorithm::Ed25519,
+        };
+        let secret = <[u8; 32]>::from_hex(secret_hex.trim().trim_start_matches("0x"))
+            .map_err(|_| "ZKP_RECEIPT_KEY must be 32 bytes of hex".to_string())?;
+        let signer = Self::from_secret(algorithm, &secret)?;
+        info!(
+            ?algorithm,
+            public_key = %signer.public_key_hex().unwrap_or_default(),
+            "verification receipts enabled"
+        );
+        Ok(signer)
+    }
+
+    pub fn algorithm(&self) -> Option<ReceiptAlgorithm> {
+        match self.key.as_ref()? {
+            SigningKey::Ed25519(_) => Some(ReceiptAlgorithm::Ed25519),
+            SigningKey::Secp256k1(_) => Some(ReceiptAlgorithm::Secp256k1),
+        }
+    }
+
+    pub fn public_key_hex(&self) -> Option<String> {
+        match self.key.as_ref()? {
+            SigningKey::Ed25519(key) => Some(hex::encode(key.verifying_key().to_bytes())),
+            SigningKey::Secp256k1(key) => Some(hex::encode(key.verifying_key().to_sec1_bytes())),
+        }
+    }
+
+    /// Signs a receipt for one verification, or returns `None` if disabled.
+    pub fn sign<T: Serialize>(
+        &self,
+        proof_kind: &str,
+        public_inputs: &T,
+        context: &[u8],
+        verified: bool,
+    ) -> Option<VerificationReceipt> {
+        self.sign_windowed(proof_kind, public_inputs, context, verified, None)
+    }
+
+    /// `sign` for proofs that may carry a validity window, recording the
+    /// reference point the window was checked against.
+    pub fn sign_windowed<T: Serialize>(
+        &self,
+        proof_kind: &str,
+        public_inputs: &T,
+        context: &[u8],
+        verified: bool,
+        validity_reference: Option<ValidityReference>,
+    ) -> Option<VerificationReceipt> {
+        let key = self.key.as_ref()?;
+        let mut receipt = VerificationReceipt {
+            proof_kind: proof_kind.to_string(),
+            public_inputs: serde_json::to_value(public_inputs).ok()?,
+            context_hash: context_digest(context),
+            verified,
+            timestamp: now_unix(),
+            service_version: SERVICE_VERSION.to_string(),
+            validity_reference,
+            algorithm: self.algorithm()?,
+            public_key: self.public_key_hex()?,
+            signature: String::new(),
+        };
+        receipt.signature = sign_with(key, &receipt.signing_bytes());
+        Some(receipt)
+    }
+
+    /// Signs `domain || message` with the receipt key, e.g. job callbacks.
+    /// Returns the hex signature, or `None` if disabled.
+    pub fn sign_message(&self, domain: &[u8], message: &[u8]) -> Option<String> {
+        let key = self.key.as_ref()?;
+        Some(sign_with(key, &[domain, message].concat()))
+    }
+}
+
+fn sign_with(key: &SigningKey, message: &[u8]) -> String {
+    match key {
+        SigningKey::Ed25519(key) => hex::encode(key.sign(message).to_bytes()),
+        SigningKey::Secp256k1(key) => {
+            let signature: k256::ecdsa::Signature = key.sign(message);
+            hex::encode(signature.to_bytes())
+        }
+    }
+}
+
+/// Checks a receipt's signature against the public key it names.
+///
+/// Whether that key belongs to a trusted service is up to the caller.
+pub fn verify_receipt(receipt: &VerificationReceipt) -> Result<(), String> {
+    if verify_signature(receipt.algorithm, &receipt.public_key, &receipt.signing_bytes(), &receipt.signature)? {
+        Ok(())
+    } else {
+        Err("signature does not match receipt".to_string())
+    }
+}
+
+/// `Ok(false)` for a well-formed signature that does not match.
+pub(crate) fn verify_signature(
+    algorithm: ReceiptAlgorithm,
+    public_key_hex: &str,
+    message: &[u8],
+    signature_hex: &str,
+) -> Result<bool, String> {
+    let public_key = Vec::from_hex(public_key_hex.trim_start_matches("0x"))
+        .map_err(|_| "invalid public_key".to_string())?;
+    let signature = Vec::from_hex(signature_hex.trim_start_matches("0x"))
+        .map_err(|_| "invalid signature".to_string())?;
+
+    match algorithm {
+        ReceiptAlgorithm::Ed25519 => {
+            let public_key: [u8; 32] = public_key.try_into().map_err(|_| "invalid public_key".to_string())?;
+            let signature: [u8; 64] = signature.try_into().map_err(|_| "invalid signature".to_string())?;
+            let key = ed25519_dalek::VerifyingKey::from_bytes(&public_key)
+                .map_err(|_| "invalid public_key".to_string())?;
+            Ok(key.verify(message, &ed25519_dalek::Signature::from_bytes(&signature)).is_ok())
+        }
+        ReceiptAlgorithm::Secp256k1 => {
+            use k256::ecdsa::signature::Verifier;
+            let key = k256::ecdsa::VerifyingKey::from_sec1_bytes(&public_key)
+                .map_err(|_| "invalid public_key".to_string())?;
+            let signature = k256::ecdsa::Signature::from_slice(&signature)
+                .map_err(|_| "invalid signature".to_string())?;
+            Ok(key.verify(message, &signature).is_ok())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn signed(algorithm: ReceiptAlgorithm) -> VerificationReceipt {
+        let signer = ReceiptSigner::from_secret(algorithm, &[0x42u8; 32]).unwrap();
+        signer
+            .sign("equality", &json!({ "c_total_hex": "aa", "c_pay_hex": "bb" }), &[0x11u8; 32], true)
+            .unwrap()
+    }
+
+    #[test]
+    fn ed25519_receipt_round_trips() {
+        let receipt = signed(ReceiptAlgorithm::Ed25519);
+        let json = serde_json::to_string(&receipt).unwrap();
+        let parsed: VerificationReceipt = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed.context_hash, hex::encode([0x11u8; 32]));
+        assert!(verify_receipt(&parsed).is_ok());
+    }
+
+    #[test]
+    fn secp256k1_receipt_verifies() {
+        assert!(verify_receipt(&signed(ReceiptAlgorithm::Secp256k1)).is_ok());
+    }
+
+    #[test]
+    fn tampered_receipt_is_rejected() {
+        for algorithm in [ReceiptAlgorithm::Ed25519, ReceiptAlgorithm::Secp256k1] {
+            let mut receipt = signed(algorithm);
+            receipt.verified = false;
+            assert!(verify_receipt(&receipt).is_err());
+
+            let mut receipt = signed(algorithm);
+            receipt.public_inputs["c_pay_hex"] = json!("cc");
+            assert!(verify_receipt(&receipt).is_err());
+        }
+    }
+
+    #[test]
+    fn validity_reference_is_signed() {
+        use bulletproof_demo::zk::validity::{ReferenceSource, WindowBasis};
+
+        let signer = ReceiptSigner::from_secret(ReceiptAlgorithm::Ed25519, &[0x42u8; 32]).unwrap();
+        let reference = ValidityReference { reference_time: 1_500, basis: WindowBasis::Timestamp, source: ReferenceSource::Caller };
+        let receipt = signer.sign_windowed("equality", &json!({}), &[0x11u8; 32], true, Some(reference)).unwrap();
+        let parsed: VerificationReceipt = serde_json::from_str(&serde_json::to_string(&receipt).unwrap()).unwrap();
+        assert!(verify_receipt(&parsed).is_ok());
+
+        let mut relabelled = receipt.clone();
+        relabelled.validity_reference = Some(ValidityReference { source: ReferenceSource::Server, ..reference });
+        assert!(verify_receipt(&relabelled).is_err());
+        let mut dropped = receipt;
+        dropped.validity_reference = None;
+        assert!(verify_receipt(&dropped).is_err());
+    }
+
+    #[test]
+    fn disabled_signer_issues_nothing() {
+        assert!(ReceiptSigner::disabled().sign("equality", &json!({}), &[], true).is_none());
+    }
+}
@@ -0,0 +1,231 @@
+//! Recycled-content ratio endpoints (`zk::recycled_content`).
+//!
+//!   POST /zkp/recycled-content/prove   — commit recycled and total mass, prove the minimum share
+//!   POST /zkp/recycled-content/verify  — verify against the commitments alone
+//!
+//! Masses are integer grams of the material; `percent` is a whole percentage.
+
+use actix_web::{post, web, HttpResponse, Responder};
+use bulletproof_demo::api::{parse_compressed_ristretto, parse_context_hash_hex, parse_scalar_hex, IntegerLike};
+use bulletproof_demo::zk::recycled_content::{
+    prove_recycled_content, verify_recycled_content, Material, RecycledContentProof, RecycledContentStatement,
+};
+use bulletproofs::PedersenGens;
+use curve25519_dalek_ng::scalar::Scalar;
+use rand::rngs::OsRng;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::pool::WorkPools;
+use crate::receipts::{ReceiptSigner, VerificationReceipt};
+use crate::{bad_req, check_replay, metrics, ErrorResponse};
+use crate::replay::{ReplayRegistry, ReplayStatus};
+
+#[derive(Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RecycledContentProveRequest {
+    material: Material,
+    recycled_mass: IntegerLike,
+    total_mass: IntegerLike,
+    /// Minimum recycled share, 0 to 100.
+    percent: u64,
+    /// Random when omitted.
+    #[serde(default)]
+    r_recycled_hex: Option<String>,
+    #[serde(default)]
+    r_total_hex: Option<String>,
+    batch: String,
+    context_hash_hex: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RecycledContentProveResponse {
+    c_recycled_hex: String,
+    c_total_hex: String,
+    r_recycled_hex: String,
+    r_total_hex: String,
+    proof_hex: String,
+    verified: bool,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RecycledContentVerifyRequest {
+    material: Material,
+    c_recycled_hex: String,
+    c_total_hex: String,
+    percent: u64,
+    batch: String,
+    context_hash_hex: String,
+    proof_hex: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RecycledContentVerifyResponse {
+    verified: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    replay: Option<ReplayStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    receipt: Option<VerificationReceipt>,
+}
+
+fn blinding(hex_str: &Option<String>, field_name: &str) -> Result<Scalar, String> {
+    match hex_str {
+        Some(hex_str) => parse_scalar_hex(hex_str).ok_or_else(|| format!("invalid {field_name}")),
+        None => Ok(Scalar::random(&mut OsRng)),
+    }
+}
+
+fn prove_share(req: &RecycledContentProveRequest) -> Result<RecycledContentProveResponse, String> {
+    let recycled = req.recycled_mass.parse_u64("recycled_mass")?;
+    let total = req.total_mass.parse_u64("total_mass")?;
+    let r_recycled = blinding(&req.r_recycled_hex, "r_recycled_hex")?;
+    let r_total = blinding(&req.r_total_hex, "r_total_hex")?;
+    let context_hash = parse_context_hash_hex(&req.context_hash_hex).ok_or("invalid context_hash_hex")?;
+
+    let pc_gens = PedersenGens::default();
+    let statement = RecycledContentStatement {
+        material: req.material,
+        percent: req.percent,
+        c_recycled: pc_gens.commit(Scalar::from(recycled), r_recycled).compress(),
+        c_total: pc_gens.commit(Scalar::from(total), r_total).compress(),
+        batch: &req.batch,
+        context_hash: &context_hash,
+    };
+    let proof = prove_recycled_content(&statement, recycled, total, r_recycled, r_total)?;
+    Ok(RecycledContentProveResponse {
+        c_recycled_hex: hex::encode(statement.c_recycled.as_bytes()),
+        c_total_hex: hex::encode(statement.c_total.as_bytes()),
+        r_recycled_hex: hex::encode(r_recycled.as_bytes()),
+        r_total_hex: hex::encode(r_total.as_bytes()),
+        verified: verify_recycled_content(&statement, &proof),
+        proof_hex: hex::encode(proof.to_bytes()),
+    })
+}
+
+#[utoipa::path(
+    tag = "recycled-content",
+    request_body = RecycledContentProveRequest,
+    responses(
+        (status = 200, body = RecycledContentProveResponse),
+        (status = 400, body = ErrorResponse),
+    )
+)]
+#[post("/zkp/recycled-content/prove")]
+pub async fn prove(req: web::Json<RecycledContentProveRequest>, pools: web::Data<WorkPools>) -> impl Responder {
+    pools.prove.run(move || match prove_share(&req) {
+        Ok(response) => {
+            metrics::record_proof_size("recycled-content", response.proof_hex.len() / 2);
+            HttpResponse::Ok().json(response)
+        }
+        Err(error) => bad_req(&error),
+    })
+    .await
+}
+
+#[utoipa::path(
+    tag = "recycled-content",
+    request_body = RecycledContentVerifyRequest,
+    responses(
+        (status = 200, body = RecycledContentVerifyResponse),
+        (status = 400, body = ErrorResponse),
+    )
+)]
+#[post("/zkp/recycled-content/verify")]
+pub async fn verify(
+    req: web::Json<RecycledContentVerifyRequest>,
+    replay: web::Data<ReplayRegistry>,
+    receipts: web::Data<ReceiptSigner>,
+    pools: web::Data<WorkPools>,
+) -> impl Responder {
+    pools.verify.run(move || {
+        let (Some(c_recycled), Some(c_total)) =
+            (parse_compressed_ristretto(&req.c_recycled_hex), parse_compressed_ristretto(&req.c_total_hex))
+        else {
+            return bad_req("invalid commitment: must be 32 bytes");
+        };
+        let Some(context_hash) = parse_context_hash_hex(&req.context_hash_hex) else {
+            return bad_req("invalid context_hash_hex");
+        };
+        let Ok(proof_bytes) = hex::decode(req.proof_hex.trim_start_matches("0x")) else {
+            return bad_req("invalid proof_hex");
+        };
+        let Some(proof) = RecycledContentProof::from_bytes(&proof_bytes) else {
+            return bad_req("invalid proof_hex");
+        };
+
+        let statement = RecycledContentStatement {
+            material: req.material,
+            percent: req.percent,
+            c_recycled,
+            c_total,
+            batch: &req.batch,
+            context_hash: &context_hash,
+        };
+        let verified = verify_recycled_content(&statement, &proof);
+        let replay = match check_replay(&replay, "recycled-content", &context_hash, &proof_bytes, verified) {
+            Ok(status) => status,
+            Err(response) => return response,
+        };
+        let receipt = receipts.sign("recycled-content", &*req, &context_hash, verified);
+        metrics::record_verification("recycled-content", verified);
+        HttpResponse::Ok().json(RecycledContentVerifyResponse { verified, replay, receipt })
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::{call_service, init_service, read_body_json, TestRequest};
+    use actix_web::App;
+    use serde_json::{json, Value};
+
+    #[actix_web::test]
+    async fn share_prove_and_verify_round_trip() {
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(WorkPools::from_env()))
+                .app_data(web::Data::new(ReplayRegistry::from_env()))
+                .app_data(web::Data::new(ReceiptSigner::disabled()))
+                .service(prove)
+                .service(verify),
+        )
+        .await;
+        let post = |uri: &str, body: &Value| TestRequest::post().uri(uri).set_json(body).to_request();
+
+        let prove_body = json!({
+            "material": "lithium",
+            "recycled_mass": 650,
+            "total_mass": "10000",
+            "percent": 6,
+            "batch": "B-2026-07",
+            "context_hash_hex": "11".repeat(32),
+        });
+        let response = call_service(&app, post("/zkp/recycled-content/prove", &prove_body)).await;
+        assert_eq!(response.status(), 200);
+        let proved: Value = read_body_json(response).await;
+        assert_eq!(proved["verified"], true);
+
+        let mut verify_body = json!({
+            "material": "lithium",
+            "c_recycled_hex": proved["c_recycled_hex"],
+            "c_total_hex": proved["c_total_hex"],
+            "percent": 6,
+            "batch": "B-2026-07",
+            "context_hash_hex": "11".repeat(32),
+            "proof_hex": proved["proof_hex"],
+        });
+        let outcome: Value = read_body_json(call_service(&app, post("/zkp/recycled-content/verify", &verify_body)).await).await;
+        assert_eq!(outcome["verified"], true);
+
+        verify_body["percent"] = json!(7);
+        let outcome: Value = read_body_json(call_service(&app, post("/zkp/recycled-content/verify", &verify_body)).await).await;
+        assert_eq!(outcome["verified"], false);
+
+        let mut too_low = prove_body.clone();
+        too_low["percent"] = json!(7);
+        let response = call_service(&app, post("/zkp/recycled-content/prove", &too_low)).await;
+        assert_eq!(response.status(), 400);
+    }
+}
@@ -0,0 +1,225 @@
+//! Proof replay registry.
+//!
+//! Keeps a persistent set of nullifiers
+//!   nullifier = SHA-256(len(kind) || kind || len(context) || context || proof)
+//! for every proof that verified successfully, so the same proof/context pair
+//! submitted again can be recorded, reported or rejected.
+//!
+//! Configured from the environment:
+//!   ZKP_REPLAY_DB   — SQLite file path (":memory:" allowed); unset disables the registry
+//!   ZKP_REPLAY_MODE — "record" (default), "reject" or "report"
+
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ReplayMode {
+    /// Store nullifiers without changing responses.
+    Record,
+    /// Store nullifiers and refuse proofs that were already seen.
+    Reject,
+    /// Store nullifiers and report first-seen time in responses.
+    Report,
+}
+
+impl ReplayMode {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "record" => Some(Self::Record),
+            "reject" => Some(Self::Reject),
+            "report" => Some(Self::Report),
+            _ => None,
+        }
+    }
+}
+
+/// Replay information attached to verify responses.
+#[derive(Serialize, Clone, Debug, ToSchema)]
+pub struct ReplayStatus {
+    pub nullifier: String,
+    /// Unix seconds at which this nullifier was first recorded.
+    pub first_seen: u64,
+    pub seen_count: u64,
+    pub duplicate: bool,
+}
+
+pub struct ReplayRegistry {
+    store: Option<Mutex<Connection>>,
+    mode: ReplayMode,
+}
+
+fn now_unix() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Nullifier for a proof of `kind` bound to `context`.
+pub fn nullifier(kind: &str, context: &[u8], proof: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update((kind.len() as u64).to_le_bytes());
+    hasher.update(kind.as_bytes());
+    hasher.update((context.len() as u64).to_le_bytes());
+    hasher.update(context);
+    hasher.update(proof);
+    hex::encode(hasher.finalize())
+}
+
+impl ReplayRegistry {
+    pub fn disabled() -> Self {
+        Self { store: None, mode: ReplayMode::Record }
+    }
+
+    pub fn open(path: &str, mode: ReplayMode) -> rusqlite::Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS proof_nullifiers (
+                nullifier  TEXT PRIMARY KEY,
+                proof_kind TEXT NOT NULL,
+                first_seen INTEGER NOT NULL,
+                seen_count INTEGER NOT NULL
+            );",
+        )?;
+        Ok(Self { store: Some(Mutex::new(conn)), mode })
+    }
+
+    /// Builds the registry from `ZKP_REPLAY_DB` / `ZKP_REPLAY_MODE`.
+    pub fn from_env() -> Self {
+        let path = match std::env::var("ZKP_REPLAY_DB") {
+            Ok(path) if !path.trim().is_empty() => path,
+            _ => return Self::disabled(),
+        };
+        let mode = match std::env::var("ZKP_REPLAY_MODE") {
+            Ok(value) => ReplayMode::parse(&value).unwrap_or_else(|| {
+                warn!(mode = %value, "unknown ZKP_REPLAY_MODE, using 'record'");
+                ReplayMode::Record
+            }),
+            Err(_) => ReplayMode::Record,
+        };
+        match Self::open(&path, mode) {
+            Ok(registry) => {
+                info!(?mode, %path, "replay registry enabled");
+                registry
+            }
+            Err(e) => {
+                error!(%path, error = %e, "failed to open replay registry");
+                Self::disabled()
+            }
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.store.is_some()
+    }
+
+    pub fn mode(&self) -> ReplayMode {
+        self.mode
+    }
+
+    /// Records a verified proof and returns its status, or `None` if disabled.
+    pub fn observe(&self, kind: &str, context: &[u8], proof: &[u8]) -> Result<Option<ReplayStatus>, String> {
+        let store = match &self.store {
+            Some(store) => store,
+            None => return Ok(None),
+        };
+        let nullifier = nullifier(kind, context, proof);
+        let conn = store.lock().map_err(|_| "replay registry lock poisoned".to_string())?;
+
+        conn.execute(
+            "INSERT INTO proof_nullifiers (nullifier, proof_kind, first_seen, seen_count)
+             VALUES (?1, ?2, ?3, 1)
+             ON CONFLICT(nullifier) DO UPDATE SET seen_count = seen_count + 1",
+            params![nullifier, kind, now_unix() as i64],
+        )
+        .map_err(|e| format!("replay registry write failed: {e}"))?;
+
+        let (first_seen, seen_count): (i64, i64) = conn
+            .query_row(
+                "SELECT first_seen, seen_count FROM proof_nullifiers WHERE nullifier = ?1",
+                params![nullifier],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .map_err(|e| format!("replay registry read failed: {e}"))?;
+
+        Ok(Some(ReplayStatus {
+            nullifier,
+            first_seen: first_seen as u64,
+            seen_count: seen_count as u64,
+            duplicate: seen_count > 1,
+        }))
+    }
+
+    /// Looks up a nullifier without recording anything.
+    pub fn lookup(&self, nullifier: &str) -> Result<Option<ReplayStatus>, String> {
+        let store = match &self.store {
+            Some(store) => store,
+            None => return Ok(None),
+        };
+        let conn = store.lock().map_err(|_| "replay registry lock poisoned".to_string())?;
+
+        conn.query_row(
+            "SELECT first_seen, seen_count FROM proof_nullifiers WHERE nullifier = ?1",
+            params![nullifier],
+            |row| {
+                let first_seen: i64 = row.get(0)?;
+                let seen_count: i64 = row.get(1)?;
+                Ok(ReplayStatus {
+                    nullifier: nullifier.to_string(),
+                    first_seen: first_seen as u64,
+                    seen_count: seen_count as u64,
+                    duplicate: seen_count > 1,
+                })
+            },
+        )
+        .optional()
+        .map_err(|e| format!("replay registry read failed: {e}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn second_submission_is_flagged_as_duplicate() {
+        let registry = ReplayRegistry::open(":memory:", ReplayMode::Reject).unwrap();
+
+        let first = registry.observe("equality", &[1u8; 32], &[2u8; 64]).unwrap().unwrap();
+        let second = registry.observe("equality", &[1u8; 32], &[2u8; 64]).unwrap().unwrap();
+
+        assert!(!first.duplicate);
+        assert!(second.duplicate);
+        assert_eq!(second.seen_count, 2);
+        assert_eq!(first.first_seen, second.first_seen);
+    }
+
+    #[test]
+    fn kind_and_context_are_part_of_the_nullifier() {
+        assert_ne!(nullifier("equality", &[1u8; 32], &[2u8; 64]), nullifier("quantity-total", &[1u8; 32], &[2u8; 64]));
+        assert_ne!(nullifier("equality", &[1u8; 32], &[2u8; 64]), nullifier("equality", &[3u8; 32], &[2u8; 64]));
+    }
+
+    #[test]
+    fn lookup_does_not_record() {
+        let registry = ReplayRegistry::open(":memory:", ReplayMode::Report).unwrap();
+        let id = nullifier("opening", &[4u8; 32], &[5u8; 96]);
+
+        assert!(registry.lookup(&id).unwrap().is_none());
+        registry.observe("opening", &[4u8; 32], &[5u8; 96]).unwrap();
+        assert_eq!(registry.lookup(&id).unwrap().unwrap().seen_count, 1);
+    }
+
+    #[test]
+    fn disabled_registry_reports_nothing() {
+        let registry = ReplayRegistry::disabled();
+        assert!(registry.observe("equality", &[0u8; 32], &[0u8; 64]).unwrap().is_none());
+    }
+}
@@ -0,0 +1,400 @@
+//! Credential status accumulator endpoints (`zk::revocation`).
+//!
+//!   POST /zkp/revocation/updates    — set credential statuses, publishing a new root (issuer scope)
+//!   GET  /zkp/revocation/root       — latest root and epoch
+//!   POST /zkp/revocation/witnesses  — witness of a credential's status against the latest root
+//!   POST /zkp/revocation/verify     — check a witness against a root, no registry lookup needed
+//!
+//! Every update batch bumps the epoch and records its root, so a root anchored
+//! on-chain or in an audit log can be matched to the epoch it came from.
+//!
+//! Configured from the environment:
+//!   ZKP_REVOCATION_DB — SQLite file path; required unless ZKP_DEV_MODE is set,
+//!                       which falls back to ":memory:"
+
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use actix_web::{get, post, web, HttpResponse, Responder};
+use bulletproof_demo::api::parse_hex32_bytes;
+use bulletproof_demo::zk::revocation::{credential_key, verify_witness, CredentialStatus, Hash, SparseMerkleTree, Witness};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+use crate::pool::WorkPools;
+use crate::receipts::{ReceiptSigner, VerificationReceipt};
+use crate::{bad_req, dev_mode, metrics, ErrorResponse};
+
+/// Largest batch accepted by one update.
+const MAX_UPDATES: usize = 1024;
+
+struct State {
+    conn: Connection,
+    tree: SparseMerkleTree,
+    epoch: u64,
+    root: Hash,
+}
+
+pub struct RevocationRegistry {
+    state: Mutex<State>,
+}
+
+fn now_unix() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0)
+}
+
+impl RevocationRegistry {
+    pub fn open(path: &str) -> rusqlite::Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS credential_status (
+                key        BLOB PRIMARY KEY,
+                cid        TEXT NOT NULL,
+                status     INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS revocation_roots (
+                epoch      INTEGER PRIMARY KEY,
+                root       BLOB NOT NULL,
+                created_at INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS revocation_roots_by_root ON revocation_roots (root);",
+        )?;
+
+        let mut tree = SparseMerkleTree::new();
+        {
+            let mut stmt = conn.prepare("SELECT key, status FROM credential_status")?;
+            let rows = stmt.query_map([], |row| Ok((row.get::<_, Vec<u8>>(0)?, row.get::<_, u8>(1)?)))?;
+            for row in rows {
+                let (key, code) = row?;
+                if let (Ok(key), Some(status)) = (Hash::try_from(key), CredentialStatus::from_code(code)) {
+                    tree.set(key, status);
+                }
+            }
+        }
+        let epoch = conn
+            .query_row("SELECT MAX(epoch) FROM revocation_roots", [], |row| row.get::<_, Option<u64>>(0))?
+            .unwrap_or(0);
+        let root = tree.root();
+        Ok(Self { state: Mutex::new(State { conn, tree, epoch, root }) })
+    }
+
+    /// Opens `ZKP_REVOCATION_DB`. Only dev mode may use an in-memory registry:
+    /// its statuses vanish on restart, so revoked credentials would read as
+    /// active again.
+    pub fn from_env() -> Result<Self, String> {
+        let path = match std::env::var("ZKP_REVOCATION_DB").ok().filter(|path| !path.trim().is_empty()) {
+            Some(path) => path,
+            None if dev_mode() => {
+                warn!("ZKP_REVOCATION_DB unset, revocation statuses are kept in memory and lost on restart");
+                ":memory:".to_string()
+            }
+            None => return Err("ZKP_REVOCATION_DB must name the revocation registry file".to_string()),
+        };
+        let registry = Self::open(&path).map_err(|e| format!("failed to open revocation registry {path}: {e}"))?;
+        info!(%path, "revocation registry ready");
+        Ok(registry)
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, State>, String> {
+        self.state.lock().map_err(|_| "revocation registry lock poisoned".to_string())
+    }
+
+    fn apply(&self, updates: &[StatusUpdate]) -> Result<RootResponse, String> {
+        let mut state = self.lock()?;
+        let now = now_unix();
+        let epoch = state.epoch + 1;
+        let tx = state.conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        let mut tree = state.tree.clone();
+        for update in updates {
+            let key = credential_key(&update.cid);
+            tree.set(key, update.status);
+            if update.status == CredentialStatus::Active {
+                tx.execute("DELETE FROM credential_status WHERE key = ?1", params![key.to_vec()])
+            } else {
+                tx.execute(
+                    "INSERT INTO credential_status (key, cid, status, updated_at) VALUES (?1, ?2, ?3, ?4)
+                     ON CONFLICT(key) DO UPDATE SET status = excluded.status, updated_at = excluded.updated_at",
+                    params![key.to_vec(), update.cid, update.status.code(), now],
+                )
+            }
+            .map_err(|e| e.to_string())?;
+        }
+        let root = tree.root();
+        tx.execute(
+            "INSERT INTO revocation_roots (epoch, root, created_at) VALUES (?1, ?2, ?3)",
+            params![epoch, root.to_vec(), now],
+        )
+        .map_err(|e| e.to_string())?;
+        tx.commit().map_err(|e| e.to_string())?;
+
+        state.tree = tree;
+        state.epoch = epoch;
+        state.root = root;
+        Ok(RootResponse::new(&state))
+    }
+
+    fn epoch_of(&self, root: &Hash) -> Result<Option<u64>, String> {
+        let state = self.lock()?;
+        if *root == SparseMerkleTree::new().root() && state.epoch == 0 {
+            return Ok(Some(0));
+        }
+        state
+            .conn
+            .query_row("SELECT MIN(epoch) FROM revocation_roots WHERE root = ?1", params![root.to_vec()], |row| {
+                row.get::<_, Option<u64>>(0)
+            })
+            .optional()
+            .map(Option::flatten)
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct StatusUpdate {
+    /// VC CID, as in the backend's `vc_status` table.
+    cid: String,
+    status: CredentialStatus,
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateRequest {
+    updates: Vec<StatusUpdate>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RootResponse {
+    epoch: u64,
+    root_hex: String,
+    /// Credentials currently revoked or suspended.
+    non_active: usize,
+}
+
+impl RootResponse {
+    fn new(state: &State) -> Self {
+        Self { epoch: state.epoch, root_hex: hex::encode(state.root), non_active: state.tree.len() }
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct WitnessRequest {
+    cid: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct WitnessResponse {
+    cid: String,
+    key_hex: String,
+    status: CredentialStatus,
+    epoch: u64,
+    root_hex: String,
+    witness_hex: String,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RevocationVerifyRequest {
+    cid: String,
+    /// Status the witness claims; "active" proves the credential is not revoked.
+    #[serde(default = "active")]
+    status: CredentialStatus,
+    root_hex: String,
+    witness_hex: String,
+}
+
+fn active() -> CredentialStatus {
+    CredentialStatus::Active
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RevocationVerifyResponse {
+    verified: bool,
+    status: CredentialStatus,
+    /// Epoch that published `root_hex`, if this server issued it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    epoch: Option<u64>,
+    /// Whether `root_hex` is the latest root.
+    current: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    receipt: Option<VerificationReceipt>,
+}
+
+fn internal(error: String) -> HttpResponse {
+    HttpResponse::InternalServerError().json(ErrorResponse { error })
+}
+
+/// Requires the issuer route group; see `security`.
+#[utoipa::path(
+    tag = "revocation",
+    request_body = UpdateRequest,
+    responses(
+        (status = 200, body = RootResponse),
+        (status = 400, body = ErrorResponse),
+    )
+)]
+#[post("/zkp/revocation/updates")]
+pub async fn update_statuses(
+    req: web::Json<UpdateRequest>,
+    registry: web::Data<RevocationRegistry>,
+    pools: web::Data<WorkPools>,
+) -> impl Responder {
+    if req.updates.is_empty() || req.updates.len() > MAX_UPDATES {
+        return bad_req(&format!("updates must hold 1 to {MAX_UPDATES} entries"));
+    }
+    if req.updates.iter().any(|update| update.cid.trim().is_empty()) {
+        return bad_req("cid must not be empty");
+    }
+    pools.prove.run(move || match registry.apply(&req.updates) {
+        Ok(response) => {
+            info!(epoch = response.epoch, updates = req.updates.len(), "published revocation root");
+            HttpResponse::Ok().json(response)
+        }
+        Err(error) => internal(error),
+    })
+    .await
+}
+
+#[utoipa::path(
+    tag = "revocation",
+    responses((status = 200, body = RootResponse))
+)]
+#[get("/zkp/revocation/root")]
+pub async fn current_root(registry: web::Data<RevocationRegistry>) -> impl Responder {
+    match registry.lock() {
+        Ok(state) => HttpResponse::Ok().json(RootResponse::new(&state)),
+        Err(error) => internal(error),
+    }
+}
+
+#[utoipa::path(
+    tag = "revocation",
+    request_body = WitnessRequest,
+    responses(
+        (status = 200, body = WitnessResponse),
+        (status = 400, body = ErrorResponse),
+    )
+)]
+#[post("/zkp/revocation/witnesses")]
+pub async fn issue_witness(
+    req: web::Json<WitnessRequest>,
+    registry: web::Data<RevocationRegistry>,
+    pools: web::Data<WorkPools>,
+) -> impl Responder {
+    if req.cid.trim().is_empty() {
+        return bad_req("cid must not be empty");
+    }
+    pools.prove.run(move || {
+        let state = match registry.lock() {
+            Ok(state) => state,
+            Err(error) => return internal(error),
+        };
+        let key = credential_key(&req.cid);
+        HttpResponse::Ok().json(WitnessResponse {
+            cid: req.cid.clone(),
+            key_hex: hex::encode(key),
+            status: state.tree.status(&key),
+            epoch: state.epoch,
+            root_hex: hex::encode(state.root),
+            witness_hex: hex::encode(state.tree.witness(&key).to_bytes()),
+        })
+    })
+    .await
+}
+
+#[utoipa::path(
+    tag = "revocation",
+    request_body = RevocationVerifyRequest,
+    responses(
+        (status = 200, body = RevocationVerifyResponse),
+        (status = 400, body = ErrorResponse),
+    )
+)]
+#[post("/zkp/revocation/verify")]
+pub async fn verify(
+    req: web::Json<RevocationVerifyRequest>,
+    registry: web::Data<RevocationRegistry>,
+    receipts: web::Data<ReceiptSigner>,
+    pools: web::Data<WorkPools>,
+) -> impl Responder {
+    pools.verify.run(move || {
+        let Some(root) = parse_hex32_bytes(&req.root_hex) else {
+            return bad_req("invalid root_hex: must be 32 bytes");
+        };
+        let Some(witness) = hex::decode(req.witness_hex.trim_start_matches("0x"))
+            .ok()
+            .and_then(|bytes| Witness::from_bytes(&bytes))
+        else {
+            return bad_req("invalid witness_hex");
+        };
+
+        let verified = verify_witness(&root, &credential_key(&req.cid), req.status, &witness);
+        let epoch = match registry.epoch_of(&root) {
+            Ok(epoch) => epoch,
+            Err(error) => return internal(error),
+        };
+        let current = registry.lock().map(|state| state.root == root).unwrap_or(false);
+        let receipt = receipts.sign("revocation", &*req, &root, verified);
+        metrics::record_verification("revocation", verified);
+        HttpResponse::Ok().json(RevocationVerifyResponse { verified, status: req.status, epoch, current, receipt })
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::{call_service, init_service, read_body_json, TestRequest};
+    use actix_web::App;
+    use serde_json::{json, Value};
+
+    #[actix_web::test]
+    async fn revocation_invalidates_active_witness() {
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(RevocationRegistry::open(":memory:").unwrap()))
+                .app_data(web::Data::new(WorkPools::from_env()))
+                .app_data(web::Data::new(ReceiptSigner::disabled()))
+                .service(update_statuses)
+                .service(current_root)
+                .service(issue_witness)
+                .service(verify),
+        )
+        .await;
+        let post = |uri: &str, body: Value| TestRequest::post().uri(uri).set_json(body).to_request();
+
+        let update = json!({ "updates": [{ "cid": "bafy-other", "status": "suspended" }] });
+        let root: Value = read_body_json(call_service(&app, post("/zkp/revocation/updates", update)).await).await;
+        assert_eq!(root["epoch"], 1);
+
+        let witness: Value = read_body_json(call_service(&app, post("/zkp/revocation/witnesses", json!({ "cid": "bafy-holder" }))).await).await;
+        assert_eq!(witness["status"], "active");
+        let not_revoked = json!({ "cid": "bafy-holder", "root_hex": witness["root_hex"], "witness_hex": witness["witness_hex"] });
+        let outcome: Value = read_body_json(call_service(&app, post("/zkp/revocation/verify", not_revoked.clone())).await).await;
+        assert_eq!(outcome["verified"], true);
+        assert_eq!(outcome["epoch"], 1);
+        assert_eq!(outcome["current"], true);
+
+        let update = json!({ "updates": [{ "cid": "bafy-holder", "status": "revoked" }] });
+        call_service(&app, post("/zkp/revocation/updates", update)).await;
+        let outcome: Value = read_body_json(call_service(&app, post("/zkp/revocation/verify", not_revoked)).await).await;
+        assert_eq!(outcome["verified"], true);
+        assert_eq!(outcome["current"], false);
+
+        let root: Value = read_body_json(call_service(&app, TestRequest::get().uri("/zkp/revocation/root").to_request()).await).await;
+        let stale_against_latest = json!({ "cid": "bafy-holder", "root_hex": root["root_hex"], "witness_hex": witness["witness_hex"] });
+        let outcome: Value = read_body_json(call_service(&app, post("/zkp/revocation/verify", stale_against_latest)).await).await;
+        assert_eq!(outcome["verified"], false);
+
+        let witness: Value = read_body_json(call_service(&app, post("/zkp/revocation/witnesses", json!({ "cid": "bafy-holder" }))).await).await;
+        assert_eq!(witness["status"], "revoked");
+        let revoked = json!({ "cid": "bafy-holder", "status": "revoked", "root_hex": witness["root_hex"], "witness_hex": witness["witness_hex"] });
+        let outcome: Value = read_body_json(call_service(&app, post("/zkp/revocation/verify", revoked)).await).await;
+        assert_eq!(outcome["verified"], true);
+        assert_eq!(outcome["epoch"], 2);
+    }
+}
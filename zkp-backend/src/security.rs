@@ -0,0 +1,718 @@
+//! Access policy: CORS origins, authentication, rate limits and body sizes.
+//!
+//! Loaded once at startup from the JSON file named by `ZKP_SECURITY_CONFIG`,
+//! then overridden by individual variables:
+//!   ZKP_CORS_ORIGINS          — comma-separated origins; unset or "*" allows any
+//!   ZKP_API_KEYS              — comma-separated keys, each valid for both route groups
+//!   ZKP_JWT_SECRET            — HS256 secret for `Authorization: Bearer <jwt>`
+//!   ZKP_REQUIRE_AUTH          — "none", "generation", "verification" or "all"
+//!   ZKP_RATE_LIMIT_PER_IP     — requests per minute per client IP
+//!   ZKP_RATE_LIMIT_PER_KEY    — requests per minute per API key / JWT subject
+//!   ZKP_MAX_BODY_BYTES        — body limit for both route groups
+//!
+//! Without any configuration the server behaves as before: any origin, no
+//! authentication, no rate limits and a 2 MiB body limit.
+//!
+//! Routes fall into groups. GET routes (health, docs, metrics, key and
+//! status lookups) are public; POST routes whose pattern mentions "verify"
+//! form the verification group; every other POST route is generation. The
+//! gRPC server (`zkp-grpc`) applies the same policy, with `Verify` methods in
+//! the verification group and the rest in generation.
+//!
+//! Two groups are never granted implicitly: issuer (`POST
+//! /zkp/revocation/updates` and `POST /zkp/bbs/issue`, which change credential
+//! statuses and sign credentials) and admin
+//! (`POST /zkp/note-link/keys`, which replaces the Groth16 keys). They always
+//! require authentication, and only config-file keys or JWT scopes that list
+//! "issuer" or "admin" may call them; keys from `ZKP_API_KEYS` and JWTs
+//! without a `scope` claim get generation and verification only. Without such
+//! a credential these routes are off.
+//!
+//! Example file:
+//!
+//! ```json
+//! {
+//!   "allowed_origins": ["https://shop.example"],
+//!   "api_keys": [{ "id": "indexer", "key": "…", "groups": ["verification"] }],
+//!   "jwt": { "secret": "…", "issuer": "https://auth.example" },
+//!   "generation": { "require_auth": true, "max_body_bytes": 65536 },
+//!   "verification": { "require_auth": false },
+//!   "rate_limit": { "per_ip": { "per_minute": 60, "burst": 20 } }
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use actix_cors::Cors;
+use actix_web::body::{to_bytes_limited, BodyStream, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{self, HeaderValue};
+use actix_web::http::Method;
+use actix_web::middleware::Next;
+use actix_web::{web, HttpMessage, HttpResponse};
+use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use subtle::ConstantTimeEq;
+use tracing::{debug, info};
+
+/// Actix's own JSON limit, kept as the default.
+const DEFAULT_MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+/// Most clients a limiter tracks; idle full buckets go first, then the least recently seen.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RouteGroup {
+    Generation,
+    Verification,
+    Issuer,
+    Admin,
+}
+
+/// POST routes in the issuer group.
+const ISSUER_ROUTES: &[&str] = &["/zkp/revocation/updates", "/zkp/bbs/issue"];
+
+/// POST routes in the admin group.
+const ADMIN_ROUTES: &[&str] = &["/zkp/note-link/keys"];
+
+/// Policy of groups that must be granted explicitly.
+const ELEVATED: GroupPolicy = GroupPolicy { require_auth: true, max_body_bytes: DEFAULT_MAX_BODY_BYTES };
+
+impl RouteGroup {
+    pub fn parse(value: &str) -> Option<RouteGroup> {
+        match value {
+            "generation" => Some(RouteGroup::Generation),
+            "verification" => Some(RouteGroup::Verification),
+            "issuer" => Some(RouteGroup::Issuer),
+            "admin" => Some(RouteGroup::Admin),
+            _ => None,
+        }
+    }
+
+    /// Groups granted when a credential lists none.
+    pub fn all() -> Vec<RouteGroup> {
+        vec![RouteGroup::Generation, RouteGroup::Verification]
+    }
+
+    /// Group of a request, or `None` for public routes.
+    pub fn of(method: &Method, route: &str) -> Option<RouteGroup> {
+        if method != Method::POST {
+            None
+        } else if ISSUER_ROUTES.contains(&route) {
+            Some(RouteGroup::Issuer)
+        } else if ADMIN_ROUTES.contains(&route) {
+            Some(RouteGroup::Admin)
+        } else if route.contains("verify") {
+            Some(RouteGroup::Verification)
+        } else {
+            Some(RouteGroup::Generation)
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApiKey {
+    pub id: String,
+    pub key: String,
+    #[serde(default = "RouteGroup::all")]
+    pub groups: Vec<RouteGroup>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JwtConfig {
+    pub secret: String,
+    #[serde(default)]
+    pub issuer: Option<String>,
+    #[serde(default)]
+    pub audience: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GroupPolicy {
+    #[serde(default)]
+    pub require_auth: bool,
+    #[serde(default = "default_max_body_bytes")]
+    pub max_body_bytes: usize,
+}
+
+fn default_max_body_bytes() -> usize {
+    DEFAULT_MAX_BODY_BYTES
+}
+
+impl Default for GroupPolicy {
+    fn default() -> Self {
+        Self { require_auth: false, max_body_bytes: DEFAULT_MAX_BODY_BYTES }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimit {
+    pub per_minute: u32,
+    /// Bucket capacity; defaults to `per_minute`.
+    #[serde(default)]
+    pub burst: Option<u32>,
+}
+
+impl RateLimit {
+    fn capacity(&self) -> f64 {
+        f64::from(self.burst.unwrap_or(self.per_minute).max(1))
+    }
+
+    fn refill_per_sec(&self) -> f64 {
+        f64::from(self.per_minute) / 60.0
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimits {
+    #[serde(default)]
+    pub per_ip: Option<RateLimit>,
+    #[serde(default)]
+    pub per_key: Option<RateLimit>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SecurityConfig {
+    /// Empty allows any origin.
+    #[serde(default)]
+    pub allowed_origins: Vec<String>,
+    #[serde(default)]
+    pub api_keys: Vec<ApiKey>,
+    #[serde(default)]
+    pub jwt: Option<JwtConfig>,
+    #[serde(default)]
+    pub generation: GroupPolicy,
+    #[serde(default)]
+    pub verification: GroupPolicy,
+    #[serde(default)]
+    pub rate_limit: RateLimits,
+}
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().map(|value| value.trim().to_string()).filter(|value| !value.is_empty())
+}
+
+fn parse_env<T: std::str::FromStr>(name: &str) -> Result<Option<T>, String> {
+    env_var(name)
+        .map(|value| value.parse::<T>().map_err(|_| format!("{name}: cannot parse {value:?}")))
+        .transpose()
+}
+
+impl SecurityConfig {
+    /// Reads `ZKP_SECURITY_CONFIG` (if set) and applies the env overrides.
+    pub fn from_env() -> Result<Self, String> {
+        let mut config = match env_var("ZKP_SECURITY_CONFIG") {
+            Some(path) => {
+                let text = std::fs::read_to_string(&path).map_err(|e| format!("{path}: {e}"))?;
+                serde_json::from_str(&text).map_err(|e| format!("{path}: {e}"))?
+            }
+            None => SecurityConfig::default(),
+        };
+
+        if let Some(origins) = env_var("ZKP_CORS_ORIGINS") {
+            config.allowed_origins = origins
+                .split(',')
+                .map(str::trim)
+                .filter(|origin| !origin.is_empty() && *origin != "*")
+                .map(str::to_string)
+                .collect();
+        }
+        if let Some(keys) = env_var("ZKP_API_KEYS") {
+            config.api_keys = keys
+                .split(',')
+                .map(str::trim)
+                .filter(|key| !key.is_empty())
+                .enumerate()
+                .map(|(i, key)| ApiKey { id: format!("env-{i}"), key: key.to_string(), groups: RouteGroup::all() })
+                .collect();
+        }
+        if let Some(secret) = env_var("ZKP_JWT_SECRET") {
+            let jwt = config.jwt.get_or_insert(JwtConfig { secret: String::new(), issuer: None, audience: None });
+            jwt.secret = secret;
+        }
+        if let Some(groups) = env_var("ZKP_REQUIRE_AUTH") {
+            let (generation, verification) = match groups.as_str() {
+                "none" => (false, false),
+                "generation" => (true, false),
+                "verification" => (false, true),
+                "all" => (true, true),
+                other => return Err(format!("ZKP_REQUIRE_AUTH: unknown value {other:?}")),
+            };
+            config.generation.require_auth = generation;
+            config.verification.require_auth = verification;
+        }
+        if let Some(per_minute) = parse_env::<u32>("ZKP_RATE_LIMIT_PER_IP")? {
+            config.rate_limit.per_ip = Some(RateLimit { per_minute, burst: None });
+        }
+        if let Some(per_minute) = parse_env::<u32>("ZKP_RATE_LIMIT_PER_KEY")? {
+            config.rate_limit.per_key = Some(RateLimit { per_minute, burst: None });
+        }
+        if let Some(limit) = parse_env::<usize>("ZKP_MAX_BODY_BYTES")? {
+            config.generation.max_body_bytes = limit;
+            config.verification.max_body_bytes = limit;
+        }
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let requires_auth = self.generation.require_auth || self.verification.require_auth;
+        if requires_auth && self.api_keys.is_empty() && self.jwt.is_none() {
+            return Err("authentication is required but no API keys or JWT secret are configured".into());
+        }
+        if self.jwt.as_ref().is_some_and(|jwt| jwt.secret.len() < 32) {
+            return Err("JWT secret must be at least 32 bytes".into());
+        }
+        if self.api_keys.iter().any(|key| key.key.len() < 16) {
+            return Err("API keys must be at least 16 characters".into());
+        }
+        Ok(())
+    }
+
+    pub fn group(&self, group: RouteGroup) -> &GroupPolicy {
+        match group {
+            RouteGroup::Generation => &self.generation,
+            RouteGroup::Verification => &self.verification,
+            RouteGroup::Issuer | RouteGroup::Admin => &ELEVATED,
+        }
+    }
+
+    /// Upper bound handed to `JsonConfig`; `enforce` applies each group's own limit.
+    pub fn json_limit(&self) -> usize {
+        self.generation.max_body_bytes.max(self.verification.max_body_bytes)
+    }
+
+    pub fn cors(&self) -> Cors {
+        let cors = Cors::default().allow_any_method().allow_any_header().expose_any_header();
+        if self.allowed_origins.is_empty() {
+            cors.allow_any_origin()
+        } else {
+            self.allowed_origins.iter().fold(cors, |cors, origin| cors.allowed_origin(origin))
+        }
+    }
+}
+
+// =============================================================================
+// Rate limiting
+// =============================================================================
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Token buckets keyed by client, refilled lazily on each request.
+pub struct RateLimiter {
+    limit: RateLimit,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: RateLimit) -> Self {
+        Self { limit, buckets: Mutex::new(HashMap::new()) }
+    }
+
+    /// Takes one token for `client`, or returns the seconds until one is available.
+    pub fn check(&self, client: &str, now: Instant) -> Result<(), u64> {
+        let capacity = self.limit.capacity();
+        let refill = self.limit.refill_per_sec();
+        let mut buckets = self.buckets.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if buckets.len() >= MAX_TRACKED_CLIENTS && !buckets.contains_key(client) {
+            buckets.retain(|_, bucket| bucket.tokens + refill * now.duration_since(bucket.updated).as_secs_f64() < capacity);
+            while buckets.len() >= MAX_TRACKED_CLIENTS {
+                let Some(oldest) = buckets.iter().min_by_key(|(_, bucket)| bucket.updated).map(|(id, _)| id.clone()) else {
+                    break;
+                };
+                buckets.remove(&oldest);
+            }
+        }
+        let bucket = buckets
+            .entry(client.to_string())
+            .or_insert(Bucket { tokens: capacity, updated: now });
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * refill).min(capacity);
+        bucket.updated = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else if refill > 0.0 {
+            Err(((1.0 - bucket.tokens) / refill).ceil() as u64)
+        } else {
+            Err(60)
+        }
+    }
+}
+
+// =============================================================================
+// Policy and middleware
+// =============================================================================
+
+#[derive(Deserialize)]
+struct Claims {
+    sub: Option<String>,
+    /// Space-separated route groups; absent grants both.
+    scope: Option<String>,
+}
+
+/// Who a request authenticated as, and which groups that identity may call.
+#[derive(Clone, Debug)]
+pub struct Principal {
+    pub id: String,
+    pub groups: Vec<RouteGroup>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Denied {
+    Unauthorized(&'static str),
+    Forbidden,
+    TooLarge(usize),
+    RateLimited(u64),
+}
+
+pub struct SecurityPolicy {
+    config: SecurityConfig,
+    jwt_key: Option<DecodingKey>,
+    jwt_validation: Validation,
+    per_ip: Option<RateLimiter>,
+    per_key: Option<RateLimiter>,
+}
+
+impl SecurityPolicy {
+    pub fn new(config: SecurityConfig) -> Self {
+        let jwt_key = config.jwt.as_ref().map(|jwt| DecodingKey::from_secret(jwt.secret.as_bytes()));
+        let mut jwt_validation = Validation::new(Algorithm::HS256);
+        if let Some(jwt) = &config.jwt {
+            if let Some(issuer) = &jwt.issuer {
+                jwt_validation.set_issuer(&[issuer]);
+            }
+            match &jwt.audience {
+                Some(audience) => jwt_validation.set_audience(&[audience]),
+                None => jwt_validation.validate_aud = false,
+            }
+        }
+        info!(
+            cors_origins = config.allowed_origins.len(),
+            api_keys = config.api_keys.len(),
+            jwt = config.jwt.is_some(),
+            generation_auth = config.generation.require_auth,
+            verification_auth = config.verification.require_auth,
+            per_ip_limit = config.rate_limit.per_ip.is_some(),
+            per_key_limit = config.rate_limit.per_key.is_some(),
+            "security policy loaded"
+        );
+        Self {
+            per_ip: config.rate_limit.per_ip.map(RateLimiter::new),
+            per_key: config.rate_limit.per_key.map(RateLimiter::new),
+            jwt_key,
+            jwt_validation,
+            config,
+        }
+    }
+
+    pub fn config(&self) -> &SecurityConfig {
+        &self.config
+    }
+
+    fn api_key(&self, presented: &str) -> Option<Principal> {
+        self.config
+            .api_keys
+            .iter()
+            .find(|key| bool::from(key.key.as_bytes().ct_eq(presented.as_bytes())))
+            .map(|key| Principal { id: format!("key:{}", key.id), groups: key.groups.clone() })
+    }
+
+    fn jwt(&self, token: &str) -> Option<Principal> {
+        let key = self.jwt_key.as_ref()?;
+        let claims = decode::<Claims>(token, key, &self.jwt_validation).ok()?.claims;
+        let groups = match claims.scope {
+            Some(scope) => scope.split_whitespace().filter_map(RouteGroup::parse).collect(),
+            None => RouteGroup::all(),
+        };
+        Some(Principal { id: format!("jwt:{}", claims.sub.unwrap_or_default()), groups })
+    }
+
+    /// Checks the `x-api-key` or `Authorization` value, whichever was sent;
+    /// `Ok(None)` when neither was. Values that are not valid UTF-8 arrive as "".
+    pub fn authenticate(&self, api_key: Option<&str>, authorization: Option<&str>) -> Result<Option<Principal>, Denied> {
+        if let Some(presented) = api_key {
+            return self.api_key(presented).map(Some).ok_or(Denied::Unauthorized("invalid API key"));
+        }
+        if let Some(value) = authorization {
+            let token = value.strip_prefix("Bearer ").ok_or(Denied::Unauthorized("expected a Bearer token"))?;
+            return self.jwt(token.trim()).map(Some).ok_or(Denied::Unauthorized("invalid or expired token"));
+        }
+        Ok(None)
+    }
+
+    pub fn limit_ip(&self, ip: IpAddr, now: Instant) -> Result<(), Denied> {
+        match &self.per_ip {
+            Some(limiter) => limiter.check(&client_ip(ip), now).map_err(Denied::RateLimited),
+            None => Ok(()),
+        }
+    }
+
+    pub fn limit_principal(&self, principal: Option<&Principal>, now: Instant) -> Result<(), Denied> {
+        match (&self.per_key, principal) {
+            (Some(limiter), Some(principal)) => limiter.check(&principal.id, now).map_err(Denied::RateLimited),
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether `principal` (or an anonymous caller) may call routes in `group`.
+    pub fn authorize(&self, group: RouteGroup, principal: Option<&Principal>) -> Result<(), Denied> {
+        match principal {
+            Some(principal) if !principal.groups.contains(&group) => Err(Denied::Forbidden),
+            None if self.config.group(group).require_auth => Err(Denied::Unauthorized("authentication required")),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the body limit of the request's route group, if it has one.
+    fn check(&self, req: &ServiceRequest, now: Instant) -> Result<Option<usize>, Denied> {
+        let route = req.match_pattern().unwrap_or_else(|| req.path().to_string());
+        let Some(group) = RouteGroup::of(req.method(), &route) else {
+            return Ok(None);
+        };
+        let policy = self.config.group(group);
+
+        let content_length = req
+            .headers()
+            .get(header::CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<usize>().ok());
+        if content_length.is_some_and(|length| length > policy.max_body_bytes) {
+            return Err(Denied::TooLarge(policy.max_body_bytes));
+        }
+
+        // Per-IP first, so failed credential guesses also spend tokens.
+        if let Some(addr) = req.peer_addr() {
+            self.limit_ip(addr.ip(), now)?;
+        }
+
+        let headers = req.headers();
+        let value_of = |name| headers.get(name).map(|value: &HeaderValue| value.to_str().unwrap_or_default());
+        let principal = self.authenticate(value_of("x-api-key"), value_of(header::AUTHORIZATION.as_str()))?;
+        self.authorize(group, principal.as_ref())?;
+        self.limit_principal(principal.as_ref(), now)?;
+        Ok(Some(policy.max_body_bytes))
+    }
+}
+
+/// IPv6 clients are limited per /64, since one host usually holds the whole prefix.
+fn client_ip(ip: IpAddr) -> String {
+    match ip {
+        IpAddr::V4(v4) => v4.to_string(),
+        IpAddr::V6(v6) => {
+            let segments = v6.segments();
+            format!("{:x}:{:x}:{:x}:{:x}::/64", segments[0], segments[1], segments[2], segments[3])
+        }
+    }
+}
+
+/// Same `{ "error": ... }` body as the server's other 4xx responses.
+fn denied_response(denied: Denied) -> HttpResponse {
+    let error = |message: String| json!({ "error": message });
+    match denied {
+        Denied::Unauthorized(reason) => HttpResponse::Unauthorized()
+            .insert_header((header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer")))
+            .json(error(reason.to_string())),
+        Denied::Forbidden => HttpResponse::Forbidden().json(error("credentials do not cover this route".into())),
+        Denied::TooLarge(limit) => {
+            HttpResponse::PayloadTooLarge().json(error(format!("request body exceeds {limit} bytes")))
+        }
+        Denied::RateLimited(retry_after) => HttpResponse::TooManyRequests()
+            .insert_header((header::RETRY_AFTER, retry_after.to_string()))
+            .json(error("rate limit exceeded".into())),
+    }
+}
+
+/// Reads the body into memory, up to `limit` bytes, and hands it back to the
+/// request. `Content-Length` alone would let chunked uploads through.
+async fn buffer_body(req: &mut ServiceRequest, limit: usize) -> Result<Result<(), Denied>, actix_web::Error> {
+    match to_bytes_limited(BodyStream::new(req.take_payload()), limit).await {
+        Ok(body) => {
+            req.set_payload(body?.into());
+            Ok(Ok(()))
+        }
+        Err(_) => Ok(Err(Denied::TooLarge(limit))),
+    }
+}
+
+/// Middleware enforcing the `SecurityPolicy` registered as app data.
+pub async fn enforce(
+    mut req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let checked = match req.app_data::<web::Data<SecurityPolicy>>() {
+        Some(policy) => policy.check(&req, Instant::now()),
+        None => Ok(None),
+    };
+    let denied = match checked {
+        Ok(Some(limit)) => buffer_body(&mut req, limit).await?.err(),
+        Ok(None) => None,
+        Err(denied) => Some(denied),
+    };
+    match denied {
+        Some(denied) => {
+            let response = denied_response(denied);
+            debug!(route = req.path(), status = response.status().as_u16(), "request denied by security policy");
+            Ok(req.into_response(response).map_into_right_body())
+        }
+        None => next.call(req).await.map(ServiceResponse::map_into_left_body),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::{call_and_read_body, call_service, init_service, TestRequest};
+    use actix_web::{middleware, post, App};
+    use std::time::Duration;
+
+    const KEY: &str = "verify-only-key-0123456789";
+
+    #[post("/zkp/verify")]
+    async fn verify(body: web::Bytes) -> HttpResponse {
+        HttpResponse::Ok().body(body)
+    }
+
+    #[post("/zkp/generate")]
+    async fn generate() -> HttpResponse {
+        HttpResponse::Ok().finish()
+    }
+
+    #[post("/zkp/note-link/keys")]
+    async fn keys() -> HttpResponse {
+        HttpResponse::Ok().finish()
+    }
+
+    #[test]
+    fn token_bucket_refills_over_time() {
+        let limiter = RateLimiter::new(RateLimit { per_minute: 60, burst: Some(2) });
+        let start = Instant::now();
+        assert!(limiter.check("a", start).is_ok());
+        assert!(limiter.check("a", start).is_ok());
+        assert_eq!(limiter.check("a", start), Err(1));
+        assert!(limiter.check("b", start).is_ok());
+        assert!(limiter.check("a", start + Duration::from_secs(1)).is_ok());
+    }
+
+    #[test]
+    fn busy_limiters_forget_the_least_recently_seen_client() {
+        let limiter = RateLimiter::new(RateLimit { per_minute: 1, burst: Some(1) });
+        let start = Instant::now();
+        for client in 0..=MAX_TRACKED_CLIENTS {
+            let now = start + Duration::from_millis(client as u64);
+            assert!(limiter.check(&client.to_string(), now).is_ok());
+        }
+        assert_eq!(limiter.buckets.lock().unwrap().len(), MAX_TRACKED_CLIENTS);
+        // The first client was dropped and starts over with a full bucket.
+        let later = start + Duration::from_secs(20);
+        assert!(limiter.check("0", later).is_ok());
+        assert!(limiter.check(&MAX_TRACKED_CLIENTS.to_string(), later).is_err());
+    }
+
+    #[test]
+    fn routes_map_onto_groups() {
+        assert_eq!(RouteGroup::of(&Method::POST, "/zkp/verify-vc"), Some(RouteGroup::Verification));
+        assert_eq!(RouteGroup::of(&Method::POST, "/v2/proofs/equality"), Some(RouteGroup::Generation));
+        assert_eq!(RouteGroup::of(&Method::GET, "/zkp/replay/{nullifier}"), None);
+        assert_eq!(RouteGroup::of(&Method::POST, "/zkp/revocation/updates"), Some(RouteGroup::Issuer));
+        assert_eq!(RouteGroup::of(&Method::POST, "/zkp/bbs/issue"), Some(RouteGroup::Issuer));
+        assert_eq!(RouteGroup::of(&Method::POST, "/zkp/note-link/keys"), Some(RouteGroup::Admin));
+        assert_eq!(RouteGroup::of(&Method::GET, "/zkp/note-link/keys"), None);
+    }
+
+    #[actix_web::test]
+    async fn keys_are_scoped_to_route_groups() {
+        let config = SecurityConfig {
+            api_keys: vec![ApiKey { id: "indexer".into(), key: KEY.into(), groups: vec![RouteGroup::Verification] }],
+            generation: GroupPolicy { require_auth: true, max_body_bytes: 64 },
+            verification: GroupPolicy { require_auth: false, max_body_bytes: 64 },
+            ..SecurityConfig::default()
+        };
+        config.validate().unwrap();
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(SecurityPolicy::new(config)))
+                .wrap(middleware::from_fn(enforce))
+                .service(verify)
+                .service(generate)
+                .service(keys),
+        )
+        .await;
+
+        let anonymous_verify = TestRequest::post().uri("/zkp/verify").to_request();
+        assert_eq!(call_service(&app, anonymous_verify).await.status(), 200);
+
+        let anonymous_generate = TestRequest::post().uri("/zkp/generate").to_request();
+        assert_eq!(call_service(&app, anonymous_generate).await.status(), 401);
+
+        let scoped_generate = TestRequest::post().uri("/zkp/generate").insert_header(("x-api-key", KEY)).to_request();
+        assert_eq!(call_service(&app, scoped_generate).await.status(), 403);
+
+        let wrong_key = TestRequest::post().uri("/zkp/verify").insert_header(("x-api-key", "nope")).to_request();
+        assert_eq!(call_service(&app, wrong_key).await.status(), 401);
+
+        let oversized = TestRequest::post()
+            .uri("/zkp/generate")
+            .insert_header((header::CONTENT_LENGTH, 65))
+            .set_payload(vec![b'0'; 65])
+            .to_request();
+        assert_eq!(call_service(&app, oversized).await.status(), 413);
+
+        let chunked = TestRequest::post()
+            .uri("/zkp/verify")
+            .insert_header((header::TRANSFER_ENCODING, "chunked"))
+            .set_payload(vec![b'0'; 65])
+            .to_request();
+        assert_eq!(call_service(&app, chunked).await.status(), 413);
+
+        let small = TestRequest::post()
+            .uri("/zkp/verify")
+            .insert_header((header::TRANSFER_ENCODING, "chunked"))
+            .set_payload("{}")
+            .to_request();
+        assert_eq!(call_and_read_body(&app, small).await, "{}");
+    }
+
+    #[actix_web::test]
+    async fn admin_routes_need_an_explicit_grant() {
+        const ADMIN_KEY: &str = "0123456789abcdef-admin";
+        let config = SecurityConfig {
+            api_keys: vec![
+                ApiKey { id: "indexer".into(), key: KEY.into(), groups: RouteGroup::all() },
+                ApiKey { id: "operator".into(), key: ADMIN_KEY.into(), groups: vec![RouteGroup::Admin] },
+            ],
+            ..SecurityConfig::default()
+        };
+        config.validate().unwrap();
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(SecurityPolicy::new(config)))
+                .wrap(middleware::from_fn(enforce))
+                .service(keys),
+        )
+        .await;
+
+        let anonymous = TestRequest::post().uri("/zkp/note-link/keys").to_request();
+        assert_eq!(call_service(&app, anonymous).await.status(), 401);
+
+        let default_key = TestRequest::post().uri("/zkp/note-link/keys").insert_header(("x-api-key", KEY)).to_request();
+        assert_eq!(call_service(&app, default_key).await.status(), 403);
+
+        let admin = TestRequest::post().uri("/zkp/note-link/keys").insert_header(("x-api-key", ADMIN_KEY)).to_request();
+        assert_eq!(call_service(&app, admin).await.status(), 200);
+    }
+}
@@ -0,0 +1,89 @@
+//! Log subscriber setup and per-request spans.
+//!
+//! Configured from the environment:
+//!   ZKP_LOG        — filter directives, e.g. "info" (default) or "info,bulletproof_demo=debug"
+//!   ZKP_LOG_FORMAT — "text" (default) or "json", one object per line
+//!
+//! Log events never carry tx hashes, binding tags, blindings or committed
+//! values: handlers and proof spans record only lengths, flags and outcomes.
+//! The HTTP and gRPC servers both call `init`; the rest of the library only
+//! emits `tracing` events, so it stays silent in the CLI, tests and WASM
+//! unless a subscriber is installed.
+
+use std::time::Instant;
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::middleware::Next;
+use tracing::{field, info, info_span, Instrument};
+use tracing_subscriber::EnvFilter;
+
+pub fn init() {
+    let filter = EnvFilter::try_from_env("ZKP_LOG").unwrap_or_else(|_| EnvFilter::new("info"));
+    let builder = tracing_subscriber::fmt().with_env_filter(filter);
+    match std::env::var("ZKP_LOG_FORMAT").as_deref().map(str::trim) {
+        Ok("json") => builder.json().flatten_event(true).init(),
+        _ => builder.init(),
+    }
+}
+
+/// Middleware wrapping each request in a `request` span and logging its outcome.
+///
+/// The span records the matched route pattern rather than the raw path, and
+/// the generated id is echoed back as `x-request-id`.
+pub async fn request_span(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let request_id = format!("{:016x}", rand::random::<u64>());
+    let route = req.match_pattern().unwrap_or_else(|| "unmatched".to_string());
+    let span = info_span!(
+        "request",
+        request_id = %request_id,
+        method = %req.method(),
+        route = %route,
+        status = field::Empty,
+        latency_ms = field::Empty,
+    );
+
+    let started = Instant::now();
+    let result = next.call(req).instrument(span.clone()).await;
+    let status = match &result {
+        Ok(res) => res.status(),
+        Err(error) => error.as_response_error().status_code(),
+    };
+    span.record("status", status.as_u16());
+    span.record("latency_ms", started.elapsed().as_millis() as u64);
+    span.in_scope(|| info!("request finished"));
+
+    let mut res = result?;
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        res.headers_mut().insert(HeaderName::from_static("x-request-id"), value);
+    }
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::{call_service, init_service, TestRequest};
+    use actix_web::{middleware, web, App, HttpResponse};
+
+    #[actix_web::test]
+    async fn responses_carry_a_request_id() {
+        let app = init_service(
+            App::new()
+                .wrap(middleware::from_fn(request_span))
+                .route("/health", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let first = call_service(&app, TestRequest::get().uri("/health").to_request()).await;
+        let second = call_service(&app, TestRequest::get().uri("/missing").to_request()).await;
+
+        let first_id = first.headers().get("x-request-id").unwrap();
+        assert_eq!(first_id.len(), 16);
+        assert_ne!(first_id, second.headers().get("x-request-id").unwrap());
+    }
+}
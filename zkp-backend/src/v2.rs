@@ -0,0 +1,643 @@
+//! Versioned API (`/v2`).
+//!
+//! Endpoints are grouped by domain and share one style: hex inputs and
+//! outputs end in `_hex`, prove calls return the proof together with the
+//! prover's self-check `verified`, verify calls return
+//! `{ verified, validity?, replay?, receipt? }`, and errors are `{ "error": ... }`.
+//! Range, equality and quantity-total proofs take an optional `validity`
+//! window; `validity` in a verify response says whether a genuine proof was
+//! presented inside it (`valid`), too early (`not_yet_valid`) or too late
+//! (`expired`).
+//!
+//!   POST /v2/commitments                      value commitment + 64-bit range proof
+//!   POST /v2/proofs/range/verify
+//!   POST /v2/proofs/equality                  POST /v2/proofs/equality/verify
+//!   POST /v2/proofs/quantity-total            POST /v2/proofs/quantity-total/verify
+//!   POST /v2/txref                            POST /v2/txref/verify
+//!   GET  /v2/deprecations                     usage of the legacy routes below
+//!
+//! The overlapping `/zkp/...` routes keep working unchanged. `deprecation`
+//! tags their responses with `Deprecation`, `Sunset` and a `Link` to the
+//! successor, and counts every call so we can tell when it is safe to drop them.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::middleware::Next;
+use actix_web::{get, post, web, HttpResponse, Responder};
+use bulletproof_demo::api::{self, parse_compressed_ristretto, parse_hex32_bytes, parse_scalar_hex, IntegerLike};
+use bulletproof_demo::zk::pedersen::{prove_value_commitment_within, verify_value_commitment_within};
+use bulletproof_demo::zk::txid_pedersen_proof::{prove_txid_commitment_with_binding, verify_txid_commitment_with_binding};
+use bulletproof_demo::zk::validity::{windowed_outcome, ValidityWindow, WindowStatus};
+use curve25519_dalek_ng::{ristretto::CompressedRistretto, scalar::Scalar};
+use hex::FromHex;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+use utoipa::ToSchema;
+
+use crate::metrics;
+use crate::pool::WorkPools;
+use crate::receipts::{ReceiptSigner, VerificationReceipt};
+use crate::replay::{ReplayRegistry, ReplayStatus};
+use crate::{api_error, bad_req, check_replay, ErrorResponse};
+
+// =============================================================================
+// Legacy route deprecation
+// =============================================================================
+
+/// `Deprecation` header value (RFC 9745): 2026-10-18T00:00:00Z.
+const DEPRECATED_AT: &str = "@1792281600";
+/// `Sunset` header value (RFC 8594).
+pub const LEGACY_SUNSET: &str = "Fri, 30 Apr 2027 00:00:00 GMT";
+
+/// Legacy route → its `/v2` successor.
+pub const LEGACY_ROUTES: &[(&str, &str)] = &[
+    ("/zkp/commit-value", "/v2/commitments"),
+    ("/zkp/generate-value-commitment", "/v2/commitments"),
+    ("/zkp/generate-value-commitment-with-blinding", "/v2/commitments"),
+    ("/zkp/generate-value-commitment-with-binding", "/v2/commitments"),
+    ("/zkp/verify-value", "/v2/proofs/range/verify"),
+    ("/zkp/verify-value-commitment", "/v2/proofs/range/verify"),
+    ("/zkp/generate", "/v2/txref"),
+    ("/zkp/commit-tx-hash", "/v2/txref"),
+    ("/zkp/verify", "/v2/txref/verify"),
+    ("/zkp/generate-equality-proof", "/v2/proofs/equality"),
+    ("/zkp/verify-equality-proof", "/v2/proofs/equality/verify"),
+    ("/zkp/generate-quantity-total-proof", "/v2/proofs/quantity-total"),
+    ("/zkp/verify-quantity-total-proof", "/v2/proofs/quantity-total/verify"),
+];
+
+/// Per-route call counters for `LEGACY_ROUTES`, in the same order.
+pub struct LegacyUsage {
+    calls: Vec<AtomicU64>,
+}
+
+impl Default for LegacyUsage {
+    fn default() -> Self {
+        Self { calls: LEGACY_ROUTES.iter().map(|_| AtomicU64::new(0)).collect() }
+    }
+}
+
+impl LegacyUsage {
+    /// Counts a call if `path` is a legacy route and returns its successor.
+    pub fn record(&self, path: &str) -> Option<&'static str> {
+        let index = LEGACY_ROUTES.iter().position(|(route, _)| *route == path)?;
+        self.calls[index].fetch_add(1, Ordering::Relaxed);
+        Some(LEGACY_ROUTES[index].1)
+    }
+
+    pub fn calls(&self, path: &str) -> u64 {
+        LEGACY_ROUTES
+            .iter()
+            .position(|(route, _)| *route == path)
+            .map_or(0, |index| self.calls[index].load(Ordering::Relaxed))
+    }
+}
+
+/// Middleware marking responses of legacy routes as deprecated.
+pub async fn deprecation(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let successor = req
+        .app_data::<web::Data<LegacyUsage>>()
+        .and_then(|usage| usage.record(req.path()));
+    if let Some(successor) = successor {
+        debug!(route = req.path(), successor, "deprecated route called");
+    }
+
+    let mut res = next.call(req).await?;
+    if let Some(successor) = successor {
+        let headers = res.headers_mut();
+        headers.insert(HeaderName::from_static("deprecation"), HeaderValue::from_static(DEPRECATED_AT));
+        headers.insert(HeaderName::from_static("sunset"), HeaderValue::from_static(LEGACY_SUNSET));
+        if let Ok(link) = HeaderValue::from_str(&format!("<{successor}>; rel=\"successor-version\"")) {
+            headers.insert(actix_web::http::header::LINK, link);
+        }
+    }
+    Ok(res)
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DeprecatedRoute {
+    route: &'static str,
+    successor: &'static str,
+    sunset: &'static str,
+    calls: u64,
+}
+
+#[utoipa::path(tag = "v2", responses((status = 200, body = Vec<DeprecatedRoute>)))]
+#[get("/v2/deprecations")]
+pub async fn deprecations(usage: web::Data<LegacyUsage>) -> impl Responder {
+    let routes: Vec<DeprecatedRoute> = LEGACY_ROUTES
+        .iter()
+        .map(|(route, successor)| DeprecatedRoute {
+            route,
+            successor,
+            sunset: LEGACY_SUNSET,
+            calls: usage.calls(route),
+        })
+        .collect();
+    HttpResponse::Ok().json(routes)
+}
+
+// =============================================================================
+// Shared request/response types
+// =============================================================================
+
+#[derive(Serialize, ToSchema)]
+pub struct CommitmentResponse {
+    commitment_hex: String,
+    proof_hex: String,
+    verified: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    validity: Option<ValidityWindow>,
+}
+
+/// Body of `/v2/txref/verify`.
+#[derive(Deserialize, Serialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CommitmentVerifyRequest {
+    commitment_hex: String,
+    proof_hex: String,
+    #[serde(default)]
+    binding_tag_hex: Option<String>,
+}
+
+/// Body of `/v2/proofs/range/verify`.
+#[derive(Deserialize, Serialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RangeVerifyRequest {
+    commitment_hex: String,
+    proof_hex: String,
+    #[serde(default)]
+    binding_tag_hex: Option<String>,
+    #[serde(default)]
+    validity: Option<ValidityWindow>,
+    /// Unix seconds (or block number) to check `validity` against; defaults to now.
+    #[serde(default)]
+    reference_time: Option<u64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct VerifyResponse {
+    verified: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    validity: Option<WindowStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    replay: Option<ReplayStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    receipt: Option<VerificationReceipt>,
+}
+
+fn binding_tag(binding_tag_hex: &Option<String>) -> Result<Option<[u8; 32]>, String> {
+    match binding_tag_hex {
+        Some(hex_str) => parse_hex32_bytes(hex_str)
+            .map(Some)
+            .ok_or_else(|| "invalid binding_tag_hex: must be 32 bytes (64 hex chars)".to_string()),
+        None => Ok(None),
+    }
+}
+
+fn parse_binding_tag(binding_tag_hex: &Option<String>) -> Result<Option<[u8; 32]>, HttpResponse> {
+    binding_tag(binding_tag_hex).map_err(|error| bad_req(&error))
+}
+
+/// Verify steps shared by every `/v2` verify endpoint: replay check, receipt, response.
+fn verify_response<T: Serialize>(
+    replay: &ReplayRegistry,
+    receipts: &ReceiptSigner,
+    kind: &str,
+    req: &T,
+    outcome: &api::Verification,
+) -> HttpResponse {
+    let verified = outcome.verified;
+    let replay = match check_replay(replay, kind, &outcome.context, &outcome.proof, verified) {
+        Ok(status) => status,
+        Err(response) => return response,
+    };
+    let receipt = receipts.sign_windowed(kind, req, &outcome.context, verified, outcome.reference);
+    metrics::record_verification(kind, verified);
+    HttpResponse::Ok().json(VerifyResponse { verified, validity: outcome.validity, replay, receipt })
+}
+
+// =============================================================================
+// /v2/commitments, /v2/proofs/range/verify
+// =============================================================================
+
+#[derive(Deserialize, Serialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CommitmentRequest {
+    value: IntegerLike,
+    /// 32-byte scalar; a random blinding is used when omitted.
+    #[serde(default)]
+    blinding_hex: Option<String>,
+    #[serde(default)]
+    binding_tag_hex: Option<String>,
+    #[serde(default)]
+    validity: Option<ValidityWindow>,
+}
+
+#[utoipa::path(
+    tag = "v2",
+    request_body = CommitmentRequest,
+    responses(
+        (status = 200, body = CommitmentResponse),
+        (status = 400, body = ErrorResponse),
+    )
+)]
+#[post("/v2/commitments")]
+pub async fn create_commitment(req: web::Json<CommitmentRequest>, pools: web::Data<WorkPools>) -> impl Responder {
+    pools.prove.run(move || match prove_commitment(&req) {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(error) => bad_req(&error),
+    })
+    .await
+}
+
+/// Body of `/v2/commitments`, also run by `/zkp/jobs` items of kind "value-range".
+pub(crate) fn prove_commitment(req: &CommitmentRequest) -> Result<CommitmentResponse, String> {
+    let value = req.value.parse_u64("value")?;
+    let blinding = match &req.blinding_hex {
+        Some(blinding_hex) => parse_scalar_hex(blinding_hex)
+            .ok_or_else(|| "invalid blinding_hex: must be 32 bytes (64 hex chars)".to_string())?,
+        None => {
+            let mut bytes = [0u8; 32];
+            OsRng.fill_bytes(&mut bytes);
+            Scalar::from_bytes_mod_order(bytes)
+        }
+    };
+    let binding_tag = binding_tag(&req.binding_tag_hex)?;
+    api::check_validity_window(&req.validity).map_err(|error| error.to_string())?;
+
+    let (commitment, proof, verified) = prove_value_commitment_within(
+        value,
+        blinding,
+        binding_tag.as_ref().map(|tag| &tag[..]),
+        req.validity.as_ref(),
+    );
+    metrics::record_proof_size("value-range", proof.len());
+    Ok(CommitmentResponse {
+        commitment_hex: hex::encode(commitment.as_bytes()),
+        proof_hex: hex::encode(proof),
+        verified,
+        validity: req.validity,
+    })
+}
+
+#[utoipa::path(
+    tag = "v2",
+    request_body = RangeVerifyRequest,
+    responses(
+        (status = 200, body = VerifyResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 409, description = "Replay registry in reject mode and proof already seen"),
+    )
+)]
+#[post("/v2/proofs/range/verify")]
+pub async fn verify_range(
+    req: web::Json<RangeVerifyRequest>,
+    replay: web::Data<ReplayRegistry>,
+    receipts: web::Data<ReceiptSigner>,
+    pools: web::Data<WorkPools>,
+) -> impl Responder {
+    pools.verify.run(move || {
+        let (commitment, proof, binding_tag) = match parse_commitment_verify(&req.commitment_hex, &req.proof_hex, &req.binding_tag_hex) {
+            Ok(parsed) => parsed,
+            Err(response) => return response,
+        };
+        let reference = match api::reference_point(&req.validity, req.reference_time) {
+            Ok(reference) => reference,
+            Err(error) => return api_error(error),
+        };
+        let window = req.validity.as_ref();
+        let holds = verify_value_commitment_within(commitment, proof.clone(), binding_tag.as_ref().map(|tag| &tag[..]), window);
+        let (verified, validity) = windowed_outcome(holds, window, reference.reference_time);
+        let context = binding_tag.map(|tag| tag.to_vec()).unwrap_or_default();
+        let reference = window.is_some().then_some(reference);
+        let outcome = api::Verification { verified, validity, reference, context, proof };
+        verify_response(&replay, &receipts, "value-range", &*req, &outcome)
+    })
+    .await
+}
+
+/// Commitment, proof bytes and optional binding tag.
+type ParsedCommitmentProof = (CompressedRistretto, Vec<u8>, Option<[u8; 32]>);
+
+fn parse_commitment_verify(
+    commitment_hex: &str,
+    proof_hex: &str,
+    binding_tag_hex: &Option<String>,
+) -> Result<ParsedCommitmentProof, HttpResponse> {
+    let commitment = parse_compressed_ristretto(commitment_hex).ok_or_else(|| bad_req("invalid commitment_hex"))?;
+    let proof = Vec::from_hex(proof_hex.trim_start_matches("0x")).map_err(|_| bad_req("invalid proof_hex"))?;
+    let binding_tag = parse_binding_tag(binding_tag_hex)?;
+    Ok((commitment, proof, binding_tag))
+}
+
+// =============================================================================
+// /v2/proofs/equality, /v2/proofs/quantity-total
+// =============================================================================
+
+#[utoipa::path(
+    tag = "v2",
+    request_body = api::EqualityProofRequest,
+    responses(
+        (status = 200, body = api::SigmaProofResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 500, body = ErrorResponse),
+    )
+)]
+#[post("/v2/proofs/equality")]
+pub async fn prove_equality(req: web::Json<api::EqualityProofRequest>, pools: web::Data<WorkPools>) -> impl Responder {
+    pools.prove.run(move || {
+        match api::generate_equality_proof(&req) {
+            Ok(response) => {
+                metrics::record_proof_hex("equality", &[&response.proof_r_hex, &response.proof_s_hex]);
+                HttpResponse::Ok().json(response)
+            }
+            Err(error) => api_error(error),
+        }
+    })
+    .await
+}
+
+#[utoipa::path(
+    tag = "v2",
+    request_body = api::EqualityVerifyRequest,
+    responses(
+        (status = 200, body = VerifyResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 409, description = "Replay registry in reject mode and proof already seen"),
+    )
+)]
+#[post("/v2/proofs/equality/verify")]
+pub async fn verify_equality(
+    req: web::Json<api::EqualityVerifyRequest>,
+    replay: web::Data<ReplayRegistry>,
+    receipts: web::Data<ReceiptSigner>,
+    pools: web::Data<WorkPools>,
+) -> impl Responder {
+    pools.verify.run(move || {
+        match api::verify_equality_proof(&req) {
+            Ok(outcome) => verify_response(&replay, &receipts, "equality", &*req, &outcome),
+            Err(error) => api_error(error),
+        }
+    })
+    .await
+}
+
+#[utoipa::path(
+    tag = "v2",
+    request_body = api::QuantityTotalProofRequest,
+    responses(
+        (status = 200, body = api::SigmaProofResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 500, body = ErrorResponse),
+    )
+)]
+#[post("/v2/proofs/quantity-total")]
+pub async fn prove_quantity_total(req: web::Json<api::QuantityTotalProofRequest>, pools: web::Data<WorkPools>) -> impl Responder {
+    pools.prove.run(move || {
+        match api::generate_quantity_total_proof(&req) {
+            Ok(response) => {
+                metrics::record_proof_hex("quantity-total", &[&response.proof_r_hex, &response.proof_s_hex]);
+                HttpResponse::Ok().json(response)
+            }
+            Err(error) => api_error(error),
+        }
+    })
+    .await
+}
+
+#[utoipa::path(
+    tag = "v2",
+    request_body = api::QuantityTotalVerifyRequest,
+    responses(
+        (status = 200, body = VerifyResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 409, description = "Replay registry in reject mode and proof already seen"),
+    )
+)]
+#[post("/v2/proofs/quantity-total/verify")]
+pub async fn verify_quantity_total(
+    req: web::Json<api::QuantityTotalVerifyRequest>,
+    replay: web::Data<ReplayRegistry>,
+    receipts: web::Data<ReceiptSigner>,
+    pools: web::Data<WorkPools>,
+) -> impl Responder {
+    pools.verify.run(move || {
+        match api::verify_quantity_total_proof(&req) {
+            Ok(outcome) => verify_response(&replay, &receipts, "quantity-total", &*req, &outcome),
+            Err(error) => api_error(error),
+        }
+    })
+    .await
+}
+
+// =============================================================================
+// /v2/txref
+// =============================================================================
+
+#[derive(Deserialize, Serialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TxRefRequest {
+    /// 32-byte transaction hash.
+    tx_hash_hex: String,
+    #[serde(default)]
+    binding_tag_hex: Option<String>,
+}
+
+#[utoipa::path(
+    tag = "v2",
+    request_body = TxRefRequest,
+    responses(
+        (status = 200, body = CommitmentResponse),
+        (status = 400, body = ErrorResponse),
+    )
+)]
+#[post("/v2/txref")]
+pub async fn commit_txref(req: web::Json<TxRefRequest>, pools: web::Data<WorkPools>) -> impl Responder {
+    pools.prove.run(move || match prove_txref(&req) {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(error) => bad_req(&error),
+    })
+    .await
+}
+
+/// Body of `/v2/txref`, also run by `/zkp/jobs` items of kind "txid".
+pub(crate) fn prove_txref(req: &TxRefRequest) -> Result<CommitmentResponse, String> {
+    let tx_hash = parse_hex32_bytes(req.tx_hash_hex.trim())
+        .ok_or_else(|| "invalid tx_hash_hex: must be 32 bytes (64 hex chars)".to_string())?;
+    let binding_tag = binding_tag(&req.binding_tag_hex)?;
+
+    let (commitment, proof, verified) = prove_txid_commitment_with_binding(
+        Scalar::from_bytes_mod_order(tx_hash),
+        binding_tag.as_ref().map(|tag| &tag[..]),
+    );
+    metrics::record_proof_size("txid", proof.len());
+    Ok(CommitmentResponse {
+        commitment_hex: hex::encode(commitment.as_bytes()),
+        proof_hex: hex::encode(proof),
+        verified,
+        validity: None,
+    })
+}
+
+#[utoipa::path(
+    tag = "v2",
+    request_body = CommitmentVerifyRequest,
+    responses(
+        (status = 200, body = VerifyResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 409, description = "Replay registry in reject mode and proof already seen"),
+    )
+)]
+#[post("/v2/txref/verify")]
+pub async fn verify_txref(
+    req: web::Json<CommitmentVerifyRequest>,
+    replay: web::Data<ReplayRegistry>,
+    receipts: web::Data<ReceiptSigner>,
+    pools: web::Data<WorkPools>,
+) -> impl Responder {
+    pools.verify.run(move || {
+        let (commitment, proof, binding_tag) = match parse_commitment_verify(&req.commitment_hex, &req.proof_hex, &req.binding_tag_hex) {
+            Ok(parsed) => parsed,
+            Err(response) => return response,
+        };
+        let context = binding_tag.map(|tag| tag.to_vec()).unwrap_or_default();
+        let verified = verify_txid_commitment_with_binding(commitment, proof.clone(), binding_tag.as_ref().map(|tag| &tag[..]));
+        let outcome = api::Verification { verified, validity: None, reference: None, context, proof };
+        verify_response(&replay, &receipts, "txid", &*req, &outcome)
+    })
+    .await
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(create_commitment)
+        .service(verify_range)
+        .service(prove_equality)
+        .service(verify_equality)
+        .service(prove_quantity_total)
+        .service(verify_quantity_total)
+        .service(commit_txref)
+        .service(verify_txref)
+        .service(deprecations);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::{call_and_read_body_json, call_service, init_service, read_body_json, TestRequest};
+    use actix_web::{middleware, App};
+    use serde_json::{json, Value};
+
+    fn app_data() -> (web::Data<LegacyUsage>, web::Data<ReplayRegistry>, web::Data<ReceiptSigner>) {
+        (
+            web::Data::new(LegacyUsage::default()),
+            web::Data::new(ReplayRegistry::disabled()),
+            web::Data::new(ReceiptSigner::disabled()),
+        )
+    }
+
+    #[test]
+    fn legacy_usage_counts_only_legacy_routes() {
+        let usage = LegacyUsage::default();
+        assert_eq!(usage.record("/zkp/commit-value"), Some("/v2/commitments"));
+        assert_eq!(usage.record("/zkp/commit-value"), Some("/v2/commitments"));
+        assert_eq!(usage.record("/v2/commitments"), None);
+        assert_eq!(usage.calls("/zkp/commit-value"), 2);
+        assert_eq!(usage.calls("/zkp/verify"), 0);
+    }
+
+    #[actix_web::test]
+    async fn range_proof_outside_its_window_is_expired() {
+        let (_, replay, receipts) = app_data();
+        let app = init_service(
+            App::new()
+                .app_data(replay)
+                .app_data(receipts)
+                .app_data(web::Data::new(WorkPools::from_env()))
+                .configure(configure),
+        )
+        .await;
+
+        let window = json!({ "not_before": 1_000, "not_after": 2_000 });
+        let req = TestRequest::post()
+            .uri("/v2/commitments")
+            .set_json(json!({ "value": 7, "validity": window }))
+            .to_request();
+        let committed: Value = call_and_read_body_json(&app, req).await;
+        assert_eq!(committed["verified"], true);
+        assert_eq!(committed["validity"]["basis"], "timestamp");
+
+        let verify = |validity: Value, reference_time: u64| {
+            TestRequest::post()
+                .uri("/v2/proofs/range/verify")
+                .set_json(json!({
+                    "commitment_hex": committed["commitment_hex"],
+                    "proof_hex": committed["proof_hex"],
+                    "validity": validity,
+                    "reference_time": reference_time,
+                }))
+                .to_request()
+        };
+        let body: Value = call_and_read_body_json(&app, verify(window.clone(), 1_500)).await;
+        assert_eq!((body["verified"].clone(), body["validity"].clone()), (json!(true), json!("valid")));
+        let body: Value = call_and_read_body_json(&app, verify(window.clone(), 2_500)).await;
+        assert_eq!((body["verified"].clone(), body["validity"].clone()), (json!(false), json!("expired")));
+        let body: Value = call_and_read_body_json(&app, verify(json!({ "not_after": 3_000 }), 2_500)).await;
+        assert_eq!(body["verified"], false);
+        assert!(body.get("validity").is_none());
+    }
+
+    #[actix_web::test]
+    async fn txref_round_trip_and_deprecation_headers() {
+        let (usage, replay, receipts) = app_data();
+        let app = init_service(
+            App::new()
+                .wrap(middleware::from_fn(deprecation))
+                .app_data(usage.clone())
+                .app_data(replay)
+                .app_data(receipts)
+                .app_data(web::Data::new(WorkPools::from_env()))
+                .configure(configure)
+                .route("/zkp/verify", web::post().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let tag = "ab".repeat(32);
+        let req = TestRequest::post()
+            .uri("/v2/txref")
+            .set_json(json!({ "tx_hash_hex": format!("0x{}", "12".repeat(32)), "binding_tag_hex": tag }))
+            .to_request();
+        let res = call_service(&app, req).await;
+        assert!(res.headers().get("deprecation").is_none());
+        let committed: Value = read_body_json(res).await;
+        assert_eq!(committed["verified"], true);
+
+        let verify = |binding_tag_hex: String| {
+            TestRequest::post()
+                .uri("/v2/txref/verify")
+                .set_json(json!({
+                    "commitment_hex": committed["commitment_hex"],
+                    "proof_hex": committed["proof_hex"],
+                    "binding_tag_hex": binding_tag_hex,
+                }))
+                .to_request()
+        };
+        let body: Value = call_and_read_body_json(&app, verify(tag)).await;
+        assert_eq!(body["verified"], true);
+        let body: Value = call_and_read_body_json(&app, verify("cd".repeat(32))).await;
+        assert_eq!(body["verified"], false);
+
+        let res = call_service(&app, TestRequest::post().uri("/zkp/verify").to_request()).await;
+        assert_eq!(res.headers().get("sunset").unwrap(), LEGACY_SUNSET);
+        assert_eq!(res.headers().get("link").unwrap(), "</v2/txref/verify>; rel=\"successor-version\"");
+        assert_eq!(usage.calls("/zkp/verify"), 1);
+    }
+}
@@ -0,0 +1,353 @@
+//! Verification of the proofs embedded in a schemaVersion 5.0 order VRC.
+//!
+//! Mirrors `backend/api/static/schemas/ev-battery-order-vrc-v5.schema.json`
+//! for the fields the proofs depend on:
+//!   credentialSubject.listing.unitPriceWei
+//!   credentialSubject.commitments.{quantity,total,payment}Commitment
+//!   credentialSubject.zkProofs.{quantityTotalProof,totalPaymentEqualityProof}
+//!   credentialSubject.attestation.contextHash
+//! Everything else in the credential is ignored here.
+//!
+//! Each embedded proof must carry the attestation's context hash, and then
+//! verify against the committed values:
+//!   quantity-total:          C_total == unit_price * C_quantity   (zk::quantity_total_proof)
+//!   total-payment equality:  C_total == C_pay                     (zk::equality_proof)
+
+use curve25519_dalek_ng::ristretto::CompressedRistretto;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::api::{parse_decimal_scalar_strict, parse_hex32_bytes};
+use crate::zk::equality_proof::{verify_equality, EqualityProof};
+use crate::zk::linear::LinearProof;
+use crate::zk::quantity_total_proof::{verify_quantity_total, QuantityTotalProof};
+
+pub const ORDER_VRC_SCHEMA_VERSION: &str = "5.0";
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderVrc {
+    pub schema_version: String,
+    pub credential_subject: CredentialSubject,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialSubject {
+    pub listing: Listing,
+    pub commitments: Commitments,
+    pub zk_proofs: ZkProofs,
+    pub attestation: Attestation,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Listing {
+    #[serde(default)]
+    pub unit_price_wei: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Commitments {
+    pub quantity_commitment: String,
+    pub total_commitment: String,
+    pub payment_commitment: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ZkProofs {
+    #[serde(default)]
+    pub schema_version: Option<String>,
+    #[serde(default)]
+    pub quantity_total_proof: Option<ProofData>,
+    #[serde(default)]
+    pub total_payment_equality_proof: Option<ProofData>,
+}
+
+/// `$defs/proofData`; the snake_case aliases match what older UIs embedded.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProofData {
+    #[serde(default)]
+    pub proof_type: String,
+    #[serde(alias = "proof_r_hex")]
+    pub proof_r_hex: String,
+    #[serde(alias = "proof_s_hex")]
+    pub proof_s_hex: String,
+    #[serde(default, alias = "context_hash_hex")]
+    pub context_hash: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Attestation {
+    #[serde(default)]
+    pub context_hash: Option<String>,
+}
+
+/// Outcome of a single check in a `VcVerificationReport`.
+#[derive(Serialize, Debug, Clone, ToSchema)]
+pub struct VcCheck {
+    pub check: String,
+    pub passed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone, ToSchema)]
+pub struct VcVerificationReport {
+    /// `true` iff every check passed.
+    pub verified: bool,
+    pub checks: Vec<VcCheck>,
+}
+
+impl VcVerificationReport {
+    fn push(&mut self, check: &str, result: Result<(), String>) {
+        let (passed, detail) = match result {
+            Ok(()) => (true, None),
+            Err(detail) => (false, Some(detail)),
+        };
+        self.checks.push(VcCheck { check: check.to_string(), passed, detail });
+    }
+}
+
+/// Parse an order VRC from a JSON value (the whole credential).
+pub fn parse_order_vrc(value: &serde_json::Value) -> Result<OrderVrc, String> {
+    OrderVrc::deserialize(value).map_err(|e| format!("invalid order VRC: {e}"))
+}
+
+fn parse_hex32(hex_str: &str) -> Option<[u8; 32]> {
+    parse_hex32_bytes(hex_str.trim())
+}
+
+fn parse_commitment(field: &str, hex_str: &str) -> Result<CompressedRistretto, String> {
+    parse_hex32(hex_str)
+        .map(CompressedRistretto)
+        .ok_or_else(|| format!("invalid {field}"))
+}
+
+fn parse_proof(proof: &ProofData) -> Result<LinearProof, String> {
+    Ok(LinearProof {
+        r_announcement: parse_hex32(&proof.proof_r_hex).ok_or("invalid proofRHex")?,
+        s_response: parse_hex32(&proof.proof_s_hex).ok_or("invalid proofSHex")?,
+    })
+}
+
+/// The proof must name the same context hash as the attestation.
+fn check_context(proof: &ProofData, attested: &[u8; 32]) -> Result<(), String> {
+    let context = proof.context_hash.as_deref().ok_or("proof has no contextHash")?;
+    match parse_hex32(context) {
+        Some(bytes) if &bytes == attested => Ok(()),
+        Some(_) => Err("proof contextHash does not match attestation.contextHash".to_string()),
+        None => Err("invalid proof contextHash".to_string()),
+    }
+}
+
+fn check_quantity_total(
+    commitments: &Commitments,
+    listing: &Listing,
+    proof: &ProofData,
+    context_hash: &[u8; 32],
+) -> Result<(), String> {
+    let c_quantity = parse_commitment("quantityCommitment", &commitments.quantity_commitment)?;
+    let c_total = parse_commitment("totalCommitment", &commitments.total_commitment)?;
+    let unit_price = listing
+        .unit_price_wei
+        .as_deref()
+        .and_then(parse_decimal_scalar_strict)
+        .ok_or("missing or invalid listing.unitPriceWei")?;
+    let proof: QuantityTotalProof = parse_proof(proof)?;
+
+    if verify_quantity_total(c_quantity, c_total, unit_price, &proof, context_hash) {
+        Ok(())
+    } else {
+        Err("proof does not verify".to_string())
+    }
+}
+
+fn check_total_payment(
+    commitments: &Commitments,
+    proof: &ProofData,
+    context_hash: &[u8; 32],
+) -> Result<(), String> {
+    let c_total = parse_commitment("totalCommitment", &commitments.total_commitment)?;
+    let c_pay = parse_commitment("paymentCommitment", &commitments.payment_commitment)?;
+    let proof: EqualityProof = parse_proof(proof)?;
+
+    if verify_equality(c_total, c_pay, &proof, context_hash) {
+        Ok(())
+    } else {
+        Err("proof does not verify".to_string())
+    }
+}
+
+/// Run every check on an order VRC and collect the results.
+///
+/// Checks, in order: `schema_version`, `attestation_context_hash`, then
+/// `<proof>_context` and `<proof>` for `quantity_total_proof` and
+/// `total_payment_equality_proof`. A proof is only verified once its
+/// context check has passed.
+pub fn verify_order_vrc(vrc: &OrderVrc) -> VcVerificationReport {
+    let mut report = VcVerificationReport { verified: false, checks: Vec::new() };
+    let subject = &vrc.credential_subject;
+
+    report.push(
+        "schema_version",
+        if vrc.schema_version == ORDER_VRC_SCHEMA_VERSION {
+            Ok(())
+        } else {
+            Err(format!("expected schemaVersion {ORDER_VRC_SCHEMA_VERSION}, got {}", vrc.schema_version))
+        },
+    );
+
+    let attested = subject.attestation.context_hash.as_deref().and_then(parse_hex32);
+    report.push(
+        "attestation_context_hash",
+        attested.map(|_| ()).ok_or_else(|| "missing or invalid attestation.contextHash".to_string()),
+    );
+
+    let proofs = [
+        ("quantity_total_proof", subject.zk_proofs.quantity_total_proof.as_ref()),
+        ("total_payment_equality_proof", subject.zk_proofs.total_payment_equality_proof.as_ref()),
+    ];
+    for (name, proof) in proofs {
+        let context_check = format!("{name}_context");
+        let (proof, attested) = match (proof, attested.as_ref()) {
+            (Some(proof), Some(attested)) => (proof, attested),
+            (None, _) => {
+                report.push(&context_check, Err("proof missing from zkProofs".to_string()));
+                report.push(name, Err("proof missing from zkProofs".to_string()));
+                continue;
+            }
+            (Some(_), None) => {
+                report.push(&context_check, Err("no attested context hash to compare against".to_string()));
+                report.push(name, Err("skipped: context check failed".to_string()));
+                continue;
+            }
+        };
+
+        let context_result = check_context(proof, attested);
+        let context_ok = context_result.is_ok();
+        report.push(&context_check, context_result);
+        if !context_ok {
+            report.push(name, Err("skipped: context check failed".to_string()));
+            continue;
+        }
+
+        let result = match name {
+            "quantity_total_proof" => check_quantity_total(&subject.commitments, &subject.listing, proof, attested),
+            _ => check_total_payment(&subject.commitments, proof, attested),
+        };
+        report.push(name, result);
+    }
+
+    report.verified = report.checks.iter().all(|check| check.passed);
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zk::equality_proof::prove_equality;
+    use crate::zk::quantity_total_proof::prove_quantity_total;
+    use bulletproofs::PedersenGens;
+    use curve25519_dalek_ng::scalar::Scalar;
+    use serde_json::json;
+
+    const UNIT_PRICE: u64 = 250;
+    const QUANTITY: u64 = 4;
+
+    fn order_vrc(context_hash: [u8; 32]) -> serde_json::Value {
+        let pc_gens = PedersenGens::default();
+        let r_qty = Scalar::from_bytes_mod_order([0x11u8; 32]);
+        let r_total = Scalar::from_bytes_mod_order([0x22u8; 32]);
+        let r_pay = Scalar::from_bytes_mod_order([0x33u8; 32]);
+        let total = Scalar::from(UNIT_PRICE * QUANTITY);
+        let c_qty = pc_gens.commit(Scalar::from(QUANTITY), r_qty).compress();
+        let c_total = pc_gens.commit(total, r_total).compress();
+        let c_pay = pc_gens.commit(total, r_pay).compress();
+
+        let qt = prove_quantity_total(c_qty, c_total, Scalar::from(UNIT_PRICE), r_qty, r_total, &context_hash)
+            .expect("quantity-total proof");
+        let eq = prove_equality(c_total, c_pay, r_total, r_pay, &context_hash).expect("equality proof");
+        let context_hex = format!("0x{}", hex::encode(context_hash));
+
+        json!({
+            "schemaVersion": "5.0",
+            "credentialSubject": {
+                "listing": { "unitPriceWei": UNIT_PRICE.to_string() },
+                "commitments": {
+                    "quantityCommitment": hex::encode(c_qty.as_bytes()),
+                    "totalCommitment": hex::encode(c_total.as_bytes()),
+                    "paymentCommitment": hex::encode(c_pay.as_bytes()),
+                },
+                "zkProofs": {
+                    "schemaVersion": "1.0",
+                    "quantityTotalProof": {
+                        "proofType": "bulletproofs",
+                        "proofRHex": hex::encode(qt.r_announcement),
+                        "proofSHex": hex::encode(qt.s_response),
+                        "contextHash": context_hex,
+                    },
+                    "totalPaymentEqualityProof": {
+                        "proofType": "bulletproofs",
+                        "proofRHex": hex::encode(eq.r_announcement),
+                        "proofSHex": hex::encode(eq.s_response),
+                        "contextHash": context_hex,
+                    },
+                },
+                "attestation": { "attestationVersion": "4.0", "contextHash": context_hex },
+            },
+        })
+    }
+
+    fn check<'a>(report: &'a VcVerificationReport, name: &str) -> &'a VcCheck {
+        report.checks.iter().find(|c| c.check == name).expect("check present")
+    }
+
+    #[test]
+    fn valid_order_vrc_passes_every_check() {
+        let vrc = parse_order_vrc(&order_vrc([0x44u8; 32])).unwrap();
+        let report = verify_order_vrc(&vrc);
+
+        assert!(report.verified, "{report:?}");
+        assert_eq!(report.checks.len(), 6);
+    }
+
+    #[test]
+    fn mismatched_attestation_context_is_reported() {
+        let mut value = order_vrc([0x55u8; 32]);
+        value["credentialSubject"]["attestation"]["contextHash"] = json!(hex::encode([0x66u8; 32]));
+
+        let report = verify_order_vrc(&parse_order_vrc(&value).unwrap());
+
+        assert!(!report.verified);
+        assert!(!check(&report, "quantity_total_proof_context").passed);
+        assert!(!check(&report, "total_payment_equality_proof").passed);
+    }
+
+    #[test]
+    fn wrong_unit_price_fails_only_the_quantity_total_check() {
+        let mut value = order_vrc([0x77u8; 32]);
+        value["credentialSubject"]["listing"]["unitPriceWei"] = json!((UNIT_PRICE + 1).to_string());
+
+        let report = verify_order_vrc(&parse_order_vrc(&value).unwrap());
+
+        assert!(!check(&report, "quantity_total_proof").passed);
+        assert!(check(&report, "total_payment_equality_proof").passed);
+    }
+
+    #[test]
+    fn missing_proof_is_a_failed_check_not_a_parse_error() {
+        let mut value = order_vrc([0x88u8; 32]);
+        value["credentialSubject"]["zkProofs"]["totalPaymentEqualityProof"] = serde_json::Value::Null;
+
+        let report = verify_order_vrc(&parse_order_vrc(&value).unwrap());
+
+        assert!(!report.verified);
+        assert!(check(&report, "quantity_total_proof").passed);
+        assert!(!check(&report, "total_payment_equality_proof").passed);
+    }
+}
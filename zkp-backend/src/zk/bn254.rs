@@ -0,0 +1,280 @@
+//! Sigma proofs over BN254 (alt_bn128) G1, verifiable on-chain.
+//!
+//! Same statements as `equality_proof` and `quantity_total_proof`, but with
+//! Pedersen commitments in the group the EVM's ecAdd/ecMul precompiles
+//! operate on:
+//!   C = v * G + r * H,  G = (1, 2),  H = hash-to-curve("zkp.bn254.pedersen.H")
+//! Both proofs show D = A - k * B = delta_r * H:
+//!   equality        A = C_price, B = C_pay,      k = 1
+//!   quantity-total  A = C_total, B = C_quantity, k = unit_price
+//!
+//! The challenge is keccak256 rather than a merlin transcript so Solidity can
+//! recompute it:
+//!   c = keccak256(tag || context_hash || k || A || B || R) mod r
+//! with every field a 32-byte big-endian word and points as (x, y), the
+//! encoding the precompiles take; the point at infinity is (0, 0).
+//!
+//! `zk::bn254_verifier` generates the matching EVM verifier.
+
+use std::sync::LazyLock;
+
+use ark_bn254::{Fq, Fr, G1Affine, G1Projective};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, Field, PrimeField, UniformRand};
+use rand::rngs::OsRng;
+use sha3::{Digest, Keccak256};
+use tracing::instrument;
+
+/// Uncompressed G1 point, x || y big-endian.
+pub type G1Bytes = [u8; 64];
+
+pub const EQUALITY_LABEL: &str = "zkp.bn254.equality.v1";
+pub const QUANTITY_TOTAL_LABEL: &str = "zkp.bn254.quantity-total.v1";
+const H_LABEL: &[u8] = b"zkp.bn254.pedersen.H";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bn254SigmaProof {
+    /// R = k * H
+    pub r_announcement: G1Bytes,
+    /// s = k + c * delta_r, big-endian
+    pub s_response: [u8; 32],
+}
+
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+/// Domain tag of a proof kind: keccak256 of its label.
+pub fn tag(label: &str) -> [u8; 32] {
+    keccak256(label.as_bytes())
+}
+
+/// Try-and-increment: the first x = keccak256(label || i) mod p on the curve,
+/// with the smaller of the two square roots as y. Nobody knows log_G(H).
+static H: LazyLock<G1Affine> = LazyLock::new(|| {
+    (0u32..)
+        .find_map(|counter| {
+            let mut preimage = H_LABEL.to_vec();
+            preimage.extend_from_slice(&counter.to_be_bytes());
+            let x = Fq::from_be_bytes_mod_order(&keccak256(&preimage));
+            let y = (x * x * x + Fq::from(3u64)).sqrt()?;
+            let y = if y.into_bigint() <= (-y).into_bigint() { y } else { -y };
+            Some(G1Affine::new(x, y))
+        })
+        .expect("hash-to-curve terminates")
+});
+
+pub fn generator_g() -> G1Affine {
+    G1Affine::generator()
+}
+
+pub fn generator_h() -> G1Affine {
+    *H
+}
+
+fn fq_bytes(value: &Fq) -> [u8; 32] {
+    value.into_bigint().to_bytes_be().try_into().expect("Fq is 32 bytes")
+}
+
+pub fn encode_point(point: &G1Affine) -> G1Bytes {
+    let mut bytes = [0u8; 64];
+    if let Some((x, y)) = point.xy() {
+        bytes[..32].copy_from_slice(&fq_bytes(x));
+        bytes[32..].copy_from_slice(&fq_bytes(y));
+    }
+    bytes
+}
+
+/// `None` unless both coordinates are canonical and the point is on the curve
+/// (G1 has cofactor 1, so that is also the subgroup check).
+pub fn decode_point(bytes: &G1Bytes) -> Option<G1Affine> {
+    if bytes.iter().all(|&b| b == 0) {
+        return Some(G1Affine::zero());
+    }
+    let coordinate = |word: &[u8]| {
+        let value = Fq::from_be_bytes_mod_order(word);
+        (fq_bytes(&value) == word).then_some(value)
+    };
+    let point = G1Affine::new_unchecked(coordinate(&bytes[..32])?, coordinate(&bytes[32..])?);
+    point.is_on_curve().then_some(point)
+}
+
+pub fn encode_scalar(value: &Fr) -> [u8; 32] {
+    value.into_bigint().to_bytes_be().try_into().expect("Fr is 32 bytes")
+}
+
+/// `None` for values at or above the group order.
+pub fn decode_scalar(bytes: &[u8; 32]) -> Option<Fr> {
+    let value = Fr::from_be_bytes_mod_order(bytes);
+    (encode_scalar(&value) == *bytes).then_some(value)
+}
+
+/// v * G + r * H
+pub fn commit(value: Fr, blinding: Fr) -> G1Bytes {
+    encode_point(&(generator_g() * value + generator_h() * blinding).into_affine())
+}
+
+fn challenge(tag: &[u8; 32], context_hash: &[u8; 32], k: &Fr, a: &G1Bytes, b: &G1Bytes, r: &G1Bytes) -> Fr {
+    let mut preimage = Vec::with_capacity(288);
+    preimage.extend_from_slice(tag);
+    preimage.extend_from_slice(context_hash);
+    preimage.extend_from_slice(&encode_scalar(k));
+    preimage.extend_from_slice(a);
+    preimage.extend_from_slice(b);
+    preimage.extend_from_slice(r);
+    Fr::from_be_bytes_mod_order(&keccak256(&preimage))
+}
+
+fn combined_point(a: &G1Bytes, b: &G1Bytes, k: &Fr) -> Option<G1Projective> {
+    Some(decode_point(a)?.into_group() - decode_point(b)? * k)
+}
+
+fn prove_difference(
+    tag: &[u8; 32],
+    context_hash: &[u8; 32],
+    k: Fr,
+    a: &G1Bytes,
+    b: &G1Bytes,
+    r_a: Fr,
+    r_b: Fr,
+) -> Result<Bn254SigmaProof, String> {
+    if combined_point(a, b, &k).is_none() {
+        return Err("invalid commitment point".to_string());
+    }
+    let nonce = Fr::rand(&mut OsRng);
+    let r_announcement = encode_point(&(generator_h() * nonce).into_affine());
+    let c = challenge(tag, context_hash, &k, a, b, &r_announcement);
+    let s = nonce + c * (r_a - k * r_b);
+    Ok(Bn254SigmaProof { r_announcement, s_response: encode_scalar(&s) })
+}
+
+/// s * H == R + c * D
+fn verify_difference(
+    tag: &[u8; 32],
+    context_hash: &[u8; 32],
+    k: Fr,
+    a: &G1Bytes,
+    b: &G1Bytes,
+    proof: &Bn254SigmaProof,
+) -> bool {
+    let (Some(d), Some(r), Some(s)) = (
+        combined_point(a, b, &k),
+        decode_point(&proof.r_announcement),
+        decode_scalar(&proof.s_response),
+    ) else {
+        return false;
+    };
+    let c = challenge(tag, context_hash, &k, a, b, &proof.r_announcement);
+    generator_h() * s == r + d * c
+}
+
+/// Proof that `c_price` and `c_pay` commit to the same value.
+#[instrument(level = "debug", skip_all)]
+pub fn prove_equality(
+    c_price: &G1Bytes,
+    c_pay: &G1Bytes,
+    r_price: Fr,
+    r_pay: Fr,
+    context_hash: &[u8; 32],
+) -> Result<Bn254SigmaProof, String> {
+    prove_difference(&tag(EQUALITY_LABEL), context_hash, Fr::from(1u64), c_price, c_pay, r_price, r_pay)
+}
+
+#[instrument(level = "debug", skip_all)]
+pub fn verify_equality(c_price: &G1Bytes, c_pay: &G1Bytes, proof: &Bn254SigmaProof, context_hash: &[u8; 32]) -> bool {
+    verify_difference(&tag(EQUALITY_LABEL), context_hash, Fr::from(1u64), c_price, c_pay, proof)
+}
+
+/// Proof that `c_total` commits to `unit_price` times the value in `c_quantity`.
+#[instrument(level = "debug", skip_all)]
+pub fn prove_quantity_total(
+    c_quantity: &G1Bytes,
+    c_total: &G1Bytes,
+    unit_price: Fr,
+    r_quantity: Fr,
+    r_total: Fr,
+    context_hash: &[u8; 32],
+) -> Result<Bn254SigmaProof, String> {
+    prove_difference(
+        &tag(QUANTITY_TOTAL_LABEL),
+        context_hash,
+        unit_price,
+        c_total,
+        c_quantity,
+        r_total,
+        r_quantity,
+    )
+}
+
+#[instrument(level = "debug", skip_all)]
+pub fn verify_quantity_total(
+    c_quantity: &G1Bytes,
+    c_total: &G1Bytes,
+    unit_price: Fr,
+    proof: &Bn254SigmaProof,
+    context_hash: &[u8; 32],
+) -> bool {
+    verify_difference(&tag(QUANTITY_TOTAL_LABEL), context_hash, unit_price, c_total, c_quantity, proof)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commitment(value: u64, blinding_byte: u8) -> (G1Bytes, Fr) {
+        let blinding = Fr::from_be_bytes_mod_order(&[blinding_byte; 32]);
+        (commit(Fr::from(value), blinding), blinding)
+    }
+
+    #[test]
+    fn generators_are_independent_curve_points() {
+        let h = generator_h();
+        assert!(h.is_on_curve());
+        assert_ne!(h, generator_g());
+        assert_eq!(decode_point(&encode_point(&h)), Some(h));
+        assert_eq!(decode_point(&[0u8; 64]), Some(G1Affine::zero()));
+
+        let mut off_curve = encode_point(&h);
+        off_curve[63] ^= 1;
+        assert_eq!(decode_point(&off_curve), None);
+    }
+
+    #[test]
+    fn equality_proof_round_trip() {
+        let context_hash = [0x11u8; 32];
+        let (c_price, r_price) = commitment(42, 0x22);
+        let (c_pay, r_pay) = commitment(42, 0x33);
+        let (c_other, _) = commitment(41, 0x33);
+
+        let proof = prove_equality(&c_price, &c_pay, r_price, r_pay, &context_hash).unwrap();
+        assert!(verify_equality(&c_price, &c_pay, &proof, &context_hash));
+        assert!(!verify_equality(&c_price, &c_other, &proof, &context_hash));
+        assert!(!verify_equality(&c_price, &c_pay, &proof, &[0x12u8; 32]));
+    }
+
+    #[test]
+    fn quantity_total_proof_round_trip() {
+        let context_hash = [0x44u8; 32];
+        let unit_price = Fr::from(250u64);
+        let (c_quantity, r_quantity) = commitment(4, 0x55);
+        let (c_total, r_total) = commitment(1000, 0x66);
+        let (c_wrong, r_wrong) = commitment(1001, 0x66);
+
+        let proof = prove_quantity_total(&c_quantity, &c_total, unit_price, r_quantity, r_total, &context_hash).unwrap();
+        assert!(verify_quantity_total(&c_quantity, &c_total, unit_price, &proof, &context_hash));
+        assert!(!verify_quantity_total(&c_quantity, &c_total, Fr::from(251u64), &proof, &context_hash));
+
+        let proof = prove_quantity_total(&c_quantity, &c_wrong, unit_price, r_quantity, r_wrong, &context_hash).unwrap();
+        assert!(!verify_quantity_total(&c_quantity, &c_wrong, unit_price, &proof, &context_hash));
+    }
+
+    #[test]
+    fn non_canonical_response_is_rejected() {
+        let context_hash = [0x77u8; 32];
+        let (c_price, r_price) = commitment(7, 0x01);
+        let (c_pay, r_pay) = commitment(7, 0x02);
+        let mut proof = prove_equality(&c_price, &c_pay, r_price, r_pay, &context_hash).unwrap();
+        proof.s_response = [0xffu8; 32];
+        assert!(!verify_equality(&c_price, &c_pay, &proof, &context_hash));
+    }
+}
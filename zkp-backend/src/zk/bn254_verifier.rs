@@ -0,0 +1,389 @@
+//! EVM verifier for the `zk::bn254` sigma proofs.
+//!
+//! `creation_code()` deploys a precompile-only contract that checks one
+//! D = A - k * B statement per call. Calldata is ten 32-byte words
+//! (`verifier_calldata`):
+//!   tag, context_hash, k, A.x, A.y, B.x, B.y, R.x, R.y, s
+//! and the contract returns abi-encoded `true` iff
+//!   s * H == R + c * (A - k * B),  c = keccak256(words 0..9) mod r
+//! with s and k below the group order. Malformed input returns `false`; the
+//! contract never reverts. It is assembled here rather than compiled so the
+//! bytes the Rust tests run in revm are the bytes that go on-chain.
+//!
+//! `solidity_library()` renders `contracts/Bn254SigmaVerifier.sol`, which
+//! embeds the creation code and encodes equality / quantity-total statements
+//! for escrow contracts. Regenerate it with `zkp-cli solidity-verifier`.
+
+use ark_bn254::Fr;
+use ark_ec::AffineRepr;
+use ark_ff::PrimeField;
+
+use super::bn254::{
+    encode_point, encode_scalar, generator_h, tag, Bn254SigmaProof, G1Bytes, EQUALITY_LABEL, QUANTITY_TOTAL_LABEL,
+};
+
+/// BN254 base field modulus p.
+const FIELD_MODULUS: [u8; 32] = hex_word("30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47");
+/// BN254 group order r.
+const GROUP_ORDER: [u8; 32] = hex_word("30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001");
+
+const fn hex_word(hex: &str) -> [u8; 32] {
+    let hex = hex.as_bytes();
+    let mut word = [0u8; 32];
+    let mut i = 0;
+    while i < 32 {
+        word[i] = (nibble(hex[2 * i]) << 4) | nibble(hex[2 * i + 1]);
+        i += 1;
+    }
+    word
+}
+
+const fn nibble(c: u8) -> u8 {
+    match c {
+        b'0'..=b'9' => c - b'0',
+        b'a'..=b'f' => c - b'a' + 10,
+        _ => panic!("invalid hex digit"),
+    }
+}
+
+mod op {
+    pub const SUB: u8 = 0x03;
+    pub const MOD: u8 = 0x06;
+    pub const LT: u8 = 0x10;
+    pub const EQ: u8 = 0x14;
+    pub const AND: u8 = 0x16;
+    pub const KECCAK256: u8 = 0x20;
+    pub const CALLDATALOAD: u8 = 0x35;
+    pub const CALLDATASIZE: u8 = 0x36;
+    pub const CALLDATACOPY: u8 = 0x37;
+    pub const CODECOPY: u8 = 0x39;
+    pub const MLOAD: u8 = 0x51;
+    pub const MSTORE: u8 = 0x52;
+    pub const GAS: u8 = 0x5a;
+    pub const PUSH1: u8 = 0x60;
+    pub const PUSH2: u8 = 0x61;
+    pub const PUSH32: u8 = 0x7f;
+    pub const SWAP1: u8 = 0x90;
+    pub const RETURN: u8 = 0xf3;
+    pub const STATICCALL: u8 = 0xfa;
+}
+
+const EC_ADD: u8 = 0x06;
+const EC_MUL: u8 = 0x07;
+
+/// Straight-line EVM assembler; the verifier needs no jumps.
+#[derive(Default)]
+struct Assembler {
+    code: Vec<u8>,
+}
+
+impl Assembler {
+    fn op(&mut self, opcode: u8) -> &mut Self {
+        self.code.push(opcode);
+        self
+    }
+
+    fn push1(&mut self, value: u8) -> &mut Self {
+        self.code.extend_from_slice(&[op::PUSH1, value]);
+        self
+    }
+
+    fn push2(&mut self, value: u16) -> &mut Self {
+        self.code.push(op::PUSH2);
+        self.code.extend_from_slice(&value.to_be_bytes());
+        self
+    }
+
+    fn push32(&mut self, word: &[u8; 32]) -> &mut Self {
+        self.code.push(op::PUSH32);
+        self.code.extend_from_slice(word);
+        self
+    }
+
+    /// memory[dest..dest + size] = calldata[offset..offset + size]
+    fn calldatacopy(&mut self, dest: u16, offset: u8, size: u16) -> &mut Self {
+        self.push2(size).push1(offset).push2(dest).op(op::CALLDATACOPY)
+    }
+
+    /// Pushes the precompile's success flag.
+    fn precompile(&mut self, address: u8, input: u16, input_size: u8, output: u16) -> &mut Self {
+        self.push1(0x40)
+            .push2(output)
+            .push1(input_size)
+            .push2(input)
+            .push1(address)
+            .op(op::GAS)
+            .op(op::STATICCALL)
+    }
+
+    /// ok &= ecMul(memory[input..input + 0x60]) -> memory[output..output + 0x40]
+    fn ec_mul(&mut self, input: u16, output: u16) -> &mut Self {
+        self.precompile(EC_MUL, input, 0x60, output).op(op::AND)
+    }
+
+    /// ok &= ecAdd(memory[input..input + 0x80]) -> memory[output..output + 0x40]
+    fn ec_add(&mut self, input: u16, output: u16) -> &mut Self {
+        self.precompile(EC_ADD, input, 0x80, output).op(op::AND)
+    }
+}
+
+/// Runtime code. The stack holds a single `ok` flag that every check ANDs
+/// into. Memory: 0x000 transcript, 0x100 s*H, 0x180/0x1c0 point scratch,
+/// 0x2a0 challenge.
+pub fn runtime_code() -> Vec<u8> {
+    let h = encode_point(&generator_h());
+    let mut asm = Assembler::default();
+    asm
+        // ok = calldatasize == 320 && s < r && k < r
+        .op(op::CALLDATASIZE)
+        .push2(0x140)
+        .op(op::EQ)
+        .push32(&GROUP_ORDER)
+        .push2(0x120)
+        .op(op::CALLDATALOAD)
+        .op(op::LT)
+        .op(op::AND)
+        .push32(&GROUP_ORDER)
+        .push1(0x40)
+        .op(op::CALLDATALOAD)
+        .op(op::LT)
+        .op(op::AND)
+        // c = keccak256(calldata[0..288]) mod r
+        .calldatacopy(0x000, 0x00, 0x120)
+        .push32(&GROUP_ORDER)
+        .push2(0x120)
+        .push1(0x00)
+        .op(op::KECCAK256)
+        .op(op::MOD)
+        .push2(0x2a0)
+        .op(op::MSTORE)
+        // k * B at 0x1c0, then negate y: (p - y) mod p
+        .calldatacopy(0x1c0, 0xa0, 0x40)
+        .push1(0x40)
+        .op(op::CALLDATALOAD)
+        .push2(0x200)
+        .op(op::MSTORE)
+        .ec_mul(0x1c0, 0x1c0)
+        .push2(0x1e0)
+        .op(op::MLOAD)
+        .push32(&FIELD_MODULUS)
+        .op(op::SUB)
+        .push32(&FIELD_MODULUS)
+        .op(op::SWAP1)
+        .op(op::MOD)
+        .push2(0x1e0)
+        .op(op::MSTORE)
+        // D = A - k * B at 0x180
+        .calldatacopy(0x180, 0x60, 0x40)
+        .ec_add(0x180, 0x180)
+        // c * D at 0x1c0
+        .push2(0x2a0)
+        .op(op::MLOAD)
+        .push2(0x1c0)
+        .op(op::MSTORE)
+        .ec_mul(0x180, 0x1c0)
+        // R + c * D at 0x180
+        .calldatacopy(0x180, 0xe0, 0x40)
+        .ec_add(0x180, 0x180)
+        // s * H at 0x100
+        .push32(&h[..32].try_into().expect("32 bytes"))
+        .push2(0x100)
+        .op(op::MSTORE)
+        .push32(&h[32..].try_into().expect("32 bytes"))
+        .push2(0x120)
+        .op(op::MSTORE)
+        .push2(0x120)
+        .op(op::CALLDATALOAD)
+        .push2(0x140)
+        .op(op::MSTORE)
+        .ec_mul(0x100, 0x100)
+        // ok &= s * H == R + c * D
+        .push2(0x100)
+        .op(op::MLOAD)
+        .push2(0x180)
+        .op(op::MLOAD)
+        .op(op::EQ)
+        .op(op::AND)
+        .push2(0x120)
+        .op(op::MLOAD)
+        .push2(0x1a0)
+        .op(op::MLOAD)
+        .op(op::EQ)
+        .op(op::AND)
+        // return abi.encode(ok)
+        .push1(0x00)
+        .op(op::MSTORE)
+        .push1(0x20)
+        .push1(0x00)
+        .op(op::RETURN);
+    asm.code
+}
+
+/// Constructor that returns `runtime_code()`.
+pub fn creation_code() -> Vec<u8> {
+    const CONSTRUCTOR_LEN: u8 = 14;
+    let runtime = runtime_code();
+    let len = u16::try_from(runtime.len()).expect("runtime fits in 64 KiB");
+    let mut asm = Assembler::default();
+    asm.push2(len)
+        .push1(CONSTRUCTOR_LEN)
+        .push1(0x00)
+        .op(op::CODECOPY)
+        .push2(len)
+        .push1(0x00)
+        .op(op::RETURN);
+    debug_assert_eq!(asm.code.len(), CONSTRUCTOR_LEN as usize);
+    asm.code.extend_from_slice(&runtime);
+    asm.code
+}
+
+/// Calldata for one verifier call.
+pub fn verifier_calldata(
+    tag: &[u8; 32],
+    context_hash: &[u8; 32],
+    k: &Fr,
+    a: &G1Bytes,
+    b: &G1Bytes,
+    proof: &Bn254SigmaProof,
+) -> Vec<u8> {
+    let mut calldata = Vec::with_capacity(320);
+    calldata.extend_from_slice(tag);
+    calldata.extend_from_slice(context_hash);
+    calldata.extend_from_slice(&encode_scalar(k));
+    calldata.extend_from_slice(a);
+    calldata.extend_from_slice(b);
+    calldata.extend_from_slice(&proof.r_announcement);
+    calldata.extend_from_slice(&proof.s_response);
+    calldata
+}
+
+pub fn equality_calldata(
+    c_price: &G1Bytes,
+    c_pay: &G1Bytes,
+    proof: &Bn254SigmaProof,
+    context_hash: &[u8; 32],
+) -> Vec<u8> {
+    verifier_calldata(&tag(EQUALITY_LABEL), context_hash, &Fr::from(1u64), c_price, c_pay, proof)
+}
+
+pub fn quantity_total_calldata(
+    c_quantity: &G1Bytes,
+    c_total: &G1Bytes,
+    unit_price: &Fr,
+    proof: &Bn254SigmaProof,
+    context_hash: &[u8; 32],
+) -> Vec<u8> {
+    verifier_calldata(&tag(QUANTITY_TOTAL_LABEL), context_hash, unit_price, c_total, c_quantity, proof)
+}
+
+/// Source of `contracts/Bn254SigmaVerifier.sol`.
+pub fn solidity_library() -> String {
+    let h = generator_h();
+    let (h_x, h_y) = h.xy().expect("H is not the identity");
+    let h_x = h_x.into_bigint();
+    let h_y = h_y.into_bigint();
+    format!(
+        r#"// SPDX-License-Identifier: MIT
+// Generated by `zkp-cli solidity-verifier` (zkp-backend/src/zk/bn254_verifier.rs). Do not edit.
+pragma solidity ^0.8.0;
+
+/// @title Bn254SigmaVerifier
+/// @notice Verifies the BN254 equality and quantity-total proofs of the ZKP
+///         backend (`zk::bn254`) against commitments C = v*G + r*H on alt_bn128 G1.
+/// @dev The check runs in a precompile-only verifier contract deployed from
+///      VERIFIER_CODE; deploy it once and pass its address to the verify functions.
+library Bn254SigmaVerifier {{
+    struct G1Point {{
+        uint256 x;
+        uint256 y;
+    }}
+
+    // R = k*H, s = k + c*delta_r
+    struct Proof {{
+        G1Point r;
+        uint256 s;
+    }}
+
+    bytes32 internal constant EQUALITY_TAG = keccak256("{equality}");
+    bytes32 internal constant QUANTITY_TOTAL_TAG = keccak256("{quantity_total}");
+
+    // Pedersen blinding generator H; the value generator G is (1, 2).
+    uint256 internal constant H_X = {h_x};
+    uint256 internal constant H_Y = {h_y};
+
+    bytes internal constant VERIFIER_CODE = hex"{code}";
+
+    function deployVerifier() internal returns (address verifier) {{
+        bytes memory code = VERIFIER_CODE;
+        assembly {{
+            verifier := create(0, add(code, 0x20), mload(code))
+        }}
+        require(verifier != address(0), "Bn254SigmaVerifier: deploy failed");
+    }}
+
+    /// @notice cPrice and cPay commit to the same value.
+    function verifyEquality(
+        address verifier,
+        G1Point memory cPrice,
+        G1Point memory cPay,
+        Proof memory proof,
+        bytes32 contextHash
+    ) internal view returns (bool) {{
+        return _verify(verifier, EQUALITY_TAG, contextHash, 1, cPrice, cPay, proof);
+    }}
+
+    /// @notice cTotal commits to unitPrice times the value in cQuantity.
+    function verifyQuantityTotal(
+        address verifier,
+        G1Point memory cQuantity,
+        G1Point memory cTotal,
+        uint256 unitPrice,
+        Proof memory proof,
+        bytes32 contextHash
+    ) internal view returns (bool) {{
+        return _verify(verifier, QUANTITY_TOTAL_TAG, contextHash, unitPrice, cTotal, cQuantity, proof);
+    }}
+
+    function _verify(
+        address verifier,
+        bytes32 tag,
+        bytes32 contextHash,
+        uint256 k,
+        G1Point memory a,
+        G1Point memory b,
+        Proof memory proof
+    ) private view returns (bool) {{
+        (bool ok, bytes memory result) = verifier.staticcall(abi.encode(tag, contextHash, k, a, b, proof));
+        return ok && result.length == 32 && abi.decode(result, (bool));
+    }}
+}}
+"#,
+        equality = EQUALITY_LABEL,
+        quantity_total = QUANTITY_TOTAL_LABEL,
+        h_x = h_x,
+        h_y = h_y,
+        code = hex::encode(creation_code()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ff::BigInteger;
+
+    #[test]
+    fn constants_match_arkworks() {
+        assert_eq!(FIELD_MODULUS.to_vec(), ark_bn254::Fq::MODULUS.to_bytes_be());
+        assert_eq!(GROUP_ORDER.to_vec(), Fr::MODULUS.to_bytes_be());
+    }
+
+    #[test]
+    fn checked_in_solidity_library_is_current() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../contracts/Bn254SigmaVerifier.sol");
+        let checked_in = std::fs::read_to_string(path).expect("contracts/Bn254SigmaVerifier.sol exists");
+        assert!(
+            checked_in == solidity_library(),
+            "contracts/Bn254SigmaVerifier.sol is stale; run `zkp-cli solidity-verifier -o ../contracts/Bn254SigmaVerifier.sol`"
+        );
+    }
+}
@@ -5,6 +5,7 @@ use curve25519_dalek::{
     scalar::Scalar,
 };
 use merlin::Transcript;
+use tracing::instrument;
 use rand::rngs::OsRng;
 use rand::{RngCore, CryptoRng};
 use tari_bulletproofs_plus::{
@@ -31,6 +32,7 @@ pub fn prove_txid_commitment(hash: [u8; 32]) -> (Vec<CompressedRistretto>, Vec<u
 /// Produce a BP⁺ proof for a full 32-byte tx-hash with a provided RNG.
 /// This version allows using a faster RNG (like ThreadRng) for testing/benchmarking.
 /// Returns `(commitments, proof bytes)`.
+#[instrument(level = "debug", skip_all)]
 pub fn prove_txid_commitment_with_rng<R: RngCore + CryptoRng>(
     hash: [u8; 32],
     rng: &mut R,
@@ -78,6 +80,7 @@ pub fn prove_txid_commitment_with_rng<R: RngCore + CryptoRng>(
 }
 
 /// Verify a BP⁺ proof produced above.
+#[instrument(level = "debug", skip_all)]
 pub fn verify_txid_commitment(
     commitments: Vec<CompressedRistretto>,
     proof_bytes: Vec<u8>,
@@ -111,9 +114,9 @@ pub fn verify_txid_commitment(
 
     let transcript = Transcript::new(LABEL);
     RistrettoRangeProof::verify_batch(
-        &mut vec![transcript],
-        &vec![statement],
-        &vec![proof],
+        &mut [transcript],
+        &[statement],
+        &[proof],
         VerifyAction::VerifyOnly,
     )
     .is_ok()
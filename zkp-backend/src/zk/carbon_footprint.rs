@@ -0,0 +1,202 @@
+//! Carbon footprint aggregation over a battery's component tree.
+//!
+//! Suppliers publish per-component footprints only as commitments
+//!   C_i = v_i * B + r_i * B_blinding        (g CO2e, from component VCs)
+//! The product seller, holding just the aggregate opening (Σ v_i, Σ r_i),
+//! commits the product footprint C_p = V * B + r_p * B_blinding and proves
+//!   Σ C_i - C_p      = (Σ r_i - r_p) * B_blinding          (linear proof: V = Σ v_i)
+//!   cap * B - C_p    opens to cap - V in [0, 2^64)          (range proof: V <= cap)
+//!   C_p              opens to V in [0, 2^64)                (range proof: V >= 0)
+//! The last proof keeps V from being a "negative" scalar near the group
+//! order. Each component's own range is the supplier VC's concern.
+//!
+//! Transcript order (MUST match between prove and verify):
+//!   context_hash -> n -> C_i* -> C_p -> cap -> sum relation -> R -> challenge
+//!   -> cap range proof -> footprint range proof
+
+use bulletproofs::PedersenGens;
+use curve25519_dalek_ng::ristretto::{CompressedRistretto, RistrettoPoint};
+use curve25519_dalek_ng::scalar::Scalar;
+use merlin::Transcript;
+use tracing::instrument;
+
+use super::comparison::{prove_non_negative_with_transcript, verify_non_negative_with_transcript};
+use super::linear::{prove_linear_with_transcript, verify_linear_with_transcript, LinearProof, LinearRelation};
+
+/// Largest component list one proof covers.
+pub const MAX_COMPONENTS: usize = 256;
+pub const FOOTPRINT_BITS: usize = 64;
+
+/// Public statement: component commitments, product commitment, declared cap.
+#[derive(Clone, Debug)]
+pub struct FootprintStatement {
+    pub components: Vec<CompressedRistretto>,
+    pub product: CompressedRistretto,
+    pub cap: u64,
+}
+
+#[derive(Clone, Debug)]
+pub struct FootprintProof {
+    sum: LinearProof,
+    cap: Vec<u8>,
+    footprint: Vec<u8>,
+}
+
+impl FootprintProof {
+    /// R || s || u16 length of the cap proof || cap proof || footprint proof.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = [self.sum.r_announcement, self.sum.s_response].concat();
+        bytes.extend_from_slice(&(self.cap.len() as u16).to_be_bytes());
+        bytes.extend_from_slice(&self.cap);
+        bytes.extend_from_slice(&self.footprint);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let sum = LinearProof { r_announcement: bytes.get(..32)?.try_into().ok()?, s_response: bytes.get(32..64)?.try_into().ok()? };
+        let len = u16::from_be_bytes(bytes.get(64..66)?.try_into().ok()?) as usize;
+        let cap = bytes.get(66..66 + len)?.to_vec();
+        let footprint = bytes[66 + len..].to_vec();
+        (!footprint.is_empty()).then_some(Self { sum, cap, footprint })
+    }
+}
+
+impl FootprintStatement {
+    fn check(&self) -> Result<(), String> {
+        if self.components.is_empty() || self.components.len() > MAX_COMPONENTS {
+            return Err(format!("components must hold 1 to {MAX_COMPONENTS} commitments"));
+        }
+        Ok(())
+    }
+
+    fn transcript(&self, context_hash: &[u8]) -> Transcript {
+        let mut transcript = Transcript::new(b"CarbonFootprintProof-v1");
+        transcript.append_message(b"context_hash", context_hash);
+        transcript.append_u64(b"n", self.components.len() as u64);
+        for component in &self.components {
+            transcript.append_message(b"C_i", component.as_bytes());
+        }
+        transcript.append_message(b"C_p", self.product.as_bytes());
+        transcript.append_u64(b"cap", self.cap);
+        transcript
+    }
+
+    /// Σ C_i - C_p
+    fn sum_relation(&self) -> LinearRelation {
+        self.components
+            .iter()
+            .fold(LinearRelation::new(), |relation, component| relation.term(Scalar::one(), *component))
+            .term(-Scalar::one(), self.product)
+    }
+
+    /// cap * B - C_p
+    fn cap_relation(&self) -> LinearRelation {
+        LinearRelation::new().term(-Scalar::one(), self.product).constant(-Scalar::from(self.cap))
+    }
+}
+
+/// Sum of the component commitments, or `None` if one fails to decompress.
+fn aggregate(components: &[CompressedRistretto]) -> Option<RistrettoPoint> {
+    components.iter().map(|c| c.decompress()).sum()
+}
+
+/// Commit the product footprint and prove it aggregates `components` and
+/// stays within `cap`.
+///
+/// `total_value` / `total_blinding` open Σ C_i; `product_blinding` blinds C_p.
+#[instrument(level = "debug", skip_all, fields(components = components.len()))]
+pub fn prove_footprint(
+    components: &[CompressedRistretto],
+    total_value: u64,
+    total_blinding: Scalar,
+    product_blinding: Scalar,
+    cap: u64,
+    context_hash: &[u8],
+) -> Result<(FootprintStatement, FootprintProof), String> {
+    let pc_gens = PedersenGens::default();
+    let sum = aggregate(components).ok_or("invalid component commitment: point decompression failed")?;
+    if sum != pc_gens.commit(Scalar::from(total_value), total_blinding) {
+        return Err("total_value and total_blinding do not open the sum of the component commitments".to_string());
+    }
+    let margin = cap.checked_sub(total_value).ok_or("footprint exceeds the declared cap")?;
+
+    let product = pc_gens.commit(Scalar::from(total_value), product_blinding).compress();
+    let statement = FootprintStatement { components: components.to_vec(), product, cap };
+    statement.check()?;
+
+    let mut transcript = statement.transcript(context_hash);
+    let sum_relation = statement.sum_relation();
+    sum_relation.append_to_transcript(&mut transcript);
+    // Σ C_i - C_p has blinding Σ r_i - r_p; fold the components into one term.
+    let blindings: Vec<Scalar> = std::iter::once(total_blinding)
+        .chain(std::iter::repeat_n(Scalar::zero(), components.len() - 1))
+        .chain(std::iter::once(product_blinding))
+        .collect();
+    let sum = prove_linear_with_transcript(&mut transcript, &sum_relation, &blindings)?;
+    let cap_proof =
+        prove_non_negative_with_transcript(&mut transcript, &statement.cap_relation(), &[product_blinding], margin, FOOTPRINT_BITS)?;
+    let own = LinearRelation::new().term(Scalar::one(), product);
+    let footprint = prove_non_negative_with_transcript(&mut transcript, &own, &[product_blinding], total_value, FOOTPRINT_BITS)?;
+    Ok((statement, FootprintProof { sum, cap: cap_proof, footprint }))
+}
+
+#[instrument(level = "debug", skip_all, fields(components = statement.components.len()))]
+pub fn verify_footprint(statement: &FootprintStatement, proof: &FootprintProof, context_hash: &[u8]) -> bool {
+    if statement.check().is_err() {
+        return false;
+    }
+    let mut transcript = statement.transcript(context_hash);
+    let sum_relation = statement.sum_relation();
+    sum_relation.append_to_transcript(&mut transcript);
+    let own = LinearRelation::new().term(Scalar::one(), statement.product);
+    verify_linear_with_transcript(&mut transcript, &sum_relation, &proof.sum)
+        && verify_non_negative_with_transcript(&mut transcript, &statement.cap_relation(), &proof.cap, FOOTPRINT_BITS)
+        && verify_non_negative_with_transcript(&mut transcript, &own, &proof.footprint, FOOTPRINT_BITS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTEXT: [u8; 32] = [0x11; 32];
+
+    fn components(values: &[u64]) -> (Vec<CompressedRistretto>, u64, Scalar) {
+        let pc_gens = PedersenGens::default();
+        let blindings: Vec<Scalar> = (1..=values.len() as u64).map(|i| Scalar::from(i * 7)).collect();
+        let commitments = values
+            .iter()
+            .zip(&blindings)
+            .map(|(v, r)| pc_gens.commit(Scalar::from(*v), *r).compress())
+            .collect();
+        (commitments, values.iter().sum(), blindings.iter().sum())
+    }
+
+    #[test]
+    fn footprint_within_cap_verifies() {
+        let (commitments, total, blinding) = components(&[41_000, 12_500, 3_200]);
+        let (statement, proof) = prove_footprint(&commitments, total, blinding, Scalar::from(99u64), 60_000, &CONTEXT).unwrap();
+        let proof = FootprintProof::from_bytes(&proof.to_bytes()).unwrap();
+        assert!(verify_footprint(&statement, &proof, &CONTEXT));
+        assert!(!verify_footprint(&statement, &proof, &[0x12; 32]));
+
+        let lower_cap = FootprintStatement { cap: 50_000, ..statement.clone() };
+        assert!(!verify_footprint(&lower_cap, &proof, &CONTEXT));
+        let mut dropped = statement.clone();
+        dropped.components.pop();
+        assert!(!verify_footprint(&dropped, &proof, &CONTEXT));
+    }
+
+    #[test]
+    fn over_cap_or_wrong_total_is_rejected() {
+        let (commitments, total, blinding) = components(&[41_000, 12_500]);
+        assert!(prove_footprint(&commitments, total, blinding, Scalar::one(), 50_000, &CONTEXT).is_err());
+        assert!(prove_footprint(&commitments, total - 1, blinding, Scalar::one(), 60_000, &CONTEXT).is_err());
+    }
+
+    #[test]
+    fn single_component_verifies() {
+        let (commitments, total, blinding) = components(&[7_000]);
+        let (statement, proof) = prove_footprint(&commitments, total, blinding, Scalar::from(5u64), 7_000, &CONTEXT).unwrap();
+        assert!(verify_footprint(&statement, &proof, &CONTEXT));
+    }
+}
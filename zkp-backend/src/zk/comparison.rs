@@ -0,0 +1,279 @@
+//! Comparison proof between two committed values.
+//!
+//! Proves a >= b for
+//!   C_a = a * B + r_a * B_blinding
+//!   C_b = b * B + r_b * B_blinding
+//! by range-proving the derived commitment
+//!   D = C_a - C_b = (a - b) * B + (r_a - r_b) * B_blinding
+//! i.e. showing a - b lies in [0, 2^n), and range-proving C_b itself in
+//! [0, 2^64). The difference is taken modulo the group order, so without the
+//! second proof a "negative" b (a value near the group order) would prove any
+//! a >= b. With both bounds a = b + (a - b) < 2^65, far below the group
+//! order, so a needs no proof of its own.
+//!
+//! Proof bytes: u16 length of the difference proof, the difference proof,
+//! then the C_b proof.
+//!
+//! The `*_non_negative_with_transcript` helpers accept any `LinearRelation`,
+//! so threshold-style rules (value >= k, sum <= cap, ratios) reuse the same
+//! range-proof step with their own transcript label.
+//!
+//! Transcript order (MUST match between prove and verify):
+//!   context_hash -> C_a -> C_b -> difference range proof -> C_b range proof
+
+use bulletproofs::{BulletproofGens, PedersenGens, RangeProof};
+use curve25519_dalek_ng::{
+    ristretto::CompressedRistretto,
+    scalar::Scalar,
+};
+use merlin::Transcript;
+use tracing::instrument;
+
+use super::linear::LinearRelation;
+
+/// Bit sizes supported by `RangeProof::prove_single`.
+pub const SUPPORTED_BIT_RANGES: [usize; 4] = [8, 16, 32, 64];
+
+/// Range of the subtrahend `b`, independent of the difference's bit range.
+const OPERAND_BITS: usize = 64;
+
+fn check_bit_range(bit_range: usize) -> Result<(), String> {
+    if SUPPORTED_BIT_RANGES.contains(&bit_range) {
+        Ok(())
+    } else {
+        Err(format!("unsupported bit range {bit_range}: expected 8, 16, 32 or 64"))
+    }
+}
+
+/// Range-proves that the relation's derived commitment D = Σ a_i * C_i - k * B
+/// opens to `value` in [0, 2^bit_range).
+///
+/// The caller MUST already have appended the context and statement to
+/// `transcript`. Returns the serialized range proof.
+pub fn prove_non_negative_with_transcript(
+    transcript: &mut Transcript,
+    relation: &LinearRelation,
+    blindings: &[Scalar],
+    value: u64,
+    bit_range: usize,
+) -> Result<Vec<u8>, String> {
+    check_bit_range(bit_range)?;
+    if bit_range < 64 && value >> bit_range != 0 {
+        return Err(format!("derived value does not fit in {bit_range} bits"));
+    }
+
+    let derived = relation
+        .combined_point()
+        .ok_or_else(|| "invalid commitment: point decompression failed".to_string())?
+        .compress();
+    let blinding = relation.combined_blinding(blindings)?;
+
+    let pc_gens = PedersenGens::default();
+    let bp_gens = BulletproofGens::new(bit_range, 1);
+    let (proof, commitment) = RangeProof::prove_single(
+        &bp_gens,
+        &pc_gens,
+        transcript,
+        value,
+        &blinding,
+        bit_range,
+    )
+    .map_err(|e| format!("range proof generation failed: {e:?}"))?;
+
+    if commitment != derived {
+        return Err("witness does not open the derived commitment".to_string());
+    }
+
+    Ok(proof.to_bytes())
+}
+
+/// Verifies a range proof produced by `prove_non_negative_with_transcript`.
+pub fn verify_non_negative_with_transcript(
+    transcript: &mut Transcript,
+    relation: &LinearRelation,
+    proof_bytes: &[u8],
+    bit_range: usize,
+) -> bool {
+    if check_bit_range(bit_range).is_err() {
+        return false;
+    }
+    let derived = match relation.combined_point() {
+        Some(point) => point.compress(),
+        None => return false,
+    };
+    let proof = match RangeProof::from_bytes(proof_bytes) {
+        Ok(p) => p,
+        Err(_) => return false,
+    };
+
+    let pc_gens = PedersenGens::default();
+    let bp_gens = BulletproofGens::new(bit_range, 1);
+    RangeProof::verify_single(&proof, &bp_gens, &pc_gens, transcript, &derived, bit_range).is_ok()
+}
+
+/// D = C_a - C_b as a linear relation.
+fn relation(c_a: CompressedRistretto, c_b: CompressedRistretto) -> LinearRelation {
+    LinearRelation::new()
+        .term(Scalar::one(), c_a)
+        .term(-Scalar::one(), c_b)
+}
+
+fn transcript(
+    c_a: CompressedRistretto,
+    c_b: CompressedRistretto,
+    context_hash: &[u8],
+) -> Transcript {
+    let mut transcript = Transcript::new(b"ComparisonProof-v1");
+    transcript.append_message(b"context_hash", context_hash);
+    transcript.append_message(b"C_a", c_a.as_bytes());
+    transcript.append_message(b"C_b", c_b.as_bytes());
+    transcript
+}
+
+/// Generate a proof that `c_a` commits to a value >= the value in `c_b`.
+///
+/// # Arguments
+/// - `a`, `r_a` — opening of `c_a`
+/// - `b`, `r_b` — opening of `c_b`
+/// - `bit_range` — the difference a - b is proven to lie in [0, 2^bit_range)
+///
+/// # Returns
+/// The serialized range proofs, or `Err` if a < b or the openings do not match.
+#[allow(clippy::too_many_arguments)]
+#[instrument(level = "debug", skip_all)]
+pub fn prove_greater_or_equal(
+    c_a: CompressedRistretto,
+    c_b: CompressedRistretto,
+    a: u64,
+    b: u64,
+    r_a: Scalar,
+    r_b: Scalar,
+    context_hash: &[u8],
+    bit_range: usize,
+) -> Result<Vec<u8>, String> {
+    let difference = a
+        .checked_sub(b)
+        .ok_or_else(|| "a is smaller than b".to_string())?;
+
+    let mut transcript = transcript(c_a, c_b, context_hash);
+    let difference =
+        prove_non_negative_with_transcript(&mut transcript, &relation(c_a, c_b), &[r_a, r_b], difference, bit_range)?;
+    let operand = LinearRelation::new().term(Scalar::one(), c_b);
+    let operand = prove_non_negative_with_transcript(&mut transcript, &operand, &[r_b], b, OPERAND_BITS)?;
+
+    let mut bytes = (difference.len() as u16).to_be_bytes().to_vec();
+    bytes.extend_from_slice(&difference);
+    bytes.extend_from_slice(&operand);
+    Ok(bytes)
+}
+
+/// Verify a proof that `c_a` commits to a value >= the value in `c_b`.
+#[instrument(level = "debug", skip_all)]
+pub fn verify_greater_or_equal(
+    c_a: CompressedRistretto,
+    c_b: CompressedRistretto,
+    proof_bytes: &[u8],
+    context_hash: &[u8],
+    bit_range: usize,
+) -> bool {
+    let Some(len) = proof_bytes.get(..2).map(|len| u16::from_be_bytes([len[0], len[1]]) as usize) else {
+        return false;
+    };
+    let Some(difference) = proof_bytes.get(2..2 + len) else {
+        return false;
+    };
+    let operand = &proof_bytes[2 + len..];
+
+    let mut transcript = transcript(c_a, c_b, context_hash);
+    verify_non_negative_with_transcript(&mut transcript, &relation(c_a, c_b), difference, bit_range)
+        && verify_non_negative_with_transcript(
+            &mut transcript,
+            &LinearRelation::new().term(Scalar::one(), c_b),
+            operand,
+            OPERAND_BITS,
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commitment(value: u64, blinding_byte: u8) -> (CompressedRistretto, Scalar) {
+        let pc_gens = PedersenGens::default();
+        let blinding = Scalar::from_bytes_mod_order([blinding_byte; 32]);
+        let commitment = pc_gens.commit(Scalar::from(value), blinding);
+        (commitment.compress(), blinding)
+    }
+
+    #[test]
+    fn greater_value_verifies() {
+        let context_hash = [0x11u8; 32];
+        let (c_a, r_a) = commitment(120, 0x22);
+        let (c_b, r_b) = commitment(100, 0x33);
+
+        let proof = prove_greater_or_equal(c_a, c_b, 120, 100, r_a, r_b, &context_hash, 32)
+            .expect("proof generation should succeed");
+
+        assert!(verify_greater_or_equal(c_a, c_b, &proof, &context_hash, 32));
+    }
+
+    #[test]
+    fn equal_values_verify() {
+        let context_hash = [0x44u8; 32];
+        let (c_a, r_a) = commitment(100, 0x55);
+        let (c_b, r_b) = commitment(100, 0x66);
+
+        let proof = prove_greater_or_equal(c_a, c_b, 100, 100, r_a, r_b, &context_hash, 64)
+            .expect("proof generation should succeed");
+
+        assert!(verify_greater_or_equal(c_a, c_b, &proof, &context_hash, 64));
+    }
+
+    #[test]
+    fn smaller_value_is_rejected() {
+        let (c_a, r_a) = commitment(99, 0x77);
+        let (c_b, r_b) = commitment(100, 0x88);
+
+        assert!(prove_greater_or_equal(c_a, c_b, 99, 100, r_a, r_b, &[0u8; 32], 64).is_err());
+    }
+
+    #[test]
+    fn wrong_context_hash_fails() {
+        let (c_a, r_a) = commitment(7, 0x99);
+        let (c_b, r_b) = commitment(3, 0xaa);
+
+        let proof = prove_greater_or_equal(c_a, c_b, 7, 3, r_a, r_b, &[0xbbu8; 32], 32)
+            .expect("proof generation should succeed");
+
+        assert!(!verify_greater_or_equal(c_a, c_b, &proof, &[0xccu8; 32], 32));
+    }
+
+    #[test]
+    fn swapped_commitments_fail() {
+        let context_hash = [0xddu8; 32];
+        let (c_a, r_a) = commitment(7, 0xee);
+        let (c_b, r_b) = commitment(3, 0xff);
+
+        let proof = prove_greater_or_equal(c_a, c_b, 7, 3, r_a, r_b, &context_hash, 32)
+            .expect("proof generation should succeed");
+
+        assert!(!verify_greater_or_equal(c_b, c_a, &proof, &context_hash, 32));
+    }
+
+    #[test]
+    fn wrapped_operand_is_rejected() {
+        let context_hash = [0x12u8; 32];
+        let (c_a, r_a) = commitment(50, 0x34);
+
+        // b = -100 leaves a - b = 150, which the difference proof alone accepts.
+        let r_b = Scalar::from_bytes_mod_order([0x56; 32]);
+        let c_b = PedersenGens::default().commit(-Scalar::from(100u64), r_b).compress();
+        let difference =
+            prove_non_negative_with_transcript(&mut transcript(c_a, c_b, &context_hash), &relation(c_a, c_b), &[r_a, r_b], 150, 64)
+                .unwrap();
+        let mut forged = (difference.len() as u16).to_be_bytes().to_vec();
+        forged.extend_from_slice(&difference);
+        forged.extend_from_slice(&[0u8; 32]);
+        assert!(!verify_greater_or_equal(c_a, c_b, &forged, &context_hash, 64));
+    }
+}
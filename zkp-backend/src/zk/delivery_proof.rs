@@ -0,0 +1,289 @@
+//! Delivery-quantity proof for the transporter confirmation stage.
+//!
+//! The transporter commits to the delivered quantity
+//!   C_delivered = delivered * B + r_delivered * B_blinding
+//! and proves, against the buyer's order commitment C_quantity, either
+//!   full delivery:    delivered == quantity   (Schnorr proof on C_delivered - C_quantity)
+//!   partial delivery: delivered <= quantity   (range proof on C_quantity - C_delivered)
+//! bound to the delivery context hash (the value recorded by
+//! `confirmDelivery(bytes32)`). A partial proof also range-proves C_delivered
+//! itself, otherwise a "negative" delivered amount (a scalar near the group
+//! order) would pass the shortfall check for any order.
+//!
+//! Transcript order (MUST match between prove and verify):
+//!   mode -> context_hash -> C_quantity -> C_delivered
+//!   -> (R | shortfall range proof -> delivered range proof)
+
+use curve25519_dalek_ng::{
+    ristretto::CompressedRistretto,
+    scalar::Scalar,
+};
+use merlin::Transcript;
+use tracing::instrument;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::comparison::{prove_non_negative_with_transcript, verify_non_negative_with_transcript};
+use super::linear::{
+    prove_linear_with_transcript, verify_linear_with_transcript, LinearProof, LinearRelation,
+};
+
+const PARTIAL_BIT_RANGE: usize = 64;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryMode {
+    Full,
+    Partial,
+}
+
+impl DeliveryMode {
+    fn label(self) -> &'static [u8] {
+        match self {
+            Self::Full => b"full",
+            Self::Partial => b"partial",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum DeliveryProof {
+    /// Schnorr proof that C_delivered - C_quantity opens to zero.
+    Full(LinearProof),
+    /// 64-bit range proofs on C_quantity - C_delivered and on C_delivered.
+    Partial { shortfall: Vec<u8>, delivered: Vec<u8> },
+}
+
+impl DeliveryProof {
+    pub fn mode(&self) -> DeliveryMode {
+        match self {
+            Self::Full(_) => DeliveryMode::Full,
+            Self::Partial { .. } => DeliveryMode::Partial,
+        }
+    }
+
+    /// Full: R || s (64 bytes). Partial: u16 length of the shortfall proof,
+    /// the shortfall proof, then the delivered proof.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Self::Full(proof) => {
+                let mut bytes = proof.r_announcement.to_vec();
+                bytes.extend_from_slice(&proof.s_response);
+                bytes
+            }
+            Self::Partial { shortfall, delivered } => {
+                let mut bytes = (shortfall.len() as u16).to_be_bytes().to_vec();
+                bytes.extend_from_slice(shortfall);
+                bytes.extend_from_slice(delivered);
+                bytes
+            }
+        }
+    }
+
+    pub fn from_bytes(mode: DeliveryMode, bytes: &[u8]) -> Option<Self> {
+        match mode {
+            DeliveryMode::Full => {
+                if bytes.len() != 64 {
+                    return None;
+                }
+                let mut r_announcement = [0u8; 32];
+                let mut s_response = [0u8; 32];
+                r_announcement.copy_from_slice(&bytes[..32]);
+                s_response.copy_from_slice(&bytes[32..]);
+                Some(Self::Full(LinearProof { r_announcement, s_response }))
+            }
+            DeliveryMode::Partial => {
+                let len = u16::from_be_bytes(bytes.get(..2)?.try_into().ok()?) as usize;
+                let shortfall = bytes.get(2..2 + len)?.to_vec();
+                let delivered = bytes[2 + len..].to_vec();
+                Some(Self::Partial { shortfall, delivered })
+            }
+        }
+    }
+}
+
+fn transcript(
+    mode: DeliveryMode,
+    c_quantity: CompressedRistretto,
+    c_delivered: CompressedRistretto,
+    context_hash: &[u8],
+) -> Transcript {
+    let mut transcript = Transcript::new(b"DeliveryQuantityProof-v1");
+    transcript.append_message(b"mode", mode.label());
+    transcript.append_message(b"context_hash", context_hash);
+    transcript.append_message(b"C_quantity", c_quantity.as_bytes());
+    transcript.append_message(b"C_delivered", c_delivered.as_bytes());
+    transcript
+}
+
+/// Generate a delivery-quantity proof.
+///
+/// # Arguments
+/// - `quantity`, `r_quantity`   — opening of the buyer's order commitment `c_quantity`
+/// - `delivered`, `r_delivered` — opening of the transporter's `c_delivered`
+/// - `context_hash` — delivery context hash
+///
+/// # Returns
+/// `Err` if the quantities do not satisfy `mode` or the openings do not match.
+#[allow(clippy::too_many_arguments)]
+#[instrument(level = "debug", skip_all)]
+pub fn prove_delivery(
+    mode: DeliveryMode,
+    c_quantity: CompressedRistretto,
+    c_delivered: CompressedRistretto,
+    quantity: u64,
+    r_quantity: Scalar,
+    delivered: u64,
+    r_delivered: Scalar,
+    context_hash: &[u8],
+) -> Result<DeliveryProof, String> {
+    let mut transcript = transcript(mode, c_quantity, c_delivered, context_hash);
+    match mode {
+        DeliveryMode::Full => {
+            if delivered != quantity {
+                return Err("full delivery requires delivered quantity == ordered quantity".to_string());
+            }
+            let relation = LinearRelation::new()
+                .term(Scalar::one(), c_delivered)
+                .term(-Scalar::one(), c_quantity);
+            prove_linear_with_transcript(&mut transcript, &relation, &[r_delivered, r_quantity])
+                .map(DeliveryProof::Full)
+        }
+        DeliveryMode::Partial => {
+            let shortfall = quantity
+                .checked_sub(delivered)
+                .ok_or_else(|| "delivered quantity exceeds ordered quantity".to_string())?;
+            let relation = LinearRelation::new()
+                .term(Scalar::one(), c_quantity)
+                .term(-Scalar::one(), c_delivered);
+            let shortfall = prove_non_negative_with_transcript(
+                &mut transcript,
+                &relation,
+                &[r_quantity, r_delivered],
+                shortfall,
+                PARTIAL_BIT_RANGE,
+            )?;
+            let own = LinearRelation::new().term(Scalar::one(), c_delivered);
+            let delivered =
+                prove_non_negative_with_transcript(&mut transcript, &own, &[r_delivered], delivered, PARTIAL_BIT_RANGE)?;
+            Ok(DeliveryProof::Partial { shortfall, delivered })
+        }
+    }
+}
+
+/// Verify a delivery-quantity proof in the mode it was produced for.
+#[instrument(level = "debug", skip_all)]
+pub fn verify_delivery(
+    c_quantity: CompressedRistretto,
+    c_delivered: CompressedRistretto,
+    proof: &DeliveryProof,
+    context_hash: &[u8],
+) -> bool {
+    let mut transcript = transcript(proof.mode(), c_quantity, c_delivered, context_hash);
+    match proof {
+        DeliveryProof::Full(proof) => {
+            let relation = LinearRelation::new()
+                .term(Scalar::one(), c_delivered)
+                .term(-Scalar::one(), c_quantity);
+            verify_linear_with_transcript(&mut transcript, &relation, proof)
+        }
+        DeliveryProof::Partial { shortfall, delivered } => {
+            let relation = LinearRelation::new()
+                .term(Scalar::one(), c_quantity)
+                .term(-Scalar::one(), c_delivered);
+            let own = LinearRelation::new().term(Scalar::one(), c_delivered);
+            verify_non_negative_with_transcript(
+                &mut transcript,
+                &relation,
+                shortfall,
+                PARTIAL_BIT_RANGE,
+            ) && verify_non_negative_with_transcript(&mut transcript, &own, delivered, PARTIAL_BIT_RANGE)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bulletproofs::PedersenGens;
+
+    fn commitment(value: u64, blinding_byte: u8) -> (CompressedRistretto, Scalar) {
+        let pc_gens = PedersenGens::default();
+        let blinding = Scalar::from_bytes_mod_order([blinding_byte; 32]);
+        let commitment = pc_gens.commit(Scalar::from(value), blinding);
+        (commitment.compress(), blinding)
+    }
+
+    #[test]
+    fn full_delivery_verifies() {
+        let context_hash = [0x11u8; 32];
+        let (c_qty, r_qty) = commitment(40, 0x22);
+        let (c_deliv, r_deliv) = commitment(40, 0x33);
+
+        let proof = prove_delivery(DeliveryMode::Full, c_qty, c_deliv, 40, r_qty, 40, r_deliv, &context_hash)
+            .expect("proof generation should succeed");
+
+        assert!(verify_delivery(c_qty, c_deliv, &proof, &context_hash));
+    }
+
+    #[test]
+    fn partial_delivery_verifies() {
+        let context_hash = [0x44u8; 32];
+        let (c_qty, r_qty) = commitment(40, 0x55);
+        let (c_deliv, r_deliv) = commitment(25, 0x66);
+
+        let proof = prove_delivery(DeliveryMode::Partial, c_qty, c_deliv, 40, r_qty, 25, r_deliv, &context_hash)
+            .expect("proof generation should succeed");
+        let decoded = DeliveryProof::from_bytes(DeliveryMode::Partial, &proof.to_bytes())
+            .expect("proof bytes should decode");
+
+        assert!(verify_delivery(c_qty, c_deliv, &decoded, &context_hash));
+    }
+
+    #[test]
+    fn full_delivery_with_shortfall_is_rejected() {
+        let (c_qty, r_qty) = commitment(40, 0x77);
+        let (c_deliv, r_deliv) = commitment(39, 0x88);
+
+        assert!(prove_delivery(DeliveryMode::Full, c_qty, c_deliv, 40, r_qty, 39, r_deliv, &[0u8; 32]).is_err());
+    }
+
+    #[test]
+    fn over_delivery_is_rejected() {
+        let (c_qty, r_qty) = commitment(40, 0x99);
+        let (c_deliv, r_deliv) = commitment(41, 0xaa);
+
+        assert!(prove_delivery(DeliveryMode::Partial, c_qty, c_deliv, 40, r_qty, 41, r_deliv, &[0u8; 32]).is_err());
+    }
+
+    #[test]
+    fn proof_does_not_verify_under_other_mode_or_context() {
+        let context_hash = [0xbbu8; 32];
+        let (c_qty, r_qty) = commitment(40, 0xcc);
+        let (c_deliv, r_deliv) = commitment(40, 0xdd);
+
+        let proof = prove_delivery(DeliveryMode::Full, c_qty, c_deliv, 40, r_qty, 40, r_deliv, &context_hash)
+            .expect("proof generation should succeed");
+
+        assert!(!verify_delivery(c_qty, c_deliv, &proof, &[0xeeu8; 32]));
+        let as_partial = DeliveryProof::from_bytes(DeliveryMode::Partial, &proof.to_bytes());
+        assert!(as_partial.is_none_or(|as_partial| !verify_delivery(c_qty, c_deliv, &as_partial, &context_hash)));
+    }
+
+    #[test]
+    fn negative_delivered_amount_is_rejected() {
+        let context_hash = [0x12u8; 32];
+        let (c_qty, r_qty) = commitment(40, 0x34);
+
+        // "Delivering" -100 leaves a shortfall of 140, which the shortfall proof alone accepts.
+        let r_deliv = Scalar::from_bytes_mod_order([0x56; 32]);
+        let c_deliv = PedersenGens::default().commit(-Scalar::from(100u64), r_deliv).compress();
+        let mut transcript = transcript(DeliveryMode::Partial, c_qty, c_deliv, &context_hash);
+        let relation = LinearRelation::new().term(Scalar::one(), c_qty).term(-Scalar::one(), c_deliv);
+        let shortfall =
+            prove_non_negative_with_transcript(&mut transcript, &relation, &[r_qty, r_deliv], 140, PARTIAL_BIT_RANGE)
+                .unwrap();
+        let forged = DeliveryProof::Partial { shortfall, delivered: vec![0u8; 32] };
+        assert!(!verify_delivery(c_qty, c_deliv, &forged, &context_hash));
+    }
+}
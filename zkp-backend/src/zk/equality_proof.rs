@@ -4,23 +4,48 @@
 //! i.e. D = C_price - C_pay = delta_r * B_blinding  where delta_r = r_price - r_pay.
 //!
 //! Transcript order (MUST match between prove and verify):
-//!   context -> C_price -> C_pay -> R -> challenge
+//!   context -> C_price -> C_pay -> [validity window] -> R -> challenge
+//!
+//! The validity window is only appended when one is given (`zk::validity`),
+//! so proofs without one keep the original layout.
+//!
+//! The Schnorr step is delegated to `zk::linear`; only the transcript layout
+//! lives here so existing proofs keep verifying.
 
-use bulletproofs::PedersenGens;
 use curve25519_dalek_ng::{
     ristretto::CompressedRistretto,
     scalar::Scalar,
 };
 use merlin::Transcript;
-use rand::rngs::OsRng;
-use serde::{Deserialize, Serialize};
-
-#[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct EqualityProof {
-    /// Compressed Ristretto point R = k * B_blinding (32 bytes hex-encoded by callers)
-    pub r_announcement: [u8; 32],
-    /// Scalar s = k + c * delta_r (32 bytes)
-    pub s_response: [u8; 32],
+use tracing::instrument;
+
+use super::linear::{
+    prove_linear_with_transcript, verify_linear_with_transcript, LinearProof, LinearRelation,
+};
+use super::validity::{append_window, ValidityWindow};
+
+/// R = k * B_blinding and s = k + c * delta_r, 32 bytes each.
+pub type EqualityProof = LinearProof;
+
+/// D = C_price - C_pay as a linear relation.
+fn relation(c_price: CompressedRistretto, c_pay: CompressedRistretto) -> LinearRelation {
+    LinearRelation::new()
+        .term(Scalar::one(), c_price)
+        .term(-Scalar::one(), c_pay)
+}
+
+fn transcript(
+    c_price: CompressedRistretto,
+    c_pay: CompressedRistretto,
+    binding_context: &[u8],
+    window: Option<&ValidityWindow>,
+) -> Transcript {
+    let mut transcript = Transcript::new(b"EqualityProof-v1");
+    transcript.append_message(b"context", binding_context);
+    transcript.append_message(b"C_price", c_price.as_bytes());
+    transcript.append_message(b"C_pay", c_pay.as_bytes());
+    append_window(&mut transcript, window);
+    transcript
 }
 
 /// Generate a Schnorr sigma equality proof.
@@ -34,6 +59,7 @@ pub struct EqualityProof {
 ///
 /// # Returns
 /// `Ok(EqualityProof)` on success; `Err(String)` if point decompression fails.
+#[instrument(level = "debug", skip_all)]
 pub fn prove_equality(
     c_price: CompressedRistretto,
     c_pay: CompressedRistretto,
@@ -41,82 +67,65 @@ pub fn prove_equality(
     r_pay: Scalar,
     binding_context: &[u8],
 ) -> Result<EqualityProof, String> {
-    let pc_gens = PedersenGens::default();
-    let delta_r = r_price - r_pay;
-
-    let mut transcript = Transcript::new(b"EqualityProof-v1");
-    transcript.append_message(b"context", binding_context);
-    transcript.append_message(b"C_price", c_price.as_bytes());
-    transcript.append_message(b"C_pay", c_pay.as_bytes());
-
-    let k = Scalar::random(&mut OsRng);
-    let r_point = &k * &pc_gens.B_blinding;
-    let r_compressed = r_point.compress();
-
-    transcript.append_message(b"R", r_compressed.as_bytes());
-
-    let mut c_bytes = [0u8; 64];
-    transcript.challenge_bytes(b"challenge", &mut c_bytes);
-    let c = Scalar::from_bytes_mod_order_wide(&c_bytes);
-
-    let s = k + c * delta_r;
+    prove_equality_within(c_price, c_pay, r_price, r_pay, binding_context, None)
+}
 
-    Ok(EqualityProof {
-        r_announcement: r_compressed.to_bytes(),
-        s_response: s.to_bytes(),
-    })
+/// `prove_equality` with an optional validity window bound into the transcript.
+#[instrument(level = "debug", skip_all, fields(window = window.is_some()))]
+pub fn prove_equality_within(
+    c_price: CompressedRistretto,
+    c_pay: CompressedRistretto,
+    r_price: Scalar,
+    r_pay: Scalar,
+    binding_context: &[u8],
+    window: Option<&ValidityWindow>,
+) -> Result<EqualityProof, String> {
+    if let Some(window) = window {
+        window.check()?;
+    }
+    prove_linear_with_transcript(
+        &mut transcript(c_price, c_pay, binding_context, window),
+        &relation(c_price, c_pay),
+        &[r_price, r_pay],
+    )
 }
 
 /// Verify a Schnorr sigma equality proof.
 ///
 /// Returns `true` iff the proof is valid: s * B_blinding == R + c * D
+#[instrument(level = "debug", skip_all)]
 pub fn verify_equality(
     c_price: CompressedRistretto,
     c_pay: CompressedRistretto,
     proof: &EqualityProof,
     binding_context: &[u8],
 ) -> bool {
-    let pc_gens = PedersenGens::default();
+    verify_equality_within(c_price, c_pay, proof, binding_context, None)
+}
 
-    let mut transcript = Transcript::new(b"EqualityProof-v1");
-    transcript.append_message(b"context", binding_context);
-    transcript.append_message(b"C_price", c_price.as_bytes());
-    transcript.append_message(b"C_pay", c_pay.as_bytes());
-    transcript.append_message(b"R", &proof.r_announcement);
-
-    let mut c_bytes = [0u8; 64];
-    transcript.challenge_bytes(b"challenge", &mut c_bytes);
-    let c = Scalar::from_bytes_mod_order_wide(&c_bytes);
-
-    let r_point = match CompressedRistretto(proof.r_announcement).decompress() {
-        Some(p) => p,
-        None => return false,
-    };
-    let s = match Scalar::from_canonical_bytes(proof.s_response) {
-        Some(sc) => sc,
-        None => return false,
-    };
-
-    // D = C_price - C_pay
-    let cp = match c_price.decompress() {
-        Some(p) => p,
-        None => return false,
-    };
-    let cpay = match c_pay.decompress() {
-        Some(p) => p,
-        None => return false,
-    };
-    let d = cp - cpay;
-
-    // Check: s * B_blinding == R + c * D
-    let lhs = &s * &pc_gens.B_blinding;
-    let rhs = r_point + &c * d;
-    lhs.compress() == rhs.compress()
+/// Verify a proof made by `prove_equality_within` against the same window.
+///
+/// Only checks the transcript; comparing the window with a reference point is
+/// the caller's job (`zk::validity::windowed_outcome`).
+#[instrument(level = "debug", skip_all, fields(window = window.is_some()))]
+pub fn verify_equality_within(
+    c_price: CompressedRistretto,
+    c_pay: CompressedRistretto,
+    proof: &EqualityProof,
+    binding_context: &[u8],
+    window: Option<&ValidityWindow>,
+) -> bool {
+    verify_linear_with_transcript(
+        &mut transcript(c_price, c_pay, binding_context, window),
+        &relation(c_price, c_pay),
+        proof,
+    )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use bulletproofs::PedersenGens;
 
     fn commitment(value: u64, blinding_byte: u8) -> (CompressedRistretto, Scalar) {
         let pc_gens = PedersenGens::default();
@@ -162,4 +171,48 @@ mod tests {
 
         assert!(!verify_equality(c_price, c_other, &proof, &context_hash));
     }
+
+    #[test]
+    fn validity_window_is_bound_to_the_proof() {
+        let context_hash = [0x12u8; 32];
+        let (c_price, r_price) = commitment(42, 0x23);
+        let (c_pay, r_pay) = commitment(42, 0x34);
+        let window = ValidityWindow { not_after: Some(1_800_000_000), ..Default::default() };
+
+        let proof = prove_equality_within(c_price, c_pay, r_price, r_pay, &context_hash, Some(&window))
+            .expect("proof generation should succeed");
+
+        assert!(verify_equality_within(c_price, c_pay, &proof, &context_hash, Some(&window)));
+        assert!(!verify_equality(c_price, c_pay, &proof, &context_hash));
+        let extended = ValidityWindow { not_after: Some(1_900_000_000), ..window };
+        assert!(!verify_equality_within(c_price, c_pay, &proof, &context_hash, Some(&extended)));
+    }
+
+    #[test]
+    fn proof_from_hand_written_transcript_verifies() {
+        use merlin::Transcript;
+        use rand::rngs::OsRng;
+
+        let pc_gens = PedersenGens::default();
+        let context_hash = [0xccu8; 32];
+        let (c_price, r_price) = commitment(42, 0xdd);
+        let (c_pay, r_pay) = commitment(42, 0xee);
+
+        let mut transcript = Transcript::new(b"EqualityProof-v1");
+        transcript.append_message(b"context", &context_hash);
+        transcript.append_message(b"C_price", c_price.as_bytes());
+        transcript.append_message(b"C_pay", c_pay.as_bytes());
+        let k = Scalar::random(&mut OsRng);
+        let r_compressed = (k * pc_gens.B_blinding).compress();
+        transcript.append_message(b"R", r_compressed.as_bytes());
+        let mut c_bytes = [0u8; 64];
+        transcript.challenge_bytes(b"challenge", &mut c_bytes);
+        let c = Scalar::from_bytes_mod_order_wide(&c_bytes);
+        let proof = EqualityProof {
+            r_announcement: r_compressed.to_bytes(),
+            s_response: (k + c * (r_price - r_pay)).to_bytes(),
+        };
+
+        assert!(verify_equality(c_price, c_pay, &proof, &context_hash));
+    }
 }
@@ -0,0 +1,268 @@
+//! Generic linear-relation Schnorr proof.
+//!
+//! Proves that a set of Pedersen commitments C_i, public coefficients a_i and
+//! a public constant k satisfy
+//!   Σ a_i * v_i = k
+//! without revealing any v_i. With
+//!   C_i = v_i * B + r_i * B_blinding
+//! the combination
+//!   D = Σ a_i * C_i - k * B = delta_r * B_blinding,  delta_r = Σ a_i * r_i
+//! opens to zero in the value base, so a Schnorr proof of knowledge of
+//! delta_r in the blinding base proves the relation.
+//!
+//! Canonical transcript order (MUST match between prove and verify):
+//!   context -> n -> (a_i, C_i)* -> constant -> R -> challenge
+//!
+//! Existing proofs with their own transcript layout (`EqualityProof-v1`,
+//! `QuantityTotalProof-v1`) use the `*_with_transcript` variants and append
+//! their statement themselves.
+
+use bulletproofs::PedersenGens;
+use curve25519_dalek_ng::{
+    ristretto::{CompressedRistretto, RistrettoPoint},
+    scalar::Scalar,
+    traits::Identity,
+};
+use merlin::Transcript;
+use rand::rngs::OsRng;
+use serde::{Deserialize, Serialize};
+
+const LABEL: &[u8] = b"LinearRelationProof-v1";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LinearProof {
+    /// Compressed Ristretto point R = k * B_blinding
+    pub r_announcement: [u8; 32],
+    /// Scalar s = k + c * delta_r
+    pub s_response: [u8; 32],
+}
+
+/// Public statement Σ a_i * C_i - k * B = delta_r * B_blinding.
+#[derive(Clone, Debug)]
+pub struct LinearRelation {
+    terms: Vec<(Scalar, CompressedRistretto)>,
+    constant: Scalar,
+}
+
+impl Default for LinearRelation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LinearRelation {
+    pub fn new() -> Self {
+        Self {
+            terms: Vec::new(),
+            constant: Scalar::zero(),
+        }
+    }
+
+    /// Adds `coefficient * commitment` to the left-hand side.
+    pub fn term(mut self, coefficient: Scalar, commitment: CompressedRistretto) -> Self {
+        self.terms.push((coefficient, commitment));
+        self
+    }
+
+    /// Sets the public constant `k` the committed values must sum to.
+    pub fn constant(mut self, constant: Scalar) -> Self {
+        self.constant = constant;
+        self
+    }
+
+    pub fn terms(&self) -> &[(Scalar, CompressedRistretto)] {
+        &self.terms
+    }
+
+    /// Appends the statement to `transcript` in canonical order.
+    pub fn append_to_transcript(&self, transcript: &mut Transcript) {
+        transcript.append_u64(b"n", self.terms.len() as u64);
+        for (coefficient, commitment) in &self.terms {
+            transcript.append_message(b"a", coefficient.as_bytes());
+            transcript.append_message(b"C", commitment.as_bytes());
+        }
+        transcript.append_message(b"constant", self.constant.as_bytes());
+    }
+
+    /// D = Σ a_i * C_i - k * B, or `None` if a commitment fails to decompress.
+    pub fn combined_point(&self) -> Option<RistrettoPoint> {
+        let pc_gens = PedersenGens::default();
+        let mut d = RistrettoPoint::identity();
+        for (coefficient, commitment) in &self.terms {
+            d += coefficient * commitment.decompress()?;
+        }
+        Some(d - self.constant * pc_gens.B)
+    }
+
+    /// delta_r = Σ a_i * r_i for the blindings of the terms, in order.
+    pub fn combined_blinding(&self, blindings: &[Scalar]) -> Result<Scalar, String> {
+        if blindings.len() != self.terms.len() {
+            return Err(format!(
+                "expected {} blindings, got {}",
+                self.terms.len(),
+                blindings.len()
+            ));
+        }
+        Ok(self
+            .terms
+            .iter()
+            .zip(blindings)
+            .map(|((coefficient, _), blinding)| coefficient * blinding)
+            .sum())
+    }
+}
+
+fn challenge_scalar(transcript: &mut Transcript) -> Scalar {
+    let mut c_bytes = [0u8; 64];
+    transcript.challenge_bytes(b"challenge", &mut c_bytes);
+    Scalar::from_bytes_mod_order_wide(&c_bytes)
+}
+
+/// Generate a linear-relation proof over the canonical transcript.
+///
+/// `blindings` are the blinding factors of the relation's commitments, in the
+/// order the terms were added.
+pub fn prove_linear(
+    relation: &LinearRelation,
+    blindings: &[Scalar],
+    context: &[u8],
+) -> Result<LinearProof, String> {
+    let mut transcript = Transcript::new(LABEL);
+    transcript.append_message(b"context", context);
+    relation.append_to_transcript(&mut transcript);
+    prove_linear_with_transcript(&mut transcript, relation, blindings)
+}
+
+/// Verify a linear-relation proof over the canonical transcript.
+pub fn verify_linear(relation: &LinearRelation, proof: &LinearProof, context: &[u8]) -> bool {
+    let mut transcript = Transcript::new(LABEL);
+    transcript.append_message(b"context", context);
+    relation.append_to_transcript(&mut transcript);
+    verify_linear_with_transcript(&mut transcript, relation, proof)
+}
+
+/// Generate the proof on a caller-prepared transcript.
+///
+/// The caller MUST already have appended the context and the full public
+/// statement; only `R` and the challenge are added here.
+pub fn prove_linear_with_transcript(
+    transcript: &mut Transcript,
+    relation: &LinearRelation,
+    blindings: &[Scalar],
+) -> Result<LinearProof, String> {
+    let pc_gens = PedersenGens::default();
+    let delta_r = relation.combined_blinding(blindings)?;
+
+    let k = Scalar::random(&mut OsRng);
+    let r_compressed = (k * pc_gens.B_blinding).compress();
+
+    transcript.append_message(b"R", r_compressed.as_bytes());
+    let c = challenge_scalar(transcript);
+    let s = k + c * delta_r;
+
+    Ok(LinearProof {
+        r_announcement: r_compressed.to_bytes(),
+        s_response: s.to_bytes(),
+    })
+}
+
+/// Verify the proof on a caller-prepared transcript.
+///
+/// Returns `true` iff s * B_blinding == R + c * D.
+pub fn verify_linear_with_transcript(
+    transcript: &mut Transcript,
+    relation: &LinearRelation,
+    proof: &LinearProof,
+) -> bool {
+    let pc_gens = PedersenGens::default();
+
+    transcript.append_message(b"R", &proof.r_announcement);
+    let c = challenge_scalar(transcript);
+
+    let r_point = match CompressedRistretto(proof.r_announcement).decompress() {
+        Some(point) => point,
+        None => return false,
+    };
+    let s = match Scalar::from_canonical_bytes(proof.s_response) {
+        Some(scalar) => scalar,
+        None => return false,
+    };
+    let d = match relation.combined_point() {
+        Some(point) => point,
+        None => return false,
+    };
+
+    let lhs = s * pc_gens.B_blinding;
+    let rhs = r_point + c * d;
+    lhs.compress() == rhs.compress()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commitment(value: u64, blinding_byte: u8) -> (CompressedRistretto, Scalar) {
+        let pc_gens = PedersenGens::default();
+        let blinding = Scalar::from_bytes_mod_order([blinding_byte; 32]);
+        let commitment = pc_gens.commit(Scalar::from(value), blinding);
+        (commitment.compress(), blinding)
+    }
+
+    #[test]
+    fn weighted_sum_with_constant_verifies() {
+        // 3 * 5 + 2 * 7 - 1 * 4 = 25
+        let context = [0x01u8; 32];
+        let (c_a, r_a) = commitment(5, 0x11);
+        let (c_b, r_b) = commitment(7, 0x22);
+        let (c_c, r_c) = commitment(4, 0x33);
+        let relation = LinearRelation::new()
+            .term(Scalar::from(3u64), c_a)
+            .term(Scalar::from(2u64), c_b)
+            .term(-Scalar::one(), c_c)
+            .constant(Scalar::from(25u64));
+
+        let proof = prove_linear(&relation, &[r_a, r_b, r_c], &context)
+            .expect("proof generation should succeed");
+
+        assert!(verify_linear(&relation, &proof, &context));
+    }
+
+    #[test]
+    fn wrong_constant_fails() {
+        let context = [0x02u8; 32];
+        let (c_a, r_a) = commitment(5, 0x44);
+        let relation = LinearRelation::new()
+            .term(Scalar::one(), c_a)
+            .constant(Scalar::from(6u64));
+
+        let proof = prove_linear(&relation, &[r_a], &context)
+            .expect("proof generation should succeed");
+
+        assert!(!verify_linear(&relation, &proof, &context));
+    }
+
+    #[test]
+    fn wrong_context_fails() {
+        let (c_a, r_a) = commitment(9, 0x55);
+        let (c_b, r_b) = commitment(9, 0x66);
+        let relation = LinearRelation::new()
+            .term(Scalar::one(), c_a)
+            .term(-Scalar::one(), c_b);
+
+        let proof = prove_linear(&relation, &[r_a, r_b], &[0x03u8; 32])
+            .expect("proof generation should succeed");
+
+        assert!(!verify_linear(&relation, &proof, &[0x04u8; 32]));
+    }
+
+    #[test]
+    fn blinding_count_mismatch_is_rejected() {
+        let (c_a, r_a) = commitment(1, 0x77);
+        let (c_b, _) = commitment(1, 0x88);
+        let relation = LinearRelation::new()
+            .term(Scalar::one(), c_a)
+            .term(-Scalar::one(), c_b);
+
+        assert!(prove_linear(&relation, &[r_a], &[0u8; 32]).is_err());
+    }
+}
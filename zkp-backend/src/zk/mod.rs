@@ -1,7 +1,19 @@
 pub mod txid_pedersen_proof;
 pub mod bp_plus_pedersen;
 pub mod pedersen;
+pub mod linear;
 pub mod equality_proof;
 pub mod quantity_total_proof;
-
-
+pub mod opening_proof;
+pub mod comparison;
+pub mod delivery_proof;
+pub mod bn254;
+pub mod bn254_verifier;
+pub mod note_link;
+pub mod mpc_range;
+pub mod revocation;
+pub mod bbs;
+pub mod passport;
+pub mod carbon_footprint;
+pub mod recycled_content;
+pub mod validity;
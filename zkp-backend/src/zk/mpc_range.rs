@@ -0,0 +1,445 @@
+//! Aggregated range proof built by several parties.
+//!
+//! Wraps the `bulletproofs` dealer/party MPC: every party keeps its own
+//! (value, blinding) and only sends protocol messages, the dealer combines
+//! them into one `RangeProof` over all parties' commitments. The dealer is
+//! stateless here: each step replays the earlier rounds from the stored
+//! messages, so a session only has to keep bytes.
+//!
+//! Rounds (m parties, n bits; m a power of two):
+//!   1. party j  -> BitCommitment  (V_j || A_j || S_j)
+//!      dealer   -> BitChallenge   (y || z)
+//!   2. party j  -> PolyCommitment (T_1_j || T_2_j)
+//!      dealer   -> PolyChallenge  (x)
+//!   3. party j  -> ProofShare     (t_x || t_x_blinding || e_blinding || l_vec || r_vec)
+//!      dealer   -> RangeProof over V_0..V_{m-1}
+//!
+//! Transcript order (MUST match between dealer and verifier):
+//!   context_hash -> rangeproof domain separator (n, m) -> V_j... -> A -> S -> ...
+//!
+//! `prove` builds the same proof in one go for a caller holding every opening.
+
+use std::sync::LazyLock;
+
+use bulletproofs::range_proof_mpc::dealer::Dealer;
+use bulletproofs::range_proof_mpc::messages::{BitChallenge, BitCommitment, PolyChallenge, PolyCommitment, ProofShare};
+use bulletproofs::range_proof_mpc::party::{Party, PartyAwaitingBitChallenge, PartyAwaitingPolyChallenge, PartyAwaitingPosition};
+use bulletproofs::range_proof_mpc::MPCError;
+use bulletproofs::{BulletproofGens, PedersenGens, RangeProof};
+use curve25519_dalek_ng::ristretto::CompressedRistretto;
+use curve25519_dalek_ng::scalar::Scalar;
+use merlin::Transcript;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use super::comparison::SUPPORTED_BIT_RANGES;
+
+/// Largest supported aggregation.
+pub const MAX_PARTIES: usize = 16;
+
+const LABEL: &[u8] = b"MpcRangeProof-v1";
+
+static PC_GENS: LazyLock<PedersenGens> = LazyLock::new(PedersenGens::default);
+static BP_GENS: LazyLock<BulletproofGens> = LazyLock::new(|| BulletproofGens::new(64, MAX_PARTIES));
+
+fn transcript(context_hash: &[u8; 32]) -> Transcript {
+    let mut transcript = Transcript::new(LABEL);
+    transcript.append_message(b"context_hash", context_hash);
+    transcript
+}
+
+pub fn check_parameters(bits: usize, parties: usize) -> Result<(), String> {
+    if !SUPPORTED_BIT_RANGES.contains(&bits) {
+        return Err(format!("unsupported bit range {bits}: expected 8, 16, 32 or 64"));
+    }
+    if !parties.is_power_of_two() || parties > MAX_PARTIES {
+        return Err(format!("parties must be a power of two up to {MAX_PARTIES}"));
+    }
+    Ok(())
+}
+
+fn mpc_error(error: MPCError) -> String {
+    match error {
+        MPCError::MalformedProofShares { bad_shares } => format!("malformed proof shares from positions {bad_shares:?}"),
+        other => format!("{other}"),
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Wire encoding
+// -----------------------------------------------------------------------------
+//
+// The message types keep their fields private but derive serde; these mirrors
+// have the same field names, so a serde round trip converts between them.
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize)]
+struct BitCommitmentWire {
+    V_j: CompressedRistretto,
+    A_j: CompressedRistretto,
+    S_j: CompressedRistretto,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BitChallengeWire {
+    y: Scalar,
+    z: Scalar,
+}
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize)]
+struct PolyCommitmentWire {
+    T_1_j: CompressedRistretto,
+    T_2_j: CompressedRistretto,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PolyChallengeWire {
+    x: Scalar,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ProofShareWire {
+    t_x: Scalar,
+    t_x_blinding: Scalar,
+    e_blinding: Scalar,
+    l_vec: Vec<Scalar>,
+    r_vec: Vec<Scalar>,
+}
+
+fn convert<A: Serialize, B: DeserializeOwned>(value: &A) -> Option<B> {
+    serde_json::from_value(serde_json::to_value(value).ok()?).ok()
+}
+
+fn words(bytes: &[u8]) -> Option<Vec<[u8; 32]>> {
+    if !bytes.len().is_multiple_of(32) {
+        return None;
+    }
+    Some(bytes.chunks_exact(32).map(|word| word.try_into().expect("32-byte chunk")).collect())
+}
+
+fn scalar(word: [u8; 32]) -> Option<Scalar> {
+    Scalar::from_canonical_bytes(word)
+}
+
+pub fn encode_bit_commitment(message: &BitCommitment) -> Vec<u8> {
+    let wire: BitCommitmentWire = convert(message).expect("bit commitment fields");
+    [wire.V_j.to_bytes(), wire.A_j.to_bytes(), wire.S_j.to_bytes()].concat()
+}
+
+/// `None` unless the bytes are three valid points.
+pub fn decode_bit_commitment(bytes: &[u8]) -> Option<BitCommitment> {
+    let [v, a, s]: [[u8; 32]; 3] = words(bytes)?.try_into().ok()?;
+    convert(&BitCommitmentWire { V_j: CompressedRistretto(v), A_j: CompressedRistretto(a), S_j: CompressedRistretto(s) })
+}
+
+/// The Pedersen commitment V_j a party's bit commitment covers.
+pub fn committed_value(message: &BitCommitment) -> CompressedRistretto {
+    let wire: BitCommitmentWire = convert(message).expect("bit commitment fields");
+    wire.V_j
+}
+
+pub fn encode_bit_challenge(message: &BitChallenge) -> Vec<u8> {
+    let wire: BitChallengeWire = convert(message).expect("bit challenge fields");
+    [wire.y.to_bytes(), wire.z.to_bytes()].concat()
+}
+
+pub fn decode_bit_challenge(bytes: &[u8]) -> Option<BitChallenge> {
+    let [y, z]: [[u8; 32]; 2] = words(bytes)?.try_into().ok()?;
+    convert(&BitChallengeWire { y: scalar(y)?, z: scalar(z)? })
+}
+
+pub fn encode_poly_commitment(message: &PolyCommitment) -> Vec<u8> {
+    let wire: PolyCommitmentWire = convert(message).expect("poly commitment fields");
+    [wire.T_1_j.to_bytes(), wire.T_2_j.to_bytes()].concat()
+}
+
+pub fn decode_poly_commitment(bytes: &[u8]) -> Option<PolyCommitment> {
+    let [t1, t2]: [[u8; 32]; 2] = words(bytes)?.try_into().ok()?;
+    convert(&PolyCommitmentWire { T_1_j: CompressedRistretto(t1), T_2_j: CompressedRistretto(t2) })
+}
+
+pub fn encode_poly_challenge(message: &PolyChallenge) -> Vec<u8> {
+    let wire: PolyChallengeWire = convert(message).expect("poly challenge fields");
+    wire.x.to_bytes().to_vec()
+}
+
+pub fn decode_poly_challenge(bytes: &[u8]) -> Option<PolyChallenge> {
+    let [x]: [[u8; 32]; 1] = words(bytes)?.try_into().ok()?;
+    convert(&PolyChallengeWire { x: scalar(x)? })
+}
+
+pub fn encode_proof_share(message: &ProofShare) -> Vec<u8> {
+    let wire: ProofShareWire = convert(message).expect("proof share fields");
+    [wire.t_x, wire.t_x_blinding, wire.e_blinding]
+        .iter()
+        .chain(&wire.l_vec)
+        .chain(&wire.r_vec)
+        .flat_map(|s| s.to_bytes())
+        .collect()
+}
+
+/// `None` unless the bytes are canonical scalars for an n-bit share.
+pub fn decode_proof_share(bytes: &[u8], bits: usize) -> Option<ProofShare> {
+    let words = words(bytes)?;
+    if words.len() != 3 + 2 * bits {
+        return None;
+    }
+    let scalars = words.into_iter().map(scalar).collect::<Option<Vec<_>>>()?;
+    convert(&ProofShareWire {
+        t_x: scalars[0],
+        t_x_blinding: scalars[1],
+        e_blinding: scalars[2],
+        l_vec: scalars[3..3 + bits].to_vec(),
+        r_vec: scalars[3 + bits..].to_vec(),
+    })
+}
+
+// -----------------------------------------------------------------------------
+// Dealer
+// -----------------------------------------------------------------------------
+
+/// Challenge for round 2, once every party's bit commitment is in.
+#[instrument(level = "debug", skip_all)]
+pub fn bit_challenge(context_hash: &[u8; 32], bits: usize, bit_commitments: &[BitCommitment]) -> Result<BitChallenge, String> {
+    check_parameters(bits, bit_commitments.len())?;
+    let mut transcript = transcript(context_hash);
+    let dealer = Dealer::new(&BP_GENS, &PC_GENS, &mut transcript, bits, bit_commitments.len()).map_err(mpc_error)?;
+    let (_, challenge) = dealer.receive_bit_commitments(bit_commitments.to_vec()).map_err(mpc_error)?;
+    Ok(challenge)
+}
+
+/// Challenge for round 3, once every party's poly commitment is in.
+#[instrument(level = "debug", skip_all)]
+pub fn poly_challenge(
+    context_hash: &[u8; 32],
+    bits: usize,
+    bit_commitments: &[BitCommitment],
+    poly_commitments: &[PolyCommitment],
+) -> Result<PolyChallenge, String> {
+    check_parameters(bits, bit_commitments.len())?;
+    let mut transcript = transcript(context_hash);
+    let dealer = Dealer::new(&BP_GENS, &PC_GENS, &mut transcript, bits, bit_commitments.len()).map_err(mpc_error)?;
+    let (dealer, _) = dealer.receive_bit_commitments(bit_commitments.to_vec()).map_err(mpc_error)?;
+    let (_, challenge) = dealer.receive_poly_commitments(poly_commitments.to_vec()).map_err(mpc_error)?;
+    Ok(challenge)
+}
+
+/// Aggregates the proof shares and checks the result.
+///
+/// # Returns
+/// The serialized proof, or `Err` naming the positions whose shares fail.
+#[instrument(level = "debug", skip_all)]
+pub fn aggregate(
+    context_hash: &[u8; 32],
+    bits: usize,
+    bit_commitments: &[BitCommitment],
+    poly_commitments: &[PolyCommitment],
+    proof_shares: &[ProofShare],
+) -> Result<Vec<u8>, String> {
+    check_parameters(bits, bit_commitments.len())?;
+    let mut transcript = transcript(context_hash);
+    let dealer = Dealer::new(&BP_GENS, &PC_GENS, &mut transcript, bits, bit_commitments.len()).map_err(mpc_error)?;
+    let (dealer, _) = dealer.receive_bit_commitments(bit_commitments.to_vec()).map_err(mpc_error)?;
+    let (dealer, _) = dealer.receive_poly_commitments(poly_commitments.to_vec()).map_err(mpc_error)?;
+    let proof = dealer.receive_shares(proof_shares).map_err(mpc_error)?;
+    Ok(proof.to_bytes())
+}
+
+/// Verifies an aggregated proof that every commitment opens to [0, 2^bits).
+#[instrument(level = "debug", skip_all)]
+pub fn verify(commitments: &[CompressedRistretto], proof_bytes: &[u8], context_hash: &[u8; 32], bits: usize) -> bool {
+    if check_parameters(bits, commitments.len()).is_err() {
+        return false;
+    }
+    let Ok(proof) = RangeProof::from_bytes(proof_bytes) else {
+        return false;
+    };
+    proof.verify_multiple(&BP_GENS, &PC_GENS, &mut transcript(context_hash), commitments, bits).is_ok()
+}
+
+/// Proves every value in [0, 2^bits) at once, as a session with one party per value would.
+///
+/// # Returns
+/// The commitments V_0..V_{m-1} and the serialized proof.
+#[instrument(level = "debug", skip_all)]
+pub fn prove(
+    values: &[u64],
+    blindings: &[Scalar],
+    context_hash: &[u8; 32],
+    bits: usize,
+) -> Result<(Vec<CompressedRistretto>, Vec<u8>), String> {
+    check_parameters(bits, values.len())?;
+    if blindings.len() != values.len() {
+        return Err("expected one blinding per value".to_string());
+    }
+    if bits < 64 && values.iter().any(|value| value >> bits != 0) {
+        return Err(format!("values must be below 2^{bits}"));
+    }
+    let (proof, commitments) =
+        RangeProof::prove_multiple(&BP_GENS, &PC_GENS, &mut transcript(context_hash), values, blindings, bits)
+            .map_err(|e| e.to_string())?;
+    Ok((commitments, proof.to_bytes()))
+}
+
+// -----------------------------------------------------------------------------
+// Party
+// -----------------------------------------------------------------------------
+
+enum PartyState {
+    AwaitingPosition(PartyAwaitingPosition<'static>),
+    AwaitingBitChallenge(PartyAwaitingBitChallenge<'static>),
+    AwaitingPolyChallenge(Box<PartyAwaitingPolyChallenge>),
+    Done,
+}
+
+/// One party's side of the protocol; the opening never leaves it.
+pub struct MpcParty {
+    commitment: CompressedRistretto,
+    state: PartyState,
+}
+
+impl MpcParty {
+    pub fn new(value: u64, blinding: Scalar, bits: usize) -> Result<Self, String> {
+        check_parameters(bits, 1)?;
+        if bits < 64 && value >> bits != 0 {
+            return Err(format!("value does not fit in {bits} bits"));
+        }
+        let party = Party::new(&BP_GENS, &PC_GENS, value, blinding, bits).map_err(mpc_error)?;
+        Ok(Self {
+            commitment: PC_GENS.commit(Scalar::from(value), blinding).compress(),
+            state: PartyState::AwaitingPosition(party),
+        })
+    }
+
+    pub fn commitment(&self) -> CompressedRistretto {
+        self.commitment
+    }
+
+    fn take(&mut self) -> PartyState {
+        std::mem::replace(&mut self.state, PartyState::Done)
+    }
+
+    /// Round 1 at `position` in the aggregation.
+    pub fn bit_commitment(&mut self, position: usize) -> Result<Vec<u8>, String> {
+        let PartyState::AwaitingPosition(party) = self.take() else {
+            return Err("bit commitment already sent".to_string());
+        };
+        let (party, message) = party.assign_position(position).map_err(mpc_error)?;
+        self.state = PartyState::AwaitingBitChallenge(party);
+        Ok(encode_bit_commitment(&message))
+    }
+
+    /// Round 2, answering the dealer's bit challenge.
+    pub fn poly_commitment(&mut self, bit_challenge: &[u8]) -> Result<Vec<u8>, String> {
+        let challenge = decode_bit_challenge(bit_challenge).ok_or("invalid bit challenge")?;
+        let PartyState::AwaitingBitChallenge(party) = self.take() else {
+            return Err("not awaiting a bit challenge".to_string());
+        };
+        let (party, message) = party.apply_challenge(&challenge);
+        self.state = PartyState::AwaitingPolyChallenge(Box::new(party));
+        Ok(encode_poly_commitment(&message))
+    }
+
+    /// Round 3, answering the dealer's poly challenge.
+    pub fn proof_share(&mut self, poly_challenge: &[u8]) -> Result<Vec<u8>, String> {
+        let challenge = decode_poly_challenge(poly_challenge).ok_or("invalid poly challenge")?;
+        let PartyState::AwaitingPolyChallenge(party) = self.take() else {
+            return Err("not awaiting a poly challenge".to_string());
+        };
+        let share = party.apply_challenge(&challenge).map_err(mpc_error)?;
+        Ok(encode_proof_share(&share))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn party(value: u64, blinding_byte: u8, bits: usize) -> MpcParty {
+        MpcParty::new(value, Scalar::from_bytes_mod_order([blinding_byte; 32]), bits).unwrap()
+    }
+
+    /// Runs every round through the byte encodings, as a session would.
+    fn run(parties: &mut [MpcParty], context_hash: &[u8; 32], bits: usize) -> Result<Vec<u8>, String> {
+        let bit_commitments: Vec<_> = parties
+            .iter_mut()
+            .enumerate()
+            .map(|(j, p)| decode_bit_commitment(&p.bit_commitment(j).unwrap()).unwrap())
+            .collect();
+        let y_z = encode_bit_challenge(&bit_challenge(context_hash, bits, &bit_commitments)?);
+        let poly_commitments: Vec<_> = parties
+            .iter_mut()
+            .map(|p| decode_poly_commitment(&p.poly_commitment(&y_z).unwrap()).unwrap())
+            .collect();
+        let x = encode_poly_challenge(&poly_challenge(context_hash, bits, &bit_commitments, &poly_commitments)?);
+        let shares: Vec<_> = parties
+            .iter_mut()
+            .map(|p| decode_proof_share(&p.proof_share(&x).unwrap(), bits).unwrap())
+            .collect();
+        aggregate(context_hash, bits, &bit_commitments, &poly_commitments, &shares)
+    }
+
+    #[test]
+    fn single_prover_proof_verifies_like_a_session() {
+        let context_hash = [0x11u8; 32];
+        let blindings: Vec<_> = (1..=4u8).map(|b| Scalar::from_bytes_mod_order([b; 32])).collect();
+        let (commitments, proof) = prove(&[7, 0, 255, 19], &blindings, &context_hash, 8).unwrap();
+        assert!(verify(&commitments, &proof, &context_hash, 8));
+        assert!(!verify(&commitments, &proof, &[0x12u8; 32], 8));
+        assert!(prove(&[7, 256], &blindings[..2], &context_hash, 8).is_err());
+        assert!(prove(&[7, 0, 1], &blindings[..3], &context_hash, 8).is_err());
+    }
+
+    #[test]
+    fn two_party_proof_round_trip() {
+        let context_hash = [0x11u8; 32];
+        let mut parties = [party(1_000, 0x22, 64), party(4, 0x33, 64)];
+        let commitments: Vec<_> = parties.iter().map(MpcParty::commitment).collect();
+
+        let proof = run(&mut parties, &context_hash, 64).unwrap();
+        assert!(verify(&commitments, &proof, &context_hash, 64));
+        assert!(!verify(&commitments, &proof, &[0x12u8; 32], 64));
+        assert!(!verify(&[commitments[1], commitments[0]], &proof, &context_hash, 64));
+    }
+
+    #[test]
+    fn bit_commitment_carries_the_party_commitment() {
+        let mut party = party(7, 0x44, 32);
+        let message = decode_bit_commitment(&party.bit_commitment(0).unwrap()).unwrap();
+        assert_eq!(committed_value(&message), party.commitment());
+        assert!(party.bit_commitment(1).is_err());
+    }
+
+    #[test]
+    fn out_of_range_value_is_rejected() {
+        assert!(MpcParty::new(256, Scalar::one(), 8).is_err());
+        assert!(check_parameters(64, 3).is_err());
+        assert!(check_parameters(12, 2).is_err());
+    }
+
+    #[test]
+    fn tampered_share_is_attributed() {
+        let context_hash = [0x55u8; 32];
+        let bits = 8;
+        let mut parties = [party(3, 0x01, bits), party(9, 0x02, bits)];
+        let bit_commitments: Vec<_> = parties
+            .iter_mut()
+            .enumerate()
+            .map(|(j, p)| decode_bit_commitment(&p.bit_commitment(j).unwrap()).unwrap())
+            .collect();
+        let y_z = encode_bit_challenge(&bit_challenge(&context_hash, bits, &bit_commitments).unwrap());
+        let poly_commitments: Vec<_> = parties
+            .iter_mut()
+            .map(|p| decode_poly_commitment(&p.poly_commitment(&y_z).unwrap()).unwrap())
+            .collect();
+        let x = encode_poly_challenge(&poly_challenge(&context_hash, bits, &bit_commitments, &poly_commitments).unwrap());
+        let mut shares: Vec<_> = parties.iter_mut().map(|p| p.proof_share(&x).unwrap()).collect();
+        shares[1][0] ^= 1;
+        let shares: Vec<_> = shares.iter().map(|s| decode_proof_share(s, bits).unwrap()).collect();
+
+        let error = aggregate(&context_hash, bits, &bit_commitments, &poly_commitments, &shares).unwrap_err();
+        assert!(error.contains("[1]"), "{error}");
+    }
+}
@@ -0,0 +1,632 @@
+//! Groth16 proof linking a payment commitment to a Railgun note.
+//!
+//! `railgunTxRef` only names the shielded transfer; nothing ties its amount
+//! to `C_pay`. A Railgun note commitment is
+//!   note = Poseidon(npk, tokenHash, value)      (circomlib Poseidon, BN254 Fr)
+//! and Ristretto arithmetic is far too expensive inside a BN254 circuit, so
+//! the circuit links the note to a Pedersen *twin* of `C_pay` on Baby Jubjub,
+//! the twisted Edwards curve defined over BN254's scalar field:
+//!   twin = value * G + r * H
+//! and proves, for public (note, tokenHash, twin, context):
+//!   - note == Poseidon(npk, tokenHash, value) for some npk
+//!   - twin == value * G + r * H for the same value
+//!   - value < 2^120 (Railgun's note value width)
+//!
+//! The order `contextHash` is a public input (reduced mod r), so a proof
+//! cannot be replayed against another order.
+//!
+//! The twin is then tied to the Ristretto `C_pay = value * B + r_pay * B_blinding`
+//! by a cross-group bit-decomposition proof (`PayLink`). Each of the
+//! `VALUE_BITS` bits is committed in both groups,
+//!   P_i = b_i * B + r_i * B_blinding      Q_i = b_i * G + s_i * H
+//! with an OR proof that both open to 0 or both open to 1. The branch
+//! challenges are 128-bit strings whose XOR is the Fiat-Shamir challenge, so
+//! they are valid, distinct exponents in both groups. The verifier checks
+//!   sum 2^i * P_i == C_pay      sum 2^i * Q_i == twin
+//! so both commitments hold the same integer below 2^120.
+//!
+//! Link transcript order (MUST match between prove and verify):
+//!   note -> tokenHash -> twin -> context -> C_pay
+//!   -> per bit (P_i, Q_i, A_0, A_1, B_0, B_1) -> challenge
+//!
+//! Keys come from a circuit-specific Groth16 setup (`setup`); whoever runs it
+//! could forge proofs, so production keys need a ceremony.
+
+use std::sync::LazyLock;
+
+use ark_bn254::{Bn254, Fr};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ed_on_bn254::constraints::EdwardsVar;
+use ark_ed_on_bn254::{EdwardsAffine, EdwardsProjective, Fr as BabyJubjubScalar};
+use ark_ff::{BigInteger, PrimeField, UniformRand};
+use ark_groth16::{Groth16, Proof, ProvingKey, VerifyingKey};
+use ark_r1cs_std::prelude::*;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, SynthesisError};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_snark::{CircuitSpecificSetupSNARK, SNARK};
+use bulletproofs::PedersenGens;
+use curve25519_dalek_ng::ristretto::{CompressedRistretto, RistrettoPoint};
+use curve25519_dalek_ng::scalar::Scalar;
+use light_poseidon::parameters::bn254_x5;
+use light_poseidon::{Poseidon, PoseidonHasher, PoseidonParameters};
+use merlin::Transcript;
+use rand::rngs::OsRng;
+use sha2::{Digest, Sha256};
+use tracing::instrument;
+
+/// Railgun note values are uint120.
+pub const VALUE_BITS: usize = 120;
+const H_LABEL: &[u8] = b"zkp.babyjubjub.pedersen.H";
+
+/// circomlib Poseidon with three inputs (state width 4).
+static POSEIDON_PARAMS: LazyLock<PoseidonParameters<Fr>> =
+    LazyLock::new(|| bn254_x5::get_poseidon_parameters::<Fr>(4).expect("circom parameters for width 4"));
+
+/// Try-and-increment on y = sha256(label || i) mod q, cofactor cleared.
+static H: LazyLock<EdwardsAffine> = LazyLock::new(|| {
+    (0u32..)
+        .find_map(|counter| {
+            let mut hasher = Sha256::new();
+            hasher.update(H_LABEL);
+            hasher.update(counter.to_be_bytes());
+            let y = Fr::from_be_bytes_mod_order(&hasher.finalize());
+            let point = EdwardsAffine::get_point_from_y_unchecked(y, false)?.mul_by_cofactor();
+            (!point.is_zero()).then_some(point)
+        })
+        .expect("hash-to-curve terminates")
+});
+
+pub fn generator_g() -> EdwardsAffine {
+    EdwardsAffine::generator()
+}
+
+pub fn generator_h() -> EdwardsAffine {
+    *H
+}
+
+/// Railgun note hash Poseidon(npk, tokenHash, value).
+pub fn note_commitment(npk: Fr, token_hash: Fr, value: u128) -> Fr {
+    Poseidon::<Fr>::new_circom(3)
+        .and_then(|mut poseidon| poseidon.hash(&[npk, token_hash, Fr::from(value)]))
+        .expect("three inputs fit the circom parameters")
+}
+
+/// value * G + r * H on Baby Jubjub.
+pub fn twin_commitment(value: u128, blinding: BabyJubjubScalar) -> EdwardsAffine {
+    (generator_g() * BabyJubjubScalar::from(value) + generator_h() * blinding).into_affine()
+}
+
+/// Order context hash as a public input.
+pub fn context_scalar(context_hash: &[u8; 32]) -> Fr {
+    Fr::from_be_bytes_mod_order(context_hash)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NoteLinkStatement {
+    pub note_commitment: Fr,
+    pub token_hash: Fr,
+    pub twin: EdwardsAffine,
+    pub context: Fr,
+}
+
+impl NoteLinkStatement {
+    /// Groth16 public inputs, in allocation order.
+    pub fn public_inputs(&self) -> Vec<Fr> {
+        vec![self.note_commitment, self.token_hash, self.twin.x, self.twin.y, self.context]
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct NoteLinkWitness {
+    /// Railgun note public key.
+    pub npk: Fr,
+    pub value: u128,
+    /// Blinding of the twin commitment.
+    pub blinding: BabyJubjubScalar,
+}
+
+impl NoteLinkWitness {
+    /// The statement this witness satisfies.
+    pub fn statement(&self, token_hash: Fr, context_hash: &[u8; 32]) -> NoteLinkStatement {
+        NoteLinkStatement {
+            note_commitment: note_commitment(self.npk, token_hash, self.value),
+            token_hash,
+            twin: twin_commitment(self.value, self.blinding),
+            context: context_scalar(context_hash),
+        }
+    }
+}
+
+/// Both halves are `None` during setup.
+#[derive(Clone, Default)]
+pub struct NoteLinkCircuit {
+    pub statement: Option<NoteLinkStatement>,
+    pub witness: Option<NoteLinkWitness>,
+}
+
+fn sbox(x: &FpVar<Fr>) -> Result<FpVar<Fr>, SynthesisError> {
+    let x4 = x.square()?.square()?;
+    Ok(x4 * x)
+}
+
+/// In-circuit twin of `light_poseidon::Poseidon::hash` (zero domain tag).
+fn poseidon_gadget(params: &PoseidonParameters<Fr>, inputs: &[FpVar<Fr>]) -> Result<FpVar<Fr>, SynthesisError> {
+    let width = params.width;
+    let half_full = params.full_rounds / 2;
+    let mut state: Vec<FpVar<Fr>> = std::iter::once(FpVar::zero()).chain(inputs.iter().cloned()).collect();
+    for round in 0..params.full_rounds + params.partial_rounds {
+        for (i, element) in state.iter_mut().enumerate() {
+            *element += params.ark[round * width + i];
+        }
+        let full = round < half_full || round >= half_full + params.partial_rounds;
+        for element in state.iter_mut().take(if full { width } else { 1 }) {
+            *element = sbox(element)?;
+        }
+        state = params
+            .mds
+            .iter()
+            .map(|row| state.iter().zip(row).fold(FpVar::zero(), |acc, (element, m)| acc + element * *m))
+            .collect();
+    }
+    Ok(state.swap_remove(0))
+}
+
+fn bits_le(bigint: impl BigInteger, count: usize) -> Vec<bool> {
+    bigint.to_bits_le().into_iter().take(count).collect()
+}
+
+impl ConstraintSynthesizer<Fr> for NoteLinkCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        let statement = self.statement.as_ref();
+        let witness = self.witness.as_ref();
+        let missing = || SynthesisError::AssignmentMissing;
+
+        // Public inputs, in `NoteLinkStatement::public_inputs` order.
+        let note = FpVar::new_input(cs.clone(), || statement.map(|s| s.note_commitment).ok_or_else(missing))?;
+        let token_hash = FpVar::new_input(cs.clone(), || statement.map(|s| s.token_hash).ok_or_else(missing))?;
+        let twin = EdwardsVar::new_input(cs.clone(), || statement.map(|s| s.twin).ok_or_else(missing))?;
+        let context = FpVar::new_input(cs.clone(), || statement.map(|s| s.context).ok_or_else(missing))?;
+        // Touch the context so it appears in a constraint, not only in the IC.
+        let _context_squared = context.square()?;
+
+        let value_bits = (0..VALUE_BITS)
+            .map(|i| Boolean::new_witness(cs.clone(), || witness.map(|w| (w.value >> i) & 1 == 1).ok_or_else(missing)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let value = Boolean::le_bits_to_fp_var(&value_bits)?;
+        let npk = FpVar::new_witness(cs.clone(), || witness.map(|w| w.npk).ok_or_else(missing))?;
+        let blinding_len = BabyJubjubScalar::MODULUS_BIT_SIZE as usize;
+        let blinding = witness.map(|w| bits_le(w.blinding.into_bigint(), blinding_len));
+        let blinding_bits = (0..blinding_len)
+            .map(|i| Boolean::new_witness(cs.clone(), || blinding.as_ref().map(|bits| bits[i]).ok_or_else(missing)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        poseidon_gadget(&POSEIDON_PARAMS, &[npk, token_hash, value])?.enforce_equal(&note)?;
+
+        let g = EdwardsVar::new_constant(cs.clone(), generator_g())?;
+        let h = EdwardsVar::new_constant(cs, generator_h())?;
+        let committed = g.scalar_mul_le(value_bits.iter())? + h.scalar_mul_le(blinding_bits.iter())?;
+        committed.enforce_equal(&twin)
+    }
+}
+
+/// Whether `witness` satisfies `statement`, without running the prover.
+pub fn is_satisfied(statement: &NoteLinkStatement, witness: &NoteLinkWitness) -> Result<bool, String> {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    NoteLinkCircuit { statement: Some(statement.clone()), witness: Some(witness.clone()) }
+        .generate_constraints(cs.clone())
+        .map_err(|e| e.to_string())?;
+    cs.is_satisfied().map_err(|e| e.to_string())
+}
+
+/// Circuit-specific Groth16 setup; the verifying key is `pk.vk`.
+#[instrument(level = "debug", skip_all)]
+pub fn setup() -> Result<ProvingKey<Bn254>, String> {
+    Groth16::<Bn254>::setup(NoteLinkCircuit::default(), &mut OsRng)
+        .map(|(pk, _)| pk)
+        .map_err(|e| e.to_string())
+}
+
+#[instrument(level = "debug", skip_all)]
+pub fn prove(
+    pk: &ProvingKey<Bn254>,
+    statement: &NoteLinkStatement,
+    witness: &NoteLinkWitness,
+) -> Result<Proof<Bn254>, String> {
+    if witness.value >> VALUE_BITS != 0 {
+        return Err(format!("value must be below 2^{VALUE_BITS}"));
+    }
+    if !is_satisfied(statement, witness)? {
+        return Err("witness does not match the note commitment or twin".to_string());
+    }
+    let circuit = NoteLinkCircuit { statement: Some(statement.clone()), witness: Some(witness.clone()) };
+    Groth16::<Bn254>::prove(pk, circuit, &mut OsRng).map_err(|e| e.to_string())
+}
+
+#[instrument(level = "debug", skip_all)]
+pub fn verify(vk: &VerifyingKey<Bn254>, statement: &NoteLinkStatement, proof: &Proof<Bn254>) -> bool {
+    Groth16::<Bn254>::verify(vk, &statement.public_inputs(), proof).unwrap_or(false)
+}
+
+// =============================================================================
+// Link to C_pay
+// =============================================================================
+
+const CHALLENGE_BYTES: usize = 16;
+/// P_i, Q_i, both branch challenges, two Ristretto and two Baby Jubjub responses.
+const BIT_LINK_BYTES: usize = 32 + 32 + 2 * CHALLENGE_BYTES + 4 * 32;
+
+type Challenge = [u8; CHALLENGE_BYTES];
+
+/// One value bit committed in both groups, with the OR proof that the two
+/// commitments open to the same bit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct BitLink {
+    p: CompressedRistretto,
+    q: EdwardsAffine,
+    /// Branch challenges for bit = 0 and bit = 1.
+    challenges: [Challenge; 2],
+    ristretto_responses: [Scalar; 2],
+    jubjub_responses: [BabyJubjubScalar; 2],
+}
+
+/// Proof that `C_pay` and the twin commit to the same value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PayLink {
+    bits: Vec<BitLink>,
+}
+
+impl PayLink {
+    /// `VALUE_BITS` records of P || Q || c_0 || c_1 || z_r0 || z_r1 || z_s0 || z_s1.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.bits.len() * BIT_LINK_BYTES);
+        for bit in &self.bits {
+            bytes.extend_from_slice(bit.p.as_bytes());
+            bytes.extend_from_slice(&to_bytes(&bit.q));
+            bytes.extend_from_slice(&bit.challenges.concat());
+            bytes.extend_from_slice(bit.ristretto_responses[0].as_bytes());
+            bytes.extend_from_slice(bit.ristretto_responses[1].as_bytes());
+            bytes.extend_from_slice(&to_bytes(&bit.jubjub_responses[0]));
+            bytes.extend_from_slice(&to_bytes(&bit.jubjub_responses[1]));
+        }
+        bytes
+    }
+
+    /// Rejects wrong lengths, non-canonical scalars and Baby Jubjub points
+    /// outside the prime-order subgroup.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != VALUE_BITS * BIT_LINK_BYTES {
+            return None;
+        }
+        let bits = bytes
+            .chunks_exact(BIT_LINK_BYTES)
+            .map(|record| {
+                let (p, rest) = record.split_at(32);
+                let (q, rest) = rest.split_at(32);
+                let (c0, rest) = rest.split_at(CHALLENGE_BYTES);
+                let (c1, rest) = rest.split_at(CHALLENGE_BYTES);
+                let ristretto = |bytes: &[u8]| Scalar::from_canonical_bytes(bytes.try_into().ok()?);
+                Some(BitLink {
+                    p: CompressedRistretto::from_slice(p),
+                    q: from_bytes(q)?,
+                    challenges: [c0.try_into().ok()?, c1.try_into().ok()?],
+                    ristretto_responses: [ristretto(&rest[..32])?, ristretto(&rest[32..64])?],
+                    jubjub_responses: [from_bytes(&rest[64..96])?, from_bytes(&rest[96..])?],
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self { bits })
+    }
+}
+
+fn link_transcript(statement: &NoteLinkStatement, c_pay: &CompressedRistretto) -> Transcript {
+    let mut transcript = Transcript::new(b"NoteLinkPayLink-v1");
+    transcript.append_message(b"note", &statement.note_commitment.into_bigint().to_bytes_be());
+    transcript.append_message(b"token_hash", &statement.token_hash.into_bigint().to_bytes_be());
+    transcript.append_message(b"twin", &to_bytes(&statement.twin));
+    transcript.append_message(b"context", &statement.context.into_bigint().to_bytes_be());
+    transcript.append_message(b"C_pay", c_pay.as_bytes());
+    transcript
+}
+
+fn append_bit(
+    transcript: &mut Transcript,
+    p: &CompressedRistretto,
+    q: &EdwardsAffine,
+    a: [RistrettoPoint; 2],
+    b: [EdwardsProjective; 2],
+) {
+    transcript.append_message(b"P", p.as_bytes());
+    transcript.append_message(b"Q", &to_bytes(q));
+    transcript.append_message(b"A_0", a[0].compress().as_bytes());
+    transcript.append_message(b"A_1", a[1].compress().as_bytes());
+    transcript.append_message(b"B_0", &to_bytes(&b[0].into_affine()));
+    transcript.append_message(b"B_1", &to_bytes(&b[1].into_affine()));
+}
+
+fn challenge(transcript: &mut Transcript) -> Challenge {
+    let mut challenge = [0u8; CHALLENGE_BYTES];
+    transcript.challenge_bytes(b"challenge", &mut challenge);
+    challenge
+}
+
+fn xor(a: &Challenge, b: &Challenge) -> Challenge {
+    std::array::from_fn(|i| a[i] ^ b[i])
+}
+
+/// The same 128-bit integer as an exponent in each group.
+fn exponents(challenge: &Challenge) -> (Scalar, BabyJubjubScalar) {
+    let mut wide = [0u8; 32];
+    wide[..CHALLENGE_BYTES].copy_from_slice(challenge);
+    (Scalar::from_bits(wide), BabyJubjubScalar::from_le_bytes_mod_order(challenge))
+}
+
+/// Announcements the verifier recomputes for branch `bit` from its challenge
+/// and responses: z_r * B_blinding - c * (P - bit * B), z_s * H - c * (Q - bit * G).
+fn branch_announcements(
+    pc_gens: &PedersenGens,
+    p: RistrettoPoint,
+    q: EdwardsProjective,
+    bit: usize,
+    challenge: &Challenge,
+    responses: (Scalar, BabyJubjubScalar),
+) -> (RistrettoPoint, EdwardsProjective) {
+    let (c_r, c_s) = exponents(challenge);
+    let (p_shift, q_shift) = if bit == 1 {
+        (p - pc_gens.B, q - generator_g())
+    } else {
+        (p, q)
+    };
+    (responses.0 * pc_gens.B_blinding - c_r * p_shift, generator_h() * responses.1 - q_shift * c_s)
+}
+
+/// Prove that `c_pay` (opened by `witness.value`, `r_pay`) and the
+/// statement's twin commit to the same value.
+#[instrument(level = "debug", skip_all)]
+pub fn prove_pay_link(
+    statement: &NoteLinkStatement,
+    witness: &NoteLinkWitness,
+    c_pay: CompressedRistretto,
+    r_pay: Scalar,
+) -> Result<PayLink, String> {
+    if witness.value >> VALUE_BITS != 0 {
+        return Err(format!("value must be below 2^{VALUE_BITS}"));
+    }
+    let pc_gens = PedersenGens::default();
+    if pc_gens.commit(Scalar::from(witness.value), r_pay).compress() != c_pay {
+        return Err("value and r_pay do not open c_pay".to_string());
+    }
+    if twin_commitment(witness.value, witness.blinding) != statement.twin {
+        return Err("value and blinding do not open the twin".to_string());
+    }
+
+    // Bit blindings weighted by 2^i sum to the full blindings; bit 0 takes the rest.
+    let mut ristretto_blindings: Vec<Scalar> = (0..VALUE_BITS).map(|_| Scalar::random(&mut OsRng)).collect();
+    let mut jubjub_blindings: Vec<BabyJubjubScalar> =
+        (0..VALUE_BITS).map(|_| BabyJubjubScalar::rand(&mut OsRng)).collect();
+    ristretto_blindings[0] = r_pay
+        - (1..VALUE_BITS).map(|i| Scalar::from(1u128 << i) * ristretto_blindings[i]).sum::<Scalar>();
+    jubjub_blindings[0] = witness.blinding
+        - (1..VALUE_BITS).map(|i| BabyJubjubScalar::from(1u128 << i) * jubjub_blindings[i]).sum::<BabyJubjubScalar>();
+
+    struct Pending {
+        bit: usize,
+        p: CompressedRistretto,
+        q: EdwardsAffine,
+        nonces: (Scalar, BabyJubjubScalar),
+        simulated: (Challenge, Scalar, BabyJubjubScalar),
+    }
+
+    let mut transcript = link_transcript(statement, &c_pay);
+    let pending: Vec<Pending> = (0..VALUE_BITS)
+        .map(|i| {
+            let bit = ((witness.value >> i) & 1) as usize;
+            let p_point = pc_gens.commit(Scalar::from(bit as u64), ristretto_blindings[i]);
+            let q_point = generator_g() * BabyJubjubScalar::from(bit as u64) + generator_h() * jubjub_blindings[i];
+            let (p, q) = (p_point.compress(), q_point.into_affine());
+
+            let nonces = (Scalar::random(&mut OsRng), BabyJubjubScalar::rand(&mut OsRng));
+            let simulated = (rand::random(), Scalar::random(&mut OsRng), BabyJubjubScalar::rand(&mut OsRng));
+            let real = (nonces.0 * pc_gens.B_blinding, generator_h() * nonces.1);
+            let fake = branch_announcements(&pc_gens, p_point, q_point, 1 - bit, &simulated.0, (simulated.1, simulated.2));
+            let (a, b) = if bit == 0 { ([real.0, fake.0], [real.1, fake.1]) } else { ([fake.0, real.0], [fake.1, real.1]) };
+            append_bit(&mut transcript, &p, &q, a, b);
+            Pending { bit, p, q, nonces, simulated }
+        })
+        .collect();
+    let total = challenge(&mut transcript);
+
+    let bits = pending
+        .into_iter()
+        .enumerate()
+        .map(|(i, pending)| {
+            let real_challenge = xor(&total, &pending.simulated.0);
+            let (c_r, c_s) = exponents(&real_challenge);
+            let real_responses = (
+                pending.nonces.0 + c_r * ristretto_blindings[i],
+                pending.nonces.1 + c_s * jubjub_blindings[i],
+            );
+            let fake = 1 - pending.bit;
+            let mut challenges = [real_challenge; 2];
+            let mut ristretto_responses = [real_responses.0; 2];
+            let mut jubjub_responses = [real_responses.1; 2];
+            challenges[fake] = pending.simulated.0;
+            ristretto_responses[fake] = pending.simulated.1;
+            jubjub_responses[fake] = pending.simulated.2;
+            BitLink { p: pending.p, q: pending.q, challenges, ristretto_responses, jubjub_responses }
+        })
+        .collect();
+    Ok(PayLink { bits })
+}
+
+/// Verify that `c_pay` and the statement's twin commit to the same value.
+#[instrument(level = "debug", skip_all)]
+pub fn verify_pay_link(statement: &NoteLinkStatement, c_pay: CompressedRistretto, link: &PayLink) -> bool {
+    let Some(c_pay_point) = c_pay.decompress() else {
+        return false;
+    };
+    if link.bits.len() != VALUE_BITS {
+        return false;
+    }
+    let pc_gens = PedersenGens::default();
+    let mut transcript = link_transcript(statement, &c_pay);
+    let mut p_sum = RistrettoPoint::default();
+    let mut q_sum = EdwardsProjective::default();
+    for (i, bit) in link.bits.iter().enumerate() {
+        let Some(p) = bit.p.decompress() else {
+            return false;
+        };
+        let q = EdwardsProjective::from(bit.q);
+        let zero = branch_announcements(
+            &pc_gens,
+            p,
+            q,
+            0,
+            &bit.challenges[0],
+            (bit.ristretto_responses[0], bit.jubjub_responses[0]),
+        );
+        let one = branch_announcements(
+            &pc_gens,
+            p,
+            q,
+            1,
+            &bit.challenges[1],
+            (bit.ristretto_responses[1], bit.jubjub_responses[1]),
+        );
+        append_bit(&mut transcript, &bit.p, &bit.q, [zero.0, one.0], [zero.1, one.1]);
+        p_sum += Scalar::from(1u128 << i) * p;
+        q_sum += q * BabyJubjubScalar::from(1u128 << i);
+    }
+    let total = challenge(&mut transcript);
+    link.bits.iter().all(|bit| xor(&bit.challenges[0], &bit.challenges[1]) == total)
+        && p_sum == c_pay_point
+        && q_sum.into_affine() == statement.twin
+}
+
+/// Compressed arkworks encoding of keys, proofs and points.
+pub fn to_bytes<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(value.compressed_size());
+    value.serialize_compressed(&mut bytes).expect("serializing to a Vec cannot fail");
+    bytes
+}
+
+/// Inverse of `to_bytes`; rejects off-curve and out-of-subgroup points.
+pub fn from_bytes<T: CanonicalDeserialize>(bytes: &[u8]) -> Option<T> {
+    T::deserialize_compressed(bytes).ok()
+}
+
+/// Stable identifier of a verifying key: sha256 of its encoding.
+pub fn key_id(vk: &VerifyingKey<Bn254>) -> String {
+    hex::encode(Sha256::digest(to_bytes(vk)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ec::twisted_edwards::TECurveConfig;
+    use ark_ed_on_bn254::EdwardsConfig;
+
+    /// Baby Jubjub's a * x^2 + y^2 = 1 + d * x^2 * y^2 holds for both generators.
+    fn on_curve(point: &EdwardsAffine) -> bool {
+        let (x2, y2) = (point.x * point.x, point.y * point.y);
+        EdwardsConfig::COEFF_A * x2 + y2 == Fr::from(1u64) + EdwardsConfig::COEFF_D * x2 * y2
+    }
+
+    fn witness(value: u128) -> NoteLinkWitness {
+        NoteLinkWitness {
+            npk: Fr::from(0x1234_5678u64),
+            value,
+            blinding: BabyJubjubScalar::from(987_654_321u64),
+        }
+    }
+
+    #[test]
+    fn generators_are_prime_order_points() {
+        for point in [generator_g(), generator_h()] {
+            assert!(on_curve(&point));
+            assert!(point.is_in_correct_subgroup_assuming_on_curve());
+        }
+        assert_ne!(generator_g(), generator_h());
+    }
+
+    #[test]
+    fn poseidon_matches_circomlib() {
+        // circomlibjs: poseidon([1, 2]) — light-poseidon's own test vector.
+        let hash = Poseidon::<Fr>::new_circom(2).unwrap().hash(&[Fr::from(1u64), Fr::from(2u64)]).unwrap();
+        assert_eq!(
+            hex::encode(hash.into_bigint().to_bytes_be()),
+            "115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a"
+        );
+    }
+
+    #[test]
+    fn circuit_accepts_matching_note_and_twin() {
+        let token_hash = Fr::from(0xabcdu64);
+        let witness = witness(1_000_000);
+        let statement = witness.statement(token_hash, &[0x11u8; 32]);
+        assert!(is_satisfied(&statement, &witness).unwrap());
+    }
+
+    #[test]
+    fn circuit_rejects_a_different_amount() {
+        let token_hash = Fr::from(0xabcdu64);
+        let statement = witness(1_000_000).statement(token_hash, &[0x11u8; 32]);
+
+        let mut cheating = statement.clone();
+        cheating.twin = twin_commitment(999_999, witness(0).blinding);
+        assert!(!is_satisfied(&cheating, &witness(1_000_000)).unwrap());
+        assert!(!is_satisfied(&statement, &witness(999_999)).unwrap());
+    }
+
+    fn pay_commitment(value: u128, blinding: u64) -> (CompressedRistretto, Scalar) {
+        let r_pay = Scalar::from(blinding);
+        (PedersenGens::default().commit(Scalar::from(value), r_pay).compress(), r_pay)
+    }
+
+    #[test]
+    fn pay_link_ties_c_pay_to_the_twin() {
+        let witness = witness(1_000_000_000_000_000_000);
+        let statement = witness.statement(Fr::from(7u64), &[0x33u8; 32]);
+        let (c_pay, r_pay) = pay_commitment(witness.value, 5);
+
+        let link = prove_pay_link(&statement, &witness, c_pay, r_pay).unwrap();
+        assert!(verify_pay_link(&statement, c_pay, &link));
+        let decoded = PayLink::from_bytes(&link.to_bytes()).unwrap();
+        assert!(verify_pay_link(&statement, c_pay, &decoded));
+
+        let other_order = NoteLinkStatement { context: context_scalar(&[0x34u8; 32]), ..statement.clone() };
+        assert!(!verify_pay_link(&other_order, c_pay, &link));
+        let (other_pay, _) = pay_commitment(witness.value + 1, 5);
+        assert!(!verify_pay_link(&statement, other_pay, &link));
+    }
+
+    #[test]
+    fn pay_link_rejects_mismatched_amounts() {
+        let witness = witness(1_000);
+        let statement = witness.statement(Fr::from(7u64), &[0x33u8; 32]);
+        let (c_pay, r_pay) = pay_commitment(999, 5);
+        assert!(prove_pay_link(&statement, &witness, c_pay, r_pay).is_err());
+
+        // Swapping one bit commitment for a commitment to 2 breaks its OR proof.
+        let (c_pay, r_pay) = pay_commitment(1_000, 5);
+        let mut link = prove_pay_link(&statement, &witness, c_pay, r_pay).unwrap();
+        link.bits[3].p = PedersenGens::default().commit(Scalar::from(2u64), Scalar::from(9u64)).compress();
+        assert!(!verify_pay_link(&statement, c_pay, &link));
+        assert!(PayLink::from_bytes(&link.to_bytes()[1..]).is_none());
+    }
+
+    #[test]
+    fn groth16_round_trip_binds_context() {
+        let pk = setup().unwrap();
+        let witness = witness(42);
+        let statement = witness.statement(Fr::from(7u64), &[0x22u8; 32]);
+        let proof = prove(&pk, &statement, &witness).unwrap();
+        assert!(verify(&pk.vk, &statement, &proof));
+
+        let proof: Proof<Bn254> = from_bytes(&to_bytes(&proof)).unwrap();
+        assert!(verify(&pk.vk, &statement, &proof));
+
+        let other_order = NoteLinkStatement { context: context_scalar(&[0x23u8; 32]), ..statement };
+        assert!(!verify(&pk.vk, &other_order, &proof));
+    }
+}
@@ -0,0 +1,166 @@
+//! Okamoto proof of knowledge of a Pedersen commitment opening.
+//!
+//! Proves knowledge of (v, r) such that
+//!   C = v * B + r * B_blinding
+//! without revealing either. Unlike the range proofs in `zk::pedersen` it says
+//! nothing about the size of v, so it is meant for commitments whose range is
+//! proven elsewhere (or does not matter), e.g. the scalar commitments used for
+//! C_qty / C_pay.
+//!
+//! Prover picks k_v, k_r and sends R = k_v * B + k_r * B_blinding, then
+//!   s_v = k_v + c * v,   s_r = k_r + c * r
+//! Verifier checks s_v * B + s_r * B_blinding == R + c * C.
+//!
+//! Transcript order (MUST match between prove and verify):
+//!   context_hash -> C -> R -> challenge
+
+use bulletproofs::PedersenGens;
+use curve25519_dalek_ng::{
+    ristretto::CompressedRistretto,
+    scalar::Scalar,
+};
+use merlin::Transcript;
+use tracing::instrument;
+use rand::rngs::OsRng;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct OpeningProof {
+    /// Compressed Ristretto point R = k_v * B + k_r * B_blinding
+    pub r_announcement: [u8; 32],
+    /// Scalar s_v = k_v + c * v
+    pub s_value: [u8; 32],
+    /// Scalar s_r = k_r + c * r
+    pub s_blinding: [u8; 32],
+}
+
+fn transcript(commitment: CompressedRistretto, context_hash: &[u8]) -> Transcript {
+    let mut transcript = Transcript::new(b"OpeningProof-v1");
+    transcript.append_message(b"context_hash", context_hash);
+    transcript.append_message(b"C", commitment.as_bytes());
+    transcript
+}
+
+fn challenge_scalar(transcript: &mut Transcript) -> Scalar {
+    let mut c_bytes = [0u8; 64];
+    transcript.challenge_bytes(b"challenge", &mut c_bytes);
+    Scalar::from_bytes_mod_order_wide(&c_bytes)
+}
+
+/// Generate a proof of knowledge of the opening `(value, blinding)` of `commitment`.
+///
+/// Returns `Err` if the witness does not open `commitment`.
+#[instrument(level = "debug", skip_all)]
+pub fn prove_opening(
+    commitment: CompressedRistretto,
+    value: Scalar,
+    blinding: Scalar,
+    context_hash: &[u8],
+) -> Result<OpeningProof, String> {
+    let pc_gens = PedersenGens::default();
+    if pc_gens.commit(value, blinding).compress() != commitment {
+        return Err("value and blinding do not open the commitment".to_string());
+    }
+
+    let mut transcript = transcript(commitment, context_hash);
+
+    let k_v = Scalar::random(&mut OsRng);
+    let k_r = Scalar::random(&mut OsRng);
+    let r_compressed = pc_gens.commit(k_v, k_r).compress();
+
+    transcript.append_message(b"R", r_compressed.as_bytes());
+    let c = challenge_scalar(&mut transcript);
+
+    Ok(OpeningProof {
+        r_announcement: r_compressed.to_bytes(),
+        s_value: (k_v + c * value).to_bytes(),
+        s_blinding: (k_r + c * blinding).to_bytes(),
+    })
+}
+
+/// Verify a proof of knowledge of a commitment opening.
+///
+/// Returns `true` iff s_v * B + s_r * B_blinding == R + c * C.
+#[instrument(level = "debug", skip_all)]
+pub fn verify_opening(
+    commitment: CompressedRistretto,
+    proof: &OpeningProof,
+    context_hash: &[u8],
+) -> bool {
+    let pc_gens = PedersenGens::default();
+
+    let mut transcript = transcript(commitment, context_hash);
+    transcript.append_message(b"R", &proof.r_announcement);
+    let c = challenge_scalar(&mut transcript);
+
+    let r_point = match CompressedRistretto(proof.r_announcement).decompress() {
+        Some(point) => point,
+        None => return false,
+    };
+    let s_value = match Scalar::from_canonical_bytes(proof.s_value) {
+        Some(scalar) => scalar,
+        None => return false,
+    };
+    let s_blinding = match Scalar::from_canonical_bytes(proof.s_blinding) {
+        Some(scalar) => scalar,
+        None => return false,
+    };
+    let c_point = match commitment.decompress() {
+        Some(point) => point,
+        None => return false,
+    };
+
+    let lhs = pc_gens.commit(s_value, s_blinding);
+    let rhs = r_point + c * c_point;
+    lhs.compress() == rhs.compress()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commitment(value: u64, blinding_byte: u8) -> (CompressedRistretto, Scalar, Scalar) {
+        let pc_gens = PedersenGens::default();
+        let value = Scalar::from(value);
+        let blinding = Scalar::from_bytes_mod_order([blinding_byte; 32]);
+        (pc_gens.commit(value, blinding).compress(), value, blinding)
+    }
+
+    #[test]
+    fn valid_opening_proof_verifies() {
+        let context_hash = [0x11u8; 32];
+        let (c, v, r) = commitment(9, 0x22);
+
+        let proof = prove_opening(c, v, r, &context_hash).expect("proof generation should succeed");
+
+        assert!(verify_opening(c, &proof, &context_hash));
+    }
+
+    #[test]
+    fn wrong_witness_is_rejected() {
+        let (c, v, _) = commitment(9, 0x33);
+        let wrong_blinding = Scalar::from_bytes_mod_order([0x44u8; 32]);
+
+        assert!(prove_opening(c, v, wrong_blinding, &[0u8; 32]).is_err());
+    }
+
+    #[test]
+    fn other_commitment_fails() {
+        let context_hash = [0x55u8; 32];
+        let (c, v, r) = commitment(9, 0x66);
+        let (c_other, _, _) = commitment(9, 0x77);
+
+        let proof = prove_opening(c, v, r, &context_hash).expect("proof generation should succeed");
+
+        assert!(!verify_opening(c_other, &proof, &context_hash));
+    }
+
+    #[test]
+    fn wrong_context_hash_fails() {
+        let (c, v, r) = commitment(9, 0x88);
+
+        let proof = prove_opening(c, v, r, &[0x99u8; 32]).expect("proof generation should succeed");
+
+        assert!(!verify_opening(c, &proof, &[0xaau8; 32]));
+    }
+}
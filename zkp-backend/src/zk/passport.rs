@@ -0,0 +1,291 @@
+//! Battery passport threshold proofs.
+//!
+//! A second-life module's telemetry is committed attribute by attribute,
+//!   C_attr = v * B + r * B_blinding
+//! and a seller proves each buyer threshold without opening C_attr:
+//!   state of health   v >= k   (basis points, 8000 = 80 %)
+//!   usable capacity   v >= k   (Wh)
+//!   cycle count       v <= k
+//! by range-proving the shifted commitment
+//!   at least:  D = C_attr - k * B  opens to v - k in [0, 2^32)
+//!   at most:   D = k * B - C_attr  opens to k - v in [0, 2^32)
+//! An "at most" proof also range-proves C_attr itself, otherwise a "negative"
+//! v (a scalar near the group order) would pass.
+//!
+//! Transcript order (MUST match between prove and verify):
+//!   context_hash -> product_id -> batch -> attribute -> threshold -> C_attr
+//!   -> shifted relation -> range proof(s)
+//! so a proof made for one product or batch never verifies for another.
+//!
+//! `BatteryPassport` is the `credentialSubject.listing.batteryPassport`
+//! section that carries the commitments and proofs in a VRC.
+
+use bulletproofs::PedersenGens;
+use curve25519_dalek_ng::ristretto::CompressedRistretto;
+use curve25519_dalek_ng::scalar::Scalar;
+use merlin::Transcript;
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use utoipa::ToSchema;
+
+use super::comparison::{prove_non_negative_with_transcript, verify_non_negative_with_transcript};
+use super::linear::LinearRelation;
+
+pub const PASSPORT_SCHEMA_VERSION: &str = "1.0";
+/// Bits of every shifted value and attribute range proof.
+pub const THRESHOLD_BITS: usize = 32;
+/// 100 % state of health in basis points.
+pub const MAX_STATE_OF_HEALTH_BPS: u64 = 10_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum BatteryAttribute {
+    /// Basis points of nominal capacity, proven as a minimum.
+    StateOfHealth,
+    /// Usable capacity in Wh, proven as a minimum.
+    Capacity,
+    /// Full equivalent cycles, proven as a maximum.
+    CycleCount,
+}
+
+impl BatteryAttribute {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::StateOfHealth => "stateOfHealth",
+            Self::Capacity => "capacity",
+            Self::CycleCount => "cycleCount",
+        }
+    }
+
+    /// Whether the threshold is an upper bound.
+    pub fn is_maximum(self) -> bool {
+        self == Self::CycleCount
+    }
+
+    /// Rejects values no battery can have, before they reach a proof.
+    pub fn check_value(self, value: u64, field_name: &str) -> Result<(), String> {
+        if self == Self::StateOfHealth && value > MAX_STATE_OF_HEALTH_BPS {
+            return Err(format!("{field_name} must be at most {MAX_STATE_OF_HEALTH_BPS} basis points"));
+        }
+        if value >> THRESHOLD_BITS != 0 {
+            return Err(format!("{field_name} does not fit in {THRESHOLD_BITS} bits"));
+        }
+        Ok(())
+    }
+}
+
+/// Product/batch binding shared by every proof of one passport.
+#[derive(Clone, Copy, Debug)]
+pub struct PassportContext<'a> {
+    pub context_hash: &'a [u8],
+    pub product_id: &'a str,
+    pub batch: &'a str,
+}
+
+/// Range proof of the shifted commitment, plus one of the attribute itself
+/// for upper bounds.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ThresholdProof {
+    shifted: Vec<u8>,
+    value: Option<Vec<u8>>,
+}
+
+impl ThresholdProof {
+    /// u16 length of the shifted proof, the shifted proof, then the attribute proof.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = (self.shifted.len() as u16).to_be_bytes().to_vec();
+        bytes.extend_from_slice(&self.shifted);
+        if let Some(value) = &self.value {
+            bytes.extend_from_slice(value);
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let len = u16::from_be_bytes(bytes.get(..2)?.try_into().ok()?) as usize;
+        let shifted = bytes.get(2..2 + len)?.to_vec();
+        let rest = &bytes[2 + len..];
+        Some(Self { shifted, value: (!rest.is_empty()).then(|| rest.to_vec()) })
+    }
+}
+
+fn transcript(ctx: &PassportContext, attribute: BatteryAttribute, threshold: u64, commitment: &CompressedRistretto) -> Transcript {
+    let mut transcript = Transcript::new(b"BatteryPassportProof-v1");
+    transcript.append_message(b"context_hash", ctx.context_hash);
+    transcript.append_message(b"product_id", ctx.product_id.as_bytes());
+    transcript.append_message(b"batch", ctx.batch.as_bytes());
+    transcript.append_message(b"attribute", attribute.as_str().as_bytes());
+    transcript.append_u64(b"threshold", threshold);
+    transcript.append_message(b"C_attr", commitment.as_bytes());
+    transcript
+}
+
+/// D = C_attr - k * B, or k * B - C_attr for upper bounds.
+fn shifted_relation(attribute: BatteryAttribute, threshold: u64, commitment: CompressedRistretto) -> LinearRelation {
+    if attribute.is_maximum() {
+        LinearRelation::new().term(-Scalar::one(), commitment).constant(-Scalar::from(threshold))
+    } else {
+        LinearRelation::new().term(Scalar::one(), commitment).constant(Scalar::from(threshold))
+    }
+}
+
+pub fn commit_attribute(value: u64, blinding: Scalar) -> CompressedRistretto {
+    PedersenGens::default().commit(Scalar::from(value), blinding).compress()
+}
+
+/// Prove that the committed `value` meets `threshold` for `attribute`.
+#[instrument(level = "debug", skip_all, fields(attribute = attribute.as_str()))]
+pub fn prove_threshold(
+    ctx: &PassportContext,
+    attribute: BatteryAttribute,
+    threshold: u64,
+    value: u64,
+    blinding: Scalar,
+) -> Result<(CompressedRistretto, ThresholdProof), String> {
+    attribute.check_value(value, "value")?;
+    attribute.check_value(threshold, "threshold")?;
+    let margin = if attribute.is_maximum() { threshold.checked_sub(value) } else { value.checked_sub(threshold) }
+        .ok_or_else(|| format!("{} does not meet the threshold", attribute.as_str()))?;
+
+    let commitment = commit_attribute(value, blinding);
+    let relation = shifted_relation(attribute, threshold, commitment);
+    let mut transcript = transcript(ctx, attribute, threshold, &commitment);
+    relation.append_to_transcript(&mut transcript);
+    let shifted = prove_non_negative_with_transcript(&mut transcript, &relation, &[blinding], margin, THRESHOLD_BITS)?;
+    let value = if attribute.is_maximum() {
+        let own = LinearRelation::new().term(Scalar::one(), commitment);
+        Some(prove_non_negative_with_transcript(&mut transcript, &own, &[blinding], value, THRESHOLD_BITS)?)
+    } else {
+        None
+    };
+    Ok((commitment, ThresholdProof { shifted, value }))
+}
+
+#[instrument(level = "debug", skip_all, fields(attribute = attribute.as_str()))]
+pub fn verify_threshold(
+    ctx: &PassportContext,
+    attribute: BatteryAttribute,
+    threshold: u64,
+    commitment: CompressedRistretto,
+    proof: &ThresholdProof,
+) -> bool {
+    if attribute.check_value(threshold, "threshold").is_err() || attribute.is_maximum() != proof.value.is_some() {
+        return false;
+    }
+    let relation = shifted_relation(attribute, threshold, commitment);
+    let mut transcript = transcript(ctx, attribute, threshold, &commitment);
+    relation.append_to_transcript(&mut transcript);
+    if !verify_non_negative_with_transcript(&mut transcript, &relation, &proof.shifted, THRESHOLD_BITS) {
+        return false;
+    }
+    match &proof.value {
+        Some(value) => {
+            let own = LinearRelation::new().term(Scalar::one(), commitment);
+            verify_non_negative_with_transcript(&mut transcript, &own, value, THRESHOLD_BITS)
+        }
+        None => true,
+    }
+}
+
+/// `credentialSubject.listing.batteryPassport`: commitments to the module's
+/// telemetry and the threshold proofs a seller publishes with the listing.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BatteryPassport {
+    pub schema_version: String,
+    pub product_id: String,
+    pub batch: String,
+    pub context_hash: String,
+    pub commitments: PassportCommitments,
+    #[serde(default)]
+    pub threshold_proofs: Vec<ThresholdProofData>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PassportCommitments {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_of_health_commitment: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capacity_commitment: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cycle_count_commitment: Option<String>,
+}
+
+impl PassportCommitments {
+    pub fn get(&self, attribute: BatteryAttribute) -> Option<&String> {
+        match attribute {
+            BatteryAttribute::StateOfHealth => self.state_of_health_commitment.as_ref(),
+            BatteryAttribute::Capacity => self.capacity_commitment.as_ref(),
+            BatteryAttribute::CycleCount => self.cycle_count_commitment.as_ref(),
+        }
+    }
+
+    pub fn set(&mut self, attribute: BatteryAttribute, commitment_hex: String) {
+        let slot = match attribute {
+            BatteryAttribute::StateOfHealth => &mut self.state_of_health_commitment,
+            BatteryAttribute::Capacity => &mut self.capacity_commitment,
+            BatteryAttribute::CycleCount => &mut self.cycle_count_commitment,
+        };
+        *slot = Some(commitment_hex);
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ThresholdProofData {
+    pub attribute: BatteryAttribute,
+    /// Decimal threshold in the attribute's unit.
+    pub threshold: String,
+    pub proof_hex: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTEXT: PassportContext = PassportContext { context_hash: &[0x11; 32], product_id: "42", batch: "B-2026-07" };
+
+    #[test]
+    fn thresholds_verify_in_both_directions() {
+        let blinding = Scalar::from(5u64);
+        let (c_soh, proof) = prove_threshold(&CONTEXT, BatteryAttribute::StateOfHealth, 8000, 8650, blinding).unwrap();
+        let proof = ThresholdProof::from_bytes(&proof.to_bytes()).unwrap();
+        assert!(verify_threshold(&CONTEXT, BatteryAttribute::StateOfHealth, 8000, c_soh, &proof));
+        assert!(!verify_threshold(&CONTEXT, BatteryAttribute::StateOfHealth, 8700, c_soh, &proof));
+
+        let (c_cycles, proof) = prove_threshold(&CONTEXT, BatteryAttribute::CycleCount, 1500, 1200, blinding).unwrap();
+        let proof = ThresholdProof::from_bytes(&proof.to_bytes()).unwrap();
+        assert!(verify_threshold(&CONTEXT, BatteryAttribute::CycleCount, 1500, c_cycles, &proof));
+        assert!(!verify_threshold(&CONTEXT, BatteryAttribute::CycleCount, 1100, c_cycles, &proof));
+        assert!(!verify_threshold(&CONTEXT, BatteryAttribute::Capacity, 1500, c_cycles, &proof));
+    }
+
+    #[test]
+    fn proofs_are_bound_to_product_and_batch() {
+        let (c, proof) = prove_threshold(&CONTEXT, BatteryAttribute::Capacity, 60_000, 64_000, Scalar::from(9u64)).unwrap();
+        let other_batch = PassportContext { batch: "B-2026-08", ..CONTEXT };
+        let other_product = PassportContext { product_id: "43", ..CONTEXT };
+        assert!(verify_threshold(&CONTEXT, BatteryAttribute::Capacity, 60_000, c, &proof));
+        assert!(!verify_threshold(&other_batch, BatteryAttribute::Capacity, 60_000, c, &proof));
+        assert!(!verify_threshold(&other_product, BatteryAttribute::Capacity, 60_000, c, &proof));
+    }
+
+    #[test]
+    fn unmet_or_impossible_values_are_rejected() {
+        let blinding = Scalar::from(3u64);
+        assert!(prove_threshold(&CONTEXT, BatteryAttribute::StateOfHealth, 8000, 7999, blinding).is_err());
+        assert!(prove_threshold(&CONTEXT, BatteryAttribute::CycleCount, 1500, 1501, blinding).is_err());
+        assert!(prove_threshold(&CONTEXT, BatteryAttribute::StateOfHealth, 8000, 10_001, blinding).is_err());
+
+        // A cycle count "below zero" would satisfy the shifted relation alone.
+        let negative = -Scalar::from(100u64);
+        let commitment = PedersenGens::default().commit(negative, blinding).compress();
+        let relation = shifted_relation(BatteryAttribute::CycleCount, 1500, commitment);
+        let mut transcript = transcript(&CONTEXT, BatteryAttribute::CycleCount, 1500, &commitment);
+        relation.append_to_transcript(&mut transcript);
+        let shifted = prove_non_negative_with_transcript(&mut transcript, &relation, &[blinding], 1600, THRESHOLD_BITS).unwrap();
+        let forged = ThresholdProof { shifted, value: Some(vec![0u8; 32]) };
+        assert!(!verify_threshold(&CONTEXT, BatteryAttribute::CycleCount, 1500, commitment, &forged));
+    }
+}
@@ -6,6 +6,9 @@ use curve25519_dalek_ng::scalar::Scalar;
 use merlin::Transcript;
 use rand::rngs::OsRng;
 use rand::RngCore;
+use tracing::{debug, instrument};
+
+use super::validity::{append_window, ValidityWindow};
 
 pub fn commit_scalar_with_blinding(
     value: Scalar,
@@ -53,13 +56,11 @@ pub fn prove_equal_42() {
         verifier.verify(&proof, &pc_gens, &bp_gens).is_ok()
     };
 
-    println!("✅ Proof verified? {}", verified);
+    debug!(verified, "prove_equal_42 demo");
 }
 
 /// Proves knowledge of a value such that Pedersen(value, r) == commitment and value in [0, 2^64)
 pub fn prove_value_commitment(value: u64) -> (CompressedRistretto, Vec<u8>, bool) {
-    println!("▶️ Running: Bulletproofs range proof for value using Pedersen commitment");
-
     let mut rng = OsRng;
 
     // Generate random blinding factor
@@ -138,12 +139,19 @@ pub fn prove_value_commitment_with_binding(
     blinding: Scalar,
     binding_tag: Option<&[u8]>,
 ) -> (CompressedRistretto, Vec<u8>, bool) {
-    if binding_tag.is_some() {
-        println!("▶️ Running: Bulletproofs range proof with binding tag");
-    } else {
-        println!("▶️ Running: Bulletproofs range proof for value with provided blinding factor");
-    }
+    prove_value_commitment_within(value, blinding, binding_tag, None)
+}
 
+/// Proves knowledge of a value with a binding tag and an optional validity window
+/// The window is appended after the binding tag, so it cannot be changed once proven;
+/// without one the transcript is the same as `prove_value_commitment_with_binding`
+#[instrument(name = "prove_value_range", level = "debug", skip_all, fields(binding_tag = binding_tag.is_some(), window = window.is_some()))]
+pub fn prove_value_commitment_within(
+    value: u64,
+    blinding: Scalar,
+    binding_tag: Option<&[u8]>,
+    window: Option<&ValidityWindow>,
+) -> (CompressedRistretto, Vec<u8>, bool) {
     let pc_gens = PedersenGens::default();
     let bp_gens = BulletproofGens::new(64, 1); // 64-bit range, 1 party
 
@@ -153,8 +161,8 @@ pub fn prove_value_commitment_with_binding(
     // ✅ Add binding tag to transcript if provided
     if let Some(binding) = binding_tag {
         transcript.append_message(b"bind", binding);
-        println!("   📎 Binding tag added to transcript: {} bytes", binding.len());
     }
+    append_window(&mut transcript, window);
     
     let (proof, commitment) = RangeProof::prove_single(
         &bp_gens,
@@ -174,6 +182,7 @@ pub fn prove_value_commitment_with_binding(
     if let Some(binding) = binding_tag {
         transcript.append_message(b"bind", binding);
     }
+    append_window(&mut transcript, window);
     
     let verified = RangeProof::verify_single(
         &proof,
@@ -184,7 +193,7 @@ pub fn prove_value_commitment_with_binding(
         64,
     ).is_ok();
 
-    println!("✅ ZK Range proof of value commitment verified? {}", verified);
+    debug!(proof_len = proof_bytes.len(), verified, "range proof generated");
 
     (commitment, proof_bytes, verified)
 }
@@ -203,6 +212,18 @@ pub fn verify_value_commitment_with_binding(
     commitment: CompressedRistretto,
     proof_bytes: Vec<u8>,
     binding_tag: Option<&[u8]>,
+) -> bool {
+    verify_value_commitment_within(commitment, proof_bytes, binding_tag, None)
+}
+
+/// Verifies a proof made by `prove_value_commitment_within` against the same window
+/// Only the transcript is checked; the caller compares the window with its reference point
+#[instrument(name = "verify_value_range", level = "debug", skip_all, fields(binding_tag = binding_tag.is_some(), window = window.is_some(), proof_len = proof_bytes.len()))]
+pub fn verify_value_commitment_within(
+    commitment: CompressedRistretto,
+    proof_bytes: Vec<u8>,
+    binding_tag: Option<&[u8]>,
+    window: Option<&ValidityWindow>,
 ) -> bool {
     let pc_gens = PedersenGens::default();
     let bp_gens = BulletproofGens::new(64, 1);
@@ -212,6 +233,7 @@ pub fn verify_value_commitment_with_binding(
     if let Some(binding) = binding_tag {
         transcript.append_message(b"bind", binding);
     }
+    append_window(&mut transcript, window);
     
     let proof = match RangeProof::from_bytes(&proof_bytes) {
         Ok(p) => p,
@@ -243,7 +265,7 @@ mod value_commitment_tests {
     fn test_invalid_value_commitment_proof() {
         let value1 = 123456u64;
         let value2 = 654321u64;
-        let (commitment1, proof_bytes, _) = prove_value_commitment(value1);
+        let (_commitment1, proof_bytes, _) = prove_value_commitment(value1);
         // Try to verify proof_bytes against a different commitment
         let (commitment2, _, _) = prove_value_commitment(value2);
         assert!(!verify_value_commitment(commitment2, proof_bytes), "Proof for value1 should not verify for commitment2");
@@ -314,6 +336,23 @@ mod value_commitment_tests {
         assert!(verified_no_tag, "Proof generated without binding tag should verify without binding tag");
     }
 
+    #[test]
+    fn test_validity_window_binding() {
+        let blinding = Scalar::from_bytes_mod_order([0x42u8; 32]);
+        let binding_tag = b"test-binding-tag-32-bytes-long!!";
+        let window = ValidityWindow { not_before: Some(100), not_after: Some(200), ..Default::default() };
+
+        // Proof with a window verifies only against the same window
+        let (commitment, proof_bytes, verified) = prove_value_commitment_within(7, blinding, Some(binding_tag), Some(&window));
+        assert!(verified, "Proof with validity window should verify");
+        assert!(verify_value_commitment_within(commitment, proof_bytes.clone(), Some(binding_tag), Some(&window)));
+
+        // Dropping or widening the window must fail
+        assert!(!verify_value_commitment_with_binding(commitment, proof_bytes.clone(), Some(binding_tag)));
+        let widened = ValidityWindow { not_after: Some(300), ..window };
+        assert!(!verify_value_commitment_within(commitment, proof_bytes, Some(binding_tag), Some(&widened)));
+    }
+
     #[test]
     fn test_commit_scalar_with_blinding_supports_values_above_u64() {
         let value_bytes = [
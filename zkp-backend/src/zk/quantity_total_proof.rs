@@ -10,56 +10,89 @@
 //!   D = C_total - unit_price * C_quantity = delta_r * B_blinding
 //! where:
 //!   delta_r = r_total - unit_price * r_quantity
+//!
+//! Transcript order (MUST match between prove and verify):
+//!   context_hash -> unit_price -> C_quantity -> C_total -> [validity window]
+//!   -> R -> challenge
+//!
+//! The Schnorr step is delegated to `zk::linear`; the validity window is
+//! only appended when one is given (`zk::validity`).
 
-use bulletproofs::PedersenGens;
 use curve25519_dalek_ng::{
     ristretto::CompressedRistretto,
     scalar::Scalar,
 };
 use merlin::Transcript;
-use rand::rngs::OsRng;
-use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use super::linear::{
+    prove_linear_with_transcript, verify_linear_with_transcript, LinearProof, LinearRelation,
+};
+use super::validity::{append_window, ValidityWindow};
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct QuantityTotalProof {
-    pub r_announcement: [u8; 32],
-    pub s_response: [u8; 32],
+pub type QuantityTotalProof = LinearProof;
+
+/// D = C_total - unit_price * C_quantity as a linear relation.
+fn relation(
+    c_quantity: CompressedRistretto,
+    c_total: CompressedRistretto,
+    unit_price: Scalar,
+) -> LinearRelation {
+    LinearRelation::new()
+        .term(-unit_price, c_quantity)
+        .term(Scalar::one(), c_total)
 }
 
-pub fn prove_quantity_total(
+fn transcript(
     c_quantity: CompressedRistretto,
     c_total: CompressedRistretto,
     unit_price: Scalar,
-    r_quantity: Scalar,
-    r_total: Scalar,
     context_hash: &[u8],
-) -> Result<QuantityTotalProof, String> {
-    let pc_gens = PedersenGens::default();
-    let delta_r = r_total - (unit_price * r_quantity);
-
+    window: Option<&ValidityWindow>,
+) -> Transcript {
     let mut transcript = Transcript::new(b"QuantityTotalProof-v1");
     transcript.append_message(b"context_hash", context_hash);
     transcript.append_message(b"unit_price", unit_price.as_bytes());
     transcript.append_message(b"C_quantity", c_quantity.as_bytes());
     transcript.append_message(b"C_total", c_total.as_bytes());
+    append_window(&mut transcript, window);
+    transcript
+}
 
-    let k = Scalar::random(&mut OsRng);
-    let r_point = &k * &pc_gens.B_blinding;
-    let r_compressed = r_point.compress();
-
-    transcript.append_message(b"R", r_compressed.as_bytes());
-
-    let mut c_bytes = [0u8; 64];
-    transcript.challenge_bytes(b"challenge", &mut c_bytes);
-    let c = Scalar::from_bytes_mod_order_wide(&c_bytes);
-    let s = k + c * delta_r;
+#[instrument(level = "debug", skip_all)]
+pub fn prove_quantity_total(
+    c_quantity: CompressedRistretto,
+    c_total: CompressedRistretto,
+    unit_price: Scalar,
+    r_quantity: Scalar,
+    r_total: Scalar,
+    context_hash: &[u8],
+) -> Result<QuantityTotalProof, String> {
+    prove_quantity_total_within(c_quantity, c_total, unit_price, r_quantity, r_total, context_hash, None)
+}
 
-    Ok(QuantityTotalProof {
-        r_announcement: r_compressed.to_bytes(),
-        s_response: s.to_bytes(),
-    })
+/// `prove_quantity_total` with an optional validity window bound into the transcript.
+#[instrument(level = "debug", skip_all, fields(window = window.is_some()))]
+pub fn prove_quantity_total_within(
+    c_quantity: CompressedRistretto,
+    c_total: CompressedRistretto,
+    unit_price: Scalar,
+    r_quantity: Scalar,
+    r_total: Scalar,
+    context_hash: &[u8],
+    window: Option<&ValidityWindow>,
+) -> Result<QuantityTotalProof, String> {
+    if let Some(window) = window {
+        window.check()?;
+    }
+    prove_linear_with_transcript(
+        &mut transcript(c_quantity, c_total, unit_price, context_hash, window),
+        &relation(c_quantity, c_total, unit_price),
+        &[r_quantity, r_total],
+    )
 }
 
+#[instrument(level = "debug", skip_all)]
 pub fn verify_quantity_total(
     c_quantity: CompressedRistretto,
     c_total: CompressedRistretto,
@@ -67,46 +100,31 @@ pub fn verify_quantity_total(
     proof: &QuantityTotalProof,
     context_hash: &[u8],
 ) -> bool {
-    let pc_gens = PedersenGens::default();
+    verify_quantity_total_within(c_quantity, c_total, unit_price, proof, context_hash, None)
+}
 
-    let mut transcript = Transcript::new(b"QuantityTotalProof-v1");
-    transcript.append_message(b"context_hash", context_hash);
-    transcript.append_message(b"unit_price", unit_price.as_bytes());
-    transcript.append_message(b"C_quantity", c_quantity.as_bytes());
-    transcript.append_message(b"C_total", c_total.as_bytes());
-    transcript.append_message(b"R", &proof.r_announcement);
-
-    let mut c_bytes = [0u8; 64];
-    transcript.challenge_bytes(b"challenge", &mut c_bytes);
-    let c = Scalar::from_bytes_mod_order_wide(&c_bytes);
-
-    let r_point = match CompressedRistretto(proof.r_announcement).decompress() {
-        Some(point) => point,
-        None => return false,
-    };
-    let s = match Scalar::from_canonical_bytes(proof.s_response) {
-        Some(scalar) => scalar,
-        None => return false,
-    };
-
-    let cq = match c_quantity.decompress() {
-        Some(point) => point,
-        None => return false,
-    };
-    let ct = match c_total.decompress() {
-        Some(point) => point,
-        None => return false,
-    };
-
-    let d = ct - (&unit_price * &cq);
-    let lhs = &s * &pc_gens.B_blinding;
-    let rhs = r_point + (&c * d);
-    lhs.compress() == rhs.compress()
+/// Transcript check for `prove_quantity_total_within`; the window itself is
+/// compared with a reference point by the caller.
+#[instrument(level = "debug", skip_all, fields(window = window.is_some()))]
+pub fn verify_quantity_total_within(
+    c_quantity: CompressedRistretto,
+    c_total: CompressedRistretto,
+    unit_price: Scalar,
+    proof: &QuantityTotalProof,
+    context_hash: &[u8],
+    window: Option<&ValidityWindow>,
+) -> bool {
+    verify_linear_with_transcript(
+        &mut transcript(c_quantity, c_total, unit_price, context_hash, window),
+        &relation(c_quantity, c_total, unit_price),
+        proof,
+    )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use bulletproofs::PedersenGens;
 
     fn commitment(value: u64, blinding_byte: u8) -> (CompressedRistretto, Scalar) {
         let pc_gens = PedersenGens::default();
@@ -220,4 +238,40 @@ mod tests {
             &verify_context,
         ));
     }
+
+    #[test]
+    fn proof_from_hand_written_transcript_verifies() {
+        use merlin::Transcript;
+        use rand::rngs::OsRng;
+
+        let pc_gens = PedersenGens::default();
+        let unit_price = Scalar::from(17u64);
+        let context_hash = [0x21u8; 32];
+        let (c_quantity, r_quantity) = commitment(9, 0x32);
+        let (c_total, r_total) = commitment(153, 0x43);
+
+        let mut transcript = Transcript::new(b"QuantityTotalProof-v1");
+        transcript.append_message(b"context_hash", &context_hash);
+        transcript.append_message(b"unit_price", unit_price.as_bytes());
+        transcript.append_message(b"C_quantity", c_quantity.as_bytes());
+        transcript.append_message(b"C_total", c_total.as_bytes());
+        let k = Scalar::random(&mut OsRng);
+        let r_compressed = (k * pc_gens.B_blinding).compress();
+        transcript.append_message(b"R", r_compressed.as_bytes());
+        let mut c_bytes = [0u8; 64];
+        transcript.challenge_bytes(b"challenge", &mut c_bytes);
+        let c = Scalar::from_bytes_mod_order_wide(&c_bytes);
+        let proof = QuantityTotalProof {
+            r_announcement: r_compressed.to_bytes(),
+            s_response: (k + c * (r_total - unit_price * r_quantity)).to_bytes(),
+        };
+
+        assert!(verify_quantity_total(
+            c_quantity,
+            c_total,
+            unit_price,
+            &proof,
+            &context_hash,
+        ));
+    }
 }
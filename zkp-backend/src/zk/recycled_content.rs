@@ -0,0 +1,232 @@
+//! Recycled-content ratio proof.
+//!
+//! Proves that committed recycled mass is at least `percent` % of committed
+//! total mass for one material of a batch:
+//!   C_recycled = m_recycled * B + r_recycled * B_blinding
+//!   C_total    = m_total    * B + r_total    * B_blinding
+//!   100 * m_recycled >= percent * m_total
+//! by range-proving the derived commitment
+//!   D = 100 * C_recycled - percent * C_total
+//!     = (100 * m_recycled - percent * m_total) * B + (100 * r_recycled - percent * r_total) * B_blinding
+//! i.e. showing the difference lies in [0, 2^64). A second range proof puts
+//! m_total in [0, 2^64) too; otherwise a "negative" total (a scalar near the
+//! group order) would make D small and positive for any recycled mass. The
+//! two bounds pin 100 * m_recycled = D + percent * m_total below 2^71, so
+//! m_recycled needs no proof of its own.
+//!
+//! Transcript order (MUST match between prove and verify):
+//!   context_hash -> batch -> material -> percent -> C_recycled -> C_total
+//!   -> relation -> share range proof -> total range proof
+
+use curve25519_dalek_ng::{
+    ristretto::CompressedRistretto,
+    scalar::Scalar,
+};
+use merlin::Transcript;
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use utoipa::ToSchema;
+
+use super::comparison::{prove_non_negative_with_transcript, verify_non_negative_with_transcript};
+use super::linear::LinearRelation;
+
+pub const RECYCLED_CONTENT_BITS: usize = 64;
+
+/// Materials with minimum recycled shares under the EU Battery Regulation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Material {
+    Cobalt,
+    Lithium,
+    Nickel,
+    Lead,
+}
+
+impl Material {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Cobalt => "cobalt",
+            Self::Lithium => "lithium",
+            Self::Nickel => "nickel",
+            Self::Lead => "lead",
+        }
+    }
+}
+
+/// Range proofs of D and of C_total.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecycledContentProof {
+    share: Vec<u8>,
+    total: Vec<u8>,
+}
+
+impl RecycledContentProof {
+    /// u16 length of the share proof, the share proof, then the total proof.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = (self.share.len() as u16).to_be_bytes().to_vec();
+        bytes.extend_from_slice(&self.share);
+        bytes.extend_from_slice(&self.total);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let len = u16::from_be_bytes(bytes.get(..2)?.try_into().ok()?) as usize;
+        let share = bytes.get(2..2 + len)?.to_vec();
+        let total = bytes[2 + len..].to_vec();
+        Some(Self { share, total })
+    }
+}
+
+/// Public statement shared by prover and verifier.
+#[derive(Clone, Copy, Debug)]
+pub struct RecycledContentStatement<'a> {
+    pub material: Material,
+    pub percent: u64,
+    pub c_recycled: CompressedRistretto,
+    pub c_total: CompressedRistretto,
+    pub batch: &'a str,
+    pub context_hash: &'a [u8],
+}
+
+impl RecycledContentStatement<'_> {
+    fn check(&self) -> Result<(), String> {
+        if self.percent > 100 {
+            return Err("percent must be between 0 and 100".to_string());
+        }
+        Ok(())
+    }
+
+    /// D = 100 * C_recycled - percent * C_total as a linear relation.
+    fn relation(&self) -> LinearRelation {
+        LinearRelation::new()
+            .term(Scalar::from(100u64), self.c_recycled)
+            .term(-Scalar::from(self.percent), self.c_total)
+    }
+
+    fn transcript(&self) -> Transcript {
+        let mut transcript = Transcript::new(b"RecycledContentProof-v1");
+        transcript.append_message(b"context_hash", self.context_hash);
+        transcript.append_message(b"batch", self.batch.as_bytes());
+        transcript.append_message(b"material", self.material.as_str().as_bytes());
+        transcript.append_u64(b"percent", self.percent);
+        transcript.append_message(b"C_recycled", self.c_recycled.as_bytes());
+        transcript.append_message(b"C_total", self.c_total.as_bytes());
+        transcript
+    }
+}
+
+/// Generate a proof that `recycled` is at least `percent` % of `total`.
+///
+/// # Returns
+/// The range proofs, or `Err` if the share is too low or the openings do not
+/// match the statement's commitments.
+#[instrument(level = "debug", skip_all, fields(material = statement.material.as_str()))]
+pub fn prove_recycled_content(
+    statement: &RecycledContentStatement,
+    recycled: u64,
+    total: u64,
+    r_recycled: Scalar,
+    r_total: Scalar,
+) -> Result<RecycledContentProof, String> {
+    statement.check()?;
+    let difference = (100 * u128::from(recycled))
+        .checked_sub(u128::from(statement.percent) * u128::from(total))
+        .ok_or_else(|| format!("recycled share is below {} %", statement.percent))?;
+    let difference = u64::try_from(difference).map_err(|_| "masses too large for a 64-bit range proof".to_string())?;
+
+    let relation = statement.relation();
+    let mut transcript = statement.transcript();
+    relation.append_to_transcript(&mut transcript);
+    let share =
+        prove_non_negative_with_transcript(&mut transcript, &relation, &[r_recycled, r_total], difference, RECYCLED_CONTENT_BITS)?;
+    let own = LinearRelation::new().term(Scalar::one(), statement.c_total);
+    let total = prove_non_negative_with_transcript(&mut transcript, &own, &[r_total], total, RECYCLED_CONTENT_BITS)?;
+    Ok(RecycledContentProof { share, total })
+}
+
+#[instrument(level = "debug", skip_all, fields(material = statement.material.as_str()))]
+pub fn verify_recycled_content(statement: &RecycledContentStatement, proof: &RecycledContentProof) -> bool {
+    if statement.check().is_err() {
+        return false;
+    }
+    let relation = statement.relation();
+    let mut transcript = statement.transcript();
+    relation.append_to_transcript(&mut transcript);
+    let own = LinearRelation::new().term(Scalar::one(), statement.c_total);
+    verify_non_negative_with_transcript(&mut transcript, &relation, &proof.share, RECYCLED_CONTENT_BITS)
+        && verify_non_negative_with_transcript(&mut transcript, &own, &proof.total, RECYCLED_CONTENT_BITS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bulletproofs::PedersenGens;
+
+    fn commitment(value: u64, blinding: u64) -> (CompressedRistretto, Scalar) {
+        let blinding = Scalar::from(blinding);
+        (PedersenGens::default().commit(Scalar::from(value), blinding).compress(), blinding)
+    }
+
+    fn statement(c_recycled: CompressedRistretto, c_total: CompressedRistretto, percent: u64) -> RecycledContentStatement<'static> {
+        RecycledContentStatement {
+            material: Material::Cobalt,
+            percent,
+            c_recycled,
+            c_total,
+            batch: "B-2026-07",
+            context_hash: &[0x11; 32],
+        }
+    }
+
+    #[test]
+    fn share_at_or_above_minimum_verifies() {
+        let (c_recycled, r_recycled) = commitment(1_600, 3);
+        let (c_total, r_total) = commitment(10_000, 5);
+        for percent in [16, 10] {
+            let statement = statement(c_recycled, c_total, percent);
+            let proof = prove_recycled_content(&statement, 1_600, 10_000, r_recycled, r_total).unwrap();
+            let decoded = RecycledContentProof::from_bytes(&proof.to_bytes()).unwrap();
+            assert!(verify_recycled_content(&statement, &decoded));
+        }
+
+        let proof = prove_recycled_content(&statement(c_recycled, c_total, 16), 1_600, 10_000, r_recycled, r_total).unwrap();
+        assert!(!verify_recycled_content(&statement(c_recycled, c_total, 17), &proof));
+        let nickel = RecycledContentStatement { material: Material::Nickel, ..statement(c_recycled, c_total, 16) };
+        assert!(!verify_recycled_content(&nickel, &proof));
+        let other_batch = RecycledContentStatement { batch: "B-2026-08", ..statement(c_recycled, c_total, 16) };
+        assert!(!verify_recycled_content(&other_batch, &proof));
+    }
+
+    #[test]
+    fn share_below_minimum_is_rejected() {
+        let (c_recycled, r_recycled) = commitment(599, 3);
+        let (c_total, r_total) = commitment(10_000, 5);
+        assert!(prove_recycled_content(&statement(c_recycled, c_total, 6), 599, 10_000, r_recycled, r_total).is_err());
+        assert!(prove_recycled_content(&statement(c_recycled, c_total, 101), 599, 10_000, r_recycled, r_total).is_err());
+    }
+
+    #[test]
+    fn wrong_opening_is_rejected() {
+        let (c_recycled, r_recycled) = commitment(1_600, 3);
+        let (c_total, r_total) = commitment(10_000, 5);
+        assert!(prove_recycled_content(&statement(c_recycled, c_total, 10), 1_700, 10_000, r_recycled, r_total).is_err());
+    }
+
+    #[test]
+    fn negative_total_mass_is_rejected() {
+        let (c_recycled, r_recycled) = commitment(0, 3);
+        // A total of -1_000 makes D = 16 * 1_000 for a batch with no recycled mass at all.
+        let r_total = Scalar::from(5u64);
+        let c_total = PedersenGens::default().commit(-Scalar::from(1_000u64), r_total).compress();
+        let statement = statement(c_recycled, c_total, 16);
+
+        let relation = statement.relation();
+        let mut transcript = statement.transcript();
+        relation.append_to_transcript(&mut transcript);
+        let share =
+            prove_non_negative_with_transcript(&mut transcript, &relation, &[r_recycled, r_total], 16_000, RECYCLED_CONTENT_BITS)
+                .unwrap();
+        let forged = RecycledContentProof { share, total: vec![0u8; 32] };
+        assert!(!verify_recycled_content(&statement, &forged));
+    }
+}
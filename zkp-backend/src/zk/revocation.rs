@@ -0,0 +1,278 @@
+//! Credential status accumulator: a sparse Merkle tree over VC CIDs.
+//!
+//! Every credential owns the leaf at key = SHA-256(cid) in a depth-256 tree.
+//! Active credentials are absent (the empty leaf), so "not revoked" is a
+//! non-membership witness against the published root:
+//!   leaf(active)    = 0^32
+//!   leaf(status)    = SHA-256(0x00 || key || status)      status: 1 revoked, 2 suspended
+//!   node(l, r)      = SHA-256(0x01 || l || r)
+//! Empty subtrees hash to precomputed defaults, so the tree only stores
+//! non-active credentials.
+//!
+//! A witness lists the siblings on the key's path from the leaf up, skipping
+//! those equal to the empty subtree; `bitmap` bit i (big-endian) marks whether
+//! the sibling at height i is present.
+
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::instrument;
+use utoipa::ToSchema;
+
+pub const DEPTH: usize = 256;
+
+pub type Hash = [u8; 32];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CredentialStatus {
+    Active,
+    Revoked,
+    Suspended,
+}
+
+impl CredentialStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Active => "active",
+            Self::Revoked => "revoked",
+            Self::Suspended => "suspended",
+        }
+    }
+
+    pub fn code(self) -> u8 {
+        match self {
+            Self::Active => 0,
+            Self::Revoked => 1,
+            Self::Suspended => 2,
+        }
+    }
+
+    pub fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0 => Some(Self::Active),
+            1 => Some(Self::Revoked),
+            2 => Some(Self::Suspended),
+            _ => None,
+        }
+    }
+}
+
+pub fn credential_key(cid: &str) -> Hash {
+    Sha256::digest(cid.as_bytes()).into()
+}
+
+pub fn leaf_hash(key: &Hash, status: CredentialStatus) -> Hash {
+    if status == CredentialStatus::Active {
+        return [0u8; 32];
+    }
+    let mut hasher = Sha256::new();
+    hasher.update([0x00]);
+    hasher.update(key);
+    hasher.update([status.code()]);
+    hasher.finalize().into()
+}
+
+fn node_hash(left: &Hash, right: &Hash) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update([0x01]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// EMPTY[h] is the root of an empty subtree of height h.
+static EMPTY: LazyLock<Vec<Hash>> = LazyLock::new(|| {
+    let mut empty = vec![[0u8; 32]];
+    for height in 0..DEPTH {
+        empty.push(node_hash(&empty[height], &empty[height]));
+    }
+    empty
+});
+
+/// Bit of `key` choosing the branch at `depth` (0 = root), MSB first.
+fn bit(key: &Hash, depth: usize) -> bool {
+    key[depth / 8] & (0x80 >> (depth % 8)) != 0
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Witness {
+    pub bitmap: [u8; 32],
+    /// Non-empty siblings, leaf level first.
+    pub siblings: Vec<Hash>,
+}
+
+impl Witness {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.bitmap.to_vec();
+        self.siblings.iter().for_each(|sibling| bytes.extend_from_slice(sibling));
+        bytes
+    }
+
+    /// `None` unless the sibling count matches the bitmap.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < 32 || !bytes.len().is_multiple_of(32) {
+            return None;
+        }
+        let bitmap: [u8; 32] = bytes[..32].try_into().ok()?;
+        let siblings: Vec<Hash> = bytes[32..].chunks_exact(32).map(|s| s.try_into().expect("32-byte chunk")).collect();
+        let present = bitmap.iter().map(|b| b.count_ones() as usize).sum::<usize>();
+        (present == siblings.len()).then_some(Self { bitmap, siblings })
+    }
+
+    fn has_sibling(&self, height: usize) -> bool {
+        bit(&self.bitmap, height)
+    }
+}
+
+/// Root over the non-active credentials.
+#[derive(Clone, Debug, Default)]
+pub struct SparseMerkleTree {
+    leaves: BTreeMap<Hash, CredentialStatus>,
+}
+
+impl SparseMerkleTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn status(&self, key: &Hash) -> CredentialStatus {
+        self.leaves.get(key).copied().unwrap_or(CredentialStatus::Active)
+    }
+
+    pub fn set(&mut self, key: Hash, status: CredentialStatus) {
+        if status == CredentialStatus::Active {
+            self.leaves.remove(&key);
+        } else {
+            self.leaves.insert(key, status);
+        }
+    }
+
+    /// Number of non-active credentials.
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    /// Root of the subtree at `depth` holding `leaves` (sorted by key).
+    fn subtree(leaves: &[(&Hash, &CredentialStatus)], depth: usize) -> Hash {
+        match leaves {
+            [] => EMPTY[DEPTH - depth],
+            [(key, status)] if depth == DEPTH => leaf_hash(key, **status),
+            _ => {
+                let split = leaves.partition_point(|(key, _)| !bit(key, depth));
+                node_hash(&Self::subtree(&leaves[..split], depth + 1), &Self::subtree(&leaves[split..], depth + 1))
+            }
+        }
+    }
+
+    #[instrument(level = "debug", skip_all, fields(leaves = self.leaves.len()))]
+    pub fn root(&self) -> Hash {
+        let leaves: Vec<_> = self.leaves.iter().collect();
+        Self::subtree(&leaves, 0)
+    }
+
+    /// Witness for the current status of `key`.
+    #[instrument(level = "debug", skip_all)]
+    pub fn witness(&self, key: &Hash) -> Witness {
+        let mut leaves: Vec<_> = self.leaves.iter().collect();
+        let mut path = Vec::with_capacity(DEPTH);
+        for depth in 0..DEPTH {
+            let split = leaves.partition_point(|(k, _)| !bit(k, depth));
+            let (left, right) = leaves.split_at(split);
+            let (ours, theirs) = if bit(key, depth) { (right, left) } else { (left, right) };
+            path.push(Self::subtree(theirs, depth + 1));
+            leaves = ours.to_vec();
+        }
+
+        let mut bitmap = [0u8; 32];
+        let mut siblings = Vec::new();
+        for (height, sibling) in path.into_iter().rev().enumerate() {
+            if sibling != EMPTY[height] {
+                bitmap[height / 8] |= 0x80 >> (height % 8);
+                siblings.push(sibling);
+            }
+        }
+        Witness { bitmap, siblings }
+    }
+}
+
+/// Checks that `key` holds `status` in the tree with `root`.
+#[instrument(level = "debug", skip_all)]
+pub fn verify_witness(root: &Hash, key: &Hash, status: CredentialStatus, witness: &Witness) -> bool {
+    let mut siblings = witness.siblings.iter();
+    let mut node = leaf_hash(key, status);
+    for height in 0..DEPTH {
+        let sibling = if witness.has_sibling(height) {
+            match siblings.next() {
+                Some(sibling) => *sibling,
+                None => return false,
+            }
+        } else {
+            EMPTY[height]
+        };
+        node = if bit(key, DEPTH - 1 - height) { node_hash(&sibling, &node) } else { node_hash(&node, &sibling) };
+    }
+    siblings.next().is_none() && node == *root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_tree_proves_everyone_active() {
+        let tree = SparseMerkleTree::new();
+        let key = credential_key("bafy-active");
+        let witness = tree.witness(&key);
+        assert!(witness.siblings.is_empty());
+        assert!(verify_witness(&tree.root(), &key, CredentialStatus::Active, &witness));
+        assert!(!verify_witness(&tree.root(), &key, CredentialStatus::Revoked, &witness));
+    }
+
+    #[test]
+    fn witnesses_track_updates() {
+        let mut tree = SparseMerkleTree::new();
+        let revoked = credential_key("bafy-revoked");
+        let suspended = credential_key("bafy-suspended");
+        let active = credential_key("bafy-active");
+        tree.set(revoked, CredentialStatus::Revoked);
+        tree.set(suspended, CredentialStatus::Suspended);
+        let root = tree.root();
+
+        for (key, status) in [
+            (revoked, CredentialStatus::Revoked),
+            (suspended, CredentialStatus::Suspended),
+            (active, CredentialStatus::Active),
+        ] {
+            let witness = Witness::from_bytes(&tree.witness(&key).to_bytes()).unwrap();
+            assert!(verify_witness(&root, &key, status, &witness), "{status:?}");
+            assert!(!verify_witness(&root, &key, CredentialStatus::Active, &witness) || status == CredentialStatus::Active);
+        }
+
+        // A holder's old "active" witness stops matching once they are revoked.
+        let stale = tree.witness(&active);
+        tree.set(active, CredentialStatus::Revoked);
+        assert!(!verify_witness(&tree.root(), &active, CredentialStatus::Active, &stale));
+
+        // Reinstating restores the earlier root.
+        tree.set(active, CredentialStatus::Active);
+        assert_eq!(tree.root(), root);
+    }
+
+    #[test]
+    fn malformed_witness_is_rejected() {
+        let mut tree = SparseMerkleTree::new();
+        let key = credential_key("bafy-1");
+        tree.set(credential_key("bafy-2"), CredentialStatus::Revoked);
+        let mut bytes = tree.witness(&key).to_bytes();
+        bytes.truncate(32);
+        assert!(Witness::from_bytes(&bytes).is_none());
+        assert!(Witness::from_bytes(&[0u8; 31]).is_none());
+    }
+}
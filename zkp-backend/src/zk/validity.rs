@@ -0,0 +1,179 @@
+//! Optional validity windows for proofs.
+//!
+//! A window bounds when a verifier accepts a proof: `not_before` and/or
+//! `not_after`, both inclusive, in unix seconds or block numbers. The bounds
+//! are appended to the proof's transcript right after its statement, so they
+//! cannot be changed once the proof is made; the verifier compares them with
+//! its own reference point.
+//!
+//! Proofs made without a window keep their original transcript and stay
+//! valid indefinitely.
+//!
+//! The reference point is the verifier's clock unless the caller supplies one
+//! (block-number windows always need one). `ValidityReference` records which,
+//! so a signed outcome cannot pass off a backdated check as a current one.
+//!
+//! Transcript layout (only when a window is given):
+//!   validity (basis) -> not_before? -> not_after?
+
+use merlin::Transcript;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Unit of the window bounds and of the verifier's reference point.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowBasis {
+    /// Unix seconds.
+    #[default]
+    Timestamp,
+    /// Block numbers of the escrow's chain.
+    Block,
+}
+
+impl WindowBasis {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Timestamp => "timestamp",
+            Self::Block => "block",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ValidityWindow {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_before: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_after: Option<u64>,
+    #[serde(default)]
+    pub basis: WindowBasis,
+}
+
+/// Where a reference point falls relative to a window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowStatus {
+    Valid,
+    NotYetValid,
+    Expired,
+}
+
+/// Who picked the reference point.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferenceSource {
+    /// The verifier's clock.
+    Server,
+    /// `reference_time` from the request.
+    Caller,
+}
+
+impl ReferenceSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Server => "server",
+            Self::Caller => "caller",
+        }
+    }
+}
+
+/// Reference point a windowed proof was checked against.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ValidityReference {
+    pub reference_time: u64,
+    pub basis: WindowBasis,
+    pub source: ReferenceSource,
+}
+
+impl ValidityWindow {
+    /// Rejects windows without bounds and windows that close before they open.
+    pub fn check(&self) -> Result<(), String> {
+        match (self.not_before, self.not_after) {
+            (None, None) => Err("validity window needs not_before or not_after".to_string()),
+            (Some(start), Some(end)) if start > end => Err("validity window ends before it starts".to_string()),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn append_to_transcript(&self, transcript: &mut Transcript) {
+        transcript.append_message(b"validity", self.basis.as_str().as_bytes());
+        if let Some(not_before) = self.not_before {
+            transcript.append_u64(b"not_before", not_before);
+        }
+        if let Some(not_after) = self.not_after {
+            transcript.append_u64(b"not_after", not_after);
+        }
+    }
+
+    pub fn status(&self, reference: u64) -> WindowStatus {
+        if self.not_before.is_some_and(|start| reference < start) {
+            WindowStatus::NotYetValid
+        } else if self.not_after.is_some_and(|end| reference > end) {
+            WindowStatus::Expired
+        } else {
+            WindowStatus::Valid
+        }
+    }
+}
+
+/// Appends `window` if there is one; without it the transcript is unchanged.
+pub fn append_window(transcript: &mut Transcript, window: Option<&ValidityWindow>) {
+    if let Some(window) = window {
+        window.append_to_transcript(transcript);
+    }
+}
+
+/// Final outcome of a windowed verification.
+///
+/// `verified` holds only if the proof checks out and `reference` lies inside
+/// the window. The status is reported only for proofs that check out, so
+/// `Expired` always means "a genuine proof, presented too late".
+pub fn windowed_outcome(
+    proof_holds: bool,
+    window: Option<&ValidityWindow>,
+    reference: u64,
+) -> (bool, Option<WindowStatus>) {
+    match window {
+        Some(window) if proof_holds => {
+            let status = window.status(reference);
+            (status == WindowStatus::Valid, Some(status))
+        }
+        _ => (proof_holds, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_covers_both_inclusive_bounds() {
+        let window = ValidityWindow { not_before: Some(100), not_after: Some(200), basis: WindowBasis::Timestamp };
+        assert_eq!(window.status(99), WindowStatus::NotYetValid);
+        assert_eq!(window.status(100), WindowStatus::Valid);
+        assert_eq!(window.status(200), WindowStatus::Valid);
+        assert_eq!(window.status(201), WindowStatus::Expired);
+
+        let open_ended = ValidityWindow { not_after: None, ..window };
+        assert_eq!(open_ended.status(u64::MAX), WindowStatus::Valid);
+    }
+
+    #[test]
+    fn empty_or_inverted_window_is_rejected() {
+        assert!(ValidityWindow::default().check().is_err());
+        assert!(ValidityWindow { not_before: Some(2), not_after: Some(1), ..Default::default() }.check().is_err());
+        assert!(ValidityWindow { not_after: Some(1), ..Default::default() }.check().is_ok());
+    }
+
+    #[test]
+    fn expired_status_only_for_holding_proofs() {
+        let window = ValidityWindow { not_after: Some(10), ..Default::default() };
+        assert_eq!(windowed_outcome(true, Some(&window), 11), (false, Some(WindowStatus::Expired)));
+        assert_eq!(windowed_outcome(false, Some(&window), 11), (false, None));
+        assert_eq!(windowed_outcome(true, Some(&window), 10), (true, Some(WindowStatus::Valid)));
+        assert_eq!(windowed_outcome(true, None, 11), (true, None));
+    }
+}
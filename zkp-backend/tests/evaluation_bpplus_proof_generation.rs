@@ -2,7 +2,6 @@
 //! Measures time to generate a BP+ range proof for transaction IDs (4 × 64-bit limbs)
 //! Structured identically to BP generation test for fair comparison
 
-use bulletproof_demo::zk::bp_plus_pedersen::{prove_txid_commitment, verify_txid_commitment};
 use std::time::Instant;
 use sha2::{Sha256, Digest};
 
@@ -31,8 +30,8 @@ fn compute_binding_tag(
     let mut hasher = Sha256::new();
     hasher.update(chain_id.as_bytes());
     hasher.update(escrow_addr);
-    hasher.update(&product_id.to_le_bytes());
-    hasher.update(&[stage]);
+    hasher.update(product_id.to_le_bytes());
+    hasher.update([stage]);
     hasher.update(schema_version.as_bytes());
     if let Some(cid) = previous_vc_cid {
         hasher.update(cid.as_bytes());
@@ -57,7 +56,7 @@ fn calculate_stats(times: &[f64]) -> Stats {
     sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
     
     let n = sorted.len();
-    let median = if n % 2 == 0 {
+    let median = if n.is_multiple_of(2) {
         (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0
     } else {
         sorted[n / 2]
@@ -44,7 +44,7 @@ mod tests {
 
         // Assertions
         assert!(verified, "BP+ proof should verify");
-        assert!(proof_bytes.len() > 0, "BP+ proof should have non-zero size");
+        assert!(!proof_bytes.is_empty(), "BP+ proof should have non-zero size");
         assert_eq!(commitments.len(), 4, "Should have 4 commitments (one per limb)");
         assert!(commitment_size == 128, "4 commitments × 32 bytes = 128 bytes");
         
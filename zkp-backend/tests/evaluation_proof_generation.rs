@@ -31,8 +31,8 @@ fn compute_binding_tag(
     let mut hasher = Sha256::new();
     hasher.update(chain_id.as_bytes());
     hasher.update(escrow_addr);
-    hasher.update(&product_id.to_le_bytes());
-    hasher.update(&[stage]);
+    hasher.update(product_id.to_le_bytes());
+    hasher.update([stage]);
     hasher.update(schema_version.as_bytes());
     if let Some(cid) = previous_vc_cid {
         hasher.update(cid.as_bytes());
@@ -64,7 +64,7 @@ fn calculate_stats(times: &[f64]) -> Stats {
     sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
     
     let n = sorted.len();
-    let median = if n % 2 == 0 {
+    let median = if n.is_multiple_of(2) {
         (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0
     } else {
         sorted[n / 2]
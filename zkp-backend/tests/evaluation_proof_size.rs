@@ -141,9 +141,9 @@ mod tests {
 
         // Assertions
         assert!(verified_32, "32-bit proof should verify");
-        assert!(proof_bytes_32.len() > 0, "32-bit proof should have non-zero size");
+        assert!(!proof_bytes_32.is_empty(), "32-bit proof should have non-zero size");
         assert!(verified_64, "64-bit proof should verify");
-        assert!(proof_bytes_64.len() > 0, "64-bit proof should have non-zero size");
+        assert!(!proof_bytes_64.is_empty(), "64-bit proof should have non-zero size");
         assert!(proof_bytes_64.len() >= proof_bytes_32.len(), 
                 "64-bit proof should be at least as large as 32-bit proof");
         
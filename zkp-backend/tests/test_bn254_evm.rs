@@ -0,0 +1,130 @@
+//! Test the BN254 sigma proofs against the generated EVM verifier
+//! Deploys `creation_code()` into revm and checks it agrees with the Rust verifier
+
+use ark_bn254::Fr;
+use ark_ff::{BigInteger, PrimeField};
+use bulletproof_demo::zk::bn254::{commit, prove_equality, prove_quantity_total, verify_equality, G1Bytes};
+use bulletproof_demo::zk::bn254_verifier::{creation_code, equality_calldata, quantity_total_calldata};
+use revm::db::{CacheDB, EmptyDB};
+use revm::primitives::{Address, ExecutionResult, Output, TxKind, U256};
+use revm::Evm;
+
+struct Chain {
+    evm: Evm<'static, (), CacheDB<EmptyDB>>,
+    verifier: Address,
+}
+
+impl Chain {
+    fn new() -> Self {
+        let mut evm = Evm::builder().with_db(CacheDB::new(EmptyDB::default())).build();
+        let tx = evm.tx_mut();
+        tx.caller = Address::repeat_byte(0x11);
+        tx.transact_to = TxKind::Create;
+        tx.data = creation_code().into();
+        tx.value = U256::ZERO;
+        let verifier = match evm.transact_commit().expect("deployment executes") {
+            ExecutionResult::Success { output: Output::Create(_, Some(address)), .. } => address,
+            other => panic!("deployment failed: {other:?}"),
+        };
+        Self { evm, verifier }
+    }
+
+    /// Returns the verifier's answer and the transaction's gas.
+    fn verify(&mut self, calldata: Vec<u8>) -> (bool, u64) {
+        let tx = self.evm.tx_mut();
+        tx.transact_to = TxKind::Call(self.verifier);
+        tx.data = calldata.into();
+        match self.evm.transact_commit().expect("call executes") {
+            ExecutionResult::Success { output: Output::Call(bytes), gas_used, .. } => {
+                assert_eq!(bytes.len(), 32, "verifier returns one word");
+                assert!(bytes[..31].iter().all(|&b| b == 0));
+                (bytes[31] == 1, gas_used)
+            }
+            other => panic!("verifier call did not succeed: {other:?}"),
+        }
+    }
+}
+
+fn commitment(value: u64, blinding_byte: u8) -> (G1Bytes, Fr) {
+    let blinding = Fr::from_be_bytes_mod_order(&[blinding_byte; 32]);
+    (commit(Fr::from(value), blinding), blinding)
+}
+
+#[test]
+fn test_equality_proof_verifies_on_chain() {
+    let mut chain = Chain::new();
+    let context_hash = [0x11u8; 32];
+    let (c_price, r_price) = commitment(1_000, 0x22);
+    let (c_pay, r_pay) = commitment(1_000, 0x33);
+    let (c_other, _) = commitment(999, 0x33);
+    let proof = prove_equality(&c_price, &c_pay, r_price, r_pay, &context_hash).unwrap();
+
+    let (verified, gas_used) = chain.verify(equality_calldata(&c_price, &c_pay, &proof, &context_hash));
+    assert!(verified);
+    println!("equality verification gas: {gas_used}");
+    assert!(gas_used < 60_000, "verification used {gas_used} gas");
+
+    assert!(!chain.verify(equality_calldata(&c_price, &c_other, &proof, &context_hash)).0);
+    assert!(!chain.verify(equality_calldata(&c_price, &c_pay, &proof, &[0x12u8; 32])).0);
+}
+
+#[test]
+fn test_quantity_total_proof_verifies_on_chain() {
+    let mut chain = Chain::new();
+    let context_hash = [0x44u8; 32];
+    let unit_price = Fr::from(250u64);
+    let (c_quantity, r_quantity) = commitment(4, 0x55);
+    let (c_total, r_total) = commitment(1_000, 0x66);
+    let proof = prove_quantity_total(&c_quantity, &c_total, unit_price, r_quantity, r_total, &context_hash).unwrap();
+
+    assert!(chain.verify(quantity_total_calldata(&c_quantity, &c_total, &unit_price, &proof, &context_hash)).0);
+    assert!(!chain.verify(quantity_total_calldata(&c_quantity, &c_total, &Fr::from(251u64), &proof, &context_hash)).0);
+    assert!(!chain.verify(quantity_total_calldata(&c_total, &c_quantity, &unit_price, &proof, &context_hash)).0);
+}
+
+#[test]
+fn test_malformed_calldata_returns_false() {
+    let mut chain = Chain::new();
+    let context_hash = [0x77u8; 32];
+    let (c_price, r_price) = commitment(5, 0x01);
+    let (c_pay, r_pay) = commitment(5, 0x02);
+    let proof = prove_equality(&c_price, &c_pay, r_price, r_pay, &context_hash).unwrap();
+    let valid = equality_calldata(&c_price, &c_pay, &proof, &context_hash);
+
+    assert!(!chain.verify(valid[..288].to_vec()).0);
+    assert!(!chain.verify([valid.as_slice(), &[0u8; 32]].concat()).0);
+
+    let mut off_curve = valid.clone();
+    off_curve[127] ^= 1;
+    assert!(!chain.verify(off_curve).0);
+
+    // s + r is the same residue but not canonical.
+    let mut s_plus_order = proof.clone();
+    let s = num_bigint::BigUint::from_bytes_be(&proof.s_response);
+    let order = num_bigint::BigUint::from_bytes_be(&Fr::MODULUS.to_bytes_be());
+    s_plus_order.s_response.copy_from_slice(&(s + order).to_bytes_be());
+    assert!(!verify_equality(&c_price, &c_pay, &s_plus_order, &context_hash));
+    assert!(!chain.verify(equality_calldata(&c_price, &c_pay, &s_plus_order, &context_hash)).0);
+}
+
+#[test]
+fn test_evm_agrees_with_rust_verifier_on_tampered_proofs() {
+    let mut chain = Chain::new();
+    let context_hash = [0x99u8; 32];
+    let (c_price, r_price) = commitment(77, 0x0a);
+    let (c_pay, r_pay) = commitment(77, 0x0b);
+    let proof = prove_equality(&c_price, &c_pay, r_price, r_pay, &context_hash).unwrap();
+
+    for byte in [0usize, 31, 63, 64, 95] {
+        let mut tampered = proof.clone();
+        if byte < 64 {
+            tampered.r_announcement[byte] ^= 0x01;
+        } else {
+            tampered.s_response[byte - 64] ^= 0x01;
+        }
+        let expected = verify_equality(&c_price, &c_pay, &tampered, &context_hash);
+        let (on_chain, _) = chain.verify(equality_calldata(&c_price, &c_pay, &tampered, &context_hash));
+        assert_eq!(on_chain, expected, "byte {byte}");
+        assert!(!on_chain);
+    }
+}
@@ -0,0 +1,102 @@
+//! Verify the checked-in note-link fixture offline
+//! Regenerate with `cargo test --release --test test_note_link_fixture -- --ignored`
+
+use ark_bn254::{Bn254, Fr};
+use ark_ed_on_bn254::{EdwardsAffine, Fr as BabyJubjubScalar};
+use ark_ff::{BigInteger, PrimeField};
+use ark_groth16::{Proof, VerifyingKey};
+use bulletproofs::PedersenGens;
+use bulletproof_demo::zk::note_link::{
+    self, context_scalar, from_bytes, key_id, to_bytes, NoteLinkStatement, NoteLinkWitness, PayLink,
+};
+use curve25519_dalek_ng::ristretto::CompressedRistretto;
+use curve25519_dalek_ng::scalar::Scalar;
+use serde_json::{json, Value};
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/note_link.json");
+
+fn field_hex(value: &Fr) -> String {
+    hex::encode(value.into_bigint().to_bytes_be())
+}
+
+fn decode<T: ark_serialize::CanonicalDeserialize>(fixture: &Value, field: &str) -> T {
+    from_bytes(&hex::decode(fixture[field].as_str().unwrap()).unwrap()).unwrap_or_else(|| panic!("{field} decodes"))
+}
+
+fn field(fixture: &Value, name: &str) -> Fr {
+    Fr::from_be_bytes_mod_order(&hex::decode(fixture[name].as_str().unwrap()).unwrap())
+}
+
+fn load() -> (Value, VerifyingKey<Bn254>, NoteLinkStatement, Proof<Bn254>) {
+    let fixture: Value = serde_json::from_str(&std::fs::read_to_string(FIXTURE).unwrap()).unwrap();
+    let context_hash: [u8; 32] = hex::decode(fixture["context_hash_hex"].as_str().unwrap()).unwrap().try_into().unwrap();
+    let statement = NoteLinkStatement {
+        note_commitment: field(&fixture, "note_commitment_hex"),
+        token_hash: field(&fixture, "token_hash_hex"),
+        twin: decode::<EdwardsAffine>(&fixture, "twin_commitment_hex"),
+        context: context_scalar(&context_hash),
+    };
+    let vk = decode(&fixture, "verifying_key_hex");
+    let proof = decode(&fixture, "proof_hex");
+    (fixture, vk, statement, proof)
+}
+
+#[test]
+fn test_fixture_proof_verifies() {
+    let (fixture, vk, statement, proof) = load();
+    assert_eq!(fixture["key_id"], key_id(&vk));
+    assert!(note_link::verify(&vk, &statement, &proof));
+}
+
+fn pay_link(fixture: &Value) -> (CompressedRistretto, PayLink) {
+    let bytes = |field: &str| hex::decode(fixture[field].as_str().unwrap()).unwrap();
+    (CompressedRistretto::from_slice(&bytes("c_pay_hex")), PayLink::from_bytes(&bytes("pay_link_hex")).unwrap())
+}
+
+#[test]
+fn test_fixture_pay_link_verifies() {
+    let (fixture, _, statement, _) = load();
+    let (c_pay, link) = pay_link(&fixture);
+    assert!(note_link::verify_pay_link(&statement, c_pay, &link));
+    let other_twin = NoteLinkStatement { twin: note_link::twin_commitment(1, BabyJubjubScalar::from(7u64)), ..statement };
+    assert!(!note_link::verify_pay_link(&other_twin, c_pay, &link));
+}
+
+#[test]
+fn test_fixture_proof_is_bound_to_its_public_inputs() {
+    let (_, vk, statement, proof) = load();
+    let other_context = NoteLinkStatement { context: context_scalar(&[0x12u8; 32]), ..statement.clone() };
+    assert!(!note_link::verify(&vk, &other_context, &proof));
+    let other_note = NoteLinkStatement { note_commitment: statement.note_commitment + Fr::from(1u64), ..statement };
+    assert!(!note_link::verify(&vk, &other_note, &proof));
+}
+
+#[test]
+#[ignore = "rewrites tests/fixtures/note_link.json"]
+fn regenerate_fixture() {
+    let pk = note_link::setup().unwrap();
+    let context_hash = [0x11u8; 32];
+    let witness = NoteLinkWitness {
+        npk: Fr::from(42u64),
+        value: 1_000_000_000_000_000_000,
+        blinding: BabyJubjubScalar::from(7u64),
+    };
+    let token_hash = Fr::from_be_bytes_mod_order(&[0xabu8; 20]);
+    let statement = witness.statement(token_hash, &context_hash);
+    let proof = note_link::prove(&pk, &statement, &witness).unwrap();
+    let r_pay = Scalar::from(11u64);
+    let c_pay = PedersenGens::default().commit(Scalar::from(witness.value), r_pay).compress();
+    let link = note_link::prove_pay_link(&statement, &witness, c_pay, r_pay).unwrap();
+    let fixture = json!({
+        "key_id": key_id(&pk.vk),
+        "verifying_key_hex": hex::encode(to_bytes(&pk.vk)),
+        "note_commitment_hex": field_hex(&statement.note_commitment),
+        "token_hash_hex": field_hex(&statement.token_hash),
+        "twin_commitment_hex": hex::encode(to_bytes(&statement.twin)),
+        "context_hash_hex": hex::encode(context_hash),
+        "proof_hex": hex::encode(to_bytes(&proof)),
+        "c_pay_hex": hex::encode(c_pay.as_bytes()),
+        "pay_link_hex": hex::encode(link.to_bytes()),
+    });
+    std::fs::write(FIXTURE, serde_json::to_string_pretty(&fixture).unwrap() + "\n").unwrap();
+}
@@ -39,7 +39,7 @@ fn test_txid_commitment_verification() {
     let (commitment, proof_bytes, _) = prove_txid_commitment_from_hex(tx_hash_hex);
     
     // Test valid verification
-    assert!(verify_txid_commitment(commitment.clone(), proof_bytes.clone()), 
+    assert!(verify_txid_commitment(commitment, proof_bytes.clone()), 
             "Valid commitment should verify");
     
     // Test invalid commitment (wrong commitment)
@@ -4,9 +4,12 @@
 //! To test the API endpoint manually:
 //! 1. Start the server: cargo run --bin bulletproof-demo
 //! 2. In another terminal, test with curl:
-//!    curl -X POST http://127.0.0.1:5010/zkp/commit-tx-hash \
-//!         -H "Content-Type: application/json" \
-//!         -d '{"tx_hash":"0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef"}'
+//!
+//! ```text
+//! curl -X POST http://127.0.0.1:5010/zkp/commit-tx-hash \
+//!      -H "Content-Type: application/json" \
+//!      -d '{"tx_hash":"0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef"}'
+//! ```
 
 // This test file documents the API endpoint
 // Actual API testing should be done manually or with integration tests
@@ -0,0 +1,98 @@
+//! Test the zkp-cli binary end to end
+//! commit → prove-quantity-total → verify-batch, plus CBOR input and exit codes
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+use serde_json::{json, Value};
+
+fn run_cli(args: &[&str], stdin: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_zkp-cli"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("zkp-cli should start");
+    child.stdin.take().unwrap().write_all(stdin).unwrap();
+    child.wait_with_output().expect("zkp-cli should finish")
+}
+
+fn run_json(args: &[&str], request: &Value) -> (i32, Value) {
+    let output = run_cli(args, request.to_string().as_bytes());
+    let body = serde_json::from_slice(&output.stdout).unwrap_or(Value::Null);
+    (output.status.code().unwrap_or(-1), body)
+}
+
+fn commit(value: u64, blinding_hex: &str) -> String {
+    let (code, body) = run_json(&["commit"], &json!({ "value": value, "blinding_hex": blinding_hex }));
+    assert_eq!(code, 0);
+    assert_eq!(body["proof_type"], "pedersen-scalar-v2");
+    body["commitment"].as_str().unwrap().to_string()
+}
+
+#[test]
+fn test_quantity_total_proof_round_trip() {
+    let r_quantity = "11".repeat(32);
+    let r_total = "22".repeat(32);
+    let context_hash_hex = "33".repeat(32);
+    let c_quantity = commit(4, &r_quantity);
+    let c_total = commit(1000, &r_total);
+
+    let (code, proof) = run_json(
+        &["prove-quantity-total"],
+        &json!({
+            "c_quantity_hex": c_quantity,
+            "c_total_hex": c_total,
+            "unit_price_wei": "250",
+            "r_quantity_hex": r_quantity,
+            "r_total_hex": r_total,
+            "context_hash_hex": context_hash_hex,
+        }),
+    );
+    assert_eq!(code, 0);
+    assert_eq!(proof["verified"], true);
+
+    let request = json!({
+        "c_quantity_hex": c_quantity,
+        "c_total_hex": c_total,
+        "unit_price_wei": "250",
+        "proof_r_hex": proof["proof_r_hex"],
+        "proof_s_hex": proof["proof_s_hex"],
+        "context_hash_hex": context_hash_hex,
+    });
+    let mut wrong_price = request.clone();
+    wrong_price["unit_price_wei"] = json!("251");
+
+    let (code, batch) = run_json(
+        &["verify-batch"],
+        &json!([
+            { "kind": "quantity-total", "request": request },
+            { "kind": "quantity-total", "request": wrong_price },
+        ]),
+    );
+    assert_eq!(code, 1, "a failed entry should fail the batch");
+    assert_eq!(batch["results"][0]["verified"], true);
+    assert_eq!(batch["results"][1]["verified"], false);
+}
+
+#[test]
+fn test_cbor_input() {
+    let request = json!({ "value": 7, "blinding_hex": "44".repeat(32) });
+    let mut cbor = Vec::new();
+    ciborium::ser::into_writer(&request, &mut cbor).unwrap();
+
+    let output = run_cli(&["commit", "--format", "cbor"], &cbor);
+    let body: Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(body["commitment"].as_str().unwrap(), commit(7, &"44".repeat(32)));
+}
+
+#[test]
+fn test_malformed_request_exits_with_error() {
+    let output = run_cli(&["commit"], br#"{ "value": 7, "blinding_hex": "zz" }"#);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("invalid blinding_hex"));
+}
@@ -0,0 +1,512 @@
+//! BBS+ over BLS12-381, mirroring `zk::bbs` in the backend byte for byte so
+//! signatures and derived proofs move freely between the browser and
+//! `/zkp/bbs/*`. See that module for the construction.
+
+use std::sync::LazyLock;
+
+use ark_bls12_381::{Bls12_381, Fq, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::pairing::Pairing;
+use ark_ec::{AffineRepr, CurveGroup, Group};
+use ark_ff::{Field, PrimeField, UniformRand, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use bulletproofs::PedersenGens;
+use curve25519_dalek_ng::ristretto::CompressedRistretto;
+use curve25519_dalek_ng::scalar::Scalar;
+use merlin::Transcript;
+use num_bigint::BigUint;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+/// Largest attribute vector a key signs.
+pub const MAX_ATTRIBUTES: usize = 64;
+/// Bits of a linked attribute's integer response: 64 value + 128 challenge + 80 statistical + carry.
+pub const LINKED_RESPONSE_BITS: u64 = 273;
+const LINKED_NONCE_LEN: usize = 34;
+const LINKED_RESPONSE_LEN: usize = 35;
+
+const G1_LEN: usize = 48;
+const SCALAR_LEN: usize = 32;
+pub const SIGNATURE_LEN: usize = G1_LEN + 2 * SCALAR_LEN;
+
+/// h_0..h_MAX_ATTRIBUTES by try-and-increment on x, cofactor cleared.
+static GENERATORS: LazyLock<Vec<G1Affine>> = LazyLock::new(|| {
+    (0..=MAX_ATTRIBUTES as u32)
+        .map(|index| {
+            (0u32..)
+                .find_map(|counter| {
+                    let mut hasher = Sha256::new();
+                    hasher.update(b"zkp.bbs.h");
+                    hasher.update(index.to_be_bytes());
+                    hasher.update(counter.to_be_bytes());
+                    let x = Fq::from_be_bytes_mod_order(&hasher.finalize());
+                    let point = G1Affine::get_point_from_x_unchecked(x, false)?.clear_cofactor();
+                    (!point.is_zero()).then_some(point)
+                })
+                .expect("hash-to-curve terminates")
+        })
+        .collect()
+});
+
+fn h(index: usize) -> G1Affine {
+    GENERATORS[index]
+}
+
+fn encode<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes).expect("serialization to a Vec");
+    bytes
+}
+
+/// Checked decoding: canonical, on-curve and in the prime-order subgroup.
+fn decode<T: CanonicalDeserialize>(bytes: &[u8]) -> Option<T> {
+    T::deserialize_compressed(bytes).ok()
+}
+
+/// Attribute scalar: text is hashed together with its name, an integer v
+/// becomes v + `name_offset(name)`, keeping v in the low 64 bits so it can be
+/// linked to a Pedersen commitment.
+pub fn attribute_scalar(name: &str, value: &str, integer: bool) -> Result<Fr, String> {
+    if integer {
+        let value = value.trim().parse::<u64>().map_err(|_| format!("attribute {name} must be a decimal u64"))?;
+        return Ok(Fr::from(value) + name_offset(name));
+    }
+    let mut hasher = Sha256::new();
+    hasher.update(b"zkp.bbs.attribute");
+    hasher.update((name.len() as u64).to_be_bytes());
+    hasher.update(name.as_bytes());
+    hasher.update(value.as_bytes());
+    Ok(Fr::from_be_bytes_mod_order(&hasher.finalize()))
+}
+
+/// H(name) * 2^64 with a 128-bit H: the part of an integer attribute's
+/// scalar that signs its name.
+pub fn name_offset(name: &str) -> Fr {
+    let mut hasher = Sha256::new();
+    hasher.update(b"zkp.bbs.integer");
+    hasher.update((name.len() as u64).to_be_bytes());
+    hasher.update(name.as_bytes());
+    let tag = u128::from_be_bytes(hasher.finalize()[..16].try_into().expect("16-byte prefix"));
+    Fr::from(tag) * Fr::from(1u128 << 64)
+}
+
+#[derive(Clone, Debug)]
+pub struct SecretKey(Fr);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublicKey(G2Affine);
+
+impl SecretKey {
+    pub fn random() -> Self {
+        Self(Fr::rand(&mut OsRng))
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        decode::<Fr>(bytes).filter(|x| !x.is_zero()).map(Self)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encode(&self.0)
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey((G2Projective::generator() * self.0).into_affine())
+    }
+}
+
+impl PublicKey {
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        decode::<G2Affine>(bytes).filter(|w| !w.is_zero()).map(Self)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encode(&self.0)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Signature {
+    a: G1Affine,
+    e: Fr,
+    s: Fr,
+}
+
+impl Signature {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [encode(&self.a), encode(&self.e), encode(&self.s)].concat()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != SIGNATURE_LEN {
+            return None;
+        }
+        Some(Self {
+            a: decode(&bytes[..G1_LEN])?,
+            e: decode(&bytes[G1_LEN..G1_LEN + SCALAR_LEN])?,
+            s: decode(&bytes[G1_LEN + SCALAR_LEN..])?,
+        })
+    }
+}
+
+fn check_count(count: usize) -> Result<(), String> {
+    if count == 0 || count > MAX_ATTRIBUTES {
+        return Err(format!("attribute count must be 1 to {MAX_ATTRIBUTES}"));
+    }
+    Ok(())
+}
+
+/// g1 + s * h_0 + Σ m_i * h_i
+fn b_point(s: Fr, messages: &[Fr]) -> G1Projective {
+    messages
+        .iter()
+        .enumerate()
+        .fold(G1Projective::generator() + h(0) * s, |acc, (i, m)| acc + h(i + 1) * m)
+}
+
+pub fn sign(sk: &SecretKey, messages: &[Fr]) -> Result<Signature, String> {
+    check_count(messages.len())?;
+    loop {
+        let e = Fr::rand(&mut OsRng);
+        let s = Fr::rand(&mut OsRng);
+        if let Some(inverse) = (sk.0 + e).inverse() {
+            let a = (b_point(s, messages) * inverse).into_affine();
+            return Ok(Signature { a, e, s });
+        }
+    }
+}
+
+pub fn verify_signature(pk: &PublicKey, messages: &[Fr], signature: &Signature) -> bool {
+    if check_count(messages.len()).is_err() || signature.a.is_zero() {
+        return false;
+    }
+    let w_e = pk.0 + G2Projective::generator() * signature.e;
+    Bls12_381::pairing(signature.a, w_e) == Bls12_381::pairing(b_point(signature.s, messages), G2Affine::generator())
+}
+
+/// Hidden attribute tied to a Pedersen commitment.
+#[derive(Clone, Copy, Debug)]
+pub struct Link {
+    pub index: usize,
+    /// `name_offset` of the attribute's name.
+    pub offset: Fr,
+    pub commitment: CompressedRistretto,
+}
+
+/// Prover side of a `Link`.
+#[derive(Clone, Copy, Debug)]
+pub struct LinkOpening {
+    pub index: usize,
+    pub offset: Fr,
+    pub commitment: CompressedRistretto,
+    pub blinding: Scalar,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinkedResponse {
+    /// z_m as a big-endian integer.
+    z_value: [u8; LINKED_RESPONSE_LEN],
+    z_blinding: Scalar,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DisclosureProof {
+    a_prime: G1Affine,
+    a_bar: G1Affine,
+    d: G1Affine,
+    challenge: u128,
+    z_e: Fr,
+    z_r2: Fr,
+    z_r3: Fr,
+    z_s: Fr,
+    /// Unlinked hidden attributes, ascending index.
+    z_hidden: Vec<Fr>,
+    /// Linked hidden attributes, ascending index.
+    z_linked: Vec<LinkedResponse>,
+}
+
+impl DisclosureProof {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = [encode(&self.a_prime), encode(&self.a_bar), encode(&self.d)].concat();
+        bytes.extend_from_slice(&self.challenge.to_be_bytes());
+        for z in [&self.z_e, &self.z_r2, &self.z_r3, &self.z_s].into_iter().chain(&self.z_hidden) {
+            bytes.extend(encode(z));
+        }
+        for linked in &self.z_linked {
+            bytes.extend_from_slice(&linked.z_value);
+            bytes.extend_from_slice(linked.z_blinding.as_bytes());
+        }
+        bytes
+    }
+
+    /// The layout depends on how many hidden attributes are unlinked/linked.
+    pub fn from_bytes(bytes: &[u8], unlinked: usize, linked: usize) -> Option<Self> {
+        let expected = 3 * G1_LEN + 16 + (4 + unlinked) * SCALAR_LEN + linked * (LINKED_RESPONSE_LEN + 32);
+        if bytes.len() != expected {
+            return None;
+        }
+        let (points, rest) = bytes.split_at(3 * G1_LEN);
+        let (challenge, rest) = rest.split_at(16);
+        let (scalars, links) = rest.split_at((4 + unlinked) * SCALAR_LEN);
+        let scalars = scalars.chunks_exact(SCALAR_LEN).map(decode::<Fr>).collect::<Option<Vec<_>>>()?;
+        let z_linked = links
+            .chunks_exact(LINKED_RESPONSE_LEN + 32)
+            .map(|chunk| {
+                Some(LinkedResponse {
+                    z_value: chunk[..LINKED_RESPONSE_LEN].try_into().ok()?,
+                    z_blinding: Scalar::from_canonical_bytes(chunk[LINKED_RESPONSE_LEN..].try_into().ok()?)?,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self {
+            a_prime: decode(&points[..G1_LEN])?,
+            a_bar: decode(&points[G1_LEN..2 * G1_LEN])?,
+            d: decode(&points[2 * G1_LEN..])?,
+            challenge: u128::from_be_bytes(challenge.try_into().ok()?),
+            z_e: scalars[0],
+            z_r2: scalars[1],
+            z_r3: scalars[2],
+            z_s: scalars[3],
+            z_hidden: scalars[4..].to_vec(),
+            z_linked,
+        })
+    }
+}
+
+fn append_point<T: CanonicalSerialize>(transcript: &mut Transcript, label: &'static [u8], point: &T) {
+    transcript.append_message(label, &encode(point));
+}
+
+/// Everything the verifier knows before the announcements.
+struct Statement<'a> {
+    pk: &'a PublicKey,
+    count: usize,
+    disclosed: &'a [(usize, Fr)],
+    links: &'a [Link],
+}
+
+impl Statement<'_> {
+    fn check(&self) -> Result<(), String> {
+        check_count(self.count)?;
+        let mut seen = vec![false; self.count];
+        for index in self.disclosed.iter().map(|(i, _)| *i).chain(self.links.iter().map(|l| l.index)) {
+            if index >= self.count || std::mem::replace(&mut seen[index], true) {
+                return Err(format!("attribute index {index} is out of range or repeated"));
+            }
+        }
+        if !self.disclosed.windows(2).all(|w| w[0].0 < w[1].0) || !self.links.windows(2).all(|w| w[0].index < w[1].index) {
+            return Err("disclosed attributes and links must be sorted by index".to_string());
+        }
+        Ok(())
+    }
+
+    /// Hidden indices that are not linked, ascending.
+    fn unlinked_hidden(&self) -> Vec<usize> {
+        (0..self.count)
+            .filter(|i| !self.disclosed.iter().any(|(d, _)| d == i) && !self.links.iter().any(|l| l.index == *i))
+            .collect()
+    }
+
+    fn transcript(&self, context_hash: &[u8], a_prime: &G1Affine, a_bar: &G1Affine, d: &G1Affine) -> Transcript {
+        let mut transcript = Transcript::new(b"BbsProof-v1");
+        transcript.append_message(b"context_hash", context_hash);
+        append_point(&mut transcript, b"w", &self.pk.0);
+        transcript.append_u64(b"L", self.count as u64);
+        for (index, message) in self.disclosed {
+            transcript.append_u64(b"disclosed_index", *index as u64);
+            append_point(&mut transcript, b"disclosed_message", message);
+        }
+        for link in self.links {
+            transcript.append_u64(b"linked_index", link.index as u64);
+            append_point(&mut transcript, b"linked_offset", &link.offset);
+            transcript.append_message(b"C", link.commitment.as_bytes());
+        }
+        append_point(&mut transcript, b"A'", a_prime);
+        append_point(&mut transcript, b"Abar", a_bar);
+        append_point(&mut transcript, b"d", d);
+        transcript
+    }
+}
+
+fn challenge(transcript: &mut Transcript, t1: &G1Affine, t2: &G1Affine, t3: &[CompressedRistretto]) -> u128 {
+    append_point(transcript, b"T1", t1);
+    append_point(transcript, b"T2", t2);
+    for t in t3 {
+        transcript.append_message(b"T3", t.as_bytes());
+    }
+    let mut bytes = [0u8; 16];
+    transcript.challenge_bytes(b"challenge", &mut bytes);
+    u128::from_be_bytes(bytes)
+}
+
+fn random_integer(len: usize) -> BigUint {
+    let mut bytes = vec![0u8; len];
+    OsRng.fill_bytes(&mut bytes);
+    BigUint::from_bytes_be(&bytes)
+}
+
+fn integer_to_fr(value: &BigUint) -> Fr {
+    Fr::from_be_bytes_mod_order(&value.to_bytes_be())
+}
+
+fn integer_to_scalar(value: &BigUint) -> Scalar {
+    let mut wide = [0u8; 64];
+    let le = value.to_bytes_le();
+    wide[..le.len()].copy_from_slice(&le);
+    Scalar::from_bytes_mod_order_wide(&wide)
+}
+
+/// The attribute as a u64, if it is one.
+fn small_integer(message: &Fr) -> Option<u64> {
+    let limbs = message.into_bigint().0;
+    limbs[1..].iter().all(|&limb| limb == 0).then_some(limbs[0])
+}
+
+/// Derive a proof disclosing `disclosed` (sorted indices) and linking the
+/// hidden attributes in `links` (sorted by index) to their commitments.
+pub fn derive_proof(
+    pk: &PublicKey,
+    signature: &Signature,
+    messages: &[Fr],
+    disclosed: &[usize],
+    links: &[LinkOpening],
+    context_hash: &[u8],
+) -> Result<DisclosureProof, String> {
+    if !verify_signature(pk, messages, signature) {
+        return Err("signature does not verify for these attributes".to_string());
+    }
+    let disclosed_messages: Vec<(usize, Fr)> =
+        disclosed.iter().map(|&i| messages.get(i).map(|m| (i, *m)).ok_or("disclosed index out of range")).collect::<Result<_, _>>()?;
+    let public_links: Vec<Link> = links.iter().map(|l| Link { index: l.index, offset: l.offset, commitment: l.commitment }).collect();
+    let statement = Statement { pk, count: messages.len(), disclosed: &disclosed_messages, links: &public_links };
+    statement.check()?;
+
+    let pc_gens = PedersenGens::default();
+    let mut linked_values = Vec::with_capacity(links.len());
+    for link in links {
+        let value = small_integer(&(messages[link.index] - link.offset))
+            .ok_or_else(|| format!("attribute {} is not a u64 under this name", link.index))?;
+        if pc_gens.commit(Scalar::from(value), link.blinding).compress() != link.commitment {
+            return Err(format!("commitment for attribute {} does not open to its value", link.index));
+        }
+        linked_values.push(value);
+    }
+
+    let r1 = loop {
+        let r1 = Fr::rand(&mut OsRng);
+        if !r1.is_zero() {
+            break r1;
+        }
+    };
+    let r2 = Fr::rand(&mut OsRng);
+    let r3 = r1.inverse().expect("r1 is non-zero");
+    let b = b_point(signature.s, messages);
+    let a_prime = (signature.a * r1).into_affine();
+    let a_bar = (b * r1 - a_prime * signature.e).into_affine();
+    let d = (b * r1 - h(0) * r2).into_affine();
+    let s_prime = signature.s - r2 * r3;
+
+    let unlinked = statement.unlinked_hidden();
+    let (k_e, k_r2, k_r3, k_s) = (Fr::rand(&mut OsRng), Fr::rand(&mut OsRng), Fr::rand(&mut OsRng), Fr::rand(&mut OsRng));
+    let k_hidden: Vec<Fr> = unlinked.iter().map(|_| Fr::rand(&mut OsRng)).collect();
+    let k_linked: Vec<BigUint> = links.iter().map(|_| random_integer(LINKED_NONCE_LEN)).collect();
+    let k_blinding: Vec<Scalar> = links.iter().map(|_| Scalar::random(&mut OsRng)).collect();
+
+    let t1 = (h(0) * k_r2 - a_prime * k_e).into_affine();
+    let mut t2 = d * k_r3 - h(0) * k_s;
+    for (index, k) in unlinked.iter().zip(&k_hidden) {
+        t2 -= h(index + 1) * k;
+    }
+    for (link, k) in links.iter().zip(&k_linked) {
+        t2 -= h(link.index + 1) * integer_to_fr(k);
+    }
+    let t3: Vec<CompressedRistretto> = k_linked
+        .iter()
+        .zip(&k_blinding)
+        .map(|(k, k_r)| pc_gens.commit(integer_to_scalar(k), *k_r).compress())
+        .collect();
+
+    let mut transcript = statement.transcript(context_hash, &a_prime, &a_bar, &d);
+    let c = challenge(&mut transcript, &t1, &t2.into_affine(), &t3);
+    let c_fr = Fr::from(c);
+
+    let z_linked = links
+        .iter()
+        .zip(linked_values)
+        .zip(k_linked.iter().zip(&k_blinding))
+        .map(|((link, value), (k, k_r))| {
+            let z = k + BigUint::from(c) * BigUint::from(value);
+            let mut z_value = [0u8; LINKED_RESPONSE_LEN];
+            let be = z.to_bytes_be();
+            z_value[LINKED_RESPONSE_LEN - be.len()..].copy_from_slice(&be);
+            LinkedResponse { z_value, z_blinding: k_r + Scalar::from(c) * link.blinding }
+        })
+        .collect();
+
+    Ok(DisclosureProof {
+        a_prime,
+        a_bar,
+        d,
+        challenge: c,
+        z_e: k_e + c_fr * signature.e,
+        z_r2: k_r2 + c_fr * r2,
+        z_r3: k_r3 + c_fr * r3,
+        z_s: k_s + c_fr * s_prime,
+        z_hidden: unlinked.iter().zip(&k_hidden).map(|(&i, k)| *k + c_fr * messages[i]).collect(),
+        z_linked,
+    })
+}
+
+/// Verify a proof over `count` attributes revealing `disclosed` (sorted by
+/// index) and linking the hidden attributes in `links` (sorted by index).
+pub fn verify_proof(
+    pk: &PublicKey,
+    count: usize,
+    disclosed: &[(usize, Fr)],
+    links: &[Link],
+    proof: &DisclosureProof,
+    context_hash: &[u8],
+) -> bool {
+    let statement = Statement { pk, count, disclosed, links };
+    if statement.check().is_err() || proof.a_prime.is_zero() {
+        return false;
+    }
+    let unlinked = statement.unlinked_hidden();
+    if proof.z_hidden.len() != unlinked.len() || proof.z_linked.len() != links.len() {
+        return false;
+    }
+    let z_linked: Vec<BigUint> = proof.z_linked.iter().map(|z| BigUint::from_bytes_be(&z.z_value)).collect();
+    if z_linked.iter().any(|z| z.bits() > LINKED_RESPONSE_BITS) {
+        return false;
+    }
+    let Some(commitments) = links.iter().map(|l| l.commitment.decompress()).collect::<Option<Vec<_>>>() else {
+        return false;
+    };
+
+    let c_fr = Fr::from(proof.challenge);
+    let c_scalar = Scalar::from(proof.challenge);
+    let t1 = h(0) * proof.z_r2 - proof.a_prime * proof.z_e - (proof.a_bar.into_group() - proof.d) * c_fr;
+    // Linked scalars are v + offset; the proof covers v, the verifier adds the offset.
+    let revealed = disclosed.iter().fold(G1Projective::generator(), |acc, (i, m)| acc + h(i + 1) * m);
+    let revealed = links.iter().fold(revealed, |acc, link| acc + h(link.index + 1) * link.offset);
+    let mut t2 = proof.d * proof.z_r3 - h(0) * proof.z_s - revealed * c_fr;
+    for (index, z) in unlinked.iter().zip(&proof.z_hidden) {
+        t2 -= h(index + 1) * z;
+    }
+    for (link, z) in links.iter().zip(&z_linked) {
+        t2 -= h(link.index + 1) * integer_to_fr(z);
+    }
+    let pc_gens = PedersenGens::default();
+    let t3: Vec<CompressedRistretto> = proof
+        .z_linked
+        .iter()
+        .zip(&z_linked)
+        .zip(&commitments)
+        .map(|((response, z), commitment)| {
+            (pc_gens.commit(integer_to_scalar(z), response.z_blinding) - c_scalar * commitment).compress()
+        })
+        .collect();
+
+    let mut transcript = statement.transcript(context_hash, &proof.a_prime, &proof.a_bar, &proof.d);
+    challenge(&mut transcript, &t1.into_affine(), &t2.into_affine(), &t3) == proof.challenge
+        && Bls12_381::pairing(proof.a_prime, pk.0) == Bls12_381::pairing(proof.a_bar, G2Affine::generator())
+}
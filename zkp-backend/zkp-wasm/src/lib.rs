@@ -1,17 +1,31 @@
+use std::sync::LazyLock;
+
+use bulletproofs::range_proof_mpc::messages::{
+    BitChallenge, BitCommitment, PolyChallenge, PolyCommitment, ProofShare,
+};
+use bulletproofs::range_proof_mpc::party::{
+    Party, PartyAwaitingBitChallenge, PartyAwaitingPolyChallenge, PartyAwaitingPosition,
+};
+use bulletproofs::range_proof_mpc::MPCError;
 use bulletproofs::{BulletproofGens, PedersenGens, RangeProof};
 use curve25519_dalek_ng::ristretto::CompressedRistretto;
 use curve25519_dalek_ng::scalar::Scalar;
 use merlin::Transcript;
 use num_bigint::BigUint;
 use rand::rngs::OsRng;
-use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
+mod bbs;
+
 #[derive(Serialize)]
 struct ValueCommitmentResponse {
     commitment: String,
     proof: String,
     verified: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    validity: Option<ValidityWindow>,
 }
 
 #[derive(Serialize)]
@@ -27,6 +41,24 @@ struct EqualityProofResponse {
     proof_r_hex: String,
     proof_s_hex: String,
     verified: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    validity: Option<ValidityWindow>,
+}
+
+#[derive(Serialize)]
+struct OpeningProofResponse {
+    commitment: String,
+    proof_r_hex: String,
+    proof_s_value_hex: String,
+    proof_s_blinding_hex: String,
+    verified: bool,
+}
+
+#[derive(Serialize)]
+struct ComparisonProofResponse {
+    proof: String,
+    bit_range: usize,
+    verified: bool,
 }
 
 #[derive(Serialize)]
@@ -34,6 +66,13 @@ struct VerifyResponse {
     verified: bool,
 }
 
+#[derive(Serialize)]
+struct WindowedVerifyResponse {
+    verified: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    validity: Option<WindowStatus>,
+}
+
 #[derive(Clone, Debug)]
 struct EqualityProof {
     r_announcement: [u8; 32],
@@ -46,6 +85,13 @@ struct QuantityTotalProof {
     s_response: [u8; 32],
 }
 
+#[derive(Clone, Debug)]
+struct OpeningProof {
+    r_announcement: [u8; 32],
+    s_value: [u8; 32],
+    s_blinding: [u8; 32],
+}
+
 fn parse_u64(value: &str) -> Result<u64, JsValue> {
     value
         .trim()
@@ -120,10 +166,89 @@ fn to_js_value<T: Serialize>(value: &T) -> Result<JsValue, JsValue> {
         .map_err(|_| JsValue::from_str("failed to serialize wasm response"))
 }
 
+// Validity windows. Must match the server's `zk::validity`: the window is
+// appended right after the statement, and only when one is given.
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum WindowBasis {
+    #[default]
+    Timestamp,
+    Block,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ValidityWindow {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    not_before: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    not_after: Option<u64>,
+    #[serde(default)]
+    basis: WindowBasis,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum WindowStatus {
+    Valid,
+    NotYetValid,
+    Expired,
+}
+
+impl ValidityWindow {
+    fn check(&self) -> Result<(), JsValue> {
+        match (self.not_before, self.not_after) {
+            (None, None) => Err(JsValue::from_str("validity window needs not_before or not_after")),
+            (Some(start), Some(end)) if start > end => Err(JsValue::from_str("validity window ends before it starts")),
+            _ => Ok(()),
+        }
+    }
+
+    fn status(&self, reference: u64) -> WindowStatus {
+        if self.not_before.is_some_and(|start| reference < start) {
+            WindowStatus::NotYetValid
+        } else if self.not_after.is_some_and(|end| reference > end) {
+            WindowStatus::Expired
+        } else {
+            WindowStatus::Valid
+        }
+    }
+}
+
+fn append_window(transcript: &mut Transcript, window: Option<&ValidityWindow>) {
+    if let Some(window) = window {
+        let basis = match window.basis {
+            WindowBasis::Timestamp => "timestamp",
+            WindowBasis::Block => "block",
+        };
+        transcript.append_message(b"validity", basis.as_bytes());
+        if let Some(not_before) = window.not_before {
+            transcript.append_u64(b"not_before", not_before);
+        }
+        if let Some(not_after) = window.not_after {
+            transcript.append_u64(b"not_after", not_after);
+        }
+    }
+}
+
+/// `verified` only for proofs that hold inside the window; the status is
+/// only reported for proofs that hold.
+fn windowed_response(holds: bool, window: Option<&ValidityWindow>, reference: u64) -> WindowedVerifyResponse {
+    match window {
+        Some(window) if holds => {
+            let status = window.status(reference);
+            WindowedVerifyResponse { verified: status == WindowStatus::Valid, validity: Some(status) }
+        }
+        _ => WindowedVerifyResponse { verified: holds, validity: None },
+    }
+}
+
 fn prove_value_commitment_with_binding_internal(
     value: u64,
     blinding: Scalar,
     binding_tag: Option<&[u8]>,
+    window: Option<&ValidityWindow>,
 ) -> Result<(CompressedRistretto, Vec<u8>, bool), JsValue> {
     let pc_gens = PedersenGens::default();
     let bp_gens = BulletproofGens::new(64, 1);
@@ -132,6 +257,7 @@ fn prove_value_commitment_with_binding_internal(
     if let Some(binding) = binding_tag {
         transcript.append_message(b"bind", binding);
     }
+    append_window(&mut transcript, window);
 
     let (proof, commitment) = RangeProof::prove_single(
         &bp_gens,
@@ -149,6 +275,7 @@ fn prove_value_commitment_with_binding_internal(
     if let Some(binding) = binding_tag {
         verify_transcript.append_message(b"bind", binding);
     }
+    append_window(&mut verify_transcript, window);
 
     let verified = RangeProof::verify_single(
         &proof,
@@ -167,6 +294,7 @@ fn verify_value_commitment_with_binding_internal(
     commitment: CompressedRistretto,
     proof_bytes: Vec<u8>,
     binding_tag: Option<&[u8]>,
+    window: Option<&ValidityWindow>,
 ) -> bool {
     let pc_gens = PedersenGens::default();
     let bp_gens = BulletproofGens::new(64, 1);
@@ -175,6 +303,7 @@ fn verify_value_commitment_with_binding_internal(
     if let Some(binding) = binding_tag {
         transcript.append_message(b"bind", binding);
     }
+    append_window(&mut transcript, window);
 
     let proof = match RangeProof::from_bytes(&proof_bytes) {
         Ok(proof) => proof,
@@ -198,6 +327,7 @@ fn prove_equality_internal(
     r_left: Scalar,
     r_right: Scalar,
     binding_context: &[u8],
+    window: Option<&ValidityWindow>,
 ) -> Result<EqualityProof, JsValue> {
     let pc_gens = PedersenGens::default();
     let delta_r = r_left - r_right;
@@ -206,6 +336,7 @@ fn prove_equality_internal(
     transcript.append_message(b"context", binding_context);
     transcript.append_message(b"C_price", c_left.as_bytes());
     transcript.append_message(b"C_pay", c_right.as_bytes());
+    append_window(&mut transcript, window);
 
     let k = Scalar::random(&mut OsRng);
     let r_point = &k * &pc_gens.B_blinding;
@@ -229,6 +360,7 @@ fn verify_equality_internal(
     c_right: CompressedRistretto,
     proof: &EqualityProof,
     binding_context: &[u8],
+    window: Option<&ValidityWindow>,
 ) -> bool {
     let pc_gens = PedersenGens::default();
 
@@ -236,6 +368,7 @@ fn verify_equality_internal(
     transcript.append_message(b"context", binding_context);
     transcript.append_message(b"C_price", c_left.as_bytes());
     transcript.append_message(b"C_pay", c_right.as_bytes());
+    append_window(&mut transcript, window);
     transcript.append_message(b"R", &proof.r_announcement);
 
     let mut c_bytes = [0u8; 64];
@@ -272,6 +405,7 @@ fn prove_quantity_total_internal(
     r_quantity: Scalar,
     r_total: Scalar,
     context_hash: &[u8],
+    window: Option<&ValidityWindow>,
 ) -> Result<QuantityTotalProof, JsValue> {
     let pc_gens = PedersenGens::default();
     let delta_r = r_total - (unit_price * r_quantity);
@@ -281,6 +415,7 @@ fn prove_quantity_total_internal(
     transcript.append_message(b"unit_price", unit_price.as_bytes());
     transcript.append_message(b"C_quantity", c_quantity.as_bytes());
     transcript.append_message(b"C_total", c_total.as_bytes());
+    append_window(&mut transcript, window);
 
     let k = Scalar::random(&mut OsRng);
     let r_point = &k * &pc_gens.B_blinding;
@@ -305,6 +440,7 @@ fn verify_quantity_total_internal(
     unit_price: Scalar,
     proof: &QuantityTotalProof,
     context_hash: &[u8],
+    window: Option<&ValidityWindow>,
 ) -> bool {
     let pc_gens = PedersenGens::default();
 
@@ -313,6 +449,7 @@ fn verify_quantity_total_internal(
     transcript.append_message(b"unit_price", unit_price.as_bytes());
     transcript.append_message(b"C_quantity", c_quantity.as_bytes());
     transcript.append_message(b"C_total", c_total.as_bytes());
+    append_window(&mut transcript, window);
     transcript.append_message(b"R", &proof.r_announcement);
 
     let mut c_bytes = [0u8; 64];
@@ -342,6 +479,685 @@ fn verify_quantity_total_internal(
     lhs.compress() == rhs.compress()
 }
 
+fn opening_transcript(commitment: CompressedRistretto, context_hash: &[u8]) -> Transcript {
+    let mut transcript = Transcript::new(b"OpeningProof-v1");
+    transcript.append_message(b"context_hash", context_hash);
+    transcript.append_message(b"C", commitment.as_bytes());
+    transcript
+}
+
+fn prove_opening_internal(
+    commitment: CompressedRistretto,
+    value: Scalar,
+    blinding: Scalar,
+    context_hash: &[u8],
+) -> Result<OpeningProof, JsValue> {
+    let pc_gens = PedersenGens::default();
+    if pc_gens.commit(value, blinding).compress() != commitment {
+        return Err(JsValue::from_str(
+            "value and blinding do not open the commitment",
+        ));
+    }
+
+    let mut transcript = opening_transcript(commitment, context_hash);
+
+    let k_v = Scalar::random(&mut OsRng);
+    let k_r = Scalar::random(&mut OsRng);
+    let r_compressed = pc_gens.commit(k_v, k_r).compress();
+
+    transcript.append_message(b"R", r_compressed.as_bytes());
+
+    let mut c_bytes = [0u8; 64];
+    transcript.challenge_bytes(b"challenge", &mut c_bytes);
+    let c = Scalar::from_bytes_mod_order_wide(&c_bytes);
+
+    Ok(OpeningProof {
+        r_announcement: r_compressed.to_bytes(),
+        s_value: (k_v + c * value).to_bytes(),
+        s_blinding: (k_r + c * blinding).to_bytes(),
+    })
+}
+
+fn verify_opening_internal(
+    commitment: CompressedRistretto,
+    proof: &OpeningProof,
+    context_hash: &[u8],
+) -> bool {
+    let pc_gens = PedersenGens::default();
+
+    let mut transcript = opening_transcript(commitment, context_hash);
+    transcript.append_message(b"R", &proof.r_announcement);
+
+    let mut c_bytes = [0u8; 64];
+    transcript.challenge_bytes(b"challenge", &mut c_bytes);
+    let c = Scalar::from_bytes_mod_order_wide(&c_bytes);
+
+    let r_point = match CompressedRistretto(proof.r_announcement).decompress() {
+        Some(point) => point,
+        None => return false,
+    };
+    let s_value = match Scalar::from_canonical_bytes(proof.s_value) {
+        Some(scalar) => scalar,
+        None => return false,
+    };
+    let s_blinding = match Scalar::from_canonical_bytes(proof.s_blinding) {
+        Some(scalar) => scalar,
+        None => return false,
+    };
+    let c_point = match commitment.decompress() {
+        Some(point) => point,
+        None => return false,
+    };
+
+    let lhs = pc_gens.commit(s_value, s_blinding);
+    let rhs = r_point + c * c_point;
+    lhs.compress() == rhs.compress()
+}
+
+// Comparison proof. Must match the server's `zk::comparison`: same
+// transcript and byte layout (u16 length of the difference proof, the
+// difference proof, then the 64-bit C_b proof).
+
+const COMPARISON_OPERAND_BITS: usize = 64;
+
+fn parse_bit_range(bit_range: Option<u32>) -> Result<usize, JsValue> {
+    match bit_range.unwrap_or(64) {
+        bits @ (8 | 16 | 32 | 64) => Ok(bits as usize),
+        _ => Err(JsValue::from_str("invalid bit_range: expected 8, 16, 32 or 64")),
+    }
+}
+
+fn comparison_transcript(
+    c_a: CompressedRistretto,
+    c_b: CompressedRistretto,
+    context_hash: &[u8],
+) -> Transcript {
+    let mut transcript = Transcript::new(b"ComparisonProof-v1");
+    transcript.append_message(b"context_hash", context_hash);
+    transcript.append_message(b"C_a", c_a.as_bytes());
+    transcript.append_message(b"C_b", c_b.as_bytes());
+    transcript
+}
+
+fn difference_commitment(
+    c_a: CompressedRistretto,
+    c_b: CompressedRistretto,
+) -> Option<CompressedRistretto> {
+    Some((c_a.decompress()? - c_b.decompress()?).compress())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn prove_greater_or_equal_internal(
+    c_a: CompressedRistretto,
+    c_b: CompressedRistretto,
+    a: u64,
+    b: u64,
+    r_a: Scalar,
+    r_b: Scalar,
+    context_hash: &[u8],
+    bit_range: usize,
+) -> Result<Vec<u8>, JsValue> {
+    let difference = a
+        .checked_sub(b)
+        .ok_or_else(|| JsValue::from_str("a is smaller than b"))?;
+    if bit_range < 64 && difference >> bit_range != 0 {
+        return Err(JsValue::from_str("difference does not fit in bit_range"));
+    }
+    let derived = difference_commitment(c_a, c_b)
+        .ok_or_else(|| JsValue::from_str("invalid commitment: point decompression failed"))?;
+
+    let pc_gens = PedersenGens::default();
+    let bp_gens = BulletproofGens::new(bit_range, 1);
+    let mut transcript = comparison_transcript(c_a, c_b, context_hash);
+    let (proof, commitment) = RangeProof::prove_single(
+        &bp_gens,
+        &pc_gens,
+        &mut transcript,
+        difference,
+        &(r_a - r_b),
+        bit_range,
+    )
+    .map_err(|_| JsValue::from_str("range proof generation failed"))?;
+
+    if commitment != derived {
+        return Err(JsValue::from_str(
+            "witness does not open the derived commitment",
+        ));
+    }
+    let operand_gens = BulletproofGens::new(COMPARISON_OPERAND_BITS, 1);
+    let (operand, _) =
+        RangeProof::prove_single(&operand_gens, &pc_gens, &mut transcript, b, &r_b, COMPARISON_OPERAND_BITS)
+            .map_err(|_| JsValue::from_str("range proof generation failed"))?;
+    let proof = proof.to_bytes();
+
+    let mut bytes = (proof.len() as u16).to_be_bytes().to_vec();
+    bytes.extend_from_slice(&proof);
+    bytes.extend_from_slice(&operand.to_bytes());
+    Ok(bytes)
+}
+
+fn verify_greater_or_equal_internal(
+    c_a: CompressedRistretto,
+    c_b: CompressedRistretto,
+    proof_bytes: &[u8],
+    context_hash: &[u8],
+    bit_range: usize,
+) -> bool {
+    let derived = match difference_commitment(c_a, c_b) {
+        Some(point) => point,
+        None => return false,
+    };
+    if proof_bytes.len() < 2 {
+        return false;
+    }
+    let len = u16::from_be_bytes([proof_bytes[0], proof_bytes[1]]) as usize;
+    let Some(difference_bytes) = proof_bytes.get(2..2 + len) else {
+        return false;
+    };
+    let operand_bytes = &proof_bytes[2 + len..];
+
+    let pc_gens = PedersenGens::default();
+    let mut transcript = comparison_transcript(c_a, c_b, context_hash);
+    let verify = |transcript: &mut Transcript, bytes: &[u8], point: &CompressedRistretto, bits: usize| {
+        RangeProof::from_bytes(bytes)
+            .map(|proof| proof.verify_single(&BulletproofGens::new(bits, 1), &pc_gens, transcript, point, bits).is_ok())
+            .unwrap_or(false)
+    };
+    verify(&mut transcript, difference_bytes, &derived, bit_range)
+        && verify(&mut transcript, operand_bytes, &c_b, COMPARISON_OPERAND_BITS)
+}
+
+// Battery passport threshold proofs. Must match the server's `zk::passport`:
+// same transcript, shifted relation and proof byte layout.
+
+const PASSPORT_THRESHOLD_BITS: usize = 32;
+const PASSPORT_MAX_STATE_OF_HEALTH_BPS: u64 = 10_000;
+
+fn parse_battery_attribute(attribute: &str) -> Result<(&'static str, bool), JsValue> {
+    match attribute {
+        "stateOfHealth" => Ok(("stateOfHealth", false)),
+        "capacity" => Ok(("capacity", false)),
+        "cycleCount" => Ok(("cycleCount", true)),
+        _ => Err(JsValue::from_str("invalid attribute: expected stateOfHealth, capacity or cycleCount")),
+    }
+}
+
+fn check_battery_value(attribute: &str, value: u64, field_name: &str) -> Result<(), JsValue> {
+    if attribute == "stateOfHealth" && value > PASSPORT_MAX_STATE_OF_HEALTH_BPS {
+        return Err(JsValue::from_str(&format!("{field_name} must be at most 10000 basis points")));
+    }
+    if value >> PASSPORT_THRESHOLD_BITS != 0 {
+        return Err(JsValue::from_str(&format!("{field_name} does not fit in 32 bits")));
+    }
+    Ok(())
+}
+
+/// Transcript up to and including the shifted relation statement, plus the
+/// shifted commitment D and its sign (D = ±(C_attr - k * B)).
+fn passport_transcript(
+    attribute: &str,
+    is_maximum: bool,
+    threshold: u64,
+    commitment: CompressedRistretto,
+    product_id: &str,
+    batch: &str,
+    context_hash: &[u8],
+) -> Option<(Transcript, CompressedRistretto, Scalar)> {
+    let sign = if is_maximum { -Scalar::one() } else { Scalar::one() };
+    let mut transcript = Transcript::new(b"BatteryPassportProof-v1");
+    transcript.append_message(b"context_hash", context_hash);
+    transcript.append_message(b"product_id", product_id.as_bytes());
+    transcript.append_message(b"batch", batch.as_bytes());
+    transcript.append_message(b"attribute", attribute.as_bytes());
+    transcript.append_u64(b"threshold", threshold);
+    transcript.append_message(b"C_attr", commitment.as_bytes());
+    transcript.append_u64(b"n", 1);
+    transcript.append_message(b"a", sign.as_bytes());
+    transcript.append_message(b"C", commitment.as_bytes());
+    transcript.append_message(b"constant", (sign * Scalar::from(threshold)).as_bytes());
+
+    let shifted = sign * (commitment.decompress()? - Scalar::from(threshold) * PedersenGens::default().B);
+    Some((transcript, shifted.compress(), sign))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn prove_battery_threshold_internal(
+    attribute: &str,
+    value: u64,
+    blinding: Scalar,
+    threshold: u64,
+    product_id: &str,
+    batch: &str,
+    context_hash: &[u8],
+) -> Result<(CompressedRistretto, Vec<u8>), JsValue> {
+    let (attribute, is_maximum) = parse_battery_attribute(attribute)?;
+    check_battery_value(attribute, value, "value")?;
+    check_battery_value(attribute, threshold, "threshold")?;
+    let margin = if is_maximum { threshold.checked_sub(value) } else { value.checked_sub(threshold) }
+        .ok_or_else(|| JsValue::from_str(&format!("{attribute} does not meet the threshold")))?;
+
+    let pc_gens = PedersenGens::default();
+    let bp_gens = BulletproofGens::new(PASSPORT_THRESHOLD_BITS, 1);
+    let commitment = pc_gens.commit(Scalar::from(value), blinding).compress();
+    let (mut transcript, _, sign) =
+        passport_transcript(attribute, is_maximum, threshold, commitment, product_id, batch, context_hash)
+            .ok_or_else(|| JsValue::from_str("invalid commitment: point decompression failed"))?;
+    let (shifted, _) = RangeProof::prove_single(&bp_gens, &pc_gens, &mut transcript, margin, &(sign * blinding), PASSPORT_THRESHOLD_BITS)
+        .map_err(|_| JsValue::from_str("range proof generation failed"))?;
+    let shifted = shifted.to_bytes();
+
+    let mut bytes = (shifted.len() as u16).to_be_bytes().to_vec();
+    bytes.extend_from_slice(&shifted);
+    if is_maximum {
+        let (own, _) = RangeProof::prove_single(&bp_gens, &pc_gens, &mut transcript, value, &blinding, PASSPORT_THRESHOLD_BITS)
+            .map_err(|_| JsValue::from_str("range proof generation failed"))?;
+        bytes.extend_from_slice(&own.to_bytes());
+    }
+    Ok((commitment, bytes))
+}
+
+fn verify_battery_threshold_internal(
+    attribute: &str,
+    threshold: u64,
+    commitment: CompressedRistretto,
+    proof_bytes: &[u8],
+    product_id: &str,
+    batch: &str,
+    context_hash: &[u8],
+) -> bool {
+    let Ok((attribute, is_maximum)) = parse_battery_attribute(attribute) else {
+        return false;
+    };
+    if check_battery_value(attribute, threshold, "threshold").is_err() || proof_bytes.len() < 2 {
+        return false;
+    }
+    let len = u16::from_be_bytes([proof_bytes[0], proof_bytes[1]]) as usize;
+    let Some(shifted_bytes) = proof_bytes.get(2..2 + len) else {
+        return false;
+    };
+    let own_bytes = &proof_bytes[2 + len..];
+    if is_maximum == own_bytes.is_empty() {
+        return false;
+    }
+    let Some((mut transcript, shifted, _)) =
+        passport_transcript(attribute, is_maximum, threshold, commitment, product_id, batch, context_hash)
+    else {
+        return false;
+    };
+
+    let pc_gens = PedersenGens::default();
+    let bp_gens = BulletproofGens::new(PASSPORT_THRESHOLD_BITS, 1);
+    let verify = |transcript: &mut Transcript, bytes: &[u8], point: &CompressedRistretto| {
+        RangeProof::from_bytes(bytes)
+            .map(|proof| proof.verify_single(&bp_gens, &pc_gens, transcript, point, PASSPORT_THRESHOLD_BITS).is_ok())
+            .unwrap_or(false)
+    };
+    verify(&mut transcript, shifted_bytes, &shifted) && (!is_maximum || verify(&mut transcript, own_bytes, &commitment))
+}
+
+// Recycled-content ratio proof. Must match the server's
+// `zk::recycled_content`: same transcript, derived commitment and byte layout
+// (u16 length of the share proof, the share proof, then the C_total proof).
+
+const RECYCLED_CONTENT_BITS: usize = 64;
+
+fn parse_material(material: &str) -> Result<&'static str, JsValue> {
+    match material {
+        "cobalt" => Ok("cobalt"),
+        "lithium" => Ok("lithium"),
+        "nickel" => Ok("nickel"),
+        "lead" => Ok("lead"),
+        _ => Err(JsValue::from_str("invalid material: expected cobalt, lithium, nickel or lead")),
+    }
+}
+
+/// Transcript up to and including the relation statement, plus
+/// D = 100 * C_recycled - percent * C_total.
+fn recycled_content_transcript(
+    material: &str,
+    percent: u64,
+    c_recycled: CompressedRistretto,
+    c_total: CompressedRistretto,
+    batch: &str,
+    context_hash: &[u8],
+) -> Option<(Transcript, CompressedRistretto)> {
+    let hundred = Scalar::from(100u64);
+    let minus_percent = -Scalar::from(percent);
+    let mut transcript = Transcript::new(b"RecycledContentProof-v1");
+    transcript.append_message(b"context_hash", context_hash);
+    transcript.append_message(b"batch", batch.as_bytes());
+    transcript.append_message(b"material", material.as_bytes());
+    transcript.append_u64(b"percent", percent);
+    transcript.append_message(b"C_recycled", c_recycled.as_bytes());
+    transcript.append_message(b"C_total", c_total.as_bytes());
+    transcript.append_u64(b"n", 2);
+    transcript.append_message(b"a", hundred.as_bytes());
+    transcript.append_message(b"C", c_recycled.as_bytes());
+    transcript.append_message(b"a", minus_percent.as_bytes());
+    transcript.append_message(b"C", c_total.as_bytes());
+    transcript.append_message(b"constant", Scalar::zero().as_bytes());
+
+    let derived = hundred * c_recycled.decompress()? + minus_percent * c_total.decompress()?;
+    Some((transcript, derived.compress()))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn prove_recycled_content_internal(
+    material: &str,
+    recycled: u64,
+    total: u64,
+    r_recycled: Scalar,
+    r_total: Scalar,
+    percent: u64,
+    batch: &str,
+    context_hash: &[u8],
+) -> Result<(CompressedRistretto, CompressedRistretto, Vec<u8>), JsValue> {
+    let material = parse_material(material)?;
+    if percent > 100 {
+        return Err(JsValue::from_str("percent must be between 0 and 100"));
+    }
+    let difference = (100 * u128::from(recycled))
+        .checked_sub(u128::from(percent) * u128::from(total))
+        .ok_or_else(|| JsValue::from_str(&format!("recycled share is below {percent} %")))?;
+    let difference = u64::try_from(difference)
+        .map_err(|_| JsValue::from_str("masses too large for a 64-bit range proof"))?;
+
+    let pc_gens = PedersenGens::default();
+    let bp_gens = BulletproofGens::new(RECYCLED_CONTENT_BITS, 1);
+    let c_recycled = pc_gens.commit(Scalar::from(recycled), r_recycled).compress();
+    let c_total = pc_gens.commit(Scalar::from(total), r_total).compress();
+    let (mut transcript, _) = recycled_content_transcript(material, percent, c_recycled, c_total, batch, context_hash)
+        .ok_or_else(|| JsValue::from_str("invalid commitment: point decompression failed"))?;
+    let blinding = Scalar::from(100u64) * r_recycled - Scalar::from(percent) * r_total;
+    let (share, _) = RangeProof::prove_single(&bp_gens, &pc_gens, &mut transcript, difference, &blinding, RECYCLED_CONTENT_BITS)
+        .map_err(|_| JsValue::from_str("range proof generation failed"))?;
+    let (own, _) = RangeProof::prove_single(&bp_gens, &pc_gens, &mut transcript, total, &r_total, RECYCLED_CONTENT_BITS)
+        .map_err(|_| JsValue::from_str("range proof generation failed"))?;
+    let share = share.to_bytes();
+
+    let mut bytes = (share.len() as u16).to_be_bytes().to_vec();
+    bytes.extend_from_slice(&share);
+    bytes.extend_from_slice(&own.to_bytes());
+    Ok((c_recycled, c_total, bytes))
+}
+
+fn verify_recycled_content_internal(
+    material: &str,
+    percent: u64,
+    c_recycled: CompressedRistretto,
+    c_total: CompressedRistretto,
+    proof_bytes: &[u8],
+    batch: &str,
+    context_hash: &[u8],
+) -> bool {
+    let Ok(material) = parse_material(material) else {
+        return false;
+    };
+    if percent > 100 || proof_bytes.len() < 2 {
+        return false;
+    }
+    let len = u16::from_be_bytes([proof_bytes[0], proof_bytes[1]]) as usize;
+    let Some(share_bytes) = proof_bytes.get(2..2 + len) else {
+        return false;
+    };
+    let total_bytes = &proof_bytes[2 + len..];
+    let Some((mut transcript, derived)) =
+        recycled_content_transcript(material, percent, c_recycled, c_total, batch, context_hash)
+    else {
+        return false;
+    };
+
+    let pc_gens = PedersenGens::default();
+    let bp_gens = BulletproofGens::new(RECYCLED_CONTENT_BITS, 1);
+    let verify = |transcript: &mut Transcript, bytes: &[u8], point: &CompressedRistretto| {
+        RangeProof::from_bytes(bytes)
+            .map(|proof| proof.verify_single(&bp_gens, &pc_gens, transcript, point, RECYCLED_CONTENT_BITS).is_ok())
+            .unwrap_or(false)
+    };
+    verify(&mut transcript, share_bytes, &derived) && verify(&mut transcript, total_bytes, &c_total)
+}
+
+// Multi-party aggregated range proof (party side). Must match the server's
+// `zk::mpc_range`: same transcript, generators and message byte layouts.
+
+const MPC_MAX_PARTIES: usize = 16;
+
+static MPC_PC_GENS: LazyLock<PedersenGens> = LazyLock::new(PedersenGens::default);
+static MPC_BP_GENS: LazyLock<BulletproofGens> =
+    LazyLock::new(|| BulletproofGens::new(64, MPC_MAX_PARTIES));
+
+fn mpc_transcript(context_hash: &[u8]) -> Transcript {
+    let mut transcript = Transcript::new(b"MpcRangeProof-v1");
+    transcript.append_message(b"context_hash", context_hash);
+    transcript
+}
+
+fn mpc_error(error: MPCError) -> JsValue {
+    JsValue::from_str(&format!("mpc range proof: {error}"))
+}
+
+// The message types keep their fields private but derive serde; these mirrors
+// have the same field names, so a serde round trip converts between them.
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize)]
+struct BitCommitmentWire {
+    V_j: CompressedRistretto,
+    A_j: CompressedRistretto,
+    S_j: CompressedRistretto,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BitChallengeWire {
+    y: Scalar,
+    z: Scalar,
+}
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize)]
+struct PolyCommitmentWire {
+    T_1_j: CompressedRistretto,
+    T_2_j: CompressedRistretto,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PolyChallengeWire {
+    x: Scalar,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ProofShareWire {
+    t_x: Scalar,
+    t_x_blinding: Scalar,
+    e_blinding: Scalar,
+    l_vec: Vec<Scalar>,
+    r_vec: Vec<Scalar>,
+}
+
+fn mpc_convert<A: Serialize, B: DeserializeOwned>(value: &A) -> Option<B> {
+    serde_json::from_value(serde_json::to_value(value).ok()?).ok()
+}
+
+fn mpc_scalar(bytes: &[u8]) -> Option<Scalar> {
+    Scalar::from_canonical_bytes(bytes.try_into().ok()?)
+}
+
+fn encode_bit_commitment(message: &BitCommitment) -> Vec<u8> {
+    let wire: BitCommitmentWire = mpc_convert(message).expect("bit commitment fields");
+    [wire.V_j.to_bytes(), wire.A_j.to_bytes(), wire.S_j.to_bytes()].concat()
+}
+
+fn decode_bit_challenge(bytes: &[u8]) -> Option<BitChallenge> {
+    if bytes.len() != 64 {
+        return None;
+    }
+    mpc_convert(&BitChallengeWire {
+        y: mpc_scalar(&bytes[..32])?,
+        z: mpc_scalar(&bytes[32..])?,
+    })
+}
+
+fn encode_poly_commitment(message: &PolyCommitment) -> Vec<u8> {
+    let wire: PolyCommitmentWire = mpc_convert(message).expect("poly commitment fields");
+    [wire.T_1_j.to_bytes(), wire.T_2_j.to_bytes()].concat()
+}
+
+fn decode_poly_challenge(bytes: &[u8]) -> Option<PolyChallenge> {
+    if bytes.len() != 32 {
+        return None;
+    }
+    mpc_convert(&PolyChallengeWire { x: mpc_scalar(bytes)? })
+}
+
+fn encode_proof_share(message: &ProofShare) -> Vec<u8> {
+    let wire: ProofShareWire = mpc_convert(message).expect("proof share fields");
+    [wire.t_x, wire.t_x_blinding, wire.e_blinding]
+        .iter()
+        .chain(&wire.l_vec)
+        .chain(&wire.r_vec)
+        .flat_map(|s| s.to_bytes())
+        .collect()
+}
+
+fn verify_mpc_range_internal(
+    commitments: &[CompressedRistretto],
+    proof_bytes: &[u8],
+    context_hash: &[u8],
+    bits: usize,
+) -> bool {
+    if !commitments.len().is_power_of_two() || commitments.len() > MPC_MAX_PARTIES {
+        return false;
+    }
+    let proof = match RangeProof::from_bytes(proof_bytes) {
+        Ok(proof) => proof,
+        Err(_) => return false,
+    };
+    proof
+        .verify_multiple(
+            &MPC_BP_GENS,
+            &MPC_PC_GENS,
+            &mut mpc_transcript(context_hash),
+            commitments,
+            bits,
+        )
+        .is_ok()
+}
+
+enum MpcPartyState {
+    AwaitingPosition(PartyAwaitingPosition<'static>),
+    AwaitingBitChallenge(PartyAwaitingBitChallenge<'static>),
+    AwaitingPolyChallenge(Box<PartyAwaitingPolyChallenge>),
+    Done,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct BbsAttribute {
+    name: String,
+    value: String,
+    #[serde(default)]
+    integer: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BbsLinkOpening {
+    index: usize,
+    commitment_hex: String,
+    blinding_hex: String,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct BbsDisclosedAttribute {
+    index: usize,
+    attribute: BbsAttribute,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct BbsLink {
+    index: usize,
+    name: String,
+    commitment_hex: String,
+}
+
+#[derive(Serialize)]
+struct BatteryThresholdProofResponse {
+    commitment_hex: String,
+    proof_hex: String,
+    verified: bool,
+}
+
+#[derive(Serialize)]
+struct RecycledContentProofResponse {
+    c_recycled_hex: String,
+    c_total_hex: String,
+    proof_hex: String,
+    verified: bool,
+}
+
+#[derive(Serialize)]
+struct BbsKeypairResponse {
+    secret_key_hex: String,
+    public_key_hex: String,
+}
+
+#[derive(Serialize)]
+struct BbsSignatureResponse {
+    signature_hex: String,
+    public_key_hex: String,
+    attribute_count: usize,
+}
+
+#[derive(Serialize)]
+struct BbsProofResponse {
+    proof_hex: String,
+    attribute_count: usize,
+    disclosed: Vec<BbsDisclosedAttribute>,
+    links: Vec<BbsLink>,
+}
+
+fn from_js_value<T: DeserializeOwned>(value: JsValue, field_name: &str) -> Result<T, JsValue> {
+    serde_wasm_bindgen::from_value(value).map_err(|e| JsValue::from_str(&format!("invalid {field_name}: {e}")))
+}
+
+/// `undefined` / `null` mean no window.
+fn parse_validity(validity: JsValue) -> Result<Option<ValidityWindow>, JsValue> {
+    if validity.is_undefined() || validity.is_null() {
+        return Ok(None);
+    }
+    from_js_value(validity, "validity").map(Some)
+}
+
+/// There is no trusted clock here, so windowed proofs need the caller's
+/// reference point (unix seconds or block number, matching the window).
+fn parse_reference_time(window: Option<&ValidityWindow>, reference_time: Option<String>) -> Result<u64, JsValue> {
+    match (window, reference_time) {
+        (_, Some(reference_time)) => reference_time
+            .trim()
+            .parse::<u64>()
+            .map_err(|_| JsValue::from_str("invalid reference_time: expected unsigned 64-bit integer string")),
+        (Some(_), None) => Err(JsValue::from_str("reference_time is required with a validity window")),
+        (None, None) => Ok(0),
+    }
+}
+
+fn parse_bbs_public_key(input: &str) -> Result<bbs::PublicKey, JsValue> {
+    bbs::PublicKey::from_bytes(&parse_hex_vec(input, "public_key_hex")?)
+        .ok_or_else(|| JsValue::from_str("invalid public_key_hex"))
+}
+
+fn bbs_scalars(attributes: &[BbsAttribute]) -> Result<Vec<ark_bls12_381::Fr>, JsValue> {
+    if attributes.is_empty() || attributes.len() > bbs::MAX_ATTRIBUTES {
+        return Err(JsValue::from_str(&format!("attributes must hold 1 to {} entries", bbs::MAX_ATTRIBUTES)));
+    }
+    attributes
+        .iter()
+        .map(|a| bbs::attribute_scalar(&a.name, &a.value, a.integer).map_err(|e| JsValue::from_str(&e)))
+        .collect()
+}
+
 #[wasm_bindgen(start)]
 pub fn wasm_start() {
     console_error_panic_hook::set_once();
@@ -357,7 +1173,7 @@ pub fn generate_value_commitment_with_blinding(
     value: String,
     blinding_hex: String,
 ) -> Result<JsValue, JsValue> {
-    generate_value_commitment_with_binding(value, blinding_hex, None)
+    generate_value_commitment_with_binding(value, blinding_hex, None, JsValue::UNDEFINED)
 }
 
 #[wasm_bindgen]
@@ -365,18 +1181,22 @@ pub fn generate_value_commitment_with_binding(
     value: String,
     blinding_hex: String,
     binding_tag_hex: Option<String>,
+    validity: JsValue,
 ) -> Result<JsValue, JsValue> {
     let value_u64 = parse_u64(&value)?;
     let blinding = Scalar::from_bytes_mod_order(parse_fixed_32_hex(&blinding_hex, "blinding_hex")?);
     let binding_tag = parse_optional_binding_tag(binding_tag_hex)?;
+    let window = parse_validity(validity)?;
+    window.as_ref().map(ValidityWindow::check).transpose()?;
 
     let (commitment, proof_bytes, verified) =
-        prove_value_commitment_with_binding_internal(value_u64, blinding, binding_tag.as_deref())?;
+        prove_value_commitment_with_binding_internal(value_u64, blinding, binding_tag.as_deref(), window.as_ref())?;
 
     to_js_value(&ValueCommitmentResponse {
         commitment: hex::encode(commitment.as_bytes()),
         proof: hex::encode(proof_bytes),
         verified,
+        validity: window,
     })
 }
 
@@ -385,18 +1205,22 @@ pub fn verify_value_commitment(
     commitment_hex: String,
     proof_hex: String,
     binding_tag_hex: Option<String>,
+    validity: JsValue,
+    reference_time: Option<String>,
 ) -> Result<JsValue, JsValue> {
     let commitment = parse_commitment_hex(&commitment_hex, "commitment")?;
     let proof_bytes = parse_hex_vec(&proof_hex, "proof")?;
     let binding_tag = parse_optional_binding_tag(binding_tag_hex)?;
-
-    to_js_value(&VerifyResponse {
-        verified: verify_value_commitment_with_binding_internal(
-            commitment,
-            proof_bytes,
-            binding_tag.as_deref(),
-        ),
-    })
+    let window = parse_validity(validity)?;
+    let reference = parse_reference_time(window.as_ref(), reference_time)?;
+
+    let holds = verify_value_commitment_with_binding_internal(
+        commitment,
+        proof_bytes,
+        binding_tag.as_deref(),
+        window.as_ref(),
+    );
+    to_js_value(&windowed_response(holds, window.as_ref(), reference))
 }
 
 #[wasm_bindgen]
@@ -425,20 +1249,24 @@ pub fn generate_equality_proof(
     r_left_hex: String,
     r_right_hex: String,
     context_hash_hex: String,
+    validity: JsValue,
 ) -> Result<JsValue, JsValue> {
     let c_left = parse_commitment_hex(&c_left_hex, "c_left_hex")?;
     let c_right = parse_commitment_hex(&c_right_hex, "c_right_hex")?;
     let r_left = parse_scalar_hex(&r_left_hex, "r_left_hex")?;
     let r_right = parse_scalar_hex(&r_right_hex, "r_right_hex")?;
     let context_hash = parse_fixed_32_hex(&context_hash_hex, "context_hash_hex")?;
+    let window = parse_validity(validity)?;
+    window.as_ref().map(ValidityWindow::check).transpose()?;
 
-    let proof = prove_equality_internal(c_left, c_right, r_left, r_right, &context_hash)?;
-    let verified = verify_equality_internal(c_left, c_right, &proof, &context_hash);
+    let proof = prove_equality_internal(c_left, c_right, r_left, r_right, &context_hash, window.as_ref())?;
+    let verified = verify_equality_internal(c_left, c_right, &proof, &context_hash, window.as_ref());
 
     to_js_value(&EqualityProofResponse {
         proof_r_hex: hex::encode(proof.r_announcement),
         proof_s_hex: hex::encode(proof.s_response),
         verified,
+        validity: window,
     })
 }
 
@@ -449,6 +1277,8 @@ pub fn verify_equality_proof(
     proof_r_hex: String,
     proof_s_hex: String,
     context_hash_hex: String,
+    validity: JsValue,
+    reference_time: Option<String>,
 ) -> Result<JsValue, JsValue> {
     let c_left = parse_commitment_hex(&c_left_hex, "c_left_hex")?;
     let c_right = parse_commitment_hex(&c_right_hex, "c_right_hex")?;
@@ -457,10 +1287,11 @@ pub fn verify_equality_proof(
         r_announcement: parse_fixed_32_hex(&proof_r_hex, "proof_r_hex")?,
         s_response: parse_fixed_32_hex(&proof_s_hex, "proof_s_hex")?,
     };
+    let window = parse_validity(validity)?;
+    let reference = parse_reference_time(window.as_ref(), reference_time)?;
 
-    to_js_value(&VerifyResponse {
-        verified: verify_equality_internal(c_left, c_right, &proof, &context_hash),
-    })
+    let holds = verify_equality_internal(c_left, c_right, &proof, &context_hash, window.as_ref());
+    to_js_value(&windowed_response(holds, window.as_ref(), reference))
 }
 
 #[wasm_bindgen]
@@ -471,6 +1302,7 @@ pub fn generate_quantity_total_proof(
     r_quantity_hex: String,
     r_total_hex: String,
     context_hash_hex: String,
+    validity: JsValue,
 ) -> Result<JsValue, JsValue> {
     let c_quantity = parse_commitment_hex(&c_quantity_hex, "c_quantity_hex")?;
     let c_total = parse_commitment_hex(&c_total_hex, "c_total_hex")?;
@@ -478,6 +1310,8 @@ pub fn generate_quantity_total_proof(
     let r_quantity = parse_scalar_hex(&r_quantity_hex, "r_quantity_hex")?;
     let r_total = parse_scalar_hex(&r_total_hex, "r_total_hex")?;
     let context_hash = parse_fixed_32_hex(&context_hash_hex, "context_hash_hex")?;
+    let window = parse_validity(validity)?;
+    window.as_ref().map(ValidityWindow::check).transpose()?;
 
     let proof = prove_quantity_total_internal(
         c_quantity,
@@ -486,17 +1320,21 @@ pub fn generate_quantity_total_proof(
         r_quantity,
         r_total,
         &context_hash,
+        window.as_ref(),
     )?;
-    let verified = verify_quantity_total_internal(c_quantity, c_total, unit_price, &proof, &context_hash);
+    let verified =
+        verify_quantity_total_internal(c_quantity, c_total, unit_price, &proof, &context_hash, window.as_ref());
 
     to_js_value(&EqualityProofResponse {
         proof_r_hex: hex::encode(proof.r_announcement),
         proof_s_hex: hex::encode(proof.s_response),
         verified,
+        validity: window,
     })
 }
 
 #[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
 pub fn verify_quantity_total_proof(
     c_quantity_hex: String,
     c_total_hex: String,
@@ -504,6 +1342,8 @@ pub fn verify_quantity_total_proof(
     proof_r_hex: String,
     proof_s_hex: String,
     context_hash_hex: String,
+    validity: JsValue,
+    reference_time: Option<String>,
 ) -> Result<JsValue, JsValue> {
     let c_quantity = parse_commitment_hex(&c_quantity_hex, "c_quantity_hex")?;
     let c_total = parse_commitment_hex(&c_total_hex, "c_total_hex")?;
@@ -513,10 +1353,11 @@ pub fn verify_quantity_total_proof(
         r_announcement: parse_fixed_32_hex(&proof_r_hex, "proof_r_hex")?,
         s_response: parse_fixed_32_hex(&proof_s_hex, "proof_s_hex")?,
     };
+    let window = parse_validity(validity)?;
+    let reference = parse_reference_time(window.as_ref(), reference_time)?;
 
-    to_js_value(&VerifyResponse {
-        verified: verify_quantity_total_internal(c_quantity, c_total, unit_price, &proof, &context_hash),
-    })
+    let holds = verify_quantity_total_internal(c_quantity, c_total, unit_price, &proof, &context_hash, window.as_ref());
+    to_js_value(&windowed_response(holds, window.as_ref(), reference))
 }
 
 #[wasm_bindgen]
@@ -526,8 +1367,9 @@ pub fn generate_total_payment_equality_proof(
     r_total_hex: String,
     r_pay_hex: String,
     context_hash_hex: String,
+    validity: JsValue,
 ) -> Result<JsValue, JsValue> {
-    generate_equality_proof(c_total_hex, c_pay_hex, r_total_hex, r_pay_hex, context_hash_hex)
+    generate_equality_proof(c_total_hex, c_pay_hex, r_total_hex, r_pay_hex, context_hash_hex, validity)
 }
 
 #[wasm_bindgen]
@@ -537,6 +1379,437 @@ pub fn verify_total_payment_equality_proof(
     proof_r_hex: String,
     proof_s_hex: String,
     context_hash_hex: String,
+    validity: JsValue,
+    reference_time: Option<String>,
+) -> Result<JsValue, JsValue> {
+    verify_equality_proof(c_total_hex, c_pay_hex, proof_r_hex, proof_s_hex, context_hash_hex, validity, reference_time)
+}
+
+#[wasm_bindgen]
+pub fn generate_opening_proof(
+    value: String,
+    blinding_hex: String,
+    context_hash_hex: String,
+) -> Result<JsValue, JsValue> {
+    let value_scalar = parse_decimal_scalar_strict(&value)?;
+    let blinding = parse_scalar_hex(&blinding_hex, "blinding_hex")?;
+    let context_hash = parse_fixed_32_hex(&context_hash_hex, "context_hash_hex")?;
+    let commitment = PedersenGens::default()
+        .commit(value_scalar, blinding)
+        .compress();
+
+    let proof = prove_opening_internal(commitment, value_scalar, blinding, &context_hash)?;
+    let verified = verify_opening_internal(commitment, &proof, &context_hash);
+
+    to_js_value(&OpeningProofResponse {
+        commitment: hex::encode(commitment.as_bytes()),
+        proof_r_hex: hex::encode(proof.r_announcement),
+        proof_s_value_hex: hex::encode(proof.s_value),
+        proof_s_blinding_hex: hex::encode(proof.s_blinding),
+        verified,
+    })
+}
+
+#[wasm_bindgen]
+pub fn verify_opening_proof(
+    commitment_hex: String,
+    proof_r_hex: String,
+    proof_s_value_hex: String,
+    proof_s_blinding_hex: String,
+    context_hash_hex: String,
+) -> Result<JsValue, JsValue> {
+    let commitment = parse_commitment_hex(&commitment_hex, "commitment_hex")?;
+    let context_hash = parse_fixed_32_hex(&context_hash_hex, "context_hash_hex")?;
+    let proof = OpeningProof {
+        r_announcement: parse_fixed_32_hex(&proof_r_hex, "proof_r_hex")?,
+        s_value: parse_fixed_32_hex(&proof_s_value_hex, "proof_s_value_hex")?,
+        s_blinding: parse_fixed_32_hex(&proof_s_blinding_hex, "proof_s_blinding_hex")?,
+    };
+
+    to_js_value(&VerifyResponse {
+        verified: verify_opening_internal(commitment, &proof, &context_hash),
+    })
+}
+
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn generate_comparison_proof(
+    c_a_hex: String,
+    c_b_hex: String,
+    a: String,
+    b: String,
+    r_a_hex: String,
+    r_b_hex: String,
+    context_hash_hex: String,
+    bit_range: Option<u32>,
+) -> Result<JsValue, JsValue> {
+    let c_a = parse_commitment_hex(&c_a_hex, "c_a_hex")?;
+    let c_b = parse_commitment_hex(&c_b_hex, "c_b_hex")?;
+    let a = parse_u64(&a)?;
+    let b = parse_u64(&b)?;
+    let r_a = parse_scalar_hex(&r_a_hex, "r_a_hex")?;
+    let r_b = parse_scalar_hex(&r_b_hex, "r_b_hex")?;
+    let context_hash = parse_fixed_32_hex(&context_hash_hex, "context_hash_hex")?;
+    let bit_range = parse_bit_range(bit_range)?;
+
+    let proof = prove_greater_or_equal_internal(c_a, c_b, a, b, r_a, r_b, &context_hash, bit_range)?;
+    let verified = verify_greater_or_equal_internal(c_a, c_b, &proof, &context_hash, bit_range);
+
+    to_js_value(&ComparisonProofResponse {
+        proof: hex::encode(proof),
+        bit_range,
+        verified,
+    })
+}
+
+#[wasm_bindgen]
+pub fn verify_comparison_proof(
+    c_a_hex: String,
+    c_b_hex: String,
+    proof_hex: String,
+    context_hash_hex: String,
+    bit_range: Option<u32>,
+) -> Result<JsValue, JsValue> {
+    let c_a = parse_commitment_hex(&c_a_hex, "c_a_hex")?;
+    let c_b = parse_commitment_hex(&c_b_hex, "c_b_hex")?;
+    let proof_bytes = parse_hex_vec(&proof_hex, "proof")?;
+    let context_hash = parse_fixed_32_hex(&context_hash_hex, "context_hash_hex")?;
+    let bit_range = parse_bit_range(bit_range)?;
+
+    to_js_value(&VerifyResponse {
+        verified: verify_greater_or_equal_internal(c_a, c_b, &proof_bytes, &context_hash, bit_range),
+    })
+}
+
+/// One party of a multi-party aggregated range proof session
+/// (`/zkp/mpc/sessions`). The value and blinding stay in this object; each
+/// method returns the hex message to post for the next round.
+#[wasm_bindgen]
+pub struct MpcRangeParty {
+    commitment: CompressedRistretto,
+    state: MpcPartyState,
+}
+
+#[wasm_bindgen]
+impl MpcRangeParty {
+    #[wasm_bindgen(constructor)]
+    pub fn new(value: String, blinding_hex: String, bits: Option<u32>) -> Result<MpcRangeParty, JsValue> {
+        let value = parse_u64(&value)?;
+        let blinding = parse_scalar_hex(&blinding_hex, "blinding_hex")?;
+        let bits = parse_bit_range(bits)?;
+        if bits < 64 && value >> bits != 0 {
+            return Err(JsValue::from_str(&format!("value does not fit in {bits} bits")));
+        }
+        let party = Party::new(&MPC_BP_GENS, &MPC_PC_GENS, value, blinding, bits).map_err(mpc_error)?;
+        Ok(MpcRangeParty {
+            commitment: MPC_PC_GENS.commit(Scalar::from(value), blinding).compress(),
+            state: MpcPartyState::AwaitingPosition(party),
+        })
+    }
+
+    pub fn commitment_hex(&self) -> String {
+        hex::encode(self.commitment.as_bytes())
+    }
+
+    /// Round 1 at `position` in the session.
+    pub fn bit_commitment(&mut self, position: u32) -> Result<String, JsValue> {
+        let MpcPartyState::AwaitingPosition(party) = std::mem::replace(&mut self.state, MpcPartyState::Done) else {
+            return Err(JsValue::from_str("bit commitment already sent"));
+        };
+        let (party, message) = party.assign_position(position as usize).map_err(mpc_error)?;
+        self.state = MpcPartyState::AwaitingBitChallenge(party);
+        Ok(hex::encode(encode_bit_commitment(&message)))
+    }
+
+    /// Round 2, answering the session's `bit_challenge_hex`.
+    pub fn poly_commitment(&mut self, bit_challenge_hex: String) -> Result<String, JsValue> {
+        let challenge = decode_bit_challenge(&parse_hex_vec(&bit_challenge_hex, "bit_challenge_hex")?)
+            .ok_or_else(|| JsValue::from_str("invalid bit_challenge_hex"))?;
+        let MpcPartyState::AwaitingBitChallenge(party) = std::mem::replace(&mut self.state, MpcPartyState::Done) else {
+            return Err(JsValue::from_str("not awaiting a bit challenge"));
+        };
+        let (party, message) = party.apply_challenge(&challenge);
+        self.state = MpcPartyState::AwaitingPolyChallenge(Box::new(party));
+        Ok(hex::encode(encode_poly_commitment(&message)))
+    }
+
+    /// Round 3, answering the session's `poly_challenge_hex`.
+    pub fn proof_share(&mut self, poly_challenge_hex: String) -> Result<String, JsValue> {
+        let challenge = decode_poly_challenge(&parse_hex_vec(&poly_challenge_hex, "poly_challenge_hex")?)
+            .ok_or_else(|| JsValue::from_str("invalid poly_challenge_hex"))?;
+        let MpcPartyState::AwaitingPolyChallenge(party) = std::mem::replace(&mut self.state, MpcPartyState::Done) else {
+            return Err(JsValue::from_str("not awaiting a poly challenge"));
+        };
+        let share = party.apply_challenge(&challenge).map_err(mpc_error)?;
+        Ok(hex::encode(encode_proof_share(&share)))
+    }
+}
+
+#[wasm_bindgen]
+pub fn verify_mpc_range_proof(
+    commitments_hex: Vec<String>,
+    proof_hex: String,
+    context_hash_hex: String,
+    bits: Option<u32>,
+) -> Result<JsValue, JsValue> {
+    let commitments = commitments_hex
+        .iter()
+        .map(|c| parse_commitment_hex(c, "commitments_hex"))
+        .collect::<Result<Vec<_>, _>>()?;
+    let proof_bytes = parse_hex_vec(&proof_hex, "proof")?;
+    let context_hash = parse_fixed_32_hex(&context_hash_hex, "context_hash_hex")?;
+    let bits = parse_bit_range(bits)?;
+
+    to_js_value(&VerifyResponse {
+        verified: verify_mpc_range_internal(&commitments, &proof_bytes, &context_hash, bits),
+    })
+}
+
+#[wasm_bindgen]
+pub fn generate_bbs_keypair() -> Result<JsValue, JsValue> {
+    let secret = bbs::SecretKey::random();
+    to_js_value(&BbsKeypairResponse {
+        secret_key_hex: hex::encode(secret.to_bytes()),
+        public_key_hex: hex::encode(secret.public_key().to_bytes()),
+    })
+}
+
+/// Signs `attributes` (`[{ name, value, integer? }]`) as a BBS+ issuer.
+#[wasm_bindgen]
+pub fn issue_bbs_signature(secret_key_hex: String, attributes: JsValue) -> Result<JsValue, JsValue> {
+    let secret = bbs::SecretKey::from_bytes(&parse_hex_vec(&secret_key_hex, "secret_key_hex")?)
+        .ok_or_else(|| JsValue::from_str("invalid secret_key_hex"))?;
+    let attributes: Vec<BbsAttribute> = from_js_value(attributes, "attributes")?;
+    let signature = bbs::sign(&secret, &bbs_scalars(&attributes)?).map_err(|e| JsValue::from_str(&e))?;
+    to_js_value(&BbsSignatureResponse {
+        signature_hex: hex::encode(signature.to_bytes()),
+        public_key_hex: hex::encode(secret.public_key().to_bytes()),
+        attribute_count: attributes.len(),
+    })
+}
+
+/// Reveals `disclosed` and ties each `links` entry
+/// (`[{ index, commitment_hex, blinding_hex }]`) to its hidden attribute.
+#[wasm_bindgen]
+pub fn derive_bbs_proof(
+    public_key_hex: String,
+    signature_hex: String,
+    attributes: JsValue,
+    disclosed: Vec<u32>,
+    links: JsValue,
+    context_hash_hex: String,
+) -> Result<JsValue, JsValue> {
+    let pk = parse_bbs_public_key(&public_key_hex)?;
+    let signature = bbs::Signature::from_bytes(&parse_hex_vec(&signature_hex, "signature_hex")?)
+        .ok_or_else(|| JsValue::from_str("invalid signature_hex"))?;
+    let attributes: Vec<BbsAttribute> = from_js_value(attributes, "attributes")?;
+    let links: Vec<BbsLinkOpening> = if links.is_undefined() || links.is_null() { Vec::new() } else { from_js_value(links, "links")? };
+    let context_hash = parse_fixed_32_hex(&context_hash_hex, "context_hash_hex")?;
+    let messages = bbs_scalars(&attributes)?;
+
+    let mut disclosed: Vec<usize> = disclosed.into_iter().map(|i| i as usize).collect();
+    disclosed.sort_unstable();
+    let mut openings = links
+        .iter()
+        .map(|link| {
+            let attribute = attributes
+                .get(link.index)
+                .filter(|attribute| attribute.integer)
+                .ok_or_else(|| JsValue::from_str(&format!("linked attribute {} must be an integer attribute", link.index)))?;
+            Ok(bbs::LinkOpening {
+                index: link.index,
+                offset: bbs::name_offset(&attribute.name),
+                commitment: parse_commitment_hex(&link.commitment_hex, "commitment_hex")?,
+                blinding: parse_scalar_hex(&link.blinding_hex, "blinding_hex")?,
+            })
+        })
+        .collect::<Result<Vec<_>, JsValue>>()?;
+    openings.sort_unstable_by_key(|link| link.index);
+
+    let proof = bbs::derive_proof(&pk, &signature, &messages, &disclosed, &openings, &context_hash)
+        .map_err(|e| JsValue::from_str(&e))?;
+    to_js_value(&BbsProofResponse {
+        proof_hex: hex::encode(proof.to_bytes()),
+        attribute_count: messages.len(),
+        disclosed: disclosed
+            .iter()
+            .map(|&index| BbsDisclosedAttribute { index, attribute: attributes[index].clone() })
+            .collect(),
+        links: openings
+            .iter()
+            .map(|link| BbsLink {
+                index: link.index,
+                name: attributes[link.index].name.clone(),
+                commitment_hex: hex::encode(link.commitment.as_bytes()),
+            })
+            .collect(),
+    })
+}
+
+#[wasm_bindgen]
+pub fn verify_bbs_proof(
+    public_key_hex: String,
+    attribute_count: u32,
+    disclosed: JsValue,
+    links: JsValue,
+    proof_hex: String,
+    context_hash_hex: String,
 ) -> Result<JsValue, JsValue> {
-    verify_equality_proof(c_total_hex, c_pay_hex, proof_r_hex, proof_s_hex, context_hash_hex)
+    let pk = parse_bbs_public_key(&public_key_hex)?;
+    let disclosed: Vec<BbsDisclosedAttribute> = from_js_value(disclosed, "disclosed")?;
+    let links: Vec<BbsLink> = if links.is_undefined() || links.is_null() { Vec::new() } else { from_js_value(links, "links")? };
+    let context_hash = parse_fixed_32_hex(&context_hash_hex, "context_hash_hex")?;
+    let count = attribute_count as usize;
+
+    let mut disclosed = disclosed
+        .iter()
+        .map(|d| {
+            bbs::attribute_scalar(&d.attribute.name, &d.attribute.value, d.attribute.integer)
+                .map(|m| (d.index, m))
+                .map_err(|e| JsValue::from_str(&e))
+        })
+        .collect::<Result<Vec<_>, JsValue>>()?;
+    disclosed.sort_unstable_by_key(|(index, _)| *index);
+    let mut links = links
+        .iter()
+        .map(|link| {
+            Ok(bbs::Link {
+                index: link.index,
+                offset: bbs::name_offset(&link.name),
+                commitment: parse_commitment_hex(&link.commitment_hex, "commitment_hex")?,
+            })
+        })
+        .collect::<Result<Vec<_>, JsValue>>()?;
+    links.sort_unstable_by_key(|link| link.index);
+
+    let unlinked = count.saturating_sub(disclosed.len() + links.len());
+    let verified = bbs::DisclosureProof::from_bytes(&parse_hex_vec(&proof_hex, "proof")?, unlinked, links.len())
+        .is_some_and(|proof| bbs::verify_proof(&pk, count, &disclosed, &links, &proof, &context_hash));
+    to_js_value(&VerifyResponse { verified })
+}
+
+/// Proves a battery passport threshold: `stateOfHealth` (basis points) and
+/// `capacity` (Wh) as minimums, `cycleCount` as a maximum.
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn generate_battery_threshold_proof(
+    attribute: String,
+    value: String,
+    blinding_hex: String,
+    threshold: String,
+    product_id: String,
+    batch: String,
+    context_hash_hex: String,
+) -> Result<JsValue, JsValue> {
+    let value = parse_u64(&value)?;
+    let blinding = parse_scalar_hex(&blinding_hex, "blinding_hex")?;
+    let threshold = parse_u64(&threshold)?;
+    let context_hash = parse_fixed_32_hex(&context_hash_hex, "context_hash_hex")?;
+
+    let (commitment, proof_bytes) =
+        prove_battery_threshold_internal(&attribute, value, blinding, threshold, &product_id, &batch, &context_hash)?;
+    let verified =
+        verify_battery_threshold_internal(&attribute, threshold, commitment, &proof_bytes, &product_id, &batch, &context_hash);
+    to_js_value(&BatteryThresholdProofResponse {
+        commitment_hex: hex::encode(commitment.as_bytes()),
+        proof_hex: hex::encode(proof_bytes),
+        verified,
+    })
+}
+
+#[wasm_bindgen]
+pub fn verify_battery_threshold_proof(
+    attribute: String,
+    commitment_hex: String,
+    threshold: String,
+    proof_hex: String,
+    product_id: String,
+    batch: String,
+    context_hash_hex: String,
+) -> Result<JsValue, JsValue> {
+    let commitment = parse_commitment_hex(&commitment_hex, "commitment_hex")?;
+    let threshold = parse_u64(&threshold)?;
+    let proof_bytes = parse_hex_vec(&proof_hex, "proof")?;
+    let context_hash = parse_fixed_32_hex(&context_hash_hex, "context_hash_hex")?;
+
+    to_js_value(&VerifyResponse {
+        verified: verify_battery_threshold_internal(
+            &attribute,
+            threshold,
+            commitment,
+            &proof_bytes,
+            &product_id,
+            &batch,
+            &context_hash,
+        ),
+    })
+}
+
+/// Proves `recycled_mass` is at least `percent` % of `total_mass` for one
+/// material of a batch.
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn generate_recycled_content_proof(
+    material: String,
+    recycled_mass: String,
+    total_mass: String,
+    r_recycled_hex: String,
+    r_total_hex: String,
+    percent: u32,
+    batch: String,
+    context_hash_hex: String,
+) -> Result<JsValue, JsValue> {
+    let recycled = parse_u64(&recycled_mass)?;
+    let total = parse_u64(&total_mass)?;
+    let r_recycled = parse_scalar_hex(&r_recycled_hex, "r_recycled_hex")?;
+    let r_total = parse_scalar_hex(&r_total_hex, "r_total_hex")?;
+    let context_hash = parse_fixed_32_hex(&context_hash_hex, "context_hash_hex")?;
+    let percent = u64::from(percent);
+
+    let (c_recycled, c_total, proof_bytes) = prove_recycled_content_internal(
+        &material,
+        recycled,
+        total,
+        r_recycled,
+        r_total,
+        percent,
+        &batch,
+        &context_hash,
+    )?;
+    let verified =
+        verify_recycled_content_internal(&material, percent, c_recycled, c_total, &proof_bytes, &batch, &context_hash);
+    to_js_value(&RecycledContentProofResponse {
+        c_recycled_hex: hex::encode(c_recycled.as_bytes()),
+        c_total_hex: hex::encode(c_total.as_bytes()),
+        proof_hex: hex::encode(proof_bytes),
+        verified,
+    })
+}
+
+#[wasm_bindgen]
+pub fn verify_recycled_content_proof(
+    material: String,
+    c_recycled_hex: String,
+    c_total_hex: String,
+    percent: u32,
+    proof_hex: String,
+    batch: String,
+    context_hash_hex: String,
+) -> Result<JsValue, JsValue> {
+    let c_recycled = parse_commitment_hex(&c_recycled_hex, "c_recycled_hex")?;
+    let c_total = parse_commitment_hex(&c_total_hex, "c_total_hex")?;
+    let proof_bytes = parse_hex_vec(&proof_hex, "proof")?;
+    let context_hash = parse_fixed_32_hex(&context_hash_hex, "context_hash_hex")?;
+
+    to_js_value(&VerifyResponse {
+        verified: verify_recycled_content_internal(
+            &material,
+            u64::from(percent),
+            c_recycled,
+            c_total,
+            &proof_bytes,
+            &batch,
+            &context_hash,
+        ),
+    })
 }